@@ -0,0 +1,97 @@
+/*
+ * Copyright 2016 Masaki Hara
+ *
+ * Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+ * http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+ * <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+ * option. This file may not be copied, modified, or distributed
+ * except according to those terms.
+ */
+
+/*
+ * C interface to yasna, available when the crate is built with the `ffi`
+ * feature, e.g.
+ *
+ *     cargo rustc --release --features ffi --crate-type cdylib
+ *
+ * Functions returning int return YASNA_OK on success and a YASNA_ERR_*
+ * code otherwise. Memory returned by the library must be released with
+ * the matching yasna_*_free function.
+ */
+
+#ifndef YASNA_H
+#define YASNA_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define YASNA_OK 0
+#define YASNA_ERR_EOF 1
+#define YASNA_ERR_EXTRA 2
+#define YASNA_ERR_INTEGER_OVERFLOW 3
+#define YASNA_ERR_STACK_OVERFLOW 4
+#define YASNA_ERR_INVALID 5
+#define YASNA_ERR_ARGUMENT 6
+#define YASNA_ERR_LIMIT 7
+
+#define YASNA_MODE_BER 0
+#define YASNA_MODE_DER 1
+
+#define YASNA_CLASS_UNIVERSAL 0
+#define YASNA_CLASS_APPLICATION 1
+#define YASNA_CLASS_CONTEXT_SPECIFIC 2
+#define YASNA_CLASS_PRIVATE 3
+
+/* A node of a value tree. */
+typedef struct YasnaValue yasna_value;
+
+/* Checks that data is a single element obeying the DER rules for
+ * identifiers and lengths. */
+int yasna_validate_der(const uint8_t *data, size_t len);
+
+/* Parses a single element. On success, *out receives a tree to be
+ * released with yasna_value_free. */
+int yasna_parse(const uint8_t *data, size_t len, int mode,
+                yasna_value **out);
+
+/* Releases a root value. NULL is ignored. */
+void yasna_value_free(yasna_value *value);
+
+/* Returns one of YASNA_CLASS_*, or -1 for NULL. */
+int yasna_value_tag_class(const yasna_value *value);
+uint64_t yasna_value_tag_number(const yasna_value *value);
+/* Returns 1 for constructed values and 0 otherwise. */
+int yasna_value_is_constructed(const yasna_value *value);
+/* Returns the contents of a primitive value, valid while the tree is. */
+const uint8_t *yasna_value_content(const yasna_value *value, size_t *len);
+size_t yasna_value_child_count(const yasna_value *value);
+/* Returns a borrowed child, or NULL if index is out of range. */
+const yasna_value *yasna_value_child(const yasna_value *value, size_t index);
+
+/* Create values; NULL is returned for invalid arguments. */
+yasna_value *yasna_value_new_primitive(int tag_class, uint64_t tag_number,
+                                       const uint8_t *data, size_t len);
+yasna_value *yasna_value_new_constructed(int tag_class, uint64_t tag_number);
+/* Appends child to a constructed parent, taking ownership of child on
+ * success. */
+int yasna_value_push_child(yasna_value *parent, yasna_value *child);
+
+/* Encodes a tree in DER. *out is released with yasna_bytes_free. */
+int yasna_encode_der(const yasna_value *value, uint8_t **out,
+                     size_t *out_len);
+void yasna_bytes_free(uint8_t *data, size_t len);
+
+/* Renders a single element as text, one line per value, in the format
+ * of openssl asn1parse. *out is released with yasna_string_free. */
+int yasna_dump(const uint8_t *data, size_t len, int mode, char **out);
+void yasna_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* YASNA_H */
@@ -0,0 +1,235 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `quickcheck::Arbitrary` implementations.
+//!
+//! The generated values always satisfy the constraints of the writer, so
+//! they can be encoded without panicking: OIDs have at least two
+//! components with a valid first pair, and times lie within the years
+//! their ASN.1 types can express.
+//!
+//! `Arbitrary` is implemented for [`Tag`], [`PCBit`], [`TagClass`],
+//! [`ObjectIdentifier`], and with `time` feature for `UTCTime` and
+//! `GeneralizedTime`. The values without a type of their own are
+//! generated through the wrappers of this module.
+//!
+//! # Features
+//!
+//! This module is enabled by `quickcheck` feature.
+//!
+//! ```toml
+//! [dependencies]
+//! yasna = { version = "*", features = ["quickcheck"] }
+//! ```
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use ::quickcheck::{Arbitrary, Gen};
+#[cfg(feature = "bit-vec")]
+use bit_vec::BitVec;
+
+use super::{PCBit, Tag, TagClass, TAG_CLASSES};
+use super::models::ObjectIdentifier;
+use super::writer::OID_MAX_ID1;
+#[cfg(feature = "time")]
+use super::models::{GeneralizedTime, UTCTime};
+
+impl Arbitrary for PCBit {
+    fn arbitrary(g: &mut Gen) -> Self {
+        *g.choose(&[PCBit::Primitive, PCBit::Constructed]).unwrap()
+    }
+}
+
+impl Arbitrary for TagClass {
+    fn arbitrary(g: &mut Gen) -> Self {
+        *g.choose(&TAG_CLASSES).unwrap()
+    }
+}
+
+impl Arbitrary for Tag {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Tag {
+            tag_class: TagClass::arbitrary(g),
+            tag_number: u64::arbitrary(g),
+        }
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let tag_class = self.tag_class;
+        Box::new(self.tag_number.shrink().map(move |tag_number| Tag {
+            tag_class,
+            tag_number,
+        }))
+    }
+}
+
+impl Arbitrary for ObjectIdentifier {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let id0 = u64::arbitrary(g) % 3;
+        let id1 = if id0 < 2 {
+            u64::arbitrary(g) % 40
+        } else {
+            u64::arbitrary(g) % OID_MAX_ID1
+        };
+        let mut components = Vec::<u64>::arbitrary(g);
+        components.splice(0..0, [id0, id1].iter().cloned());
+        ObjectIdentifier::new(components)
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let (head, tail) = self.components().split_at(2);
+        let head = head.to_vec();
+        Box::new(tail.to_vec().shrink().map(move |tail| {
+            let mut components = head.clone();
+            components.extend(tail);
+            ObjectIdentifier::new(components)
+        }))
+    }
+}
+
+/// The contents of a PrintableString, for
+/// [`write_printable_string`](super::DERWriter::write_printable_string).
+///
+/// Only letters, digits, space and `'()+,-./:=?` are generated.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrintableString(pub String);
+
+const PRINTABLE_CHARS: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ\
+    abcdefghijklmnopqrstuvwxyz0123456789 '()+,-./:=?";
+
+impl Arbitrary for PrintableString {
+    fn arbitrary(g: &mut Gen) -> Self {
+        let len = usize::arbitrary(g) % (g.size() + 1);
+        PrintableString((0..len)
+            .map(|_| *g.choose(PRINTABLE_CHARS).unwrap() as char)
+            .collect())
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(self.0.shrink()
+            .filter(|s| s.bytes().all(|b| PRINTABLE_CHARS.contains(&b)))
+            .map(PrintableString))
+    }
+}
+
+/// A BIT STRING of any length, for
+/// [`write_bitvec`](super::DERWriter::write_bitvec).
+///
+/// This struct is enabled by `bit-vec` feature.
+#[cfg(feature = "bit-vec")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitString(pub BitVec);
+
+#[cfg(feature = "bit-vec")]
+impl Arbitrary for BitString {
+    fn arbitrary(g: &mut Gen) -> Self {
+        BitString(Vec::<bool>::arbitrary(g).into_iter().collect())
+    }
+
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let bits = self.0.iter().collect::<Vec<bool>>();
+        Box::new(bits.shrink()
+            .map(|bits| BitString(bits.into_iter().collect())))
+    }
+}
+
+/// Returns a timestamp in `min..=max`, in seconds.
+#[cfg(feature = "time")]
+fn timestamp(g: &mut Gen, min: i64, max: i64) -> i64 {
+    min + (u64::arbitrary(g) % (max - min + 1) as u64) as i64
+}
+
+#[cfg(feature = "time")]
+impl Arbitrary for UTCTime {
+    fn arbitrary(g: &mut Gen) -> Self {
+        // 1950-01-01T00:00:00Z to 2049-12-31T23:59:59Z
+        let t = timestamp(g, -631152000, 2524607999);
+        let datetime = ::time::OffsetDateTime::from_unix_timestamp(t).unwrap();
+        UTCTime::from_datetime(datetime)
+    }
+}
+
+/// Leap seconds are not generated.
+#[cfg(feature = "time")]
+impl Arbitrary for GeneralizedTime {
+    fn arbitrary(g: &mut Gen) -> Self {
+        // 0000-01-01T00:00:00Z to 9999-12-31T23:59:59Z
+        let t = timestamp(g, -62167219200, 253402300799);
+        let nanos = if bool::arbitrary(g) {
+            u32::arbitrary(g) % 1_000_000_000
+        } else {
+            0
+        };
+        let datetime = ::time::OffsetDateTime::from_unix_timestamp_nanos(
+            t as i128 * 1_000_000_000 + nanos as i128).unwrap();
+        let sub_nano: Vec<u8> = if nanos != 0 && bool::arbitrary(g) {
+            let len = usize::arbitrary(g) % 8;
+            (0..len).map(|_| b'0' + u8::arbitrary(g) % 10).collect()
+        } else {
+            Vec::new()
+        };
+        GeneralizedTime::from_datetime_and_sub_nano(datetime, &sub_nano)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ::quickcheck::quickcheck;
+    use super::*;
+    use super::super::{construct_der, parse_der};
+
+    quickcheck! {
+        fn prop_tag_roundtrip(tag: Tag) -> bool {
+            let der = construct_der(|writer| {
+                writer.write_tagged_implicit(tag, |writer| writer.write_null())
+            });
+            let tag2 = parse_der(&der, |reader| {
+                let tag2 = reader.lookahead_tag()?;
+                reader.read_tagged_implicit(tag2, |reader| reader.read_null())?;
+                Ok(tag2)
+            }).unwrap();
+            tag == tag2
+        }
+
+        fn prop_oid_roundtrip(oid: ObjectIdentifier) -> bool {
+            let der = construct_der(|writer| writer.write_oid(&oid));
+            parse_der(&der, |reader| reader.read_oid()).unwrap() == oid
+        }
+
+        fn prop_printable_string_roundtrip(s: PrintableString) -> bool {
+            let der = construct_der(|writer| {
+                writer.write_printable_string(&s.0)
+            });
+            parse_der(&der, |reader| reader.read_printable_string())
+                .unwrap() == s.0
+        }
+    }
+
+    #[cfg(feature = "bit-vec")]
+    quickcheck! {
+        fn prop_bitvec_roundtrip(bits: BitString) -> bool {
+            let der = construct_der(|writer| writer.write_bitvec(&bits.0));
+            parse_der(&der, |reader| reader.read_bitvec()).unwrap() == bits.0
+        }
+    }
+
+    #[cfg(feature = "time")]
+    quickcheck! {
+        fn prop_utctime_roundtrip(t: UTCTime) -> bool {
+            let der = construct_der(|writer| writer.write_utctime(&t));
+            parse_der(&der, |reader| reader.read_utctime()).unwrap() == t
+        }
+
+        fn prop_generalized_time_roundtrip(t: GeneralizedTime) -> bool {
+            let der = construct_der(|writer| writer.write_generalized_time(&t));
+            parse_der(&der, |reader| reader.read_generalized_time())
+                .unwrap() == t
+        }
+    }
+}
@@ -0,0 +1,36 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use alloc::vec::Vec;
+
+use ::crypto_bigint::{Limb, Uint, Word};
+
+use super::BigIntBackend;
+use crate::{ASN1Error, ASN1ErrorKind, ASN1Result};
+
+impl<const LIMBS: usize> BigIntBackend for Uint<LIMBS> {
+    fn from_sign_magnitude(non_negative: bool, magnitude: &[u8])
+            -> ASN1Result<Self> {
+        if !non_negative || magnitude.len() > Self::BYTES {
+            return Err(ASN1Error::new(ASN1ErrorKind::IntegerOverflow));
+        }
+        let mut words = [0 as Word; LIMBS];
+        for (i, &b) in magnitude.iter().rev().enumerate() {
+            words[i / Limb::BYTES] |= (b as Word) << (8 * (i % Limb::BYTES));
+        }
+        Ok(Uint::from_words(words))
+    }
+
+    fn to_sign_magnitude(&self) -> (bool, Vec<u8>) {
+        let mut bytes = Vec::with_capacity(Self::BYTES);
+        for word in self.as_words().iter().rev() {
+            bytes.extend_from_slice(&word.to_be_bytes());
+        }
+        (true, bytes)
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use alloc::vec::Vec;
+
+use ::ibig::ops::UnsignedAbs;
+use ::ibig::{IBig, UBig};
+
+use super::BigIntBackend;
+use crate::{ASN1Error, ASN1ErrorKind, ASN1Result};
+
+impl BigIntBackend for IBig {
+    fn from_sign_magnitude(non_negative: bool, magnitude: &[u8])
+            -> ASN1Result<Self> {
+        let magnitude = IBig::from(UBig::from_be_bytes(magnitude));
+        Ok(if non_negative { magnitude } else { -magnitude })
+    }
+
+    fn to_sign_magnitude(&self) -> (bool, Vec<u8>) {
+        (*self >= IBig::from(0u8), self.unsigned_abs().to_be_bytes())
+    }
+}
+
+impl BigIntBackend for UBig {
+    fn from_sign_magnitude(non_negative: bool, magnitude: &[u8])
+            -> ASN1Result<Self> {
+        if !non_negative {
+            return Err(ASN1Error::new(ASN1ErrorKind::IntegerOverflow));
+        }
+        Ok(UBig::from_be_bytes(magnitude))
+    }
+
+    fn to_sign_magnitude(&self) -> (bool, Vec<u8>) {
+        (true, self.to_be_bytes())
+    }
+}
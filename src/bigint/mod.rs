@@ -0,0 +1,138 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Glue between ASN.1 INTEGER and the supported big-integer crates.
+//!
+//! Each backend only has to convert from and to a sign and a big-endian
+//! magnitude; the two's complement handling is shared here.
+
+use alloc::vec::Vec;
+
+use super::{ASN1Result, BERReader, DERWriter};
+
+#[cfg(feature = "num-bigint")]
+mod num;
+#[cfg(feature = "ibig")]
+mod ibig;
+#[cfg(feature = "crypto-bigint")]
+mod crypto_bigint;
+
+/// A big-integer type that can be read from and written as ASN.1 INTEGER.
+pub(crate) trait BigIntBackend: Sized {
+    /// Builds a value from its sign and its big-endian magnitude.
+    ///
+    /// The magnitude has no leading zero bytes; zero is the empty slice.
+    fn from_sign_magnitude(non_negative: bool, magnitude: &[u8])
+        -> ASN1Result<Self>;
+
+    /// Returns the sign and the big-endian magnitude of the value.
+    fn to_sign_magnitude(&self) -> (bool, Vec<u8>);
+}
+
+/// Reads an ASN.1 INTEGER value into a big-integer backend.
+pub(crate) fn read<T: BigIntBackend>(reader: BERReader) -> ASN1Result<T> {
+    let (mut bytes, non_negative) = reader.read_bigint_bytes()?;
+    if !non_negative {
+        negate(&mut bytes);
+    }
+    let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+    T::from_sign_magnitude(non_negative, &bytes[start..])
+}
+
+/// Writes a value of a big-integer backend as an ASN.1 INTEGER value.
+pub(crate) fn write<T: BigIntBackend>(writer: DERWriter, val: &T) {
+    let (non_negative, mut bytes) = val.to_sign_magnitude();
+    let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+    bytes.drain(..start);
+    if non_negative || bytes.is_empty() {
+        writer.write_bigint_bytes(&bytes, true);
+    } else {
+        negate(&mut bytes);
+        if bytes[0] < 128 {
+            bytes.insert(0, 255);
+        }
+        writer.write_bigint_bytes(&bytes, false);
+    }
+}
+
+/// Negates a big-endian two's complement number in place.
+fn negate(bytes: &mut [u8]) {
+    let mut carry: usize = 1;
+    for b in bytes.iter_mut().rev() {
+        let bval = 255 - (*b as usize);
+        *b = (bval + carry) as u8;
+        carry = (bval + carry) >> 8;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{construct_der, parse_ber, parse_der};
+    use super::*;
+
+    const TESTS : &[(i64, &[u8])] = &[
+        (-65537, &[2, 3, 254, 255, 255]),
+        (-65535, &[2, 3, 255, 0, 1]),
+        (-256, &[2, 2, 255, 0]),
+        (-128, &[2, 1, 128]),
+        (-1, &[2, 1, 255]),
+        (0, &[2, 1, 0]),
+        (127, &[2, 1, 127]),
+        (128, &[2, 2, 0, 128]),
+        (65535, &[2, 3, 0, 255, 255]),
+    ];
+
+    fn test_backend<T, F>(from_i64: F)
+            where T: BigIntBackend + PartialEq + core::fmt::Debug,
+                  F: Fn(i64) -> Option<T> {
+        for &(value, data) in TESTS {
+            let result = parse_der(data, |reader| read::<T>(reader));
+            match from_i64(value) {
+                Some(evalue) => {
+                    assert_eq!(result.unwrap(), evalue);
+                    let der = construct_der(|writer| write(writer, &evalue));
+                    assert_eq!(der, data);
+                },
+                None => {
+                    assert!(result.is_err());
+                },
+            }
+        }
+        parse_ber(&[2, 2, 0, 127], |reader| read::<T>(reader)).unwrap_err();
+    }
+
+    #[cfg(feature = "num-bigint")]
+    #[test]
+    fn test_num_bigint_backend() {
+        use num_bigint::{BigInt, BigUint};
+        use num_traits::FromPrimitive;
+        test_backend(BigInt::from_i64);
+        test_backend(BigUint::from_i64);
+    }
+
+    #[cfg(feature = "ibig")]
+    #[test]
+    fn test_ibig_backend() {
+        use core::convert::TryFrom;
+        use ::ibig::{IBig, UBig};
+        test_backend(|value| Some(IBig::from(value)));
+        test_backend(|value| UBig::try_from(value).ok());
+    }
+
+    #[cfg(feature = "crypto-bigint")]
+    #[test]
+    fn test_crypto_bigint_backend() {
+        use core::convert::TryFrom;
+        use ::crypto_bigint::{U64, U128};
+        test_backend(|value| u64::try_from(value).ok().map(U128::from_u64));
+        test_backend(|value| u64::try_from(value).ok().map(U64::from_u64));
+        // 2^64 does not fit in `U64`.
+        parse_der(&[2, 9, 1, 0, 0, 0, 0, 0, 0, 0, 0],
+            |reader| read::<U64>(reader)).unwrap_err();
+    }
+}
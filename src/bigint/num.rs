@@ -0,0 +1,41 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use alloc::vec::Vec;
+
+use num_bigint::{BigInt, BigUint, Sign};
+
+use super::BigIntBackend;
+use crate::{ASN1Error, ASN1ErrorKind, ASN1Result};
+
+impl BigIntBackend for BigInt {
+    fn from_sign_magnitude(non_negative: bool, magnitude: &[u8])
+            -> ASN1Result<Self> {
+        let sign = if non_negative { Sign::Plus } else { Sign::Minus };
+        Ok(BigInt::from_bytes_be(sign, magnitude))
+    }
+
+    fn to_sign_magnitude(&self) -> (bool, Vec<u8>) {
+        let (sign, bytes) = self.to_bytes_be();
+        (sign != Sign::Minus, bytes)
+    }
+}
+
+impl BigIntBackend for BigUint {
+    fn from_sign_magnitude(non_negative: bool, magnitude: &[u8])
+            -> ASN1Result<Self> {
+        if !non_negative {
+            return Err(ASN1Error::new(ASN1ErrorKind::IntegerOverflow));
+        }
+        Ok(BigUint::from_bytes_be(magnitude))
+    }
+
+    fn to_sign_magnitude(&self) -> (bool, Vec<u8>) {
+        (true, self.to_bytes_be())
+    }
+}
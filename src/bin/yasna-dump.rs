@@ -0,0 +1,209 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! `yasna-dump`: dumps, validates and extracts DER/BER/PEM data.
+//!
+//! ```text
+//! yasna-dump [--inform der|ber|pem] [--validate] [--extract PATH] [FILE]
+//! ```
+//!
+//! The dump format follows `openssl asn1parse`. `PATH` is a list of
+//! zero-based indices separated by dots; the first index selects a
+//! top-level element, e.g. `0.1` is the second child of the first one.
+//! The element is extracted as it is encoded in the input, e.g. BER stays
+//! BER.
+
+use std::env;
+use std::fs::File;
+use std::io::{self, BufReader, Read, Write};
+use std::process;
+
+use yasna::{BERMode, Base64Reader, Event, TlvLength};
+
+const USAGE: &str = "\
+usage: yasna-dump [OPTIONS] [FILE]
+
+Reads DER, BER or PEM data from FILE (or stdin) and prints its structure.
+
+options:
+    --inform FORMAT   input format: der, ber or pem (default: guess)
+    --validate        check that the identifiers and lengths of the input
+                      obey DER (the contents are not checked)
+    --extract PATH    write the encoding of the element at PATH (e.g.
+                      0.2.1), as found in the input, to stdout instead of
+                      dumping
+    -h, --help        print this message
+";
+
+#[derive(Clone, Copy, PartialEq)]
+enum Inform {
+    Der,
+    Ber,
+    Pem,
+}
+
+struct Options {
+    inform: Option<Inform>,
+    validate: bool,
+    extract: Option<Vec<usize>>,
+    file: Option<String>,
+}
+
+fn main() {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(msg) => {
+            eprintln!("yasna-dump: {}\n\n{}", msg, USAGE);
+            process::exit(2);
+        },
+    };
+    if let Err(msg) = run(&options) {
+        eprintln!("yasna-dump: {}", msg);
+        process::exit(1);
+    }
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I)
+        -> Result<Options, String> {
+    let mut options = Options {
+        inform: None,
+        validate: false,
+        extract: None,
+        file: None,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                process::exit(0);
+            },
+            "--inform" => {
+                let value = args.next()
+                    .ok_or_else(|| "--inform needs a value".to_string())?;
+                options.inform = Some(match value.as_str() {
+                    "der" => Inform::Der,
+                    "ber" => Inform::Ber,
+                    "pem" => Inform::Pem,
+                    _ => return Err(format!("unknown format: {}", value)),
+                });
+            },
+            "--validate" => options.validate = true,
+            "--extract" => {
+                let value = args.next()
+                    .ok_or_else(|| "--extract needs a path".to_string())?;
+                options.extract = Some(parse_path(&value)?);
+            },
+            _ if arg.starts_with('-') && arg != "-" => {
+                return Err(format!("unknown option: {}", arg));
+            },
+            _ => {
+                if options.file.is_some() {
+                    return Err("more than one input file".to_string());
+                }
+                options.file = Some(arg);
+            },
+        }
+    }
+    Ok(options)
+}
+
+fn parse_path(path: &str) -> Result<Vec<usize>, String> {
+    path.split('.')
+        .map(|index| index.parse()
+            .map_err(|_| format!("invalid path: {}", path)))
+        .collect()
+}
+
+fn run(options: &Options) -> Result<(), String> {
+    let data = read_input(options)?;
+    let mode = match options.inform {
+        Some(Inform::Der) => BERMode::Der,
+        _ => BERMode::Ber,
+    };
+    if options.validate {
+        yasna::walk_ber(&data, BERMode::Der, &mut |_| Ok(()))
+            .map_err(|e| format!(
+                "input has invalid DER identifiers or lengths: {}", e))?;
+        eprintln!("valid DER identifiers and lengths");
+        return Ok(());
+    }
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let result = if let Some(ref path) = options.extract {
+        let raw = find(&data, mode, path)?
+            .ok_or_else(|| "no element at the given path".to_string())?;
+        out.write_all(raw)
+    } else {
+        let text = yasna::dump_ber(&data, mode).map_err(|e| e.to_string())?;
+        out.write_all(text.as_bytes())
+    };
+    result.map_err(|e| e.to_string())
+}
+
+fn read_input(options: &Options) -> Result<Vec<u8>, String> {
+    let mut data = Vec::new();
+    match options.file {
+        Some(ref file) if file != "-" => {
+            File::open(file).and_then(|mut f| f.read_to_end(&mut data))
+                .map_err(|e| format!("{}: {}", file, e))?;
+        },
+        _ => {
+            io::stdin().read_to_end(&mut data).map_err(|e| e.to_string())?;
+        },
+    }
+    let pem = match options.inform {
+        Some(inform) => inform == Inform::Pem,
+        None => data.windows(11).any(|w| w == b"-----BEGIN "),
+    };
+    if pem {
+        let mut der = Vec::new();
+        Base64Reader::new_pem(BufReader::new(&data[..]), None)
+            .read_to_end(&mut der).map_err(|e| e.to_string())?;
+        data = der;
+    }
+    Ok(data)
+}
+
+/// Returns the encoding of the element at `path`, if any.
+fn find<'a>(data: &'a [u8], mode: BERMode, path: &[usize])
+        -> Result<Option<&'a [u8]>, String> {
+    // The indices of the open elements, and of the next child
+    let mut indices = Vec::new();
+    let mut next = 0;
+    let mut found = None;
+    yasna::walk_ber(data, mode, &mut |event| {
+        match event {
+            Event::BeginConstructed { offset, .. } |
+                    Event::Primitive { offset, .. } => {
+                indices.push(next);
+                if found.is_none() && indices == path {
+                    found = Some(offset);
+                }
+                next = 0;
+                if let Event::Primitive { .. } = event {
+                    next = indices.pop().unwrap() + 1;
+                }
+            },
+            Event::EndConstructed { .. } => {
+                next = indices.pop().unwrap() + 1;
+            },
+        }
+        Ok(())
+    }).map_err(|e| e.to_string())?;
+    let offset = match found {
+        Some(offset) => offset,
+        None => return Ok(None),
+    };
+    match yasna::tlv_length(&data[offset..], mode) {
+        Ok(TlvLength::Complete(len)) => Ok(Some(&data[offset..offset + len])),
+        Ok(TlvLength::Incomplete { .. }) =>
+            Err("unexpected end of data".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -0,0 +1,559 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Streaming access to CMS (RFC 5652) messages.
+//!
+//! A CMS message can be far too large to hold in memory, especially
+//! when it is BER-encoded with indefinite lengths. The functions here
+//! read the outer structure from an `io::Read` and hand the
+//! encapsulated content to the caller chunk by chunk, only buffering
+//! the small elements around it.
+//!
+//! # Features
+//!
+//! This module is enabled by `std` feature.
+//!
+//! ```toml
+//! [dependencies]
+//! yasna = { version = "*", features = ["std"] }
+//! ```
+
+#![forbid(missing_docs)]
+
+use alloc::vec::Vec;
+use std::io::{self, Read};
+
+use super::{parse_ber_general, ASN1Result, BERMode, BERReader};
+use super::{PCBit, Tag, TAG_CLASSES};
+use super::models::ObjectIdentifier;
+use super::tags::{TAG_EOC, TAG_INTEGER, TAG_OCTETSTRING, TAG_OID};
+use super::tags::{TAG_SEQUENCE, TAG_SET};
+
+/// `id-data` (1.2.840.113549.1.7.1)
+const ID_DATA: &[u64] = &[1, 2, 840, 113549, 1, 7, 1];
+/// `id-signedData` (1.2.840.113549.1.7.2)
+const ID_SIGNED_DATA: &[u64] = &[1, 2, 840, 113549, 1, 7, 2];
+
+const MAX_DEPTH: usize = 100;
+const CHUNK_SIZE: usize = 8192;
+
+/// The outer structure of a CMS `ContentInfo`, as read by
+/// [`stream_content`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct ContentInfo {
+    /// The `contentType` field
+    pub content_type: ObjectIdentifier,
+    /// The fields of the `SignedData`, if the content type is
+    /// `id-signedData`
+    pub signed_data: Option<SignedData>,
+}
+
+/// The fields of a CMS `SignedData` other than the encapsulated content.
+///
+/// Elements which may be large or are not needed to locate the content
+/// are kept as their encodings, in the mode of the message, to be parsed
+/// with [`parse_ber_general`](crate::parse_ber_general).
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct SignedData {
+    /// The `version` field
+    pub version: u8,
+    /// The encoding of the `digestAlgorithms` SET
+    pub digest_algorithms: Vec<u8>,
+    /// The `eContentType` field of `encapContentInfo`
+    pub econtent_type: ObjectIdentifier,
+    /// Whether `eContent` was present; it is absent from detached
+    /// signatures
+    pub econtent_present: bool,
+    /// The encoding of the `[0] certificates` element, if present
+    pub certificates: Option<Vec<u8>>,
+    /// The encoding of the `[1] crls` element, if present
+    pub crls: Option<Vec<u8>>,
+    /// The encoding of the `signerInfos` SET
+    pub signer_infos: Vec<u8>,
+}
+
+/// Reads a CMS `ContentInfo` from `reader`, passing the encapsulated
+/// content to `callback` chunk by chunk.
+///
+/// Two content types are understood: `id-data`, whose octets are
+/// streamed directly, and `id-signedData`, whose `eContent` is streamed.
+/// Other content types are rejected. Constructed OCTET STRINGs are
+/// flattened, so the chunks concatenate to the content octets.
+///
+/// Reading stops at the end of the `ContentInfo`; anything after it is
+/// left in `reader`. Malformed input is reported as an `io::Error` of
+/// kind `InvalidData`, and errors from `callback` are passed through.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::BERMode;
+/// use yasna::models::ObjectIdentifier;
+/// // id-data, with "Hello!" split into two indefinite length segments
+/// let data = &[48, 128, 6, 9, 42, 134, 72, 134, 247, 13, 1, 7, 1,
+///     160, 128, 36, 128, 4, 3, 72, 101, 108, 4, 3, 108, 111, 33,
+///     0, 0, 0, 0, 0, 0];
+/// let mut content = Vec::new();
+/// let info = yasna::cms::stream_content(&data[..], BERMode::Ber, |chunk| {
+///     content.extend_from_slice(chunk);
+///     Ok(())
+/// }).unwrap();
+/// assert_eq!(content, b"Hello!");
+/// assert_eq!(info.content_type,
+///     ObjectIdentifier::from_slice(&[1, 2, 840, 113549, 1, 7, 1]));
+/// ```
+pub fn stream_content<R, F>(reader: R, mode: BERMode, mut callback: F)
+        -> io::Result<ContentInfo>
+        where R: Read, F: FnMut(&[u8]) -> io::Result<()> {
+    let mut stream = Stream {
+        inner: reader,
+        mode,
+        pos: 0,
+    };
+    let outer = stream.expect_header(TAG_SEQUENCE, PCBit::Constructed)?;
+    let content_type = stream.read_oid()?;
+    let explicit = stream.expect_header(Tag::context(0), PCBit::Constructed)?;
+    let signed_data = if content_type.components() == ID_DATA {
+        let header = stream.read_header()?;
+        if header.tag != TAG_OCTETSTRING {
+            return Err(invalid_data("unexpected tag"));
+        }
+        stream.stream_octets(&header, &mut callback, 0)?;
+        None
+    } else if content_type.components() == ID_SIGNED_DATA {
+        Some(stream.read_signed_data(&mut callback)?)
+    } else {
+        return Err(invalid_data("unsupported content type"));
+    };
+    stream.end(&explicit)?;
+    stream.end(&outer)?;
+    Ok(ContentInfo {
+        content_type,
+        signed_data,
+    })
+}
+
+/// Identifier and length octets of an element.
+#[derive(Debug)]
+struct Header {
+    tag: Tag,
+    pcbit: PCBit,
+    /// The length of the contents, or `None` for the indefinite form
+    length: Option<u64>,
+    /// The stream position after the contents, for the definite form
+    end: Option<u64>,
+    /// The identifier and length octets as read
+    raw: Vec<u8>,
+}
+
+impl Header {
+    fn is_eoc(&self) -> bool {
+        self.tag == TAG_EOC && self.pcbit == PCBit::Primitive &&
+            self.length == Some(0)
+    }
+}
+
+struct Stream<R> {
+    inner: R,
+    mode: BERMode,
+    pos: u64,
+}
+
+impl<R: Read> Stream<R> {
+    fn read_u8(&mut self, raw: &mut Vec<u8>) -> io::Result<u8> {
+        let mut b = [0; 1];
+        self.inner.read_exact(&mut b)?;
+        self.pos += 1;
+        raw.push(b[0]);
+        Ok(b[0])
+    }
+
+    fn read_header(&mut self) -> io::Result<Header> {
+        let mut raw = Vec::new();
+        let first = self.read_u8(&mut raw)?;
+        let tag_class = TAG_CLASSES[(first >> 6) as usize];
+        let pcbit = if first & 32 == 0 {
+            PCBit::Primitive
+        } else {
+            PCBit::Constructed
+        };
+        let mut tag_number = (first & 31) as u64;
+        if tag_number == 31 {
+            tag_number = 0;
+            loop {
+                let b = self.read_u8(&mut raw)?;
+                if tag_number == 0 && b == 128 {
+                    return Err(invalid_data("non-minimal tag number"));
+                }
+                tag_number = tag_number.checked_mul(128)
+                    .ok_or_else(|| invalid_data("tag number too large"))?
+                    + (b & 127) as u64;
+                if b & 128 == 0 {
+                    break;
+                }
+            }
+            if tag_number < 31 {
+                return Err(invalid_data("non-minimal tag number"));
+            }
+        }
+        let b = self.read_u8(&mut raw)?;
+        let length = if b < 128 {
+            Some(b as u64)
+        } else if b == 128 {
+            if pcbit == PCBit::Primitive || self.mode == BERMode::Der {
+                return Err(invalid_data("unexpected indefinite length"));
+            }
+            None
+        } else if b == 255 {
+            return Err(invalid_data("invalid length"));
+        } else {
+            let mut length: u64 = 0;
+            for _ in 0..(b & 127) {
+                let b = self.read_u8(&mut raw)?;
+                length = length.checked_mul(256)
+                    .ok_or_else(|| invalid_data("length too large"))?
+                    + b as u64;
+                if self.mode.is_canonical() && length == 0 {
+                    return Err(invalid_data("non-minimal length"));
+                }
+            }
+            if self.mode.is_canonical() && length < 128 {
+                return Err(invalid_data("non-minimal length"));
+            }
+            Some(length)
+        };
+        if length.is_some() && pcbit == PCBit::Constructed &&
+                self.mode == BERMode::Cer {
+            return Err(invalid_data("unexpected definite length"));
+        }
+        let end = match length {
+            Some(length) => Some(self.pos.checked_add(length)
+                .ok_or_else(|| invalid_data("length too large"))?),
+            None => None,
+        };
+        Ok(Header {
+            tag: Tag {
+                tag_class,
+                tag_number,
+            },
+            pcbit,
+            length,
+            end,
+            raw,
+        })
+    }
+
+    fn expect_header(&mut self, tag: Tag, pcbit: PCBit)
+            -> io::Result<Header> {
+        let header = self.read_header()?;
+        if header.tag != tag || header.pcbit != pcbit {
+            return Err(invalid_data("unexpected tag"));
+        }
+        Ok(header)
+    }
+
+    /// Reads the header of the next element in `parent`, or returns
+    /// `None` at its end, consuming the end-of-contents octets.
+    fn next_in(&mut self, parent: &Header) -> io::Result<Option<Header>> {
+        if let Some(end) = parent.end {
+            if self.pos >= end {
+                return Ok(None);
+            }
+        }
+        let header = self.read_header()?;
+        if header.is_eoc() {
+            if parent.end.is_some() {
+                return Err(invalid_data("unexpected end-of-contents"));
+            }
+            return Ok(None);
+        }
+        self.check_within(parent, &header)?;
+        Ok(Some(header))
+    }
+
+    fn check_within(&self, parent: &Header, header: &Header)
+            -> io::Result<()> {
+        match (parent.end, header.end) {
+            (Some(end), Some(child_end)) if child_end > end => {
+                Err(invalid_data("element exceeds its parent"))
+            },
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that `header`'s contents have been read up to its end.
+    fn end(&mut self, header: &Header) -> io::Result<()> {
+        match header.end {
+            Some(end) if self.pos == end => Ok(()),
+            Some(_) => Err(invalid_data("extra data in element")),
+            None => {
+                let eoc = self.read_header()?;
+                if !eoc.is_eoc() {
+                    return Err(invalid_data("extra data in element"));
+                }
+                Ok(())
+            },
+        }
+    }
+
+    /// Reads the contents of `header` and appends the whole element
+    /// to `buf`.
+    fn copy_element(&mut self, header: &Header, buf: &mut Vec<u8>,
+            depth: usize) -> io::Result<()> {
+        if depth > MAX_DEPTH {
+            return Err(invalid_data("nesting too deep"));
+        }
+        buf.extend_from_slice(&header.raw);
+        match header.length {
+            Some(length) => {
+                let start = buf.len();
+                (&mut self.inner).take(length).read_to_end(buf)?;
+                if ((buf.len() - start) as u64) < length {
+                    return Err(io::ErrorKind::UnexpectedEof.into());
+                }
+                self.pos += length;
+            },
+            None => {
+                loop {
+                    let child = self.read_header()?;
+                    if child.is_eoc() {
+                        buf.extend_from_slice(&child.raw);
+                        break;
+                    }
+                    self.copy_element(&child, buf, depth + 1)?;
+                }
+            },
+        }
+        Ok(())
+    }
+
+    /// Reads the next element and parses it with `f`.
+    fn read_small<T, F>(&mut self, header: &Header, f: F) -> io::Result<T>
+            where F: for<'a, 'b> FnOnce(BERReader<'a, 'b>) -> ASN1Result<T> {
+        let mut buf = Vec::new();
+        self.copy_element(header, &mut buf, 0)?;
+        parse_ber_general(&buf, self.mode, f)
+            .map_err(|_| invalid_data("malformed element"))
+    }
+
+    fn read_oid(&mut self) -> io::Result<ObjectIdentifier> {
+        let header = self.expect_header(TAG_OID, PCBit::Primitive)?;
+        self.read_small(&header, |reader| reader.read_oid())
+    }
+
+    /// Passes the contents of the OCTET STRING `header` to `callback`.
+    fn stream_octets<F>(&mut self, header: &Header, callback: &mut F,
+            depth: usize) -> io::Result<()>
+            where F: FnMut(&[u8]) -> io::Result<()> {
+        if depth > MAX_DEPTH {
+            return Err(invalid_data("nesting too deep"));
+        }
+        match header.pcbit {
+            PCBit::Primitive => {
+                let mut remaining = header.length.unwrap_or(0);
+                let mut chunk = [0; CHUNK_SIZE];
+                while remaining > 0 {
+                    let len = if remaining < CHUNK_SIZE as u64 {
+                        remaining as usize
+                    } else {
+                        CHUNK_SIZE
+                    };
+                    self.inner.read_exact(&mut chunk[..len])?;
+                    self.pos += len as u64;
+                    remaining -= len as u64;
+                    callback(&chunk[..len])?;
+                }
+            },
+            PCBit::Constructed => {
+                if self.mode == BERMode::Der {
+                    return Err(invalid_data("constructed OCTET STRING"));
+                }
+                while let Some(child) = self.next_in(header)? {
+                    if child.tag != TAG_OCTETSTRING {
+                        return Err(invalid_data("unexpected tag"));
+                    }
+                    self.stream_octets(&child, callback, depth + 1)?;
+                }
+                if header.end.is_some() {
+                    self.end(header)?;
+                }
+            },
+        }
+        Ok(())
+    }
+
+    fn read_signed_data<F>(&mut self, callback: &mut F)
+            -> io::Result<SignedData>
+            where F: FnMut(&[u8]) -> io::Result<()> {
+        let seq = self.expect_header(TAG_SEQUENCE, PCBit::Constructed)?;
+        let header = self.expect_header(TAG_INTEGER, PCBit::Primitive)?;
+        let version = self.read_small(&header, |reader| reader.read_u8())?;
+        let header = self.expect_header(TAG_SET, PCBit::Constructed)?;
+        let mut digest_algorithms = Vec::new();
+        self.copy_element(&header, &mut digest_algorithms, 0)?;
+
+        let encap = self.expect_header(TAG_SEQUENCE, PCBit::Constructed)?;
+        let econtent_type = self.read_oid()?;
+        let econtent_present = match self.next_in(&encap)? {
+            Some(explicit) => {
+                if explicit.tag != Tag::context(0) ||
+                        explicit.pcbit != PCBit::Constructed {
+                    return Err(invalid_data("unexpected tag"));
+                }
+                let header = self.read_header()?;
+                if header.tag != TAG_OCTETSTRING {
+                    return Err(invalid_data("unexpected tag"));
+                }
+                self.check_within(&explicit, &header)?;
+                self.stream_octets(&header, callback, 0)?;
+                self.end(&explicit)?;
+                self.end(&encap)?;
+                true
+            },
+            None => false,
+        };
+
+        let mut certificates = None;
+        let mut crls = None;
+        let mut signer_infos = None;
+        while let Some(header) = self.next_in(&seq)? {
+            let mut buf = Vec::new();
+            self.copy_element(&header, &mut buf, 0)?;
+            let slot = if signer_infos.is_some() {
+                return Err(invalid_data("extra data in SignedData"));
+            } else if header.tag == TAG_SET {
+                &mut signer_infos
+            } else if header.tag == Tag::context(0) &&
+                    certificates.is_none() && crls.is_none() {
+                &mut certificates
+            } else if header.tag == Tag::context(1) && crls.is_none() {
+                &mut crls
+            } else {
+                return Err(invalid_data("unexpected tag"));
+            };
+            *slot = Some(buf);
+        }
+        if seq.end.is_some() {
+            self.end(&seq)?;
+        }
+        let signer_infos = signer_infos
+            .ok_or_else(|| invalid_data("missing signerInfos"))?;
+        Ok(SignedData {
+            version,
+            digest_algorithms,
+            econtent_type,
+            econtent_present,
+            certificates,
+            crls,
+            signer_infos,
+        })
+    }
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec;
+    use super::*;
+    use super::super::construct_der;
+
+    fn signed_data(content: Option<&[u8]>) -> Vec<u8> {
+        construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_oid(
+                    &ObjectIdentifier::from_slice(ID_SIGNED_DATA));
+                writer.next().write_tagged(Tag::context(0), |writer| {
+                    writer.write_sequence(|writer| {
+                        writer.next().write_u8(1);
+                        writer.next().write_set(|writer| {
+                            writer.next().write_null();
+                        });
+                        writer.next().write_sequence(|writer| {
+                            writer.next().write_oid(
+                                &ObjectIdentifier::from_slice(ID_DATA));
+                            if let Some(content) = content {
+                                writer.next().write_tagged(
+                                    Tag::context(0),
+                                    |writer| writer.write_bytes(content));
+                            }
+                        });
+                        writer.next().write_tagged_implicit(
+                            Tag::context(0), |writer| {
+                                writer.write_sequence(|_| {})
+                            });
+                        writer.next().write_set(|writer| {
+                            writer.next().write_bool(true);
+                        });
+                    })
+                });
+            })
+        })
+    }
+
+    fn collect(data: &[u8], mode: BERMode)
+            -> io::Result<(ContentInfo, Vec<u8>)> {
+        let mut content = Vec::new();
+        let info = stream_content(data, mode, |chunk| {
+            content.extend_from_slice(chunk);
+            Ok(())
+        })?;
+        Ok((info, content))
+    }
+
+    #[test]
+    fn test_stream_signed_data() {
+        let long = vec![7; 20000];
+        for &content in &[&b"Hello!"[..], &long[..]] {
+            let data = signed_data(Some(content));
+            let (info, streamed) = collect(&data, BERMode::Der).unwrap();
+            assert_eq!(streamed, content);
+            let signed = info.signed_data.unwrap();
+            assert_eq!(signed.version, 1);
+            assert_eq!(signed.digest_algorithms, &[49, 2, 5, 0]);
+            assert!(signed.econtent_present);
+            assert_eq!(signed.certificates.unwrap(), &[160, 0]);
+            assert_eq!(signed.crls, None);
+            assert_eq!(signed.signer_infos, &[49, 3, 1, 1, 255]);
+        }
+
+        let data = signed_data(None);
+        let (info, streamed) = collect(&data, BERMode::Der).unwrap();
+        assert!(streamed.is_empty());
+        assert!(!info.signed_data.unwrap().econtent_present);
+
+        // Truncated input
+        let data = signed_data(Some(b"Hello!"));
+        for len in 0..data.len() {
+            collect(&data[..len], BERMode::Der).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn test_stream_indefinite() {
+        // SignedData with indefinite lengths and a segmented eContent
+        let data: &[u8] = &[
+            48, 128, 6, 9, 42, 134, 72, 134, 247, 13, 1, 7, 2, 160, 128,
+            48, 128, 2, 1, 1, 49, 0,
+            48, 128, 6, 9, 42, 134, 72, 134, 247, 13, 1, 7, 1,
+            160, 128, 36, 128, 4, 2, 72, 105, 36, 3, 4, 1, 33, 0, 0, 0, 0,
+            0, 0,
+            49, 128, 0, 0,
+            0, 0, 0, 0, 0, 0];
+        let (info, content) = collect(data, BERMode::Ber).unwrap();
+        assert_eq!(content, b"Hi!");
+        assert_eq!(info.signed_data.unwrap().signer_infos, &[49, 128, 0, 0]);
+        collect(data, BERMode::Der).unwrap_err();
+
+        // An unknown content type
+        let data: &[u8] = &[48, 128, 6, 1, 0, 160, 128, 5, 0, 0, 0, 0, 0];
+        collect(data, BERMode::Ber).unwrap_err();
+    }
+}
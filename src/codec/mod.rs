@@ -0,0 +1,139 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![forbid(missing_docs)]
+
+use std::io;
+
+use bytes::BytesMut;
+use tokio_util::codec::{Decoder, Encoder};
+
+use super::{tlv_length, BERMode, TlvLength};
+use super::{encode_der, DEREncodable};
+
+/// A codec framing a byte stream into BER/DER-encoded messages.
+///
+/// Each frame is exactly one TLV: message boundaries are determined from
+/// the length of the outermost element or, for the indefinite length
+/// form, from its end-of-contents octets. Frames are returned
+/// undecoded, to be parsed with [`parse_ber`](crate::parse_ber) and
+/// friends. Any [`DEREncodable`] value can be sent.
+///
+/// Messages longer than [`max_length`](BerCodec::max_length) are
+/// rejected with an `io::Error` of kind `InvalidData`, as are malformed
+/// headers.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::BytesMut;
+/// use tokio_util::codec::{Decoder, Encoder};
+/// use yasna::{BERMode, BerCodec};
+/// let mut codec = BerCodec::new(BERMode::Der);
+/// let mut buf = BytesMut::new();
+/// codec.encode((10, true), &mut buf).unwrap();
+/// codec.encode(3, &mut buf).unwrap();
+/// buf.truncate(buf.len() - 1);
+/// let frame = codec.decode(&mut buf).unwrap().unwrap();
+/// assert_eq!(&frame[..], &[48, 6, 2, 1, 10, 1, 1, 255]);
+/// assert!(codec.decode(&mut buf).unwrap().is_none());
+/// ```
+///
+/// # Features
+///
+/// This type is enabled by `codec` feature.
+///
+/// ```toml
+/// [dependencies]
+/// yasna = { version = "*", features = ["codec"] }
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct BerCodec {
+    mode: BERMode,
+    max_length: usize,
+}
+
+const DEFAULT_MAX_LENGTH: usize = 8 * 1024 * 1024;
+
+impl BerCodec {
+    /// Creates a codec accepting messages of up to 8 MiB.
+    ///
+    /// In [`BERMode::Der`], the indefinite length form is rejected.
+    pub fn new(mode: BERMode) -> Self {
+        BerCodec::with_max_length(mode, DEFAULT_MAX_LENGTH)
+    }
+
+    /// Creates a codec accepting messages of up to `max_length` bytes,
+    /// including the outermost identifier and length octets.
+    pub fn with_max_length(mode: BERMode, max_length: usize) -> Self {
+        BerCodec {
+            mode,
+            max_length,
+        }
+    }
+
+    /// Returns the maximum message length.
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+
+    /// Returns the mode in which message headers are checked.
+    pub fn mode(&self) -> BERMode {
+        self.mode
+    }
+}
+
+impl Decoder for BerCodec {
+    type Item = BytesMut;
+    type Error = io::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> io::Result<Option<BytesMut>> {
+        match tlv_length(src, self.mode)? {
+            TlvLength::Complete(length) if length <= self.max_length => {
+                Ok(Some(src.split_to(length)))
+            },
+            TlvLength::Incomplete { needs_at_least }
+                    if needs_at_least <= self.max_length => {
+                src.reserve(needs_at_least - src.len());
+                Ok(None)
+            },
+            _ => Err(io::Error::new(io::ErrorKind::InvalidData,
+                "message exceeds maximum length")),
+        }
+    }
+}
+
+impl<T: DEREncodable> Encoder<T> for BerCodec {
+    type Error = io::Error;
+
+    fn encode(&mut self, item: T, dst: &mut BytesMut) -> io::Result<()> {
+        dst.extend_from_slice(&encode_der(&item));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ber_codec() {
+        let mut codec = BerCodec::with_max_length(BERMode::Ber, 8);
+        let mut buf = BytesMut::new();
+        buf.extend_from_slice(b"\x30\x80\x02\x01\x0a\x00");
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+        buf.extend_from_slice(b"\x00\x05\x00\x04");
+        let frame = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(&frame[..], b"\x30\x80\x02\x01\x0a\x00\x00");
+        let frame = codec.decode(&mut buf).unwrap().unwrap();
+        assert_eq!(&frame[..], b"\x05\x00");
+        assert!(codec.decode(&mut buf).unwrap().is_none());
+        buf.extend_from_slice(b"\x07\x00\x00\x00\x00\x00\x00\x00");
+        codec.decode(&mut buf).unwrap_err();
+    }
+}
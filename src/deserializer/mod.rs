@@ -15,9 +15,15 @@ use alloc::string::String;
 use num_bigint::{BigInt,BigUint};
 #[cfg(feature = "bit-vec")]
 use bit_vec::BitVec;
+#[cfg(feature = "ibig")]
+use ibig::{IBig,UBig};
+#[cfg(feature = "crypto-bigint")]
+use crypto_bigint::Uint;
 
 use super::{ASN1Result,BERMode,BERReader,parse_ber_general};
-use super::models::{ObjectIdentifier,TaggedDerValue};
+use super::{ParseOptions,parse_with_options};
+use super::models::{ObjectIdentifier,OidIri,RelativeOidIri,TaggedDerValue};
+use super::models::{Date,TimeOfDay,DateTime,Duration,External};
 #[cfg(feature = "time")]
 use super::models::{UTCTime,GeneralizedTime};
 
@@ -45,13 +51,18 @@ use super::models::{UTCTime,GeneralizedTime};
 /// - The decoder for `Vec<T>` is implemented as SEQUENCE OF decoder.
 /// - `()` as NULL decoder.
 /// - Tuples (except `()`) as SEQUENCE decoder.
-/// - `Vec<u8>` as OCTETSTRING decoder.
+/// - `Vec<u8>` as OCTETSTRING decoder. Newtypes over `Vec<u8>` or `[u8; N]`
+///   can opt in via [`octet_string_newtype!`](crate::octet_string_newtype).
 /// - `BitVec` as BITSTRING decoder.
 /// - `String` as UTF8String decoder.
-/// - `i64`, `u64`, `i32`, `u32`, `i16`, `u16`, `BigInt`, `BigUint`
+/// - `i64`, `u64`, `i32`, `u32`, `i16`, `u16`, `BigInt`, `BigUint`,
+///   `IBig`, `UBig`, `crypto_bigint::Uint`
 ///   as INTEGER decoder. (`u8` is avoided because of confliction.)
 /// - `bool` as BOOLEAN decoder.
 /// - `ObjectIdentifier` as OBJECTT IDENTIFIER decoder.
+/// - `OidIri`/`RelativeOidIri` as OID-IRI/RELATIVE-OID-IRI decoder.
+/// - `Date`/`TimeOfDay`/`DateTime`/`Duration` as
+///   DATE/TIME-OF-DAY/DATE-TIME/DURATION decoder.
 /// - `UTCTime`/`GeneralizedTime` as UTCTime/GeneralizedTime decoder.
 pub trait BERDecodable: Sized {
     /// Reads an ASN.1 value from `BERReader` and converts it to `Self`.
@@ -98,6 +109,23 @@ pub fn decode_ber_general<T:BERDecodable>(src: &[u8], mode: BERMode)
     })
 }
 
+/// Decodes BER/DER-encoded data with the given [`ParseOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use yasna::ParseOptions;
+/// let asn : i64 = yasna::decode_with_options(
+///     &[2, 2, 1, 0], ParseOptions::rfc5280()).unwrap();
+/// assert_eq!(asn, 256);
+/// ```
+pub fn decode_with_options<T:BERDecodable>(src: &[u8], options: ParseOptions)
+        -> ASN1Result<T> {
+    parse_with_options(src, options, |reader| {
+        T::decode_ber(reader)
+    })
+}
+
 /// Reads an ASN.1 value from `&[u8]`.
 ///
 /// If you want to accept only DER-encoded data,
@@ -202,6 +230,27 @@ impl BERDecodable for BigUint {
     }
 }
 
+#[cfg(feature = "ibig")]
+impl BERDecodable for IBig {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        super::bigint::read(reader)
+    }
+}
+
+#[cfg(feature = "ibig")]
+impl BERDecodable for UBig {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        super::bigint::read(reader)
+    }
+}
+
+#[cfg(feature = "crypto-bigint")]
+impl<const LIMBS: usize> BERDecodable for Uint<LIMBS> {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        super::bigint::read(reader)
+    }
+}
+
 impl BERDecodable for bool {
     fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
         reader.read_bool()
@@ -233,6 +282,48 @@ impl BERDecodable for ObjectIdentifier {
     }
 }
 
+impl BERDecodable for OidIri {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        reader.read_oid_iri()
+    }
+}
+
+impl BERDecodable for RelativeOidIri {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        reader.read_relative_oid_iri()
+    }
+}
+
+impl BERDecodable for Date {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        reader.read_date()
+    }
+}
+
+impl BERDecodable for TimeOfDay {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        reader.read_time_of_day()
+    }
+}
+
+impl BERDecodable for DateTime {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        reader.read_date_time()
+    }
+}
+
+impl BERDecodable for Duration {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        reader.read_duration()
+    }
+}
+
+impl BERDecodable for External {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        reader.read_external()
+    }
+}
+
 #[cfg(feature = "time")]
 impl BERDecodable for UTCTime {
     fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
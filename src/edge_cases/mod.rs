@@ -0,0 +1,209 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Tricky encodings for differential testing.
+//!
+//! [`edge_cases`] returns a corpus of encodings that exercise the
+//! corners of X.690: tag and length boundaries, nested indefinite
+//! lengths, and non-canonical or invalid variants. Each one is flagged
+//! with whether BER and DER accept it, so that the behavior of an
+//! application, or of another ASN.1 implementation, can be compared
+//! against yasna's.
+//!
+//! # Examples
+//!
+//! ```
+//! use yasna::edge_cases::edge_cases;
+//! for case in edge_cases() {
+//!     let result = yasna::parse_der(&case.encoding, |reader| {
+//!         reader.read_tagged_der()
+//!     });
+//!     if case.der_valid {
+//!         assert!(result.is_ok(), "{}", case.name);
+//!     }
+//! }
+//! ```
+//!
+//! # Features
+//!
+//! This module is enabled by `edge-cases` feature.
+//!
+//! ```toml
+//! [dependencies]
+//! yasna = { version = "*", features = ["edge-cases"] }
+//! ```
+
+#![forbid(missing_docs)]
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// An encoding produced by [`edge_cases`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct EdgeCase {
+    /// A short description, unique within the corpus
+    pub name: &'static str,
+    /// The encoding of a single value
+    pub encoding: Vec<u8>,
+    /// Whether the encoding is valid BER
+    pub ber_valid: bool,
+    /// Whether the encoding is valid DER
+    pub der_valid: bool,
+}
+
+/// Returns the corpus of edge cases.
+///
+/// The values are BOOLEANs, INTEGERs, OCTET STRINGs, SEQUENCEs and
+/// context-specific primitive values, so that validity only depends on
+/// the rules of X.690 for those types. The corpus may grow in future
+/// versions.
+pub fn edge_cases() -> Vec<EdgeCase> {
+    let mut cases = Vec::new();
+    let mut add = |name, encoding, ber_valid, der_valid| {
+        cases.push(EdgeCase {
+            name,
+            encoding,
+            ber_valid,
+            der_valid,
+        });
+    };
+
+    // Tags
+    add("tag_30_low_form", vec![0x9e, 0], true, true);
+    add("tag_31_high_form", vec![0x9f, 31, 0], true, true);
+    add("tag_30_high_form", vec![0x9f, 30, 0], false, false);
+    add("tag_127_one_octet", vec![0x9f, 0x7f, 0], true, true);
+    add("tag_128_two_octets", vec![0x9f, 0x81, 0, 0], true, true);
+    add("tag_leading_zero_octet", vec![0x9f, 0x80, 31, 0], false, false);
+    add("tag_u64_max", {
+        let mut v = vec![0x9f, 0x81];
+        v.extend_from_slice(&[0xff; 8]);
+        v.extend_from_slice(&[0x7f, 0]);
+        v
+    }, true, true);
+    add("tag_truncated", vec![0x9f, 0x81], false, false);
+
+    // Lengths
+    for &len in &[0usize, 1, 127, 128, 255, 256, 65535, 65536] {
+        let name = match len {
+            0 => "length_0",
+            1 => "length_1",
+            127 => "length_127_short_form",
+            128 => "length_128_long_form",
+            255 => "length_255",
+            256 => "length_256",
+            65535 => "length_65535",
+            _ => "length_65536",
+        };
+        add(name, octet_string(&length_octets(len), len), true, true);
+    }
+    add("length_0_long_form", octet_string(&[0x81, 0], 0), true, false);
+    add("length_127_long_form", octet_string(&[0x81, 127], 127),
+        true, false);
+    add("length_128_leading_zero", octet_string(&[0x82, 0, 128], 128),
+        true, false);
+    add("length_reserved_0xff", vec![4, 0xff], false, false);
+    add("length_exceeds_data", vec![4, 2, 0], false, false);
+    add("length_truncated", vec![4, 0x82, 1], false, false);
+
+    // Indefinite lengths
+    add("indefinite_empty_sequence", vec![0x30, 0x80, 0, 0], true, false);
+    add("indefinite_nested_sequences",
+        vec![0x30, 0x80, 0x30, 0x80, 0x30, 0x80, 0, 0, 0, 0, 0, 0],
+        true, false);
+    add("indefinite_inside_definite",
+        vec![0x30, 7, 0x30, 0x80, 1, 1, 0xff, 0, 0], true, false);
+    add("definite_inside_indefinite",
+        vec![0x30, 0x80, 0x30, 3, 1, 1, 0xff, 0, 0], true, false);
+    add("indefinite_primitive", vec![4, 0x80, 0, 0], false, false);
+    add("indefinite_missing_eoc", vec![0x30, 0x80, 5, 0], false, false);
+    add("eoc_at_top_level", vec![0, 0], false, false);
+
+    // Constructed strings
+    add("constructed_octet_string",
+        vec![0x24, 6, 4, 1, 0xaa, 4, 1, 0xbb], true, false);
+    add("constructed_octet_string_indefinite",
+        vec![0x24, 0x80, 4, 1, 0xaa, 0x24, 0x80, 4, 1, 0xbb, 0, 0, 0, 0],
+        true, false);
+    add("constructed_octet_string_bad_segment",
+        vec![0x24, 3, 2, 1, 0], false, false);
+
+    // BOOLEAN
+    add("boolean_true_0xff", vec![1, 1, 0xff], true, true);
+    add("boolean_true_0x01", vec![1, 1, 1], true, false);
+    add("boolean_empty", vec![1, 0], false, false);
+    add("boolean_two_octets", vec![1, 2, 0, 0], false, false);
+    add("boolean_constructed", vec![0x21, 3, 1, 1, 0xff], false, false);
+
+    // INTEGER
+    add("integer_127", vec![2, 1, 0x7f], true, true);
+    add("integer_128", vec![2, 2, 0, 0x80], true, true);
+    add("integer_minus_128", vec![2, 1, 0x80], true, true);
+    add("integer_minus_129", vec![2, 2, 0xff, 0x7f], true, true);
+    add("integer_redundant_zero", vec![2, 2, 0, 0x7f], false, false);
+    add("integer_redundant_ff", vec![2, 2, 0xff, 0x80], false, false);
+    add("integer_empty", vec![2, 0], false, false);
+
+    cases
+}
+
+/// Returns the identifier and length octets given as `length`, followed
+/// by `len` contents octets of an OCTET STRING.
+fn octet_string(length: &[u8], len: usize) -> Vec<u8> {
+    let mut v = vec![4];
+    v.extend_from_slice(length);
+    v.resize(v.len() + len, 0x5a);
+    v
+}
+
+/// Returns the shortest length octets for `len`.
+fn length_octets(len: usize) -> Vec<u8> {
+    if len < 128 {
+        return vec![len as u8];
+    }
+    let bytes = (len as u64).to_be_bytes();
+    let skip = bytes.iter().take_while(|&&b| b == 0).count();
+    let mut v = vec![0x80 | (8 - skip) as u8];
+    v.extend_from_slice(&bytes[skip..]);
+    v
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::{parse_ber_general, ASN1Result, BERMode, BERReader};
+    use super::super::tags::{TAG_BOOLEAN, TAG_INTEGER, TAG_OCTETSTRING};
+    use super::super::tags::TAG_SEQUENCE;
+
+    fn read_any(reader: BERReader) -> ASN1Result<()> {
+        match reader.lookahead_tag()? {
+            TAG_BOOLEAN => reader.read_bool().map(|_| ()),
+            TAG_INTEGER => reader.read_bigint_bytes().map(|_| ()),
+            TAG_OCTETSTRING => reader.read_bytes().map(|_| ()),
+            TAG_SEQUENCE => reader.read_sequence(|reader| {
+                while reader.read_optional(read_any)?.is_some() {}
+                Ok(())
+            }),
+            _ => reader.read_tagged_der().map(|_| ()),
+        }
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        let cases = edge_cases();
+        for (i, case) in cases.iter().enumerate() {
+            assert!(cases[..i].iter().all(|c| c.name != case.name));
+            assert!(case.ber_valid || !case.der_valid);
+            for &(mode, valid) in &[(BERMode::Ber, case.ber_valid),
+                    (BERMode::Der, case.der_valid)] {
+                let result = parse_ber_general(&case.encoding, mode, read_any);
+                assert_eq!(result.is_ok(), valid, "{} {:?}", case.name, mode);
+            }
+        }
+    }
+}
@@ -0,0 +1,515 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! C bindings.
+//!
+//! This module exports a small C API for parsing untrusted BER/DER input
+//! into a value tree, dumping it, validating DER, and encoding value trees
+//! back to DER. The matching declarations are in `include/yasna.h`.
+//!
+//! A shared library can be built with:
+//!
+//! ```text
+//! cargo rustc --release --features ffi --crate-type cdylib
+//! ```
+//!
+//! All functions returning an `int` status return [`YASNA_OK`] on success
+//! and one of the `YASNA_ERR_*` codes otherwise. Memory handed out by this
+//! module must be released with the matching `yasna_*_free` function.
+//!
+//! # Features
+//!
+//! This module is enabled by `ffi` feature.
+//!
+//! ```toml
+//! [dependencies]
+//! yasna = { version = "*", features = ["ffi"] }
+//! ```
+
+#![allow(unsafe_code)]
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::ptr;
+use core::slice;
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+
+use super::{PCBit, Tag, TAG_CLASSES};
+use super::{ASN1Error, ASN1ErrorKind, ASN1Result, BERMode};
+use super::models::TaggedDerValue;
+use super::{construct_der, dump_ber, parse_ber_general};
+
+/// The operation succeeded.
+pub const YASNA_OK: c_int = 0;
+/// The input ended unexpectedly.
+pub const YASNA_ERR_EOF: c_int = 1;
+/// There are extra bytes after the value.
+pub const YASNA_ERR_EXTRA: c_int = 2;
+/// A length or tag number does not fit the implementation limits.
+pub const YASNA_ERR_INTEGER_OVERFLOW: c_int = 3;
+/// The value is nested too deeply.
+pub const YASNA_ERR_STACK_OVERFLOW: c_int = 4;
+/// The input is not valid BER/DER.
+pub const YASNA_ERR_INVALID: c_int = 5;
+/// A required pointer argument was null, or an argument was out of range.
+pub const YASNA_ERR_ARGUMENT: c_int = 6;
+/// The input exceeds a resource limit set for the parse.
+pub const YASNA_ERR_LIMIT: c_int = 7;
+
+/// Selects BER parsing in [`yasna_parse`] and [`yasna_dump`].
+pub const YASNA_MODE_BER: c_int = 0;
+/// Selects DER parsing in [`yasna_parse`] and [`yasna_dump`].
+pub const YASNA_MODE_DER: c_int = 1;
+
+const MAX_DEPTH: usize = 100;
+
+/// A node of a parsed or constructed value tree.
+///
+/// This type is opaque to C, where it is known as `yasna_value`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct YasnaValue {
+    tag: Tag,
+    pcbit: PCBit,
+    content: Vec<u8>,
+    children: Vec<YasnaValue>,
+}
+
+fn error_code(e: ASN1Error) -> c_int {
+    match e.kind() {
+        ASN1ErrorKind::Eof => YASNA_ERR_EOF,
+        ASN1ErrorKind::Extra => YASNA_ERR_EXTRA,
+        ASN1ErrorKind::IntegerOverflow => YASNA_ERR_INTEGER_OVERFLOW,
+        ASN1ErrorKind::StackOverflow => YASNA_ERR_STACK_OVERFLOW,
+        ASN1ErrorKind::Invalid => YASNA_ERR_INVALID,
+        ASN1ErrorKind::LimitExceeded => YASNA_ERR_LIMIT,
+    }
+}
+
+fn mode_from_c(mode: c_int) -> Option<BERMode> {
+    match mode {
+        YASNA_MODE_BER => Some(BERMode::Ber),
+        YASNA_MODE_DER => Some(BERMode::Der),
+        _ => None,
+    }
+}
+
+unsafe fn input<'a>(data: *const u8, len: usize) -> Option<&'a [u8]> {
+    if data.is_null() {
+        if len == 0 { Some(&[]) } else { None }
+    } else {
+        Some(slice::from_raw_parts(data, len))
+    }
+}
+
+fn parse_tree(buf: &[u8], mode: BERMode, depth: usize)
+        -> ASN1Result<YasnaValue> {
+    if depth > MAX_DEPTH {
+        return Err(ASN1Error::new(ASN1ErrorKind::StackOverflow));
+    }
+    let (tagged, raw) = parse_ber_general(buf, mode, |reader| {
+        reader.read_with_buffer(|reader| reader.read_tagged_der())
+    })?;
+    let mut value = YasnaValue {
+        tag: tagged.tag(),
+        pcbit: tagged.pcbit(),
+        content: Vec::new(),
+        children: Vec::new(),
+    };
+    match tagged.pcbit() {
+        PCBit::Primitive => value.content = tagged.value().to_vec(),
+        PCBit::Constructed => {
+            let mut children = Vec::new();
+            parse_ber_general(raw, mode, |reader| {
+                reader.read_tagged_implicit(tagged.tag(), |reader| {
+                    reader.read_sequence_of(|reader| {
+                        let (_, raw) = reader.read_with_buffer(|reader| {
+                            reader.read_tagged_der()
+                        })?;
+                        children.push(parse_tree(raw, mode, depth + 1)?);
+                        Ok(())
+                    })
+                })
+            })?;
+            value.children = children;
+        },
+    }
+    Ok(value)
+}
+
+fn encode_tree(value: &YasnaValue) -> Vec<u8> {
+    let content = match value.pcbit {
+        PCBit::Primitive => value.content.clone(),
+        PCBit::Constructed => value.children.iter()
+            .flat_map(encode_tree).collect(),
+    };
+    let tagged = TaggedDerValue::from_tag_pc_and_bytes(
+        value.tag, value.pcbit, content);
+    construct_der(|writer| writer.write_tagged_der(&tagged))
+}
+
+/// Checks that `data` is a single element obeying the DER rules for
+/// identifiers and lengths.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes, or be null if `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn yasna_validate_der(data: *const u8, len: usize)
+        -> c_int {
+    let buf = match input(data, len) {
+        Some(buf) => buf,
+        None => return YASNA_ERR_ARGUMENT,
+    };
+    match parse_tree(buf, BERMode::Der, 0) {
+        Ok(_) => YASNA_OK,
+        Err(e) => error_code(e),
+    }
+}
+
+/// Parses a single element into a value tree.
+///
+/// On success, `*out` receives a tree to be released with
+/// [`yasna_value_free`].
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes, or be null if `len` is 0.
+/// `out` must be a valid pointer to write to.
+#[no_mangle]
+pub unsafe extern "C" fn yasna_parse(data: *const u8, len: usize,
+        mode: c_int, out: *mut *mut YasnaValue) -> c_int {
+    let (buf, mode) = match (input(data, len), mode_from_c(mode)) {
+        (Some(buf), Some(mode)) if !out.is_null() => (buf, mode),
+        _ => return YASNA_ERR_ARGUMENT,
+    };
+    match parse_tree(buf, mode, 0) {
+        Ok(value) => {
+            *out = Box::into_raw(Box::new(value));
+            YASNA_OK
+        },
+        Err(e) => error_code(e),
+    }
+}
+
+/// Releases a value tree. Null is ignored.
+///
+/// # Safety
+///
+/// `value` must be null or a root obtained from this module that has not
+/// been freed or attached to a parent.
+#[no_mangle]
+pub unsafe extern "C" fn yasna_value_free(value: *mut YasnaValue) {
+    if !value.is_null() {
+        drop(Box::from_raw(value));
+    }
+}
+
+/// Returns the tag class (0 to 3, in the order of the identifier bits),
+/// or -1 if `value` is null.
+///
+/// # Safety
+///
+/// `value` must be null or a valid value.
+#[no_mangle]
+pub unsafe extern "C" fn yasna_value_tag_class(value: *const YasnaValue)
+        -> c_int {
+    match value.as_ref() {
+        Some(value) => value.tag.tag_class as c_int,
+        None => -1,
+    }
+}
+
+/// Returns the tag number, or 0 if `value` is null.
+///
+/// # Safety
+///
+/// `value` must be null or a valid value.
+#[no_mangle]
+pub unsafe extern "C" fn yasna_value_tag_number(value: *const YasnaValue)
+        -> u64 {
+    value.as_ref().map_or(0, |value| value.tag.tag_number)
+}
+
+/// Returns 1 for constructed values and 0 otherwise.
+///
+/// # Safety
+///
+/// `value` must be null or a valid value.
+#[no_mangle]
+pub unsafe extern "C" fn yasna_value_is_constructed(value: *const YasnaValue)
+        -> c_int {
+    match value.as_ref() {
+        Some(value) => (value.pcbit == PCBit::Constructed) as c_int,
+        None => 0,
+    }
+}
+
+/// Returns the contents of a primitive value and stores its length
+/// in `*len`. Constructed values have no contents of their own.
+///
+/// The returned pointer is valid as long as the tree is.
+///
+/// # Safety
+///
+/// `value` must be null or a valid value, and `len` must be a valid
+/// pointer to write to.
+#[no_mangle]
+pub unsafe extern "C" fn yasna_value_content(value: *const YasnaValue,
+        len: *mut usize) -> *const u8 {
+    if len.is_null() {
+        return ptr::null();
+    }
+    match value.as_ref() {
+        Some(value) => {
+            *len = value.content.len();
+            value.content.as_ptr()
+        },
+        None => {
+            *len = 0;
+            ptr::null()
+        },
+    }
+}
+
+/// Returns the number of children of a constructed value.
+///
+/// # Safety
+///
+/// `value` must be null or a valid value.
+#[no_mangle]
+pub unsafe extern "C" fn yasna_value_child_count(value: *const YasnaValue)
+        -> usize {
+    value.as_ref().map_or(0, |value| value.children.len())
+}
+
+/// Returns the `index`-th child, or null if there is no such child.
+///
+/// The returned pointer is borrowed from the tree and must not be freed.
+///
+/// # Safety
+///
+/// `value` must be null or a valid value.
+#[no_mangle]
+pub unsafe extern "C" fn yasna_value_child(value: *const YasnaValue,
+        index: usize) -> *const YasnaValue {
+    match value.as_ref().and_then(|value| value.children.get(index)) {
+        Some(child) => child,
+        None => ptr::null(),
+    }
+}
+
+/// Creates a primitive value with a copy of `data` as its contents.
+///
+/// Returns null if an argument is invalid.
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes, or be null if `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn yasna_value_new_primitive(tag_class: c_int,
+        tag_number: u64, data: *const u8, len: usize) -> *mut YasnaValue {
+    let content = match input(data, len) {
+        Some(content) => content.to_vec(),
+        None => return ptr::null_mut(),
+    };
+    new_value(tag_class, tag_number, PCBit::Primitive, content)
+}
+
+/// Creates a constructed value without children.
+///
+/// Returns null if an argument is invalid.
+#[no_mangle]
+pub extern "C" fn yasna_value_new_constructed(tag_class: c_int,
+        tag_number: u64) -> *mut YasnaValue {
+    new_value(tag_class, tag_number, PCBit::Constructed, Vec::new())
+}
+
+fn new_value(tag_class: c_int, tag_number: u64, pcbit: PCBit,
+        content: Vec<u8>) -> *mut YasnaValue {
+    let tag_class = match TAG_CLASSES.get(tag_class as usize) {
+        Some(&tag_class) => tag_class,
+        _ => return ptr::null_mut(),
+    };
+    Box::into_raw(Box::new(YasnaValue {
+        tag: Tag { tag_class, tag_number },
+        pcbit,
+        content,
+        children: Vec::new(),
+    }))
+}
+
+/// Appends `child` to the constructed value `parent`, taking ownership
+/// of `child`.
+///
+/// On failure, ownership of `child` stays with the caller.
+///
+/// # Safety
+///
+/// `parent` must be a valid value and `child` a root obtained from this
+/// module; they must not be the same tree.
+#[no_mangle]
+pub unsafe extern "C" fn yasna_value_push_child(parent: *mut YasnaValue,
+        child: *mut YasnaValue) -> c_int {
+    if child.is_null() {
+        return YASNA_ERR_ARGUMENT;
+    }
+    match parent.as_mut() {
+        Some(parent) if parent.pcbit == PCBit::Constructed => {
+            parent.children.push(*Box::from_raw(child));
+            YASNA_OK
+        },
+        _ => YASNA_ERR_ARGUMENT,
+    }
+}
+
+/// Encodes a value tree in DER.
+///
+/// On success, `*out` and `*out_len` receive a buffer to be released
+/// with [`yasna_bytes_free`].
+///
+/// # Safety
+///
+/// `value` must be a valid value, and `out` and `out_len` valid pointers
+/// to write to.
+#[no_mangle]
+pub unsafe extern "C" fn yasna_encode_der(value: *const YasnaValue,
+        out: *mut *mut u8, out_len: *mut usize) -> c_int {
+    let value = match value.as_ref() {
+        Some(value) if !out.is_null() && !out_len.is_null() => value,
+        _ => return YASNA_ERR_ARGUMENT,
+    };
+    let der = encode_tree(value).into_boxed_slice();
+    *out_len = der.len();
+    *out = Box::into_raw(der) as *mut u8;
+    YASNA_OK
+}
+
+/// Releases a buffer returned by [`yasna_encode_der`]. Null is ignored.
+///
+/// # Safety
+///
+/// `data` and `len` must be exactly as returned by [`yasna_encode_der`].
+#[no_mangle]
+pub unsafe extern "C" fn yasna_bytes_free(data: *mut u8, len: usize) {
+    if !data.is_null() {
+        drop(Box::from_raw(ptr::slice_from_raw_parts_mut(data, len)));
+    }
+}
+
+/// Parses a single element and renders it as text, in the format of
+/// [`dump_ber`].
+///
+/// On success, `*out` receives a NUL-terminated string to be released
+/// with [`yasna_string_free`].
+///
+/// # Safety
+///
+/// `data` must point to `len` readable bytes, or be null if `len` is 0.
+/// `out` must be a valid pointer to write to.
+#[no_mangle]
+pub unsafe extern "C" fn yasna_dump(data: *const u8, len: usize,
+        mode: c_int, out: *mut *mut c_char) -> c_int {
+    let (buf, mode) = match (input(data, len), mode_from_c(mode)) {
+        (Some(buf), Some(mode)) if !out.is_null() => (buf, mode),
+        _ => return YASNA_ERR_ARGUMENT,
+    };
+    let text = match parse_tree(buf, mode, 0)
+            .and_then(|_| dump_ber(buf, mode)) {
+        Ok(text) => text,
+        Err(e) => return error_code(e),
+    };
+    // Decoded strings may contain NUL.
+    let text = text.replace('\0', "\\0");
+    *out = CString::new(text).unwrap().into_raw();
+    YASNA_OK
+}
+
+/// Releases a string returned by [`yasna_dump`]. Null is ignored.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by [`yasna_dump`].
+#[no_mangle]
+pub unsafe extern "C" fn yasna_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CStr;
+    use super::*;
+
+    #[test]
+    fn test_ffi_parse_and_encode() {
+        let data = b"\x30\x80\x02\x01\x0a\xa0\x03\x01\x01\xff\x00\x00";
+        unsafe {
+            let mut value = ptr::null_mut();
+            assert_eq!(yasna_parse(data.as_ptr(), data.len(),
+                YASNA_MODE_DER, &mut value), YASNA_ERR_INVALID);
+            assert_eq!(yasna_parse(data.as_ptr(), data.len(),
+                YASNA_MODE_BER, &mut value), YASNA_OK);
+            assert_eq!(yasna_value_tag_class(value), 0);
+            assert_eq!(yasna_value_tag_number(value), 16);
+            assert_eq!(yasna_value_child_count(value), 2);
+            let child = yasna_value_child(value, 0);
+            let mut len = 0;
+            let content = yasna_value_content(child, &mut len);
+            assert_eq!(slice::from_raw_parts(content, len), &[10]);
+            assert!(yasna_value_child(value, 2).is_null());
+            assert_eq!(yasna_value_is_constructed(
+                yasna_value_child(value, 1)), 1);
+
+            let mut der = ptr::null_mut();
+            assert_eq!(yasna_encode_der(value, &mut der, &mut len), YASNA_OK);
+            let expected = b"\x30\x08\x02\x01\x0a\xa0\x03\x01\x01\xff";
+            assert_eq!(slice::from_raw_parts(der, len), expected);
+            assert_eq!(yasna_validate_der(der, len), YASNA_OK);
+            yasna_bytes_free(der, len);
+            yasna_value_free(value);
+        }
+    }
+
+    #[test]
+    fn test_ffi_construct() {
+        unsafe {
+            let seq = yasna_value_new_constructed(0, 16);
+            let int = yasna_value_new_primitive(0, 2, [10].as_ptr(), 1);
+            assert!(yasna_value_new_constructed(4, 16).is_null());
+            assert_eq!(yasna_value_push_child(int, seq), YASNA_ERR_ARGUMENT);
+            assert_eq!(yasna_value_push_child(seq, int), YASNA_OK);
+            let mut der = ptr::null_mut();
+            let mut len = 0;
+            assert_eq!(yasna_encode_der(seq, &mut der, &mut len), YASNA_OK);
+            assert_eq!(slice::from_raw_parts(der, len), b"\x30\x03\x02\x01\x0a");
+            yasna_bytes_free(der, len);
+            yasna_value_free(seq);
+        }
+    }
+
+    #[test]
+    fn test_ffi_dump() {
+        let data = b"\x30\x05\xa1\x03\x02\x01\x0a";
+        unsafe {
+            let mut text = ptr::null_mut();
+            assert_eq!(yasna_dump(data.as_ptr(), data.len(),
+                YASNA_MODE_DER, &mut text), YASNA_OK);
+            assert_eq!(CStr::from_ptr(text).to_str().unwrap(), concat!(
+                "    0:d=0  hl=2 l=   5 cons: SEQUENCE\n",
+                "    2:d=1  hl=2 l=   3 cons: cont [ 1 ]\n",
+                "    4:d=2  hl=2 l=   1 prim: INTEGER           :0A\n",
+            ));
+            yasna_string_free(text);
+            assert_eq!(yasna_dump(data.as_ptr(), 3, YASNA_MODE_DER,
+                &mut text), YASNA_ERR_EOF);
+            assert_eq!(yasna_validate_der(ptr::null(), 1), YASNA_ERR_ARGUMENT);
+        }
+    }
+}
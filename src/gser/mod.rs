@@ -0,0 +1,296 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Rendering in the Generic String Encoding Rules (GSER, RFC 3641).
+//!
+//! GSER is the human-readable form of ASN.1 values used by LDAP, e.g.
+//! for attribute values whose syntax has no string form of its own.
+//! [`to_gser`] renders BER/DER-encoded data without knowing its schema:
+//!
+//! - BOOLEAN, INTEGER, NULL, OCTET STRING, BIT STRING and OBJECT
+//!   IDENTIFIER values are rendered as in RFC 3641.
+//! - Character strings that yasna can decode, UTCTime and
+//!   GeneralizedTime are rendered as quoted strings.
+//! - ENUMERATED values are rendered as their number, as the names of
+//!   the enumeration are unknown.
+//! - SEQUENCE and SET values, and other constructed values, are
+//!   rendered as `{ a, b }`, i.e. as SEQUENCE OF and SET OF are, as
+//!   the names of their components are unknown.
+//! - Any other value is rendered as its contents octets, as an OCTET
+//!   STRING would be.
+//!
+//! # Examples
+//!
+//! ```
+//! use yasna::BERMode;
+//! // SEQUENCE { INTEGER -1, UTF8String "say \"hi\"", BIT STRING '101'B }
+//! let data = &[48, 17, 2, 1, 255, 12, 8, 115, 97, 121, 32, 34, 104, 105,
+//!     34, 3, 2, 5, 160];
+//! assert_eq!(yasna::gser::to_gser(data, BERMode::Der).unwrap(),
+//!     r#"{ -1, "say ""hi""", '101'B }"#);
+//! ```
+
+#![forbid(missing_docs)]
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use super::{parse_ber_general, ASN1Error, ASN1ErrorKind, ASN1Result};
+use super::{BERMode, BERReader, PCBit, TagClass};
+use super::tags::{TAG_EOC, TAG_BOOLEAN, TAG_INTEGER, TAG_BITSTRING};
+use super::tags::TAG_OCTETSTRING;
+use super::tags::{TAG_NULL, TAG_OID, TAG_ENUM, TAG_UTF8STRING, TAG_SEQUENCE};
+use super::tags::{TAG_SET, TAG_NUMERICSTRING, TAG_PRINTABLESTRING};
+use super::tags::{TAG_IA5STRING, TAG_UTCTIME, TAG_GENERALIZEDTIME};
+use super::tags::{TAG_VISIBLESTRING, TAG_BMPSTRING, TAG_UNIVERSALSTRING};
+
+/// The maximum nesting of values, as in the reader.
+const MAX_DEPTH: usize = 100;
+
+/// Renders `buf`, which must hold a single value, in GSER.
+///
+/// Fails if the data is malformed.
+pub fn to_gser(buf: &[u8], mode: BERMode) -> ASN1Result<String> {
+    let mut out = String::new();
+    parse_ber_general(buf, mode, |reader| {
+        write_value(reader, mode, 0, &mut out)
+    })?;
+    Ok(out)
+}
+
+/// Appends `s` to `out` as a GSER quoted string, doubling the quotes
+/// within.
+///
+/// This helps rendering the components of a known type, in addition to
+/// [`to_gser`].
+///
+/// # Examples
+///
+/// ```
+/// let mut out = String::from("{ commonName ");
+/// yasna::gser::write_quoted(&mut out, "a \"b\"");
+/// out.push_str(" }");
+/// assert_eq!(out, r#"{ commonName "a ""b""" }"#);
+/// ```
+pub fn write_quoted(out: &mut String, s: &str) {
+    out.push('"');
+    for c in s.chars() {
+        if c == '"' {
+            out.push('"');
+        }
+        out.push(c);
+    }
+    out.push('"');
+}
+
+fn write_value(reader: BERReader, mode: BERMode, depth: usize,
+        out: &mut String) -> ASN1Result<()> {
+    if depth > MAX_DEPTH {
+        return Err(ASN1Error::new(ASN1ErrorKind::StackOverflow));
+    }
+    let tag = reader.lookahead_tag()?;
+    match tag {
+        // The end of an indefinite-length value, which isn't a value
+        TAG_EOC => return Err(ASN1Error::new(ASN1ErrorKind::Invalid)),
+        TAG_BOOLEAN => {
+            out.push_str(if reader.read_bool()? { "TRUE" } else { "FALSE" });
+        },
+        TAG_INTEGER => {
+            let (bytes, nonnegative) = reader.read_bigint_bytes()?;
+            write_integer(out, &bytes, nonnegative);
+        },
+        TAG_ENUM => {
+            let _ = write!(out, "{}", reader.read_enum()?);
+        },
+        TAG_NULL => {
+            reader.read_null()?;
+            out.push_str("NULL");
+        },
+        TAG_OCTETSTRING => write_hstring(out, &reader.read_bytes()?),
+        TAG_BITSTRING => {
+            let (bytes, len) = reader.read_bitvec_bytes()?;
+            out.push('\'');
+            for i in 0..len {
+                out.push(if bytes[i / 8] & (128 >> (i % 8)) != 0 {
+                    '1'
+                } else {
+                    '0'
+                });
+            }
+            out.push_str("'B");
+        },
+        TAG_OID => {
+            let _ = write!(out, "{}", reader.read_oid()?);
+        },
+        TAG_UTF8STRING => write_quoted(out, &reader.read_utf8string()?),
+        TAG_NUMERICSTRING => {
+            write_quoted(out, &reader.read_numeric_string()?);
+        },
+        TAG_PRINTABLESTRING => {
+            write_quoted(out, &reader.read_printable_string()?);
+        },
+        TAG_IA5STRING => write_quoted(out, &reader.read_ia5_string()?),
+        TAG_VISIBLESTRING => {
+            write_quoted(out, &reader.read_visible_string()?);
+        },
+        TAG_BMPSTRING => write_quoted(out, &reader.read_bmp_string()?),
+        TAG_UNIVERSALSTRING => {
+            write_quoted(out, &reader.read_universal_string()?);
+        },
+        TAG_UTCTIME | TAG_GENERALIZEDTIME => {
+            // The value is kept as encoded, which is valid GSER.
+            let bytes = reader.read_tagged_implicit(tag, |reader| {
+                reader.read_bytes()
+            })?;
+            let s = match core::str::from_utf8(&bytes) {
+                Ok(s) if s.bytes().all(|b| b.is_ascii_graphic()) => s,
+                _ => return Err(ASN1Error::new(ASN1ErrorKind::Invalid)),
+            };
+            write_quoted(out, s);
+        },
+        TAG_SEQUENCE => {
+            write_elements(out, |out| reader.read_sequence_of(|reader| {
+                write_element(reader, mode, depth, out)
+            }))?;
+        },
+        TAG_SET => {
+            write_elements(out, |out| reader.read_set_of(|reader| {
+                write_element(reader, mode, depth, out)
+            }))?;
+        },
+        _ => {
+            let (_, pcbit) = reader.peek_tag()?;
+            if tag.tag_class == TagClass::Universal ||
+                    pcbit == PCBit::Primitive {
+                let bytes = reader.read_tagged_implicit(tag, |reader| {
+                    reader.read_bytes()
+                })?;
+                write_hstring(out, &bytes);
+            } else {
+                reader.read_tagged_implicit(tag, |reader| {
+                    write_elements(out, |out| {
+                        reader.read_sequence_of(|reader| {
+                            write_element(reader, mode, depth, out)
+                        })
+                    })
+                })?;
+            }
+        },
+    }
+    Ok(())
+}
+
+/// Writes the elements written by `callback` between braces.
+fn write_elements<F>(out: &mut String, callback: F) -> ASN1Result<()>
+        where F: FnOnce(&mut String) -> ASN1Result<()> {
+    out.push('{');
+    let start = out.len();
+    callback(out)?;
+    if out.len() > start {
+        // Drop the comma after the last element
+        out.pop();
+    }
+    out.push_str(" }");
+    Ok(())
+}
+
+fn write_element(reader: BERReader, mode: BERMode, depth: usize,
+        out: &mut String) -> ASN1Result<()> {
+    // SET OF is read until an element fails, so nothing is left behind
+    // on failure.
+    let len = out.len();
+    out.push(' ');
+    if let Err(e) = write_value(reader, mode, depth + 1, out) {
+        out.truncate(len);
+        return Err(e);
+    }
+    out.push(',');
+    Ok(())
+}
+
+fn write_hstring(out: &mut String, bytes: &[u8]) {
+    out.push('\'');
+    for &b in bytes {
+        let _ = write!(out, "{:02X}", b);
+    }
+    out.push_str("'H");
+}
+
+/// Writes the INTEGER whose two's complement representation is `bytes`
+/// in decimal.
+fn write_integer(out: &mut String, bytes: &[u8], nonnegative: bool) {
+    let mut magnitude = bytes.to_vec();
+    if !nonnegative {
+        out.push('-');
+        // Negate: invert, then add one
+        let mut carry = true;
+        for b in magnitude.iter_mut().rev() {
+            *b = !*b;
+            if carry {
+                let (sum, overflow) = b.overflowing_add(1);
+                *b = sum;
+                carry = overflow;
+            }
+        }
+    }
+    let mut digits = Vec::new();
+    while magnitude.iter().any(|&b| b != 0) {
+        let mut rem = 0u32;
+        for b in magnitude.iter_mut() {
+            let cur = rem * 256 + *b as u32;
+            *b = (cur / 10) as u8;
+            rem = cur % 10;
+        }
+        digits.push(b'0' + rem as u8);
+    }
+    if digits.is_empty() {
+        digits.push(b'0');
+    }
+    out.extend(digits.iter().rev().map(|&d| d as char));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_gser() {
+        let tests : &[(&[u8], &str)] = &[
+            (&[1, 1, 0], "FALSE"),
+            (&[2, 1, 0], "0"),
+            (&[2, 2, 0, 128], "128"),
+            (&[2, 1, 128], "-128"),
+            (&[2, 9, 1, 0, 0, 0, 0, 0, 0, 0, 0], "18446744073709551616"),
+            (&[2, 9, 255, 0, 0, 0, 0, 0, 0, 0, 0], "-18446744073709551616"),
+            (&[10, 1, 3], "3"),
+            (&[5, 0], "NULL"),
+            (&[4, 2, 0xab, 1], "'AB01'H"),
+            (&[3, 1, 0], "''B"),
+            (&[6, 3, 85, 4, 3], "2.5.4.3"),
+            (&[19, 2, 65, 66], "\"AB\""),
+            (&[30, 4, 0, 34, 0, 97], "\"\"\"a\""),
+            (&[28, 4, 0, 0, 0, 97], "\"a\""),
+            (&[23, 13, 56, 50, 48, 49, 48, 50, 49, 50, 48, 48, 48, 48, 90],
+                "\"820102120000Z\""),
+            (&[48, 0], "{ }"),
+            (&[49, 5, 1, 1, 255, 5, 0], "{ TRUE, NULL }"),
+            (&[48, 6, 48, 4, 48, 2, 5, 0], "{ { { NULL } } }"),
+            (&[128, 2, 1, 2], "'0102'H"),
+            (&[161, 5, 2, 1, 1, 5, 0], "{ 1, NULL }"),
+            (&[9, 1, 64], "'40'H"),
+        ];
+        for &(data, gser) in tests {
+            assert_eq!(to_gser(data, BERMode::Der).unwrap(), gser);
+        }
+        assert_eq!(to_gser(&[48, 128, 161, 128, 5, 0, 0, 0, 0, 0],
+            BERMode::Ber).unwrap(), "{ { NULL } }");
+        to_gser(&[48, 3, 48, 1, 5], BERMode::Der).unwrap_err();
+        to_gser(&[23, 2, 32, 90], BERMode::Der).unwrap_err();
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Conversions to and from the `asn1-rs` crate.
+
+use core::convert::TryFrom;
+
+use crate::models::{ObjectIdentifier, TaggedDerValue};
+use crate::{ASN1Error, ASN1ErrorKind, PCBit, Tag, TagClass};
+
+impl From<TagClass> for ::asn1_rs::Class {
+    fn from(tag_class: TagClass) -> Self {
+        match tag_class {
+            TagClass::Universal => ::asn1_rs::Class::Universal,
+            TagClass::Application => ::asn1_rs::Class::Application,
+            TagClass::ContextSpecific => ::asn1_rs::Class::ContextSpecific,
+            TagClass::Private => ::asn1_rs::Class::Private,
+        }
+    }
+}
+
+impl From<::asn1_rs::Class> for TagClass {
+    fn from(class: ::asn1_rs::Class) -> Self {
+        match class {
+            ::asn1_rs::Class::Universal => TagClass::Universal,
+            ::asn1_rs::Class::Application => TagClass::Application,
+            ::asn1_rs::Class::ContextSpecific => TagClass::ContextSpecific,
+            ::asn1_rs::Class::Private => TagClass::Private,
+        }
+    }
+}
+
+impl TryFrom<&ObjectIdentifier> for ::asn1_rs::Oid<'static> {
+    type Error = ::asn1_rs::OidParseError;
+
+    fn try_from(oid: &ObjectIdentifier) -> Result<Self, Self::Error> {
+        ::asn1_rs::Oid::from(oid.components())
+    }
+}
+
+impl TryFrom<&::asn1_rs::Oid<'_>> for ObjectIdentifier {
+    type Error = ASN1Error;
+
+    /// Fails with `IntegerOverflow` if an arc does not fit in `u64`.
+    ///
+    /// The `Oid` is assumed to be absolute, not relative.
+    fn try_from(oid: &::asn1_rs::Oid<'_>) -> Result<Self, Self::Error> {
+        match oid.iter() {
+            Some(arcs) => Ok(ObjectIdentifier::new(arcs.collect())),
+            None => Err(ASN1Error::new(ASN1ErrorKind::IntegerOverflow)),
+        }
+    }
+}
+
+impl From<&::asn1_rs::Any<'_>> for TaggedDerValue {
+    fn from(any: &::asn1_rs::Any<'_>) -> Self {
+        let tag = Tag {
+            tag_class: TagClass::from(any.class()),
+            tag_number: u64::from(any.tag().0),
+        };
+        let pcbit = if any.header.constructed() {
+            PCBit::Constructed
+        } else {
+            PCBit::Primitive
+        };
+        TaggedDerValue::from_tag_pc_and_bytes(tag, pcbit, any.data.to_vec())
+    }
+}
+
+impl<'a> TryFrom<&'a TaggedDerValue> for ::asn1_rs::Any<'a> {
+    type Error = ASN1Error;
+
+    /// Fails with `IntegerOverflow` if the tag number does not fit in
+    /// `u32`.
+    fn try_from(value: &'a TaggedDerValue) -> Result<Self, Self::Error> {
+        let tag = value.tag();
+        let tag_number = u32::try_from(tag.tag_number)
+            .map_err(|_| ASN1Error::new(ASN1ErrorKind::IntegerOverflow))?;
+        let header = ::asn1_rs::Header::new(
+            tag.tag_class.into(),
+            value.pcbit() == PCBit::Constructed,
+            ::asn1_rs::Tag(tag_number),
+            ::asn1_rs::Length::Definite(value.value().len()));
+        Ok(::asn1_rs::Any::new(header, value.value()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::TryFrom;
+    use super::*;
+
+    #[test]
+    fn test_asn1_rs_oid_roundtrip() {
+        let oid = ObjectIdentifier::from_slice(&[1, 2, 840, 113549, 1, 1, 11]);
+        let aoid = ::asn1_rs::Oid::try_from(&oid).unwrap();
+        assert_eq!(aoid.as_bytes(), &[42, 134, 72, 134, 247, 13, 1, 1, 11]);
+        assert_eq!(ObjectIdentifier::try_from(&aoid).unwrap(), oid);
+    }
+
+    #[test]
+    fn test_asn1_rs_any_roundtrip() {
+        let value = TaggedDerValue::from_tag_pc_and_bytes(
+            Tag::application(5), PCBit::Constructed, [2, 1, 10].to_vec());
+        let any = ::asn1_rs::Any::try_from(&value).unwrap();
+        assert_eq!(any.class(), ::asn1_rs::Class::Application);
+        assert_eq!(any.tag(), ::asn1_rs::Tag(5));
+        assert_eq!(TaggedDerValue::from(&any), value);
+
+        let value = TaggedDerValue::from_tag_and_bytes(
+            Tag::context(1 << 32), [].to_vec());
+        assert!(::asn1_rs::Any::try_from(&value).is_err());
+    }
+}
@@ -0,0 +1,18 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Conversions between yasna's types and those of other ASN.1 crates.
+//!
+//! Each conversion is gated on the feature named after the other crate.
+
+#[cfg(any(feature = "const-oid", feature = "der"))]
+mod rustcrypto;
+#[cfg(feature = "asn1-rs")]
+mod asn1_rs;
+#[cfg(feature = "rasn")]
+mod rasn;
@@ -0,0 +1,135 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Conversions to and from the `rasn` crate.
+
+use core::convert::TryFrom;
+
+use alloc::vec::Vec;
+
+use crate::models::{ObjectIdentifier, TaggedDerValue};
+use crate::{ASN1Error, ASN1ErrorKind, Tag, TagClass};
+use crate::{construct_der, parse_der};
+
+impl From<TagClass> for ::rasn::types::Class {
+    fn from(tag_class: TagClass) -> Self {
+        match tag_class {
+            TagClass::Universal => ::rasn::types::Class::Universal,
+            TagClass::Application => ::rasn::types::Class::Application,
+            TagClass::ContextSpecific => ::rasn::types::Class::Context,
+            TagClass::Private => ::rasn::types::Class::Private,
+        }
+    }
+}
+
+impl From<::rasn::types::Class> for TagClass {
+    fn from(class: ::rasn::types::Class) -> Self {
+        match class {
+            ::rasn::types::Class::Universal => TagClass::Universal,
+            ::rasn::types::Class::Application => TagClass::Application,
+            ::rasn::types::Class::Context => TagClass::ContextSpecific,
+            ::rasn::types::Class::Private => TagClass::Private,
+        }
+    }
+}
+
+impl From<::rasn::types::Tag> for Tag {
+    fn from(tag: ::rasn::types::Tag) -> Self {
+        Tag {
+            tag_class: tag.class.into(),
+            tag_number: u64::from(tag.value),
+        }
+    }
+}
+
+impl TryFrom<Tag> for ::rasn::types::Tag {
+    type Error = ASN1Error;
+
+    /// Fails with `IntegerOverflow` if the tag number does not fit in
+    /// `u32`.
+    fn try_from(tag: Tag) -> Result<Self, Self::Error> {
+        let value = u32::try_from(tag.tag_number)
+            .map_err(|_| ASN1Error::new(ASN1ErrorKind::IntegerOverflow))?;
+        Ok(::rasn::types::Tag::new(tag.tag_class.into(), value))
+    }
+}
+
+impl From<&::rasn::types::Oid> for ObjectIdentifier {
+    fn from(oid: &::rasn::types::Oid) -> Self {
+        ObjectIdentifier::new(oid.iter().map(|&arc| u64::from(arc)).collect())
+    }
+}
+
+impl From<::rasn::types::ObjectIdentifier> for ObjectIdentifier {
+    fn from(oid: ::rasn::types::ObjectIdentifier) -> Self {
+        ObjectIdentifier::from(&*oid)
+    }
+}
+
+impl TryFrom<&ObjectIdentifier> for ::rasn::types::ObjectIdentifier {
+    type Error = ASN1Error;
+
+    /// Fails with `IntegerOverflow` if an arc does not fit in `u32`, and
+    /// with `Invalid` if `rasn` rejects the first two arcs.
+    fn try_from(oid: &ObjectIdentifier) -> Result<Self, Self::Error> {
+        let arcs = oid.components().iter()
+            .map(|&component| u32::try_from(component))
+            .collect::<Result<Vec<u32>, _>>()
+            .map_err(|_| ASN1Error::new(ASN1ErrorKind::IntegerOverflow))?;
+        ::rasn::types::ObjectIdentifier::new(arcs)
+            .ok_or_else(|| ASN1Error::new(ASN1ErrorKind::Invalid))
+    }
+}
+
+impl From<&TaggedDerValue> for ::rasn::types::Any {
+    fn from(value: &TaggedDerValue) -> Self {
+        ::rasn::types::Any::new(construct_der(|writer| {
+            writer.write_tagged_der(value)
+        }))
+    }
+}
+
+impl TryFrom<&::rasn::types::Any> for TaggedDerValue {
+    type Error = ASN1Error;
+
+    /// Fails unless the contents of the `Any` are a single DER value.
+    fn try_from(any: &::rasn::types::Any) -> Result<Self, Self::Error> {
+        parse_der(any.as_bytes(), |reader| reader.read_tagged_der())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::TryFrom;
+    use super::*;
+
+    #[test]
+    fn test_rasn_oid_roundtrip() {
+        let oid = ObjectIdentifier::from_slice(&[1, 2, 840, 113549, 1, 1, 11]);
+        let roid = ::rasn::types::ObjectIdentifier::try_from(&oid).unwrap();
+        assert_eq!(&*roid, &[1, 2, 840, 113549, 1, 1, 11][..]);
+        assert_eq!(ObjectIdentifier::from(roid), oid);
+
+        let too_big = ObjectIdentifier::from_slice(&[1, 2, 1 << 32]);
+        assert!(::rasn::types::ObjectIdentifier::try_from(&too_big).is_err());
+    }
+
+    #[test]
+    fn test_rasn_tag_and_any_roundtrip() {
+        let tag = ::rasn::types::Tag::try_from(Tag::context(3)).unwrap();
+        assert_eq!(tag, ::rasn::types::Tag::new(
+            ::rasn::types::Class::Context, 3));
+        assert_eq!(Tag::from(tag), Tag::context(3));
+
+        let value = TaggedDerValue::from_tag_and_bytes(
+            Tag::context(3), [1, 2].to_vec());
+        let any = ::rasn::types::Any::from(&value);
+        assert_eq!(any.as_bytes(), &[131, 2, 1, 2]);
+        assert_eq!(TaggedDerValue::try_from(&any).unwrap(), value);
+    }
+}
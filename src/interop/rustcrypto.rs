@@ -0,0 +1,194 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Conversions to and from the RustCrypto `const-oid` and `der` crates.
+
+use core::convert::TryFrom;
+
+#[cfg(feature = "const-oid")]
+use crate::models::ObjectIdentifier;
+#[cfg(feature = "der")]
+use crate::models::TaggedDerValue;
+#[cfg(feature = "der")]
+use crate::{PCBit, Tag, TagClass};
+#[cfg(feature = "der")]
+use ::der::Tagged;
+
+#[cfg(feature = "const-oid")]
+impl From<&::const_oid::ObjectIdentifier> for ObjectIdentifier {
+    fn from(oid: &::const_oid::ObjectIdentifier) -> Self {
+        ObjectIdentifier::new(oid.arcs().map(u64::from).collect())
+    }
+}
+
+#[cfg(feature = "const-oid")]
+impl From<::const_oid::ObjectIdentifier> for ObjectIdentifier {
+    fn from(oid: ::const_oid::ObjectIdentifier) -> Self {
+        ObjectIdentifier::from(&oid)
+    }
+}
+
+#[cfg(feature = "const-oid")]
+impl TryFrom<&ObjectIdentifier> for ::const_oid::ObjectIdentifier {
+    type Error = ::const_oid::Error;
+
+    /// Fails if an arc does not fit in `u32`, or if the identifier
+    /// is otherwise not representable by `const_oid` (e.g. too long).
+    fn try_from(oid: &ObjectIdentifier) -> Result<Self, Self::Error> {
+        let mut arcs = [0u32; ::const_oid::ObjectIdentifier::MAX_SIZE];
+        if oid.components().len() > arcs.len() {
+            return Err(::const_oid::Error::Length);
+        }
+        for (arc, &component) in arcs.iter_mut().zip(oid.components()) {
+            *arc = u32::try_from(component)
+                .map_err(|_| ::const_oid::Error::ArcTooBig)?;
+        }
+        ::const_oid::ObjectIdentifier::from_arcs(
+            arcs[..oid.components().len()].iter().cloned())
+    }
+}
+
+#[cfg(feature = "const-oid")]
+impl TryFrom<ObjectIdentifier> for ::const_oid::ObjectIdentifier {
+    type Error = ::const_oid::Error;
+
+    fn try_from(oid: ObjectIdentifier) -> Result<Self, Self::Error> {
+        ::const_oid::ObjectIdentifier::try_from(&oid)
+    }
+}
+
+#[cfg(feature = "der")]
+impl From<::der::Tag> for Tag {
+    /// The primitive/constructed bit of `der::Tag` is dropped, as it is
+    /// not part of [`Tag`].
+    fn from(tag: ::der::Tag) -> Self {
+        let tag_class = match tag.class() {
+            ::der::Class::Universal => TagClass::Universal,
+            ::der::Class::Application => TagClass::Application,
+            ::der::Class::ContextSpecific => TagClass::ContextSpecific,
+            ::der::Class::Private => TagClass::Private,
+        };
+        Tag {
+            tag_class,
+            tag_number: u64::from(tag.number().value()),
+        }
+    }
+}
+
+/// Builds a `der::Tag` out of a yasna tag and an explicit P/C bit.
+#[cfg(feature = "der")]
+fn der_tag(tag: Tag, pcbit: PCBit) -> ::der::Result<::der::Tag> {
+    // Tag numbers above 30 need the high-tag-number form.
+    if tag.tag_number > 30 {
+        return Err(::der::ErrorKind::TagNumberInvalid.into());
+    }
+    let octet = ((tag.tag_class as u8) << 6) | ((pcbit as u8) << 5) |
+        tag.tag_number as u8;
+    ::der::Tag::try_from(octet)
+}
+
+#[cfg(feature = "der")]
+impl TryFrom<Tag> for ::der::Tag {
+    type Error = ::der::Error;
+
+    /// Fails if the tag number does not fit in the low-tag-number form,
+    /// or if `der` does not know the universal tag.
+    ///
+    /// As [`Tag`] has no primitive/constructed bit, SEQUENCE and SET
+    /// are mapped to their constructed forms and every other tag is
+    /// mapped to its primitive form. Use the conversion between
+    /// [`TaggedDerValue`] and `der::Any` to keep the bit intact.
+    fn try_from(tag: Tag) -> ::der::Result<Self> {
+        let pcbit = match tag.tag_class {
+            TagClass::Universal if tag.tag_number == 16 ||
+                tag.tag_number == 17 => PCBit::Constructed,
+            _ => PCBit::Primitive,
+        };
+        der_tag(tag, pcbit)
+    }
+}
+
+#[cfg(feature = "der")]
+impl From<&::der::Any> for TaggedDerValue {
+    fn from(any: &::der::Any) -> Self {
+        let pcbit = if any.tag().is_constructed() {
+            PCBit::Constructed
+        } else {
+            PCBit::Primitive
+        };
+        TaggedDerValue::from_tag_pc_and_bytes(
+            Tag::from(any.tag()), pcbit, any.value().to_vec())
+    }
+}
+
+#[cfg(feature = "der")]
+impl From<::der::Any> for TaggedDerValue {
+    fn from(any: ::der::Any) -> Self {
+        TaggedDerValue::from(&any)
+    }
+}
+
+#[cfg(feature = "der")]
+impl TryFrom<&TaggedDerValue> for ::der::Any {
+    type Error = ::der::Error;
+
+    fn try_from(value: &TaggedDerValue) -> ::der::Result<Self> {
+        let tag = der_tag(value.tag(), value.pcbit())?;
+        ::der::Any::new(tag, value.value())
+    }
+}
+
+#[cfg(feature = "der")]
+impl TryFrom<TaggedDerValue> for ::der::Any {
+    type Error = ::der::Error;
+
+    fn try_from(value: TaggedDerValue) -> ::der::Result<Self> {
+        ::der::Any::try_from(&value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::TryFrom;
+    use super::*;
+
+    #[cfg(feature = "const-oid")]
+    #[test]
+    fn test_const_oid_roundtrip() {
+        let oid = ObjectIdentifier::from_slice(&[1, 2, 840, 113549, 1, 1, 11]);
+        let coid = ::const_oid::ObjectIdentifier::try_from(&oid).unwrap();
+        assert_eq!(coid, ::const_oid::ObjectIdentifier::new_unwrap(
+            "1.2.840.113549.1.1.11"));
+        assert_eq!(ObjectIdentifier::from(coid), oid);
+
+        let too_big = ObjectIdentifier::from_slice(&[1, 2, 1 << 32]);
+        assert!(::const_oid::ObjectIdentifier::try_from(&too_big).is_err());
+    }
+
+    #[cfg(feature = "der")]
+    #[test]
+    fn test_der_tag_roundtrip() {
+        use crate::tags::{TAG_INTEGER, TAG_SEQUENCE};
+        let tests = [
+            (TAG_INTEGER, ::der::Tag::Integer),
+            (TAG_SEQUENCE, ::der::Tag::Sequence),
+            (Tag::context(3), ::der::TagNumber::N3.context_specific(false)),
+        ];
+        for &(tag, der_tag) in &tests {
+            assert_eq!(::der::Tag::try_from(tag).unwrap(), der_tag);
+            assert_eq!(Tag::from(der_tag), tag);
+        }
+        assert!(::der::Tag::try_from(Tag::context(31)).is_err());
+
+        let value = TaggedDerValue::from_tag_pc_and_bytes(
+            Tag::context(0), PCBit::Constructed, [2, 1, 10].to_vec());
+        let any = ::der::Any::try_from(&value).unwrap();
+        assert!(any.tag().is_constructed());
+        assert_eq!(TaggedDerValue::from(any), value);
+    }
+}
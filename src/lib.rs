@@ -67,7 +67,8 @@
 //! }
 //! ```
 
-#![forbid(unsafe_code)]
+#![cfg_attr(not(feature = "ffi"), forbid(unsafe_code))]
+#![cfg_attr(feature = "ffi", deny(unsafe_code))]
 #![deny(missing_docs)]
 #![no_std]
 
@@ -75,24 +76,71 @@ extern crate alloc;
 #[cfg(any(test, feature = "std"))]
 extern crate std;
 
+#[macro_use]
+mod macros;
 pub mod tags;
 pub mod models;
 mod writer;
 mod reader;
 mod deserializer;
 mod serializer;
+mod interop;
+pub mod gser;
+#[cfg(feature = "std")]
+mod pem;
+#[cfg(feature = "std")]
+pub mod cms;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "codec")]
+mod codec;
+#[cfg(feature = "quickcheck")]
+pub mod arbitrary;
+#[cfg(feature = "edge-cases")]
+pub mod edge_cases;
+#[cfg(any(feature = "num-bigint", feature = "ibig",
+    feature = "crypto-bigint"))]
+mod bigint;
 
 pub use crate::writer::{construct_der,try_construct_der};
 pub use crate::writer::{construct_der_seq,try_construct_der_seq};
+pub use crate::writer::{construct_ber,construct_cer};
+pub use crate::writer::encoded_len;
 pub use crate::writer::{DERWriter,DERWriterSeq,DERWriterSet};
+pub use crate::writer::{encode_der_into,BufferTooSmall};
+pub use crate::writer::{SliceWriter,SliceWriterSeq};
+#[cfg(feature = "std")]
+pub use crate::writer::StreamWriter;
 pub use crate::reader::{parse_ber_general,parse_ber,parse_der,BERMode};
-pub use crate::reader::{BERReader,BERReaderSeq,BERReaderSet};
+pub use crate::reader::{parse_ber_prefix,parse_der_prefix};
+pub use crate::reader::{parse_ber_multiple,parse_der_multiple,ParseMultiple};
+pub use crate::reader::{parse_with_options,AllowedTypes,ParseOptions,Profile};
+pub use crate::reader::LeniencyFlags;
+pub use crate::reader::{tlv_length,TlvLength};
+pub use crate::reader::{find_oid,OidLocation};
+pub use crate::reader::{walk_ber,walk_with_options,Event,Visitor};
+pub use crate::reader::dump_ber;
+pub use crate::reader::{match_pattern,Pattern};
+pub use crate::reader::{parse_limited,DefaultLimits,Limits};
+pub use crate::reader::{PushParser,PushStatus};
+#[cfg(feature = "std")]
+pub use crate::reader::{parse_ber_from_reader,parse_from_reader_with_options};
+#[cfg(feature = "tokio")]
+pub use crate::reader::{parse_ber_async,parse_async_with_options};
+pub use crate::reader::{BERReader,BERReaderSeq,BERReaderSet,Checkpoint};
+pub use crate::reader::{StringDecoding,TeletexDecoding};
 pub use crate::reader::{ASN1Error,ASN1ErrorKind,ASN1Result};
 pub use crate::deserializer::{BERDecodable,decode_ber_general,decode_ber,decode_der};
+pub use crate::deserializer::decode_with_options;
 pub use crate::serializer::{DEREncodable,encode_der};
+#[cfg(feature = "std")]
+pub use crate::pem::Base64Reader;
+#[cfg(feature = "codec")]
+pub use crate::codec::BerCodec;
 
 /// A value of the ASN.1 primitive/constructed ("P/C") bit.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum PCBit {
     /// The bit's value is "Primitive"
     Primitive = 0,
@@ -109,6 +157,7 @@ pub enum PCBit {
 /// - context specific
 /// - PRIVATE
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum TagClass {
     /// The UNIVERSAL tag class
     Universal = 0,
@@ -139,6 +188,7 @@ const TAG_CLASSES : [TagClass; 4] = [
 /// - A tag number is a nonnegative integer.
 ///   In this library, tag numbers are assumed to fit into `u64`.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct Tag {
     /// The tag class
     pub tag_class: TagClass,
@@ -0,0 +1,78 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+/// Implements [`BERDecodable`](crate::BERDecodable) and
+/// [`DEREncodable`](crate::DEREncodable) for a newtype wrapping bytes,
+/// so that it is encoded as OCTET STRING.
+///
+/// The newtype must be a tuple struct whose only field is either
+/// `Vec<u8>` or `[u8; N]`. For the array form, decoding fails with
+/// [`ASN1ErrorKind::Invalid`](crate::ASN1ErrorKind::Invalid) if the
+/// length of the OCTET STRING is not exactly `N`.
+///
+/// # Examples
+///
+/// ```
+/// use yasna;
+/// #[derive(Debug, PartialEq)]
+/// struct KeyId(Vec<u8>);
+/// yasna::octet_string_newtype!(KeyId, Vec<u8>);
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Nonce([u8; 4]);
+/// yasna::octet_string_newtype!(Nonce, [u8; 4]);
+///
+/// let der = yasna::encode_der(&KeyId(vec![1, 2, 3]));
+/// assert_eq!(der, vec![4, 3, 1, 2, 3]);
+/// let key_id : KeyId = yasna::decode_der(&der).unwrap();
+/// assert_eq!(key_id, KeyId(vec![1, 2, 3]));
+///
+/// let der = yasna::encode_der(&Nonce([1, 2, 3, 4]));
+/// assert_eq!(der, vec![4, 4, 1, 2, 3, 4]);
+/// let nonce : Nonce = yasna::decode_der(&der).unwrap();
+/// assert_eq!(nonce, Nonce([1, 2, 3, 4]));
+/// assert!(yasna::decode_der::<Nonce>(&[4, 3, 1, 2, 3]).is_err());
+/// ```
+#[macro_export]
+macro_rules! octet_string_newtype {
+    ($name:ident, Vec<u8>) => {
+        impl $crate::BERDecodable for $name {
+            fn decode_ber<'a, 'b>(reader: $crate::BERReader<'a, 'b>)
+                    -> $crate::ASN1Result<Self> {
+                Ok($name(reader.read_bytes()?))
+            }
+        }
+
+        impl $crate::DEREncodable for $name {
+            fn encode_der<'a>(&self, writer: $crate::DERWriter<'a>) {
+                writer.write_bytes(&self.0)
+            }
+        }
+    };
+    ($name:ident, [u8; $len:expr]) => {
+        impl $crate::BERDecodable for $name {
+            fn decode_ber<'a, 'b>(reader: $crate::BERReader<'a, 'b>)
+                    -> $crate::ASN1Result<Self> {
+                let bytes = reader.read_bytes()?;
+                if bytes.len() != $len {
+                    return Err($crate::ASN1Error::new(
+                        $crate::ASN1ErrorKind::Invalid));
+                }
+                let mut value = [0u8; $len];
+                value.copy_from_slice(&bytes);
+                Ok($name(value))
+            }
+        }
+
+        impl $crate::DEREncodable for $name {
+            fn encode_der<'a>(&self, writer: $crate::DERWriter<'a>) {
+                writer.write_bytes(&self.0)
+            }
+        }
+    };
+}
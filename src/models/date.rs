@@ -0,0 +1,525 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use alloc::vec::Vec;
+
+/// A calendar date between 0000-01-01 and 9999-12-31.
+///
+/// Corresponds to ASN.1 DATE type, encoded as `YYYYMMDD`.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::models::Date;
+/// let date = Date::parse(b"20240229").unwrap();
+/// assert_eq!((date.year(), date.month(), date.day()), (2024, 2, 29));
+/// assert!(Date::parse(b"20230229").is_none());
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Date {
+    year: u16,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    /// Constructs a `Date`.
+    ///
+    /// # Errors
+    ///
+    /// It returns `None` if the year is beyond 9999, or the month or the
+    /// day doesn't exist.
+    pub fn new(year: u16, month: u8, day: u8) -> Option<Self> {
+        let leap = match year % 100 {
+            // A multiple of 100 is one of 400 iff it is one of 16.
+            0 => year & 15 == 0,
+            _ => year & 3 == 0,
+        };
+        let days = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if leap => 29,
+            2 => 28,
+            _ => return None,
+        };
+        if year > 9999 || day == 0 || day > days {
+            return None;
+        }
+        Some(Date { year, month, day })
+    }
+
+    /// Parses the contents of an ASN.1 DATE.
+    ///
+    /// # Errors
+    ///
+    /// It returns `None` if the given string is not of the form
+    /// `YYYYMMDD` or does not specify a correct date.
+    pub fn parse(buf: &[u8]) -> Option<Self> {
+        if buf.len() != 8 {
+            return None;
+        }
+        Date::new(parse_digits(&buf[..4])? as u16,
+            parse_digits(&buf[4..6])? as u8, parse_digits(&buf[6..])? as u8)
+    }
+
+    /// Returns the year.
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    /// Returns the month, from 1 to 12.
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// Returns the day of the month, from 1.
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// Returns ASN.1 canonical representation of the date as `Vec<u8>`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(8);
+        push_digits(&mut buf, self.year as u32, 4);
+        push_digits(&mut buf, self.month as u32, 2);
+        push_digits(&mut buf, self.day as u32, 2);
+        buf
+    }
+}
+
+/// A time of day between 00:00:00 and 23:59:59. It cannot express
+/// fractional seconds and leap seconds.
+///
+/// Corresponds to ASN.1 TIME-OF-DAY type, encoded as `hhmmss`.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::models::TimeOfDay;
+/// let time = TimeOfDay::parse(b"123456").unwrap();
+/// assert_eq!((time.hour(), time.minute(), time.second()), (12, 34, 56));
+/// assert!(TimeOfDay::parse(b"240000").is_none());
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct TimeOfDay {
+    hour: u8,
+    minute: u8,
+    second: u8,
+}
+
+impl TimeOfDay {
+    /// Constructs a `TimeOfDay`.
+    ///
+    /// # Errors
+    ///
+    /// It returns `None` if a field is out of range.
+    pub fn new(hour: u8, minute: u8, second: u8) -> Option<Self> {
+        if hour > 23 || minute > 59 || second > 59 {
+            return None;
+        }
+        Some(TimeOfDay { hour, minute, second })
+    }
+
+    /// Parses the contents of an ASN.1 TIME-OF-DAY.
+    ///
+    /// # Errors
+    ///
+    /// It returns `None` if the given string is not of the form `hhmmss`
+    /// or does not specify a correct time.
+    pub fn parse(buf: &[u8]) -> Option<Self> {
+        if buf.len() != 6 {
+            return None;
+        }
+        TimeOfDay::new(parse_digits(&buf[..2])? as u8,
+            parse_digits(&buf[2..4])? as u8, parse_digits(&buf[4..])? as u8)
+    }
+
+    /// Returns the hour, from 0 to 23.
+    pub fn hour(&self) -> u8 {
+        self.hour
+    }
+
+    /// Returns the minute, from 0 to 59.
+    pub fn minute(&self) -> u8 {
+        self.minute
+    }
+
+    /// Returns the second, from 0 to 59.
+    pub fn second(&self) -> u8 {
+        self.second
+    }
+
+    /// Returns ASN.1 canonical representation of the time as `Vec<u8>`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::with_capacity(6);
+        push_digits(&mut buf, self.hour as u32, 2);
+        push_digits(&mut buf, self.minute as u32, 2);
+        push_digits(&mut buf, self.second as u32, 2);
+        buf
+    }
+}
+
+/// A local date and time, without timezone information, with an optional
+/// fraction of a second.
+///
+/// Corresponds to ASN.1 DATE-TIME type, encoded as `YYYYMMDDhhmmss`. The
+/// fraction can't be expressed in DATE-TIME; it is written to
+/// GeneralizedTime by
+/// [`write_generalized_time_from`](crate::DERWriter::write_generalized_time_from).
+///
+/// # Examples
+///
+/// ```
+/// use yasna::models::DateTime;
+/// let datetime = DateTime::parse(b"20240229123456").unwrap();
+/// assert_eq!(datetime.date().day(), 29);
+/// assert_eq!(datetime.time().second(), 56);
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct DateTime {
+    date: Date,
+    time: TimeOfDay,
+    nanosecond: u32,
+}
+
+impl DateTime {
+    /// Constructs a `DateTime`, without a fraction of a second.
+    pub fn new(date: Date, time: TimeOfDay) -> Self {
+        DateTime { date, time, nanosecond: 0 }
+    }
+
+    /// Sets the fraction of the second, in nanoseconds.
+    ///
+    /// # Errors
+    ///
+    /// It returns `None` if `nanosecond` is 1,000,000,000 or more.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::models::DateTime;
+    /// let datetime = DateTime::parse(b"20240229123456").unwrap();
+    /// let datetime = datetime.with_nanosecond(500_000_000).unwrap();
+    /// assert_eq!(datetime.nanosecond(), 500_000_000);
+    /// assert!(datetime.with_nanosecond(1_000_000_000).is_none());
+    /// ```
+    pub fn with_nanosecond(self, nanosecond: u32) -> Option<Self> {
+        if nanosecond >= 1_000_000_000 {
+            return None;
+        }
+        Some(DateTime { nanosecond, ..self })
+    }
+
+    /// Parses the contents of an ASN.1 DATE-TIME.
+    ///
+    /// # Errors
+    ///
+    /// It returns `None` if the given string is not of the form
+    /// `YYYYMMDDhhmmss` or does not specify a correct date and time.
+    pub fn parse(buf: &[u8]) -> Option<Self> {
+        if buf.len() != 14 {
+            return None;
+        }
+        Some(DateTime::new(Date::parse(&buf[..8])?,
+            TimeOfDay::parse(&buf[8..])?))
+    }
+
+    /// Returns the date.
+    pub fn date(&self) -> &Date {
+        &self.date
+    }
+
+    /// Returns the time of day.
+    pub fn time(&self) -> &TimeOfDay {
+        &self.time
+    }
+
+    /// Returns the fraction of the second, in nanoseconds.
+    pub fn nanosecond(&self) -> u32 {
+        self.nanosecond
+    }
+
+    /// Returns ASN.1 canonical representation of the datetime as
+    /// `Vec<u8>`. The fraction of the second is left out.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = self.date.to_bytes();
+        buf.extend_from_slice(&self.time.to_bytes());
+        buf
+    }
+}
+
+/// A duration, in years, months, weeks, days, hours, minutes and
+/// seconds. It cannot express fractional components.
+///
+/// Corresponds to ASN.1 DURATION type, encoded as an ISO 8601 duration:
+/// either `PnW`, or `PnYnMnDTnHnMnS` where zero components can be
+/// omitted.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::models::Duration;
+/// let duration = Duration::parse(b"P1DT12H").unwrap();
+/// assert_eq!((duration.days(), duration.hours()), (1, 12));
+/// assert_eq!(Duration::parse(b"P3W").unwrap().weeks(), 3);
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Duration {
+    years: u32,
+    months: u32,
+    weeks: u32,
+    days: u32,
+    hours: u32,
+    minutes: u32,
+    seconds: u32,
+}
+
+impl Duration {
+    /// Constructs a `Duration` from its calendar and clock components.
+    pub fn new(years: u32, months: u32, days: u32, hours: u32, minutes: u32,
+            seconds: u32) -> Self {
+        Duration {
+            years, months, weeks: 0, days, hours, minutes, seconds,
+        }
+    }
+
+    /// Constructs a `Duration` of a number of weeks.
+    pub fn from_weeks(weeks: u32) -> Self {
+        Duration { weeks, ..Duration::default() }
+    }
+
+    /// Parses the contents of an ASN.1 DURATION.
+    ///
+    /// # Errors
+    ///
+    /// It returns `None` if the given string is not an ISO 8601 duration
+    /// as above, or has a fractional component or a component exceeding
+    /// `u32`.
+    pub fn parse(buf: &[u8]) -> Option<Self> {
+        if buf.first() != Some(&b'P') {
+            return None;
+        }
+        let mut duration = Duration::default();
+        // The designators in the order they must appear
+        let designators = b"YMWDTHMS";
+        let mut next = 0;
+        let mut components = 0;
+        let mut weeks = false;
+        let mut in_time = false;
+        let mut i = 1;
+        while i < buf.len() {
+            if buf[i] == b'T' {
+                if in_time {
+                    return None;
+                }
+                in_time = true;
+                next = 5;
+                i += 1;
+                // At least one time component must follow
+                if i == buf.len() {
+                    return None;
+                }
+                continue;
+            }
+            let len = buf[i..].iter().take_while(|b| b.is_ascii_digit())
+                .count();
+            if len == 0 || i + len == buf.len() {
+                return None;
+            }
+            let value = parse_digits(&buf[i..i + len])?;
+            let designator = buf[i + len];
+            let pos = (next..designators.len()).find(|&j| {
+                designators[j] == designator && (j >= 5) == in_time && j != 4
+            })?;
+            match pos {
+                0 => duration.years = value,
+                1 => duration.months = value,
+                2 => {
+                    duration.weeks = value;
+                    weeks = true;
+                },
+                3 => duration.days = value,
+                5 => duration.hours = value,
+                6 => duration.minutes = value,
+                _ => duration.seconds = value,
+            }
+            next = pos + 1;
+            components += 1;
+            i += len + 1;
+        }
+        // Weeks can't be combined with the other components.
+        if components == 0 || (components > 1 && weeks) {
+            return None;
+        }
+        Some(duration)
+    }
+
+    /// Returns the years.
+    pub fn years(&self) -> u32 {
+        self.years
+    }
+
+    /// Returns the months.
+    pub fn months(&self) -> u32 {
+        self.months
+    }
+
+    /// Returns the weeks.
+    pub fn weeks(&self) -> u32 {
+        self.weeks
+    }
+
+    /// Returns the days.
+    pub fn days(&self) -> u32 {
+        self.days
+    }
+
+    /// Returns the hours.
+    pub fn hours(&self) -> u32 {
+        self.hours
+    }
+
+    /// Returns the minutes.
+    pub fn minutes(&self) -> u32 {
+        self.minutes
+    }
+
+    /// Returns the seconds.
+    pub fn seconds(&self) -> u32 {
+        self.seconds
+    }
+
+    /// Returns the representation of the duration as `Vec<u8>`, with the
+    /// zero components omitted.
+    ///
+    /// # Panics
+    ///
+    /// It panics if weeks are combined with other non-zero components,
+    /// which ISO 8601 can't express.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.push(b'P');
+        if self.weeks > 0 {
+            assert!(Duration::from_weeks(self.weeks) == *self,
+                "Can't express weeks with other components in DURATION");
+            push_number(&mut buf, self.weeks);
+            buf.push(b'W');
+            return buf;
+        }
+        for &(value, designator) in &[(self.years, b'Y'),
+                (self.months, b'M'), (self.days, b'D')] {
+            if value > 0 {
+                push_number(&mut buf, value);
+                buf.push(designator);
+            }
+        }
+        if self.hours > 0 || self.minutes > 0 || self.seconds > 0 {
+            buf.push(b'T');
+            for &(value, designator) in &[(self.hours, b'H'),
+                    (self.minutes, b'M'), (self.seconds, b'S')] {
+                if value > 0 {
+                    push_number(&mut buf, value);
+                    buf.push(designator);
+                }
+            }
+        }
+        if buf.len() == 1 {
+            buf.extend_from_slice(b"0D");
+        }
+        buf
+    }
+}
+
+/// Parses the decimal digits `buf`, which must be non-empty.
+fn parse_digits(buf: &[u8]) -> Option<u32> {
+    if buf.is_empty() {
+        return None;
+    }
+    let mut value: u32 = 0;
+    for &b in buf {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        value = value.checked_mul(10)?.checked_add((b - b'0') as u32)?;
+    }
+    Some(value)
+}
+
+/// Pushes `value` with exactly `width` digits.
+fn push_digits(buf: &mut Vec<u8>, value: u32, width: u32) {
+    for i in (0..width).rev() {
+        buf.push((value / 10u32.pow(i) % 10) as u8 + b'0');
+    }
+}
+
+fn push_number(buf: &mut Vec<u8>, value: u32) {
+    let mut width = 1;
+    while width < 10 && value >= 10u32.pow(width) {
+        width += 1;
+    }
+    push_digits(buf, value, width);
+}
+
+#[test]
+fn test_date_parse() {
+    let date = Date::parse(b"00000101").unwrap();
+    assert_eq!((date.year(), date.month(), date.day()), (0, 1, 1));
+    assert_eq!(date.to_bytes(), b"00000101");
+    assert_eq!(Date::parse(b"20000229").unwrap().to_bytes(), b"20000229");
+    let invalid: &[&[u8]] = &[b"", b"2024022", b"202402290", b"19000229",
+        b"20241301", b"20240100", b"2024-2-1", b"+2024021"];
+    for &data in invalid {
+        assert!(Date::parse(data).is_none());
+    }
+}
+
+#[test]
+fn test_time_of_day_parse() {
+    let time = TimeOfDay::parse(b"235959").unwrap();
+    assert_eq!((time.hour(), time.minute(), time.second()), (23, 59, 59));
+    assert_eq!(time.to_bytes(), b"235959");
+    let invalid: &[&[u8]] = &[b"", b"12345", b"1234567", b"126000",
+        b"123460", b"12:34:"];
+    for &data in invalid {
+        assert!(TimeOfDay::parse(data).is_none());
+    }
+}
+
+#[test]
+fn test_date_time_parse() {
+    let datetime = DateTime::parse(b"99991231235959").unwrap();
+    assert_eq!(datetime, DateTime::new(Date::new(9999, 12, 31).unwrap(),
+        TimeOfDay::new(23, 59, 59).unwrap()));
+    assert_eq!(datetime.to_bytes(), b"99991231235959");
+    assert!(DateTime::parse(b"2024022912345").is_none());
+    assert!(DateTime::parse(b"20240229123456Z").is_none());
+    assert!(DateTime::parse(b"20240230123456").is_none());
+}
+
+#[test]
+fn test_duration_parse() {
+    let tests: &[(&[u8], Duration)] = &[
+        (b"P1Y2M3DT4H5M6S", Duration::new(1, 2, 3, 4, 5, 6)),
+        (b"P3W", Duration::from_weeks(3)),
+        (b"PT36H", Duration::new(0, 0, 0, 36, 0, 0)),
+        (b"P1M", Duration::new(0, 1, 0, 0, 0, 0)),
+        (b"PT1M", Duration::new(0, 0, 0, 0, 1, 0)),
+        (b"P0D", Duration::default()),
+    ];
+    for &(data, duration) in tests {
+        assert_eq!(Duration::parse(data).unwrap(), duration);
+        assert_eq!(duration.to_bytes(), data);
+    }
+    assert_eq!(Duration::parse(b"P0Y0DT0S").unwrap().to_bytes(), b"P0D");
+    let invalid: &[&[u8]] = &[b"", b"P", b"PT", b"P1DT", b"1D", b"P1", b"PD",
+        b"P1D2Y", b"P1H", b"PT1D", b"P1W1D", b"P1Y1W", b"P1DT1H1M1S1S",
+        b"PT1.5S", b"P4294967296D", b"P1TD", b"P0W1D"];
+    for &data in invalid {
+        assert!(Duration::parse(data).is_none(), "{:?}", data);
+    }
+}
@@ -0,0 +1,487 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use alloc::vec::Vec;
+
+/// A timestamp type that can be read from and written to ASN.1
+/// UTCTime and GeneralizedTime.
+///
+/// The trait describes a point in time as seconds and nanoseconds since
+/// the Unix epoch (1970-01-01T00:00:00Z), so that any timestamp type can
+/// be used with [`read_utctime_as`], [`read_generalized_time_as`],
+/// [`write_utctime_from`] and [`write_generalized_time_from`] without
+/// depending on a particular date-time library. Leap seconds and
+/// fractional seconds finer than a nanosecond can't be expressed this
+/// way; use `GeneralizedTime` (`time` feature) to preserve them.
+///
+/// It is implemented for [`DateTime`](super::DateTime) in UTC,
+/// `time::OffsetDateTime` (`time` feature),
+/// `chrono::DateTime<chrono::Utc>` (`chrono` feature) and
+/// `std::time::SystemTime` (`std` feature).
+///
+/// [`read_utctime_as`]: crate::BERReader::read_utctime_as
+/// [`read_generalized_time_as`]: crate::BERReader::read_generalized_time_as
+/// [`write_utctime_from`]: crate::DERWriter::write_utctime_from
+/// [`write_generalized_time_from`]: crate::DERWriter::write_generalized_time_from
+///
+/// # Examples
+///
+/// ```
+/// use yasna::models::AsnDateTime;
+/// #[derive(Debug, PartialEq)]
+/// struct Seconds(i64);
+/// impl AsnDateTime for Seconds {
+///     fn from_unix_time(secs: i64, nanos: u32) -> Option<Self> {
+///         if nanos == 0 { Some(Seconds(secs)) } else { None }
+///     }
+///     fn to_unix_time(&self) -> (i64, u32) {
+///         (self.0, 0)
+///     }
+/// }
+/// let der = yasna::construct_der(|writer| {
+///     writer.write_utctime_from(&Seconds(378820800))
+/// });
+/// assert_eq!(&der[2..], b"820102120000Z");
+/// let asn: Seconds = yasna::parse_der(&der, |reader| {
+///     reader.read_utctime_as()
+/// }).unwrap();
+/// assert_eq!(asn, Seconds(378820800));
+/// ```
+pub trait AsnDateTime: Sized {
+    /// Constructs a value from seconds and nanoseconds since the Unix
+    /// epoch, `nanos` being less than 1,000,000,000.
+    ///
+    /// Returns `None` if the value can't be represented.
+    fn from_unix_time(secs: i64, nanos: u32) -> Option<Self>;
+
+    /// Returns seconds and nanoseconds since the Unix epoch. The
+    /// nanoseconds must be less than 1,000,000,000.
+    fn to_unix_time(&self) -> (i64, u32);
+}
+
+#[cfg(feature = "time")]
+impl AsnDateTime for ::time::OffsetDateTime {
+    fn from_unix_time(secs: i64, nanos: u32) -> Option<Self> {
+        let nanos = secs as i128 * 1_000_000_000 + nanos as i128;
+        ::time::OffsetDateTime::from_unix_timestamp_nanos(nanos).ok()
+    }
+
+    fn to_unix_time(&self) -> (i64, u32) {
+        (self.unix_timestamp(), self.nanosecond())
+    }
+}
+
+/// Leap seconds are written as the last nanosecond of the previous second.
+#[cfg(feature = "chrono")]
+impl AsnDateTime for ::chrono::DateTime<::chrono::Utc> {
+    fn from_unix_time(secs: i64, nanos: u32) -> Option<Self> {
+        ::chrono::DateTime::from_timestamp(secs, nanos)
+    }
+
+    fn to_unix_time(&self) -> (i64, u32) {
+        (self.timestamp(), self.timestamp_subsec_nanos().min(999_999_999))
+    }
+}
+
+#[cfg(feature = "std")]
+impl AsnDateTime for std::time::SystemTime {
+    fn from_unix_time(secs: i64, nanos: u32) -> Option<Self> {
+        use std::time::{Duration, UNIX_EPOCH};
+        if secs >= 0 {
+            UNIX_EPOCH.checked_add(Duration::new(secs as u64, nanos))
+        } else {
+            UNIX_EPOCH.checked_sub(Duration::new((-secs) as u64, 0))?
+                .checked_add(Duration::new(0, nanos))
+        }
+    }
+
+    fn to_unix_time(&self) -> (i64, u32) {
+        use std::time::UNIX_EPOCH;
+        match self.duration_since(UNIX_EPOCH) {
+            Ok(d) => (d.as_secs() as i64, d.subsec_nanos()),
+            Err(e) => {
+                let d = e.duration();
+                if d.subsec_nanos() == 0 {
+                    (-(d.as_secs() as i64), 0)
+                } else {
+                    (-(d.as_secs() as i64) - 1, 1_000_000_000 - d.subsec_nanos())
+                }
+            },
+        }
+    }
+}
+
+/// Interprets the date and time as UTC, so that UTCTime and
+/// GeneralizedTime can be written from calendar components without any
+/// date-time library. The fraction of the second is written to
+/// GeneralizedTime, in as few digits as possible.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::models::{Date, DateTime, TimeOfDay};
+/// let datetime = DateTime::new(Date::new(2049, 12, 31).unwrap(),
+///     TimeOfDay::new(23, 59, 59).unwrap());
+/// let der = yasna::construct_der(|writer| {
+///     writer.write_utctime_from(&datetime)
+/// });
+/// assert_eq!(&der[2..], b"491231235959Z");
+/// let der = yasna::construct_der(|writer| {
+///     writer.write_generalized_time_from(&datetime)
+/// });
+/// assert_eq!(&der[2..], b"20491231235959Z");
+/// let datetime = datetime.with_nanosecond(250_000_000).unwrap();
+/// let der = yasna::construct_der(|writer| {
+///     writer.write_generalized_time_from(&datetime)
+/// });
+/// assert_eq!(&der[2..], b"20491231235959.25Z");
+/// ```
+impl AsnDateTime for super::DateTime {
+    fn from_unix_time(secs: i64, nanos: u32) -> Option<Self> {
+        use super::{Date, TimeOfDay};
+        if !(GENERALIZED_TIME_MIN..GENERALIZED_TIME_END).contains(&secs) {
+            return None;
+        }
+        let (days, hour, minute, second) = split_timestamp(secs);
+        let (year, month, day) = civil_from_days(days);
+        super::DateTime::new(
+            Date::new(year as u16, month as u8, day as u8)?,
+            TimeOfDay::new(hour as u8, minute as u8, second as u8)?)
+            .with_nanosecond(nanos)
+    }
+
+    fn to_unix_time(&self) -> (i64, u32) {
+        let (date, time) = (self.date(), self.time());
+        let days = days_from_civil(date.year() as i64, date.month() as i64,
+            date.day() as i64);
+        (days * 86400 + time.hour() as i64 * 3600 +
+            time.minute() as i64 * 60 + time.second() as i64,
+            self.nanosecond())
+    }
+}
+
+/// 1950-01-01T00:00:00Z
+const UTCTIME_MIN: i64 = -631152000;
+/// 2050-01-01T00:00:00Z
+const UTCTIME_END: i64 = 2524608000;
+/// 0000-01-01T00:00:00Z
+const GENERALIZED_TIME_MIN: i64 = -62167219200;
+/// 10000-01-01T00:00:00Z
+const GENERALIZED_TIME_END: i64 = 253402300800;
+
+/// Returns the number of days from 1970-01-01 to the given date
+/// in the proleptic Gregorian calendar.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = if year >= 0 { year } else { year - 399 } / 400;
+    let yoe = year - era * 400;
+    let mp = if month > 2 { month - 3 } else { month + 9 };
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Inverse of `days_from_civil`.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719468;
+    let era = if days >= 0 { days } else { days - 146096 } / 146097;
+    let doe = days - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = if month <= 2 { yoe + era * 400 + 1 } else { yoe + era * 400 };
+    (year, month, day)
+}
+
+fn is_valid_date(year: i64, month: i64, day: i64) -> bool {
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days_in_month).contains(&day)
+}
+
+fn digits(buf: &[u8], pos: usize, len: usize) -> Option<i64> {
+    let bytes = buf.get(pos..pos + len)?;
+    let mut value = 0;
+    for &b in bytes {
+        if !b.is_ascii_digit() {
+            return None;
+        }
+        value = value * 10 + (b - b'0') as i64;
+    }
+    Some(value)
+}
+
+/// Parses a time zone designator at `pos` to the end of `buf`, returning
+/// the offset in seconds. The minutes of the offset are optional if
+/// `short_offset` is true.
+fn parse_offset(buf: &[u8], pos: usize, short_offset: bool) -> Option<i64> {
+    let sign = match buf.get(pos) {
+        Some(&b'Z') if buf.len() == pos + 1 => return Some(0),
+        Some(&b'+') => 1,
+        Some(&b'-') => -1,
+        _ => return None,
+    };
+    let hour = digits(buf, pos + 1, 2)?;
+    let minute = if short_offset && buf.len() == pos + 3 {
+        0
+    } else if buf.len() == pos + 5 {
+        digits(buf, pos + 3, 2)?
+    } else {
+        return None;
+    };
+    if !(hour < 24 && minute < 60) {
+        return None;
+    }
+    Some(sign * (hour * 3600 + minute * 60))
+}
+
+fn to_timestamp(year: i64, month: i64, day: i64, hour: i64, minute: i64,
+        second: i64) -> Option<i64> {
+    if !(is_valid_date(year, month, day) &&
+            hour < 24 && minute < 60 && second < 60) {
+        return None;
+    }
+    Some(days_from_civil(year, month, day) * 86400
+        + hour * 3600 + minute * 60 + second)
+}
+
+/// Parses the contents of a UTCTime into Unix time, with the same rules
+/// as `UTCTime::parse`.
+pub(crate) fn parse_utctime(buf: &[u8]) -> Option<i64> {
+    let year = digits(buf, 0, 2)?;
+    let year = if year < 50 { year + 2000 } else { year + 1900 };
+    let month = digits(buf, 2, 2)?;
+    let day = digits(buf, 4, 2)?;
+    let hour = digits(buf, 6, 2)?;
+    let minute = digits(buf, 8, 2)?;
+    let (second, i) = match digits(buf, 10, 2) {
+        Some(second) => (second, 12),
+        None => (0, 10),
+    };
+    let offset = parse_offset(buf, i, false)?;
+    let t = to_timestamp(year, month, day, hour, minute, second)? - offset;
+    if !(UTCTIME_MIN..UTCTIME_END).contains(&t) {
+        return None;
+    }
+    Some(t)
+}
+
+/// Parses the contents of a GeneralizedTime into Unix time, with the same
+/// rules as `GeneralizedTime::parse`, except that leap seconds are
+/// rejected and fractions are truncated to nanoseconds.
+pub(crate) fn parse_generalized_time(buf: &[u8]) -> Option<(i64, u32)> {
+    let year = digits(buf, 0, 4)?;
+    let month = digits(buf, 4, 2)?;
+    let day = digits(buf, 6, 2)?;
+    let hour = digits(buf, 8, 2)?;
+    let mut i = 10;
+    // The length of the last given unit in nanoseconds
+    let mut unit: i64 = 3_600_000_000_000;
+    let mut minute = 0;
+    let mut second = 0;
+    if let Some(m) = digits(buf, i, 2) {
+        minute = m;
+        unit = 60_000_000_000;
+        i += 2;
+        if let Some(s) = digits(buf, i, 2) {
+            second = s;
+            unit = 1_000_000_000;
+            i += 2;
+        }
+    }
+    let mut fraction: i64 = 0;
+    if (buf.get(i) == Some(&b'.') || buf.get(i) == Some(&b',')) &&
+            digits(buf, i + 1, 1).is_some() {
+        i += 1;
+        let start = i;
+        while digits(buf, i, 1).is_some() {
+            i += 1;
+        }
+        let mut carry: i64 = 0;
+        for &b in buf[start..i].iter().rev() {
+            carry = ((b - b'0') as i64 * unit + carry) / 10;
+        }
+        fraction = carry;
+    }
+    let offset = parse_offset(buf, i, true)?;
+    let t = to_timestamp(year, month, day, hour, minute, second)? - offset
+        + fraction / 1_000_000_000;
+    if !(GENERALIZED_TIME_MIN..GENERALIZED_TIME_END).contains(&t) {
+        return None;
+    }
+    Some((t, (fraction % 1_000_000_000) as u32))
+}
+
+fn push_digits(buf: &mut Vec<u8>, value: i64, len: u32) {
+    for k in (0..len).rev() {
+        buf.push((value / 10i64.pow(k) % 10) as u8 + b'0');
+    }
+}
+
+fn split_timestamp(secs: i64) -> (i64, i64, i64, i64) {
+    let days = if secs >= 0 { secs / 86400 } else { (secs - 86399) / 86400 };
+    let rem = secs - days * 86400;
+    (days, rem / 3600, rem / 60 % 60, rem % 60)
+}
+
+/// Returns the DER contents of a UTCTime, or `None` if the time
+/// is out of range or has a fractional part.
+pub(crate) fn format_utctime(secs: i64, nanos: u32) -> Option<Vec<u8>> {
+    if !(UTCTIME_MIN..UTCTIME_END).contains(&secs) || nanos != 0 {
+        return None;
+    }
+    let (days, hour, minute, second) = split_timestamp(secs);
+    let (year, month, day) = civil_from_days(days);
+    let mut buf = Vec::with_capacity(13);
+    for &(value, len) in &[(year % 100, 2), (month, 2), (day, 2),
+            (hour, 2), (minute, 2), (second, 2)] {
+        push_digits(&mut buf, value, len);
+    }
+    buf.push(b'Z');
+    Some(buf)
+}
+
+/// Returns the DER contents of a GeneralizedTime, or `None` if the time
+/// is out of range.
+pub(crate) fn format_generalized_time(secs: i64, nanos: u32)
+        -> Option<Vec<u8>> {
+    if !(GENERALIZED_TIME_MIN..GENERALIZED_TIME_END).contains(&secs) ||
+            nanos >= 1_000_000_000 {
+        return None;
+    }
+    let (days, hour, minute, second) = split_timestamp(secs);
+    let (year, month, day) = civil_from_days(days);
+    let mut buf = Vec::with_capacity(24);
+    for &(value, len) in &[(year, 4), (month, 2), (day, 2),
+            (hour, 2), (minute, 2), (second, 2)] {
+        push_digits(&mut buf, value, len);
+    }
+    if nanos != 0 {
+        buf.push(b'.');
+        push_digits(&mut buf, nanos as i64, 9);
+        while buf.last() == Some(&b'0') {
+            buf.pop();
+        }
+    }
+    buf.push(b'Z');
+    Some(buf)
+}
+
+/// Checks that the contents of a GeneralizedTime, which
+/// `parse_generalized_time` accepts, are in the form required by DER: with
+/// seconds, a fraction separated by `.` and without trailing zeros, and
+/// `Z`. The fraction may be longer than nanoseconds.
+pub(crate) fn is_der_generalized_time(buf: &[u8]) -> bool {
+    if buf.len() < 15 || buf.last() != Some(&b'Z') ||
+            digits(buf, 0, 14).is_none() {
+        return false;
+    }
+    let fraction = &buf[14..buf.len() - 1];
+    fraction.is_empty() || (fraction.len() >= 2 && fraction[0] == b'.' &&
+        fraction[1..].iter().all(|b| b.is_ascii_digit()) &&
+        fraction.last() != Some(&b'0'))
+}
+
+#[test]
+fn test_civil_days() {
+    assert_eq!(days_from_civil(1970, 1, 1), 0);
+    assert_eq!(days_from_civil(2000, 3, 1), 11017);
+    assert_eq!(days_from_civil(0, 1, 1), -719528);
+    for &days in &[-719528, -1, 0, 59, 11016, 11017, 2932896] {
+        let (y, m, d) = civil_from_days(days);
+        assert_eq!(days_from_civil(y, m, d), days);
+    }
+}
+
+#[test]
+fn test_parse_utctime_unix() {
+    assert_eq!(parse_utctime(b"8201021200Z"), Some(378820800));
+    assert_eq!(parse_utctime(b"8201020700-0500"), Some(378820800));
+    assert_eq!(parse_utctime(b"000229123456Z"), Some(951827696));
+    assert_eq!(parse_utctime(b"500101000000Z"), Some(UTCTIME_MIN));
+    for &buf in &[&b"010229123456Z"[..], b"8201021200", b"8201021260Z",
+            b"8201021200+2400", b"8201021200Z ", b"491231235959-0001",
+            b"500101000000+0001"] {
+        assert_eq!(parse_utctime(buf), None);
+    }
+}
+
+#[test]
+fn test_parse_generalized_time_unix() {
+    assert_eq!(parse_generalized_time(b"19851106210627.3Z"),
+        Some((500159187, 300_000_000)));
+    assert_eq!(parse_generalized_time(b"1985110621.14159Z"),
+        Some((500159309, 724_000_000)));
+    assert_eq!(parse_generalized_time(b"19851106210627.3-05"),
+        Some((500177187, 300_000_000)));
+    assert_eq!(parse_generalized_time(b"00000101000000Z"),
+        Some((GENERALIZED_TIME_MIN, 0)));
+    for &buf in &[&b"19851106210627.3"[..], b"19851106210660Z",
+            b"19851106210627.Z", b"99991231235959-0100"] {
+        assert_eq!(parse_generalized_time(buf), None);
+    }
+}
+
+#[test]
+fn test_date_time_unix() {
+    use super::{Date, DateTime, TimeOfDay};
+    let tests = &[
+        ((1970, 1, 1), (0, 0, 0), 0),
+        ((1982, 1, 2), (12, 0, 0), 378820800),
+        ((0, 1, 1), (0, 0, 0), GENERALIZED_TIME_MIN),
+        ((9999, 12, 31), (23, 59, 59), GENERALIZED_TIME_END - 1),
+    ];
+    for &((year, month, day), (hour, minute, second), secs) in tests {
+        let datetime = DateTime::new(Date::new(year, month, day).unwrap(),
+            TimeOfDay::new(hour, minute, second).unwrap());
+        assert_eq!(datetime.to_unix_time(), (secs, 0));
+        assert_eq!(DateTime::from_unix_time(secs, 0), Some(datetime));
+    }
+    let datetime = DateTime::new(Date::new(1969, 12, 31).unwrap(),
+        TimeOfDay::new(23, 59, 59).unwrap()).with_nanosecond(1).unwrap();
+    assert_eq!(datetime.to_unix_time(), (-1, 1));
+    assert_eq!(DateTime::from_unix_time(-1, 1), Some(datetime));
+    assert_eq!(DateTime::from_unix_time(GENERALIZED_TIME_END, 0), None);
+}
+
+#[test]
+fn test_format_unix_time() {
+    assert_eq!(format_utctime(378820800, 0).unwrap(), b"820102120000Z");
+    assert_eq!(format_utctime(-631152000, 0).unwrap(), b"500101000000Z");
+    assert_eq!(format_utctime(-631152001, 0), None);
+    assert_eq!(format_utctime(378820800, 1), None);
+    assert_eq!(format_generalized_time(500159309, 724_000_000).unwrap(),
+        b"19851106210829.724Z");
+    assert_eq!(format_generalized_time(-62167219200, 0).unwrap(),
+        b"00000101000000Z");
+    assert_eq!(format_generalized_time(-1, 1).unwrap(),
+        b"19691231235959.000000001Z");
+    assert_eq!(format_generalized_time(253402300800, 0), None);
+}
+
+#[test]
+fn test_der_generalized_time() {
+    for &buf in &[&b"19851106210627Z"[..], b"19851106210627.3Z",
+            b"19851106210627.3000000001Z"] {
+        assert!(is_der_generalized_time(buf));
+    }
+    for &buf in &[&b"1985110621Z"[..], b"198511062106Z",
+            b"19851106210627.30Z", b"19851106210627,3Z",
+            b"19851106210627.Z", b"19851106210627.3-05",
+            b"19851106210627-05"] {
+        assert!(!is_der_generalized_time(buf));
+    }
+}
@@ -17,6 +17,7 @@ use super::super::tags::*;
 /// When constructed from bytes, the caller is responsible for
 /// providing valid DER.
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct TaggedDerValue {
     tag: Tag,
     pcbit: PCBit,
@@ -0,0 +1,48 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::{ObjectIdentifier, TaggedDerValue};
+
+/// A value of the ASN.1 EXTERNAL type, as encoded in BER (X.690 8.18).
+///
+/// ```text
+/// [UNIVERSAL 8] IMPLICIT SEQUENCE {
+///     direct-reference      OBJECT IDENTIFIER OPTIONAL,
+///     indirect-reference    INTEGER OPTIONAL,
+///     data-value-descriptor ObjectDescriptor OPTIONAL,
+///     encoding CHOICE {
+///         single-ASN1-type  [0] ABSTRACT-SYNTAX.&Type,
+///         octet-aligned     [1] IMPLICIT OCTET STRING,
+///         arbitrary         [2] IMPLICIT BIT STRING } }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct External {
+    /// The OID identifying the abstract and transfer syntaxes
+    pub direct_reference: Option<ObjectIdentifier>,
+    /// The presentation context identifier negotiated by OSI
+    pub indirect_reference: Option<i64>,
+    /// The human-readable description of the value
+    pub data_value_descriptor: Option<String>,
+    /// The value
+    pub encoding: ExternalEncoding,
+}
+
+/// The encoding of the value of an [`External`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum ExternalEncoding {
+    /// A single ASN.1 value
+    SingleAsn1Type(TaggedDerValue),
+    /// An encoding which is an integral number of octets
+    OctetAligned(Vec<u8>),
+    /// Any other encoding, as the bytes of a bit string and its length
+    /// in bits
+    Arbitrary(Vec<u8>, usize),
+}
@@ -0,0 +1,127 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use alloc::string::String;
+use core::fmt::{self, Display};
+
+/// A value of the ASN.1 OID-IRI type: an object identifier written as
+/// the Unicode labels of its arcs, e.g. `/ISO/Registration_Authority`.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::models::OidIri;
+/// let iri = OidIri::new("/Joint-ISO-ITU-T/Example".to_string()).unwrap();
+/// assert_eq!(iri.as_str(), "/Joint-ISO-ITU-T/Example");
+/// assert!(OidIri::new("Joint-ISO-ITU-T/Example".to_string()).is_none());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct OidIri {
+    iri: String,
+}
+
+impl OidIri {
+    /// Constructs a new `OidIri`.
+    ///
+    /// # Errors
+    ///
+    /// It returns `None` if `iri` doesn't consist of one or more arcs,
+    /// each preceded by `/`. An arc is a non-empty label made of letters,
+    /// digits, `-`, `.`, `_`, `~` and non-ASCII characters, and must not
+    /// have leading zeros if it is an integer.
+    pub fn new(iri: String) -> Option<Self> {
+        match iri.as_bytes().first() {
+            Some(b'/') if is_valid_arcs(&iri[1..]) => Some(OidIri { iri }),
+            _ => None,
+        }
+    }
+
+    /// Returns the IRI.
+    pub fn as_str(&self) -> &str {
+        &self.iri
+    }
+}
+
+impl Display for OidIri {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.iri)
+    }
+}
+
+/// A value of the ASN.1 RELATIVE-OID-IRI type: the Unicode labels of
+/// arcs relative to some object identifier, e.g. `Example/1`.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::models::RelativeOidIri;
+/// let iri = RelativeOidIri::new("Example/1".to_string()).unwrap();
+/// assert_eq!(iri.as_str(), "Example/1");
+/// assert!(RelativeOidIri::new("/Example".to_string()).is_none());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct RelativeOidIri {
+    iri: String,
+}
+
+impl RelativeOidIri {
+    /// Constructs a new `RelativeOidIri`.
+    ///
+    /// # Errors
+    ///
+    /// It returns `None` if `iri` doesn't consist of one or more arcs
+    /// separated by `/`, where arcs are as in [`OidIri::new`].
+    pub fn new(iri: String) -> Option<Self> {
+        if is_valid_arcs(&iri) {
+            Some(RelativeOidIri { iri })
+        } else {
+            None
+        }
+    }
+
+    /// Returns the IRI.
+    pub fn as_str(&self) -> &str {
+        &self.iri
+    }
+}
+
+impl Display for RelativeOidIri {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.iri)
+    }
+}
+
+fn is_valid_arcs(arcs: &str) -> bool {
+    arcs.split('/').all(|arc| {
+        let label_ok = arc.chars().all(|c| {
+            !c.is_ascii() || c.is_ascii_alphanumeric() ||
+                c == '-' || c == '.' || c == '_' || c == '~'
+        });
+        let integer = arc.bytes().all(|b| b.is_ascii_digit());
+        !arc.is_empty() && label_ok &&
+            !(integer && arc.len() > 1 && arc.starts_with('0'))
+    })
+}
+
+#[test]
+fn test_iri_new() {
+    use alloc::string::ToString;
+    let valid = &["/ISO", "/ISO/Registration_Authority/0",
+        "/2/27/\u{E9}t\u{E9}", "/a.b-c~d/10"];
+    for &iri in valid {
+        assert_eq!(OidIri::new(iri.to_string()).unwrap().as_str(), iri);
+        assert_eq!(RelativeOidIri::new(iri[1..].to_string()).unwrap()
+            .to_string(), &iri[1..]);
+        assert!(RelativeOidIri::new(iri.to_string()).is_none());
+        assert!(OidIri::new(iri[1..].to_string()).is_none());
+    }
+    let invalid = &["", "/", "//ISO", "/ISO/", "/01", "/IS O", "/ISO#1"];
+    for &iri in invalid {
+        assert!(OidIri::new(iri.to_string()).is_none());
+    }
+}
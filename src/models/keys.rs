@@ -0,0 +1,295 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Public and private key containers: SubjectPublicKeyInfo (RFC 5280)
+//! and PKCS #8 PrivateKeyInfo, encrypted or not (RFC 5958).
+//!
+//! These types only wrap and unwrap keys: the key bytes themselves are
+//! left to the algorithm-specific code.
+//!
+//! # Examples
+//!
+//! ```
+//! use yasna::models::ObjectIdentifier;
+//! use yasna::models::keys::SubjectPublicKeyInfo;
+//! use yasna::models::x509::AlgorithmIdentifier;
+//! // id-Ed25519
+//! let algorithm = AlgorithmIdentifier {
+//!     algorithm: ObjectIdentifier::from_slice(&[1, 3, 101, 112]),
+//!     parameters: None,
+//! };
+//! let spki = SubjectPublicKeyInfo::new(algorithm, vec![0; 32]);
+//! let der = yasna::encode_der(&spki);
+//! assert_eq!(&der[..12], &[48, 42, 48, 5, 6, 3, 43, 101, 112, 3, 33, 0]);
+//! let spki2: SubjectPublicKeyInfo = yasna::decode_der(&der).unwrap();
+//! assert_eq!(spki2.subject_public_key, vec![0; 32]);
+//! ```
+//!
+//! # Features
+//!
+//! This module is enabled by `x509` feature.
+//!
+//! ```toml
+//! [dependencies]
+//! yasna = { version = "*", features = ["x509"] }
+//! ```
+
+use alloc::vec::Vec;
+
+use super::super::{BERDecodable, BERReader, DEREncodable, DERWriter};
+use super::super::{ASN1Result, ASN1Error, ASN1ErrorKind, Tag};
+use super::TaggedDerValue;
+use super::x509::AlgorithmIdentifier;
+
+/// `SubjectPublicKeyInfo`: a public key and its algorithm.
+///
+/// ```text
+/// SubjectPublicKeyInfo ::= SEQUENCE {
+///     algorithm         AlgorithmIdentifier,
+///     subjectPublicKey  BIT STRING }
+/// ```
+///
+/// The key must be a whole number of bytes, which is the case for every
+/// public key format in use.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct SubjectPublicKeyInfo {
+    /// The algorithm and its parameters
+    pub algorithm: AlgorithmIdentifier,
+    /// The contents of the `subjectPublicKey` bit string
+    pub subject_public_key: Vec<u8>,
+}
+
+impl SubjectPublicKeyInfo {
+    /// Wraps a public key.
+    pub fn new(algorithm: AlgorithmIdentifier, subject_public_key: Vec<u8>)
+            -> Self {
+        SubjectPublicKeyInfo {
+            algorithm,
+            subject_public_key,
+        }
+    }
+}
+
+impl BERDecodable for SubjectPublicKeyInfo {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        reader.read_sequence(|reader| {
+            let algorithm = AlgorithmIdentifier::decode_ber(reader.next())?;
+            let subject_public_key = read_key_bits(reader.next())?;
+            Ok(SubjectPublicKeyInfo {
+                algorithm,
+                subject_public_key,
+            })
+        })
+    }
+}
+
+impl DEREncodable for SubjectPublicKeyInfo {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_sequence(|writer| {
+            self.algorithm.encode_der(writer.next());
+            let key = &self.subject_public_key;
+            writer.next().write_bitvec_bytes(key, key.len() * 8);
+        })
+    }
+}
+
+/// `PrivateKeyInfo` (also known as `OneAsymmetricKey`): an unencrypted
+/// PKCS #8 private key.
+///
+/// ```text
+/// PrivateKeyInfo ::= SEQUENCE {
+///     version                   INTEGER { v1(0), v2(1) },
+///     privateKeyAlgorithm       AlgorithmIdentifier,
+///     privateKey                OCTET STRING,
+///     attributes            [0] IMPLICIT Attributes OPTIONAL,
+///     publicKey             [1] IMPLICIT BIT STRING OPTIONAL }
+/// ```
+///
+/// The version is not stored: v2 is written if and only if there is a
+/// public key.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PrivateKeyInfo {
+    /// The algorithm and its parameters
+    pub algorithm: AlgorithmIdentifier,
+    /// The contents of the `privateKey` octet string
+    pub private_key: Vec<u8>,
+    /// The encoded `Attribute` elements, if the `attributes` field is
+    /// present
+    pub attributes: Option<Vec<TaggedDerValue>>,
+    /// The contents of the `publicKey` bit string, if present
+    pub public_key: Option<Vec<u8>>,
+}
+
+impl PrivateKeyInfo {
+    /// Wraps a private key, without attributes or public key.
+    pub fn new(algorithm: AlgorithmIdentifier, private_key: Vec<u8>) -> Self {
+        PrivateKeyInfo {
+            algorithm,
+            private_key,
+            attributes: None,
+            public_key: None,
+        }
+    }
+}
+
+impl BERDecodable for PrivateKeyInfo {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        reader.read_sequence(|reader| {
+            let version = reader.next().read_u8()?;
+            if version > 1 {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            let algorithm = AlgorithmIdentifier::decode_ber(reader.next())?;
+            let private_key = reader.next().read_bytes()?;
+            let attributes = reader.read_optional(|reader| {
+                reader.read_tagged_implicit(Tag::context(0), |reader| {
+                    reader.collect_set_of(|reader| reader.read_tagged_der())
+                })
+            })?;
+            let public_key = if version == 1 {
+                reader.read_optional(|reader| {
+                    reader.read_tagged_implicit(Tag::context(1), read_key_bits)
+                })?
+            } else {
+                None
+            };
+            Ok(PrivateKeyInfo {
+                algorithm,
+                private_key,
+                attributes,
+                public_key,
+            })
+        })
+    }
+}
+
+impl DEREncodable for PrivateKeyInfo {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_sequence(|writer| {
+            let version = if self.public_key.is_some() { 1 } else { 0 };
+            writer.next().write_u8(version);
+            self.algorithm.encode_der(writer.next());
+            writer.next().write_bytes(&self.private_key);
+            if let Some(ref attributes) = self.attributes {
+                writer.next().write_tagged_implicit(Tag::context(0), |writer| {
+                    writer.write_set_of(|writer| {
+                        for attribute in attributes {
+                            writer.next().write_tagged_der(attribute);
+                        }
+                    })
+                });
+            }
+            if let Some(ref key) = self.public_key {
+                writer.next().write_tagged_implicit(Tag::context(1), |writer| {
+                    writer.write_bitvec_bytes(key, key.len() * 8)
+                });
+            }
+        })
+    }
+}
+
+/// `EncryptedPrivateKeyInfo`: an encrypted PKCS #8 private key.
+///
+/// ```text
+/// EncryptedPrivateKeyInfo ::= SEQUENCE {
+///     encryptionAlgorithm  AlgorithmIdentifier,
+///     encryptedData        OCTET STRING }
+/// ```
+///
+/// Decrypting `encrypted_data` yields the DER encoding of a
+/// [`PrivateKeyInfo`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct EncryptedPrivateKeyInfo {
+    /// The encryption scheme and its parameters, e.g. PBES2
+    pub encryption_algorithm: AlgorithmIdentifier,
+    /// The encrypted `PrivateKeyInfo`
+    pub encrypted_data: Vec<u8>,
+}
+
+impl BERDecodable for EncryptedPrivateKeyInfo {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        reader.read_sequence(|reader| {
+            let encryption_algorithm =
+                AlgorithmIdentifier::decode_ber(reader.next())?;
+            let encrypted_data = reader.next().read_bytes()?;
+            Ok(EncryptedPrivateKeyInfo {
+                encryption_algorithm,
+                encrypted_data,
+            })
+        })
+    }
+}
+
+impl DEREncodable for EncryptedPrivateKeyInfo {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_sequence(|writer| {
+            self.encryption_algorithm.encode_der(writer.next());
+            writer.next().write_bytes(&self.encrypted_data);
+        })
+    }
+}
+
+/// Reads a BIT STRING holding a whole number of bytes.
+fn read_key_bits(reader: BERReader) -> ASN1Result<Vec<u8>> {
+    let (bytes, len) = reader.read_bitvec_bytes()?;
+    if len != bytes.len() * 8 {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+    Ok(bytes)
+}
+
+#[test]
+fn test_keys_roundtrip() {
+    use super::super::{decode_der, encode_der};
+    use super::ObjectIdentifier;
+    use alloc::vec;
+
+    let x25519 = AlgorithmIdentifier {
+        algorithm: ObjectIdentifier::from_slice(&[1, 3, 101, 110]),
+        parameters: None,
+    };
+
+    // RFC 8410, section 10.3
+    let data = &[48, 46, 2, 1, 0, 48, 5, 6, 3, 43, 101, 110, 4, 34, 4, 32,
+        1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16,
+        17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32];
+    let value: PrivateKeyInfo = decode_der(data).unwrap();
+    assert_eq!(value.algorithm, x25519);
+    assert_eq!(value.private_key.len(), 34);
+    assert_eq!(value.attributes, None);
+    assert_eq!(value.public_key, None);
+    assert_eq!(encode_der(&value), &data[..]);
+
+    let mut value = PrivateKeyInfo::new(x25519.clone(), vec![4, 1, 7]);
+    value.attributes = Some(vec![]);
+    value.public_key = Some(vec![9, 9]);
+    let der = encode_der(&value);
+    assert_eq!(der, &[48, 22, 2, 1, 1, 48, 5, 6, 3, 43, 101, 110,
+        4, 3, 4, 1, 7, 160, 0, 129, 3, 0, 9, 9][..]);
+    assert_eq!(decode_der::<PrivateKeyInfo>(&der).unwrap(), value);
+
+    // publicKey in a v1 structure
+    decode_der::<PrivateKeyInfo>(&[48, 19, 2, 1, 0, 48, 5, 6, 3, 43, 101,
+        110, 4, 3, 4, 1, 7, 129, 2, 0, 9]).unwrap_err();
+    // Unknown version
+    decode_der::<PrivateKeyInfo>(&[48, 14, 2, 1, 2, 48, 5, 6, 3, 43, 101,
+        110, 4, 2, 4, 0]).unwrap_err();
+
+    let data = &[48, 12, 48, 5, 6, 3, 43, 101, 110, 3, 3, 0, 9, 9];
+    let value: SubjectPublicKeyInfo = decode_der(data).unwrap();
+    assert_eq!(value, SubjectPublicKeyInfo::new(x25519.clone(), vec![9, 9]));
+    assert_eq!(encode_der(&value), &data[..]);
+    // Partial byte
+    decode_der::<SubjectPublicKeyInfo>(
+        &[48, 12, 48, 5, 6, 3, 43, 101, 110, 3, 3, 1, 9, 8]).unwrap_err();
+
+    let data = &[48, 12, 48, 5, 6, 3, 43, 101, 110, 4, 3, 1, 2, 3];
+    let value: EncryptedPrivateKeyInfo = decode_der(data).unwrap();
+    assert_eq!(value.encrypted_data, &[1, 2, 3]);
+    assert_eq!(encode_der(&value), &data[..]);
+}
@@ -11,11 +11,33 @@
 #![forbid(missing_docs)]
 
 mod oid;
+mod iri;
 #[cfg(feature = "time")]
 mod time;
 mod der;
+mod datetime;
+mod date;
+mod external;
+pub mod snmp;
+#[cfg(feature = "x509")]
+pub mod x509;
+#[cfg(feature = "x509")]
+pub mod keys;
+#[cfg(feature = "const-generics")]
+mod tagging;
 
 pub use self::oid::{ObjectIdentifier, ParseOidError};
+pub use self::iri::{OidIri, RelativeOidIri};
 #[cfg(feature = "time")]
 pub use self::time::{UTCTime,GeneralizedTime};
 pub use self::der::TaggedDerValue;
+pub use self::datetime::AsnDateTime;
+pub use self::date::{Date, TimeOfDay, DateTime, Duration};
+pub use self::external::{External, ExternalEncoding};
+#[cfg(feature = "const-generics")]
+pub use self::tagging::{Explicit, Implicit};
+#[cfg(feature = "const-generics")]
+pub use self::tagging::{UNIVERSAL, APPLICATION, CONTEXT, PRIVATE};
+pub(crate) use self::datetime::{parse_utctime, parse_generalized_time};
+pub(crate) use self::datetime::{format_utctime, format_generalized_time};
+pub(crate) use self::datetime::is_der_generalized_time;
@@ -120,7 +120,20 @@ impl Display for ObjectIdentifier {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for ObjectIdentifier {
+    fn format(&self, f: defmt::Formatter) {
+        if let Some((&first, rest)) = self.components.split_first() {
+            defmt::write!(f, "{=u64}", first);
+            for &component in rest {
+                defmt::write!(f, ".{=u64}", component);
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 /// An error indicating failure to parse an Object identifier
 pub struct ParseOidError(());
 
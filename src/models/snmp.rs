@@ -0,0 +1,187 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Application types of the SNMP SMI (RFC 2578, RFC 3416).
+//!
+//! Each type is an IMPLICITly tagged INTEGER or OCTET STRING. The
+//! wrappers here read and write them with their tags, so that a varbind
+//! value can be handled without spelling out the tagging.
+//!
+//! # Examples
+//!
+//! ```
+//! use yasna::models::snmp::{Counter32, TimeTicks};
+//! let der = yasna::encode_der(&(Counter32(300), TimeTicks(5)));
+//! assert_eq!(der, &[48, 7, 65, 2, 1, 44, 67, 1, 5]);
+//! let (c, t): (Counter32, TimeTicks) = yasna::decode_ber(&der).unwrap();
+//! assert_eq!((c.0, t.0), (300, 5));
+//! ```
+
+use alloc::vec::Vec;
+
+use super::super::{BERDecodable, BERReader, DEREncodable, DERWriter};
+use super::super::{ASN1Result, ASN1Error, ASN1ErrorKind, Tag, TagClass};
+
+/// `IpAddress ::= [APPLICATION 0] IMPLICIT OCTET STRING (SIZE (4))`
+pub const TAG_IPADDRESS : Tag = Tag {
+    tag_class: TagClass::Application,
+    tag_number: 0,
+};
+/// `Counter32 ::= [APPLICATION 1] IMPLICIT INTEGER (0..4294967295)`
+pub const TAG_COUNTER32 : Tag = Tag {
+    tag_class: TagClass::Application,
+    tag_number: 1,
+};
+/// `Gauge32 ::= [APPLICATION 2] IMPLICIT INTEGER (0..4294967295)`
+///
+/// `Unsigned32` shares this tag.
+pub const TAG_GAUGE32 : Tag = Tag {
+    tag_class: TagClass::Application,
+    tag_number: 2,
+};
+/// `TimeTicks ::= [APPLICATION 3] IMPLICIT INTEGER (0..4294967295)`
+pub const TAG_TIMETICKS : Tag = Tag {
+    tag_class: TagClass::Application,
+    tag_number: 3,
+};
+/// `Opaque ::= [APPLICATION 4] IMPLICIT OCTET STRING`
+pub const TAG_OPAQUE : Tag = Tag {
+    tag_class: TagClass::Application,
+    tag_number: 4,
+};
+/// `Counter64 ::= [APPLICATION 6] IMPLICIT INTEGER (0..18446744073709551615)`
+pub const TAG_COUNTER64 : Tag = Tag {
+    tag_class: TagClass::Application,
+    tag_number: 6,
+};
+
+macro_rules! implicit_integer {
+    ($name:ident, $ty:ty, $tag:expr, $read:ident, $write:ident, $doc:literal) => {
+        #[doc = $doc]
+        #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd,
+            Hash)]
+        pub struct $name(pub $ty);
+
+        impl BERDecodable for $name {
+            fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+                reader.read_tagged_implicit($tag, |reader| {
+                    reader.$read().map($name)
+                })
+            }
+        }
+
+        impl DEREncodable for $name {
+            fn encode_der(&self, writer: DERWriter) {
+                writer.write_tagged_implicit($tag, |writer| {
+                    writer.$write(self.0)
+                })
+            }
+        }
+    };
+}
+
+implicit_integer!(Counter32, u32, TAG_COUNTER32, read_u32, write_u32,
+    "`Counter32`: a wrapping counter.");
+implicit_integer!(Gauge32, u32, TAG_GAUGE32, read_u32, write_u32,
+    "`Gauge32` (also `Unsigned32`): a non-negative value which may go \
+     up and down.");
+implicit_integer!(TimeTicks, u32, TAG_TIMETICKS, read_u32, write_u32,
+    "`TimeTicks`: a duration in hundredths of a second.");
+implicit_integer!(Counter64, u64, TAG_COUNTER64, read_u64, write_u64,
+    "`Counter64`: a wrapping 64-bit counter.");
+
+/// `IpAddress`: an IPv4 address, in network byte order.
+///
+/// With `std` feature, it converts from and to `std::net::Ipv4Addr`.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct IpAddress(pub [u8; 4]);
+
+impl BERDecodable for IpAddress {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        let bytes = reader.read_tagged_implicit(TAG_IPADDRESS, |reader| {
+            reader.read_bytes()
+        })?;
+        if bytes.len() != 4 {
+            return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+        }
+        Ok(IpAddress([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+impl DEREncodable for IpAddress {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_tagged_implicit(TAG_IPADDRESS, |writer| {
+            writer.write_bytes(&self.0)
+        })
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::net::Ipv4Addr> for IpAddress {
+    fn from(addr: std::net::Ipv4Addr) -> Self {
+        IpAddress(addr.octets())
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<IpAddress> for std::net::Ipv4Addr {
+    fn from(addr: IpAddress) -> Self {
+        addr.0.into()
+    }
+}
+
+/// `Opaque`: arbitrary data, usually the BER encoding of another value.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Opaque(pub Vec<u8>);
+
+impl BERDecodable for Opaque {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        reader.read_tagged_implicit(TAG_OPAQUE, |reader| {
+            reader.read_bytes().map(Opaque)
+        })
+    }
+}
+
+impl DEREncodable for Opaque {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_tagged_implicit(TAG_OPAQUE, |writer| {
+            writer.write_bytes(&self.0)
+        })
+    }
+}
+
+#[test]
+fn test_snmp_roundtrip() {
+    use super::super::{decode_ber, decode_der, encode_der};
+    use alloc::vec;
+
+    let data = &[65, 5, 0, 255, 255, 255, 255];
+    assert_eq!(decode_der::<Counter32>(data).unwrap(), Counter32(4294967295));
+    assert_eq!(encode_der(&Counter32(4294967295)), &data[..]);
+    decode_der::<Counter32>(&[65, 5, 1, 0, 0, 0, 0]).unwrap_err();
+    decode_der::<Gauge32>(&[65, 1, 0]).unwrap_err();
+    decode_der::<Gauge32>(&[66, 1, 255]).unwrap_err();
+    assert_eq!(encode_der(&Gauge32(1)), &[66, 1, 1]);
+    assert_eq!(encode_der(&TimeTicks(128)), &[67, 2, 0, 128]);
+
+    let data = &[70, 9, 0, 255, 255, 255, 255, 255, 255, 255, 255];
+    assert_eq!(decode_der::<Counter64>(data).unwrap(), Counter64(18446744073709551615));
+    assert_eq!(encode_der(&Counter64(18446744073709551615)), &data[..]);
+
+    let data = &[64, 4, 192, 0, 2, 1];
+    assert_eq!(decode_der::<IpAddress>(data).unwrap(),
+        IpAddress([192, 0, 2, 1]));
+    assert_eq!(encode_der(&IpAddress([192, 0, 2, 1])), &data[..]);
+    decode_der::<IpAddress>(&[64, 3, 192, 0, 2]).unwrap_err();
+
+    // Opaque may be segmented in BER.
+    let data = &[100, 128, 4, 1, 1, 4, 1, 2, 0, 0];
+    assert_eq!(decode_ber::<Opaque>(data).unwrap(), Opaque(vec![1, 2]));
+    decode_der::<Opaque>(data).unwrap_err();
+    assert_eq!(encode_der(&Opaque(vec![1, 2])), &[68, 2, 1, 2]);
+}
@@ -0,0 +1,151 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::super::{BERDecodable, BERReader, DEREncodable, DERWriter};
+use super::super::{ASN1Result, Tag, TAG_CLASSES};
+
+/// The UNIVERSAL class, for the `CLASS` parameter of [`Explicit`] and
+/// [`Implicit`]
+pub const UNIVERSAL: u8 = 0;
+/// The APPLICATION class, for the `CLASS` parameter of [`Explicit`] and
+/// [`Implicit`]
+pub const APPLICATION: u8 = 1;
+/// The CONTEXT-SPECIFIC class, for the `CLASS` parameter of [`Explicit`]
+/// and [`Implicit`]
+pub const CONTEXT: u8 = 2;
+/// The PRIVATE class, for the `CLASS` parameter of [`Explicit`] and
+/// [`Implicit`]
+pub const PRIVATE: u8 = 3;
+
+/// A value of type `T`, explicitly tagged with the tag of class `CLASS`
+/// and number `N`: `[CLASS N] EXPLICIT T`.
+///
+/// `CLASS` is one of [`UNIVERSAL`], [`APPLICATION`], [`CONTEXT`] and
+/// [`PRIVATE`].
+///
+/// # Examples
+///
+/// ```
+/// use yasna::models::{Explicit, CONTEXT};
+/// let der = yasna::encode_der(&Explicit::<CONTEXT, 0, i64>(10));
+/// assert_eq!(der, &[160, 3, 2, 1, 10]);
+/// let value: Explicit<CONTEXT, 0, i64> = yasna::decode_der(&der).unwrap();
+/// assert_eq!(value.0, 10);
+/// ```
+///
+/// # Features
+///
+/// This type is enabled by `const-generics` feature, which requires
+/// Rust 1.51.
+///
+/// ```toml
+/// [dependencies]
+/// yasna = { version = "*", features = ["const-generics"] }
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Explicit<const CLASS: u8, const N: u64, T>(pub T);
+
+impl<const CLASS: u8, const N: u64, T> Explicit<CLASS, N, T> {
+    /// The tag of the value
+    pub const TAG: Tag = Tag {
+        tag_class: TAG_CLASSES[CLASS as usize],
+        tag_number: N,
+    };
+}
+
+impl<const CLASS: u8, const N: u64, T> BERDecodable for Explicit<CLASS, N, T>
+        where T: BERDecodable {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        reader.read_tagged(Self::TAG, T::decode_ber).map(Explicit)
+    }
+}
+
+impl<const CLASS: u8, const N: u64, T> DEREncodable for Explicit<CLASS, N, T>
+        where T: DEREncodable {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_tagged(Self::TAG, |writer| self.0.encode_der(writer))
+    }
+}
+
+/// A value of type `T`, implicitly tagged with the tag of class `CLASS`
+/// and number `N`: `[CLASS N] IMPLICIT T`.
+///
+/// `CLASS` is one of [`UNIVERSAL`], [`APPLICATION`], [`CONTEXT`] and
+/// [`PRIVATE`]. As in ASN.1, `T` must not be a CHOICE type, whose tag
+/// can't be replaced.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::models::{Implicit, CONTEXT};
+/// let der = yasna::encode_der(&Implicit::<CONTEXT, 1, bool>(true));
+/// assert_eq!(der, &[129, 1, 255]);
+/// let value: Implicit<CONTEXT, 1, bool> = yasna::decode_der(&der).unwrap();
+/// assert!(value.0);
+/// ```
+///
+/// # Features
+///
+/// This type is enabled by `const-generics` feature, which requires
+/// Rust 1.51.
+///
+/// ```toml
+/// [dependencies]
+/// yasna = { version = "*", features = ["const-generics"] }
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Implicit<const CLASS: u8, const N: u64, T>(pub T);
+
+impl<const CLASS: u8, const N: u64, T> Implicit<CLASS, N, T> {
+    /// The tag of the value
+    pub const TAG: Tag = Tag {
+        tag_class: TAG_CLASSES[CLASS as usize],
+        tag_number: N,
+    };
+}
+
+impl<const CLASS: u8, const N: u64, T> BERDecodable for Implicit<CLASS, N, T>
+        where T: BERDecodable {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        reader.read_tagged_implicit(Self::TAG, T::decode_ber).map(Implicit)
+    }
+}
+
+impl<const CLASS: u8, const N: u64, T> DEREncodable for Implicit<CLASS, N, T>
+        where T: DEREncodable {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_tagged_implicit(Self::TAG, |writer| {
+            self.0.encode_der(writer)
+        })
+    }
+}
+
+#[test]
+fn test_tagging_roundtrip() {
+    use alloc::vec;
+    use alloc::vec::Vec;
+    use super::super::{decode_ber, decode_der, encode_der};
+
+    type Pair = (Explicit<CONTEXT, 0, i64>, Implicit<APPLICATION, 40, Vec<u8>>);
+    let data = &[48, 10, 160, 3, 2, 1, 10, 95, 40, 2, 1, 2];
+    let pair: Pair = decode_der(data).unwrap();
+    assert_eq!(pair, (Explicit(10), Implicit(vec![1, 2])));
+    assert_eq!(encode_der(&pair), &data[..]);
+
+    // The tag must match.
+    decode_der::<Explicit<CONTEXT, 1, i64>>(&[160, 3, 2, 1, 10]).unwrap_err();
+    decode_der::<Explicit<PRIVATE, 0, i64>>(&[160, 3, 2, 1, 10]).unwrap_err();
+    decode_der::<Implicit<CONTEXT, 0, i64>>(&[160, 3, 2, 1, 10]).unwrap_err();
+
+    // Explicit tagging is constructed, even around a primitive value.
+    decode_ber::<Explicit<CONTEXT, 0, i64>>(&[128, 1, 10]).unwrap_err();
+    assert_eq!(decode_ber::<Explicit<CONTEXT, 0, i64>>(
+        &[160, 128, 2, 1, 10, 0, 0]).unwrap(), Explicit(10));
+
+    assert_eq!(encode_der(&Implicit::<UNIVERSAL, 10, i64>(3)), &[10, 1, 3]);
+}
@@ -0,0 +1,461 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+//! Building blocks of X.509 certificates and related structures
+//! (RFC 5280).
+//!
+//! This is not a certificate library: it provides the few structures
+//! that almost every PKI-related format shares, so that they need not be
+//! written again on top of yasna.
+//!
+//! # Examples
+//!
+//! ```
+//! use yasna::models::ObjectIdentifier;
+//! use yasna::models::x509::AlgorithmIdentifier;
+//! // sha256WithRSAEncryption, with NULL parameters
+//! let der = [48, 13, 6, 9, 42, 134, 72, 134, 247, 13, 1, 1, 11, 5, 0];
+//! let alg: AlgorithmIdentifier = yasna::decode_der(&der).unwrap();
+//! assert_eq!(alg.algorithm,
+//!     ObjectIdentifier::from_slice(&[1, 2, 840, 113549, 1, 1, 11]));
+//! assert_eq!(yasna::encode_der(&alg), der);
+//! ```
+//!
+//! # Features
+//!
+//! This module is enabled by `x509` feature.
+//!
+//! ```toml
+//! [dependencies]
+//! yasna = { version = "*", features = ["x509"] }
+//! ```
+
+use alloc::vec::Vec;
+
+use super::super::{BERDecodable, BERReader, DEREncodable, DERWriter};
+use super::super::{ASN1Result, ASN1Error, ASN1ErrorKind, Profile};
+use super::super::tags::{TAG_INTEGER, TAG_UTCTIME, TAG_GENERALIZEDTIME};
+use super::{GeneralizedTime, ObjectIdentifier, TaggedDerValue, UTCTime};
+
+/// `AlgorithmIdentifier`: an algorithm OID and its optional parameters.
+///
+/// ```text
+/// AlgorithmIdentifier ::= SEQUENCE {
+///     algorithm   OBJECT IDENTIFIER,
+///     parameters  ANY DEFINED BY algorithm OPTIONAL }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct AlgorithmIdentifier {
+    /// The algorithm
+    pub algorithm: ObjectIdentifier,
+    /// The parameters, whose type depends on the algorithm
+    pub parameters: Option<TaggedDerValue>,
+}
+
+impl BERDecodable for AlgorithmIdentifier {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        reader.read_sequence(|reader| {
+            let algorithm = reader.next().read_oid()?;
+            let parameters = reader.read_optional(|reader| {
+                reader.read_tagged_der()
+            })?;
+            Ok(AlgorithmIdentifier {
+                algorithm,
+                parameters,
+            })
+        })
+    }
+}
+
+impl DEREncodable for AlgorithmIdentifier {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_sequence(|writer| {
+            writer.next().write_oid(&self.algorithm);
+            if let Some(ref parameters) = self.parameters {
+                writer.next().write_tagged_der(parameters);
+            }
+        })
+    }
+}
+
+/// `Extension`: a certificate, CRL or request extension.
+///
+/// ```text
+/// Extension ::= SEQUENCE {
+///     extnID      OBJECT IDENTIFIER,
+///     critical    BOOLEAN DEFAULT FALSE,
+///     extnValue   OCTET STRING }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Extension {
+    /// The extension type
+    pub extn_id: ObjectIdentifier,
+    /// Whether a consumer must reject the structure if it doesn't
+    /// understand the extension
+    pub critical: bool,
+    /// The DER encoding of the extension value
+    pub extn_value: Vec<u8>,
+}
+
+impl BERDecodable for Extension {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        reader.read_sequence(|reader| {
+            let extn_id = reader.next().read_oid()?;
+            let critical = reader.read_default(false, |reader| {
+                reader.read_bool()
+            })?;
+            let extn_value = reader.next().read_bytes()?;
+            Ok(Extension {
+                extn_id,
+                critical,
+                extn_value,
+            })
+        })
+    }
+}
+
+impl DEREncodable for Extension {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_sequence(|writer| {
+            writer.next().write_oid(&self.extn_id);
+            if self.critical {
+                writer.next().write_bool(true);
+            }
+            writer.next().write_bytes(&self.extn_value);
+        })
+    }
+}
+
+/// `CertificateSerialNumber`: an integer of arbitrary length, kept as
+/// its two's complement big-endian encoding.
+///
+/// With [`Profile::Rfc5280`], the serial number must be positive and at
+/// most 20 octets long.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::ParseOptions;
+/// use yasna::models::x509::SerialNumber;
+/// let der = [2, 2, 0, 200];
+/// let serial: SerialNumber = yasna::decode_der(&der).unwrap();
+/// assert_eq!(serial.bytes, &[0, 200]);
+/// assert!(yasna::decode_with_options::<SerialNumber>(
+///     &[2, 1, 0], ParseOptions::rfc5280()).is_err());
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct SerialNumber {
+    /// The contents octets of the INTEGER
+    pub bytes: Vec<u8>,
+}
+
+impl BERDecodable for SerialNumber {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        let profile = reader.options().profile();
+        let (bytes, positive) = reader.read_bigint_bytes()?;
+        if profile == Profile::Rfc5280 &&
+                (!positive || bytes.len() > 20 || bytes == [0u8]) {
+            return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+        }
+        Ok(SerialNumber {
+            bytes,
+        })
+    }
+}
+
+impl DEREncodable for SerialNumber {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_tagged_der(&TaggedDerValue::from_tag_and_bytes(
+            TAG_INTEGER, self.bytes.clone()));
+    }
+}
+
+/// `AttributeTypeAndValue`: a single attribute of a [`Name`].
+///
+/// ```text
+/// AttributeTypeAndValue ::= SEQUENCE {
+///     type    OBJECT IDENTIFIER,
+///     value   ANY DEFINED BY type }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct AttributeTypeAndValue {
+    /// The attribute type, e.g. `2.5.4.3` (commonName)
+    pub attr_type: ObjectIdentifier,
+    /// The attribute value, usually a string
+    pub value: TaggedDerValue,
+}
+
+impl BERDecodable for AttributeTypeAndValue {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        reader.read_sequence(|reader| {
+            let attr_type = reader.next().read_oid()?;
+            let value = reader.next().read_tagged_der()?;
+            Ok(AttributeTypeAndValue {
+                attr_type,
+                value,
+            })
+        })
+    }
+}
+
+impl DEREncodable for AttributeTypeAndValue {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_sequence(|writer| {
+            writer.next().write_oid(&self.attr_type);
+            writer.next().write_tagged_der(&self.value);
+        })
+    }
+}
+
+/// `Name`: a distinguished name, as a sequence of relative
+/// distinguished names (RDNs).
+///
+/// ```text
+/// Name ::= RDNSequence
+/// RDNSequence ::= SEQUENCE OF RelativeDistinguishedName
+/// RelativeDistinguishedName ::= SET SIZE (1..MAX) OF AttributeTypeAndValue
+/// ```
+///
+/// Each RDN is usually a single attribute. When encoding, the
+/// attributes of an RDN are sorted as DER requires.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Name {
+    /// The RDNs, from the most significant one (usually the country)
+    pub rdns: Vec<Vec<AttributeTypeAndValue>>,
+}
+
+impl Name {
+    /// Returns the values of all attributes of the given type, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::models::ObjectIdentifier;
+    /// use yasna::models::x509::Name;
+    /// let der = [48, 15, 49, 13, 48, 11, 6, 3, 85, 4, 3, 12, 4, 116, 101, 115, 116];
+    /// let name: Name = yasna::decode_der(&der).unwrap();
+    /// let cn = ObjectIdentifier::from_slice(&[2, 5, 4, 3]);
+    /// let values: Vec<_> = name.attributes(&cn).collect();
+    /// assert_eq!(values[0].as_str(), Some("test"));
+    /// ```
+    pub fn attributes<'a>(&'a self, attr_type: &'a ObjectIdentifier)
+            -> impl Iterator<Item = &'a TaggedDerValue> + 'a {
+        self.rdns.iter()
+            .flat_map(|rdn| rdn.iter())
+            .filter(move |atv| &atv.attr_type == attr_type)
+            .map(|atv| &atv.value)
+    }
+}
+
+impl BERDecodable for Name {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        let rdns = reader.collect_sequence_of(|reader| {
+            let rdn = reader.collect_set_of(AttributeTypeAndValue::decode_ber)?;
+            if rdn.is_empty() {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            Ok(rdn)
+        })?;
+        Ok(Name {
+            rdns,
+        })
+    }
+}
+
+impl DEREncodable for Name {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_sequence_of(|writer| {
+            for rdn in self.rdns.iter() {
+                writer.next().write_set_of(|writer| {
+                    for atv in rdn.iter() {
+                        atv.encode_der(writer.next());
+                    }
+                });
+            }
+        })
+    }
+}
+
+/// `Time`: a point in time, encoded as UTCTime until 2049 and as
+/// GeneralizedTime from 2050 on.
+///
+/// ```text
+/// Time ::= CHOICE {
+///     utcTime        UTCTime,
+///     generalTime    GeneralizedTime }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Time {
+    /// The `utcTime` alternative
+    UtcTime(UTCTime),
+    /// The `generalTime` alternative
+    GeneralTime(GeneralizedTime),
+}
+
+impl Time {
+    /// Returns the time as `OffsetDateTime`.
+    pub fn datetime(&self) -> &::time::OffsetDateTime {
+        match *self {
+            Time::UtcTime(ref t) => t.datetime(),
+            Time::GeneralTime(ref t) => t.datetime(),
+        }
+    }
+}
+
+impl BERDecodable for Time {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        match reader.lookahead_tag()? {
+            TAG_UTCTIME => Ok(Time::UtcTime(reader.read_utctime()?)),
+            TAG_GENERALIZEDTIME => {
+                Ok(Time::GeneralTime(reader.read_generalized_time()?))
+            },
+            _ => Err(ASN1Error::new(ASN1ErrorKind::Invalid)),
+        }
+    }
+}
+
+impl DEREncodable for Time {
+    fn encode_der(&self, writer: DERWriter) {
+        match *self {
+            Time::UtcTime(ref t) => writer.write_utctime(t),
+            Time::GeneralTime(ref t) => writer.write_generalized_time(t),
+        }
+    }
+}
+
+/// `Validity`: the validity period of a certificate.
+///
+/// ```text
+/// Validity ::= SEQUENCE {
+///     notBefore   Time,
+///     notAfter    Time }
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Validity {
+    /// The beginning of the validity period
+    pub not_before: Time,
+    /// The end of the validity period
+    pub not_after: Time,
+}
+
+impl BERDecodable for Validity {
+    fn decode_ber(reader: BERReader) -> ASN1Result<Self> {
+        reader.read_sequence(|reader| {
+            let not_before = Time::decode_ber(reader.next())?;
+            let not_after = Time::decode_ber(reader.next())?;
+            Ok(Validity {
+                not_before,
+                not_after,
+            })
+        })
+    }
+}
+
+impl DEREncodable for Validity {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_sequence(|writer| {
+            self.not_before.encode_der(writer.next());
+            self.not_after.encode_der(writer.next());
+        })
+    }
+}
+
+#[test]
+fn test_x509_roundtrip() {
+    use super::super::{decode_der, encode_der};
+
+    let tests : &[&[u8]] = &[
+        // ecdsa-with-SHA256, without parameters
+        &[48, 10, 6, 8, 42, 134, 72, 206, 61, 4, 3, 2],
+    ];
+    for &data in tests {
+        let value: AlgorithmIdentifier = decode_der(data).unwrap();
+        assert_eq!(encode_der(&value), data);
+    }
+
+    let tests : &[(bool, &[u8])] = &[
+        // basicConstraints, critical, CA:TRUE
+        (true, &[48, 15, 6, 3, 85, 29, 19, 1, 1, 255, 4, 5, 48, 3, 1, 1, 255]),
+        (false, &[48, 12, 6, 3, 85, 29, 19, 4, 5, 48, 3, 1, 1, 255]),
+    ];
+    for &(critical, data) in tests {
+        let value: Extension = decode_der(data).unwrap();
+        assert_eq!(value.critical, critical);
+        assert_eq!(value.extn_value, &[48, 3, 1, 1, 255]);
+        assert_eq!(encode_der(&value), data);
+    }
+    // An explicit DEFAULT value is not DER.
+    decode_der::<Extension>(
+        &[48, 15, 6, 3, 85, 29, 19, 1, 1, 0, 4, 5, 48, 3, 1, 1, 255])
+        .unwrap_err();
+
+    // C=JP, CN=test
+    let data = &[48, 30, 49, 11, 48, 9, 6, 3, 85, 4, 6, 19, 2, 74, 80,
+        49, 15, 48, 13, 6, 3, 85, 4, 3, 12, 6, 121, 97, 115, 110, 97, 33];
+    let value: Name = decode_der(data).unwrap();
+    assert_eq!(value.rdns.len(), 2);
+    assert_eq!(value.rdns[0][0].value.as_str(), Some("JP"));
+    assert_eq!(encode_der(&value), &data[..]);
+    decode_der::<Name>(&[48, 2, 49, 0]).unwrap_err();
+
+    // 2049-12-31T23:59:59Z to 2050-01-01T00:00:00Z
+    let data = &[48, 32, 23, 13, 52, 57, 49, 50, 51, 49, 50, 51, 53, 57, 53,
+        57, 90, 24, 15, 50, 48, 53, 48, 48, 49, 48, 49, 48, 48, 48, 48, 48,
+        48, 90];
+    let value: Validity = decode_der(data).unwrap();
+    assert_eq!(value.not_after.datetime().unix_timestamp(), 2524608000);
+    match value.not_before {
+        Time::UtcTime(_) => {},
+        Time::GeneralTime(_) => panic!("expected UTCTime"),
+    }
+    assert_eq!(encode_der(&value), &data[..]);
+}
+
+#[test]
+fn test_x509_rfc5280() {
+    use super::super::{decode_der, decode_with_options, ParseOptions};
+
+    let tests : &[(&[u8], bool)] = &[
+        (&[2, 1, 1], true),
+        (&[2, 1, 0], false),
+        (&[2, 1, 255], false),
+        (&[2, 20, 127, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0], true),
+        (&[2, 21, 0, 128, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+            0, 0], false),
+    ];
+    for &(data, ok) in tests {
+        let value: SerialNumber = decode_der(data).unwrap();
+        assert_eq!(super::super::encode_der(&value), data);
+        assert_eq!(decode_with_options::<SerialNumber>(
+            data, ParseOptions::rfc5280()).is_ok(), ok);
+    }
+
+    let tests : &[(&[u8], bool)] = &[
+        // 491231235959Z
+        (b"\x17\x0d491231235959Z", true),
+        // 20491231235959Z
+        (b"\x18\x0f20491231235959Z", false),
+        // 20500101000000Z
+        (b"\x18\x0f20500101000000Z", true),
+        // 20500101000000.5Z
+        (b"\x18\x1120500101000000.5Z", false),
+    ];
+    for &(data, ok) in tests {
+        decode_der::<Time>(data).unwrap();
+        assert_eq!(decode_with_options::<Time>(
+            data, ParseOptions::rfc5280()).is_ok(), ok);
+    }
+    // Seconds are required, even in BER.
+    let options = ParseOptions::new(super::super::BERMode::Ber)
+        .with_profile(Profile::Rfc5280);
+    decode_with_options::<Time>(b"\x17\x0b4912312359Z", ParseOptions::new(
+        super::super::BERMode::Ber)).unwrap();
+    decode_with_options::<Time>(b"\x17\x0b4912312359Z", options)
+        .unwrap_err();
+}
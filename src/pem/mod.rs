@@ -0,0 +1,414 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![forbid(missing_docs)]
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use std::io::{self, BufRead, Read};
+
+/// Decodes base64 on the fly, optionally inside PEM framing.
+///
+/// The adapter decodes what the underlying [`BufRead`] has buffered, one
+/// buffer at a time, so memory use stays bounded however long the input
+/// and its lines are.
+///
+/// Whitespace in the base64 text is ignored. In PEM mode, everything
+/// before the `-----BEGIN` line is skipped, RFC 1421 style headers
+/// (lines containing `:`) are ignored, and reading stops at the
+/// `-----END` line. Lines longer than 256 bytes can't be BEGIN, END or
+/// header lines, and are read as base64 text.
+///
+/// Malformed input is reported as an `io::Error` of kind
+/// `InvalidData`. Without PEM framing, this includes header lines.
+///
+/// # Examples
+///
+/// ```
+/// use std::io::Read;
+/// use yasna::Base64Reader;
+/// let pem = b"junk\n-----BEGIN DATA-----\nMAYCAQoBAf8=\n-----END DATA-----\n";
+/// let mut der = Vec::new();
+/// Base64Reader::new_pem(&pem[..], Some("DATA"))
+///     .read_to_end(&mut der).unwrap();
+/// let asn = yasna::parse_der(&der, |reader| {
+///     reader.read_sequence(|reader| {
+///         let i = reader.next().read_i64()?;
+///         let b = reader.next().read_bool()?;
+///         return Ok((i, b));
+///     })
+/// }).unwrap();
+/// assert_eq!(asn, (10, true));
+/// ```
+///
+/// # Features
+///
+/// This type is enabled by `std` feature.
+///
+/// ```toml
+/// [dependencies]
+/// yasna = { version = "*", features = ["std"] }
+/// ```
+#[derive(Debug)]
+pub struct Base64Reader<R> {
+    inner: R,
+    decoder: Decoder,
+    pos: usize,
+}
+
+/// The longest line which may be a BEGIN, END or header line.
+const MAX_LINE_LEN: usize = 256;
+
+/// The state of a [`Base64Reader`] besides its input.
+#[derive(Debug)]
+struct Decoder {
+    state: State,
+    pem: bool,
+    label: Option<String>,
+    /// The start of the current line, in PEM mode
+    line: Vec<u8>,
+    /// Whether the current line is too long to be buffered
+    long_line: bool,
+    decoded: Vec<u8>,
+    quad: [u8; 4],
+    quad_len: usize,
+    padding: usize,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum State {
+    /// Looking for the BEGIN line of a PEM document.
+    BeforeBegin,
+    /// Decoding base64 lines.
+    Body,
+    /// The END line or the end of the input has been seen.
+    Done,
+}
+
+impl<R: BufRead> Base64Reader<R> {
+    /// Creates an adapter reading bare base64 text from `inner`.
+    pub fn new(inner: R) -> Self {
+        Base64Reader::with_state(inner, State::Body, false, None)
+    }
+
+    /// Creates an adapter reading a PEM document from `inner`.
+    ///
+    /// If `label` is given, BEGIN lines with other labels are skipped.
+    pub fn new_pem(inner: R, label: Option<&str>) -> Self {
+        Base64Reader::with_state(
+            inner, State::BeforeBegin, true, label.map(String::from))
+    }
+
+    fn with_state(inner: R, state: State, pem: bool, label: Option<String>)
+            -> Self {
+        Base64Reader {
+            inner,
+            decoder: Decoder {
+                state,
+                pem,
+                label,
+                line: Vec::new(),
+                long_line: false,
+                decoded: Vec::new(),
+                quad: [0; 4],
+                quad_len: 0,
+                padding: 0,
+            },
+            pos: 0,
+        }
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Reads the input until some decoded bytes are available
+    /// or the input is exhausted.
+    fn fill(&mut self) -> io::Result<()> {
+        let decoder = &mut self.decoder;
+        decoder.decoded.clear();
+        self.pos = 0;
+        while decoder.decoded.is_empty() && decoder.state != State::Done {
+            let len = {
+                let buf = self.inner.fill_buf()?;
+                if buf.is_empty() {
+                    decoder.end_of_input()?;
+                    break;
+                }
+                decoder.feed(buf)?
+            };
+            self.inner.consume(len);
+        }
+        if decoder.state == State::Done && decoder.quad_len != 0 {
+            return Err(invalid_data("truncated base64 data"));
+        }
+        Ok(())
+    }
+}
+
+impl Decoder {
+    /// Decodes `buf` up to the END line, returning the number of bytes
+    /// consumed.
+    fn feed(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for (i, &c) in buf.iter().enumerate() {
+            if self.state == State::Done {
+                return Ok(i);
+            }
+            if !self.pem {
+                self.decode_char(c)?;
+            } else if self.long_line {
+                if c == b'\n' {
+                    self.long_line = false;
+                } else if self.state == State::Body {
+                    self.decode_char(c)?;
+                }
+            } else {
+                self.line.push(c);
+                if c == b'\n' {
+                    self.end_line()?;
+                } else if self.line.len() >= MAX_LINE_LEN {
+                    self.long_line = true;
+                    if self.state == State::Body {
+                        self.decode_line()?;
+                    }
+                    self.line.clear();
+                }
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn end_of_input(&mut self) -> io::Result<()> {
+        if !self.line.is_empty() {
+            self.end_line()?;
+        }
+        if self.state == State::BeforeBegin {
+            return Err(invalid_data("missing PEM BEGIN line"));
+        } else if self.pem && self.state != State::Done {
+            return Err(invalid_data("missing PEM END line"));
+        }
+        self.state = State::Done;
+        Ok(())
+    }
+
+    fn end_line(&mut self) -> io::Result<()> {
+        match self.state {
+            State::BeforeBegin => {
+                if self.is_begin_line() {
+                    self.state = State::Body;
+                }
+            },
+            State::Body => {
+                if self.line.starts_with(b"-----END") {
+                    self.state = State::Done;
+                } else if !self.line.contains(&b':') {
+                    self.decode_line()?;
+                }
+            },
+            State::Done => unreachable!(),
+        }
+        self.line.clear();
+        Ok(())
+    }
+
+    fn is_begin_line(&self) -> bool {
+        let rest = match strip(&self.line, b"-----BEGIN ") {
+            Some(rest) => rest,
+            None => return false,
+        };
+        let rest = trim_end(rest);
+        let label = match rest.len().checked_sub(5) {
+            Some(end) if &rest[end..] == b"-----" => &rest[..end],
+            _ => return false,
+        };
+        match self.label {
+            Some(ref expected) => label == expected.as_bytes(),
+            None => true,
+        }
+    }
+
+    fn decode_line(&mut self) -> io::Result<()> {
+        for i in 0..self.line.len() {
+            let c = self.line[i];
+            self.decode_char(c)?;
+        }
+        Ok(())
+    }
+
+    fn decode_char(&mut self, c: u8) -> io::Result<()> {
+        if c.is_ascii_whitespace() {
+            return Ok(());
+        }
+        if c == b'=' {
+            if self.quad_len < 2 {
+                return Err(invalid_data("misplaced base64 padding"));
+            }
+            self.padding += 1;
+            self.quad[self.quad_len] = 0;
+        } else if self.padding != 0 {
+            return Err(invalid_data("data after base64 padding"));
+        } else {
+            self.quad[self.quad_len] = match decode_char(c) {
+                Some(v) => v,
+                None => return Err(invalid_data("invalid base64 character")),
+            };
+        }
+        self.quad_len += 1;
+        if self.quad_len == 4 {
+            let q = self.quad;
+            let bytes = [
+                (q[0] << 2) | (q[1] >> 4),
+                (q[1] << 4) | (q[2] >> 2),
+                (q[2] << 6) | q[3],
+            ];
+            self.decoded.extend_from_slice(&bytes[..3 - self.padding]);
+            self.quad_len = 0;
+        }
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Read for Base64Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos >= self.decoder.decoded.len() {
+            self.fill()?;
+        }
+        let available = &self.decoder.decoded[self.pos..];
+        let len = available.len().min(buf.len());
+        buf[..len].copy_from_slice(&available[..len]);
+        self.pos += len;
+        Ok(len)
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn decode_char(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+fn strip<'a>(line: &'a [u8], prefix: &[u8]) -> Option<&'a [u8]> {
+    if line.starts_with(prefix) {
+        Some(&line[prefix.len()..])
+    } else {
+        None
+    }
+}
+
+fn trim_end(mut line: &[u8]) -> &[u8] {
+    while let Some((&last, rest)) = line.split_last() {
+        if !last.is_ascii_whitespace() {
+            break;
+        }
+        line = rest;
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+    use super::*;
+
+    fn decode(reader: &mut dyn Read) -> io::Result<Vec<u8>> {
+        let mut out = Vec::new();
+        reader.read_to_end(&mut out)?;
+        Ok(out)
+    }
+
+    #[test]
+    fn test_base64_reader() {
+        let tests : &[(&[u8], &[u8])] = &[
+            (b"", b""),
+            (b"TWFu", b"Man"),
+            (b"TWE=", b"Ma"),
+            (b"TQ==", b"M"),
+            (b"TW\nFu\r\nTWE=\n", b"ManMa"),
+            (b" TWFu TWFu ", b"ManMan"),
+        ];
+        for &(data, edata) in tests {
+            let out = decode(&mut Base64Reader::new(data)).unwrap();
+            assert_eq!(out, edata);
+        }
+
+        let tests : &[&[u8]] = &[
+            b"TWF", b"TW=u", b"T===", b"TQ==TWFu", b"TW?u",
+            b"TWFu\n-----END X-----\n", b"Proc-Type: 4,ENCRYPTED\nTWFu",
+        ];
+        for &data in tests {
+            decode(&mut Base64Reader::new(data)).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn test_base64_reader_bounded() {
+        use std::io::BufReader;
+        let data = b"TWFu".repeat(10000);
+        let pem = [&b"-----BEGIN X-----\n"[..], &data, b"\n-----END X-----\n"]
+            .concat();
+        for &pem_mode in &[false, true] {
+            let input = if pem_mode { &pem[..] } else { &data[..] };
+            let input = BufReader::with_capacity(64, input);
+            let mut reader = if pem_mode {
+                Base64Reader::new_pem(input, None)
+            } else {
+                Base64Reader::new(input)
+            };
+            let mut out = Vec::new();
+            let mut buf = [0; 16];
+            loop {
+                let len = reader.read(&mut buf).unwrap();
+                if len == 0 {
+                    break;
+                }
+                out.extend_from_slice(&buf[..len]);
+                assert!(reader.decoder.decoded.capacity() <= 256);
+                assert!(reader.decoder.line.capacity() <= 256);
+            }
+            assert_eq!(out, b"Man".repeat(10000));
+        }
+    }
+
+    #[test]
+    fn test_base64_reader_pem() {
+        let pem : &[u8] = b"Bag Attributes\n\
+            -----BEGIN OTHER-----\nAAAA\n-----END OTHER-----\n\
+            -----BEGIN CERTIFICATE-----\r\n\
+            Proc-Type: 4,ENCRYPTED\r\n\
+            \r\n\
+            TWFu\r\nTQ==\r\n\
+            -----END CERTIFICATE-----\r\n\
+            trailer\n";
+        let out = decode(
+            &mut Base64Reader::new_pem(pem, Some("CERTIFICATE"))).unwrap();
+        assert_eq!(out, b"ManM");
+        let out = decode(&mut Base64Reader::new_pem(pem, None)).unwrap();
+        assert_eq!(out, [0, 0, 0]);
+
+        let tests : &[&[u8]] = &[
+            b"",
+            b"TWFu\n",
+            b"-----BEGIN CERTIFICATE-----\nTWFu\n",
+            b"-----BEGIN OTHER-----\nTWFu\n-----END OTHER-----\n",
+        ];
+        for &data in tests {
+            decode(&mut Base64Reader::new_pem(data, Some("CERTIFICATE")))
+                .unwrap_err();
+        }
+    }
+}
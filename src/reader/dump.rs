@@ -0,0 +1,158 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![forbid(missing_docs)]
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use core::fmt::Write;
+use core::str;
+
+use super::super::{Tag, TagClass};
+use super::{parse_ber_general, walk_with_options, Event};
+use super::{ASN1Result, BERMode, BERReaderImpl, ParseOptions};
+
+/// Renders the BER/DER-encoded values in `buf` as text, one line per
+/// value, in the format of `openssl asn1parse`.
+///
+/// Each line gives the offset, the depth, the lengths of the header and
+/// the contents (`inf` for the indefinite form), and the tag of the
+/// value. Some universal primitive values are decoded, and OCTET STRING
+/// and BIT STRING are shown in hex. The structure is traversed with
+/// [`walk_ber`](super::walk_ber), and is validated likewise.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::BERMode;
+/// let data = &[48, 5, 161, 3, 2, 1, 10];
+/// assert_eq!(yasna::dump_ber(data, BERMode::Der).unwrap(), concat!(
+///     "    0:d=0  hl=2 l=   5 cons: SEQUENCE\n",
+///     "    2:d=1  hl=2 l=   3 cons: cont [ 1 ]\n",
+///     "    4:d=2  hl=2 l=   1 prim: INTEGER           :0A\n",
+/// ));
+/// ```
+pub fn dump_ber(buf: &[u8], mode: BERMode) -> ASN1Result<String> {
+    let options = ParseOptions::new(mode);
+    let mut out = String::new();
+    let mut depth = 0;
+    walk_with_options(buf, options, &mut |event| {
+        match event {
+            Event::BeginConstructed { tag, offset, len } => {
+                let length = match len {
+                    Some(len) => format!("{:4}", len),
+                    None => " inf".to_string(),
+                };
+                let _ = writeln!(out, "{:5}:d={:<2} hl={} l={} cons: {}",
+                    offset, depth, header_len(buf, offset, options), length,
+                    tag_name(tag));
+                depth += 1;
+            },
+            Event::Primitive { tag, offset, content } => {
+                let header_len = header_len(buf, offset, options);
+                let raw = &buf[offset..offset + header_len + content.len()];
+                let _ = write!(out, "{:5}:d={:<2} hl={} l={:4} prim: ",
+                    offset, depth, header_len, content.len());
+                let _ = match value_text(tag, raw, content) {
+                    Some(value) => writeln!(out, "{:<18}:{}",
+                        tag_name(tag), value),
+                    None => writeln!(out, "{}", tag_name(tag)),
+                };
+            },
+            Event::EndConstructed { .. } => depth -= 1,
+        }
+        Ok(())
+    })?;
+    Ok(out)
+}
+
+/// Returns the length of the identifier and length octets at `offset`,
+/// which the traversal has read already.
+fn header_len(buf: &[u8], offset: usize, options: ParseOptions) -> usize {
+    let mut reader = BERReaderImpl::with_pos(buf, offset, options);
+    let _ = reader.read_identifier();
+    let _ = reader.read_length();
+    reader.pos - offset
+}
+
+fn tag_name(tag: Tag) -> String {
+    let name = match tag.tag_class {
+        TagClass::Universal => match tag.tag_number {
+            0 => "EOC",
+            1 => "BOOLEAN",
+            2 => "INTEGER",
+            3 => "BIT STRING",
+            4 => "OCTET STRING",
+            5 => "NULL",
+            6 => "OBJECT",
+            7 => "OBJECT DESCRIPTOR",
+            8 => "EXTERNAL",
+            9 => "REAL",
+            10 => "ENUMERATED",
+            11 => "EMBEDDED PDV",
+            12 => "UTF8STRING",
+            13 => "RELATIVE-OID",
+            14 => "TIME",
+            16 => "SEQUENCE",
+            17 => "SET",
+            18 => "NUMERICSTRING",
+            19 => "PRINTABLESTRING",
+            20 => "T61STRING",
+            21 => "VIDEOTEXSTRING",
+            22 => "IA5STRING",
+            23 => "UTCTIME",
+            24 => "GENERALIZEDTIME",
+            25 => "GRAPHICSTRING",
+            26 => "VISIBLESTRING",
+            27 => "GENERALSTRING",
+            28 => "UNIVERSALSTRING",
+            30 => "BMPSTRING",
+            31 => "DATE",
+            32 => "TIME-OF-DAY",
+            33 => "DATE-TIME",
+            34 => "DURATION",
+            n => return format!("[UNIVERSAL {}]", n),
+        },
+        TagClass::Application =>
+            return format!("appl [ {} ]", tag.tag_number),
+        TagClass::ContextSpecific =>
+            return format!("cont [ {} ]", tag.tag_number),
+        TagClass::Private => return format!("priv [ {} ]", tag.tag_number),
+    };
+    name.to_string()
+}
+
+/// Renders a primitive universal value, if known. `raw` is its whole
+/// encoding.
+fn value_text(tag: Tag, raw: &[u8], content: &[u8]) -> Option<String> {
+    if tag.tag_class != TagClass::Universal {
+        return None;
+    }
+    let parse = |f: &dyn Fn(super::BERReader) -> ASN1Result<String>| {
+        parse_ber_general(raw, BERMode::Ber, f).ok()
+    };
+    match tag.tag_number {
+        1 => parse(&|reader| reader.read_bool()
+            .map(|b| if b { "TRUE" } else { "FALSE" }.to_string())),
+        2 | 10 => Some(hex(content)),
+        6 => parse(&|reader| reader.read_oid().map(|oid| oid.to_string())),
+        12 | 18 | 19 | 22 | 23 | 24 | 26 =>
+            str::from_utf8(content).ok().map(|s| s.to_string()),
+        30 => parse(&|reader| reader.read_bmp_string()),
+        3 | 4 => Some(format!("[HEX DUMP]:{}", hex(content))),
+        _ => None,
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len() * 2);
+    for b in bytes {
+        let _ = write!(s, "{:02X}", b);
+    }
+    s
+}
@@ -11,12 +11,14 @@ use std::{error::Error, io};
 use core::fmt::{self, Display};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct ASN1Error {
     kind: ASN1ErrorKind,
 }
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum ASN1ErrorKind {
-    Eof, Extra, IntegerOverflow, StackOverflow, Invalid,
+    Eof, Extra, IntegerOverflow, StackOverflow, Invalid, LimitExceeded,
 }
 
 pub type ASN1Result<T> = Result<T, ASN1Error>;
@@ -49,6 +51,7 @@ impl Error for ASN1Error {
             ASN1ErrorKind::IntegerOverflow => "Integer overflow",
             ASN1ErrorKind::StackOverflow => "Stack overflow",
             ASN1ErrorKind::Invalid => "Invalid data",
+            ASN1ErrorKind::LimitExceeded => "Resource limit exceeded",
         }
     }
 }
@@ -0,0 +1,183 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![forbid(missing_docs)]
+
+use super::super::{PCBit, Tag};
+use super::super::tags::TAG_EOC;
+use super::{ASN1Error, ASN1ErrorKind, ASN1Result, BERMode};
+use super::{BERReaderImpl, ParseOptions};
+
+/// An event of the traversal done by [`walk_ber`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Event<'a> {
+    /// A constructed value starts. Its elements follow, then
+    /// [`EndConstructed`](Event::EndConstructed).
+    BeginConstructed {
+        /// The tag of the value
+        tag: Tag,
+        /// The position of the identifier octets in the input
+        offset: usize,
+        /// The length of the contents, or `None` for the indefinite form
+        len: Option<usize>,
+    },
+    /// A primitive value.
+    Primitive {
+        /// The tag of the value
+        tag: Tag,
+        /// The position of the identifier octets in the input
+        offset: usize,
+        /// The contents octets
+        content: &'a [u8],
+    },
+    /// The constructed value started last ends.
+    EndConstructed {
+        /// The tag of the value
+        tag: Tag,
+    },
+}
+
+/// Receives the events of [`walk_ber`].
+///
+/// It is implemented for closures taking an [`Event`].
+pub trait Visitor<'a> {
+    /// Handles `event`. An error stops the traversal, and is returned
+    /// from [`walk_ber`].
+    fn visit(&mut self, event: Event<'a>) -> ASN1Result<()>;
+}
+
+impl<'a, F> Visitor<'a> for F where F: FnMut(Event<'a>) -> ASN1Result<()> {
+    fn visit(&mut self, event: Event<'a>) -> ASN1Result<()> {
+        self(event)
+    }
+}
+
+/// Traverses the BER/DER-encoded values in `buf`, reporting each of them
+/// to `visitor`, without decoding nor allocating anything.
+///
+/// `buf` is a sequence of values, which are traversed in depth-first
+/// order. Only the structure is validated: the contents of primitive
+/// values are passed as they are, e.g. DER wrapped in an OCTET STRING is
+/// not entered. This suits dump tools, or indexing and filtering large
+/// inputs.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::{BERMode, Event};
+/// use yasna::tags::{TAG_INTEGER, TAG_SEQUENCE};
+/// let data = &[48, 3, 2, 1, 10];
+/// let mut events = Vec::new();
+/// yasna::walk_ber(data, BERMode::Der, &mut |event| {
+///     events.push(event);
+///     Ok(())
+/// }).unwrap();
+/// assert_eq!(events, vec![
+///     Event::BeginConstructed { tag: TAG_SEQUENCE, offset: 0, len: Some(3) },
+///     Event::Primitive { tag: TAG_INTEGER, offset: 2, content: &[10] },
+///     Event::EndConstructed { tag: TAG_SEQUENCE },
+/// ]);
+/// ```
+pub fn walk_ber<'a, V>(buf: &'a [u8], mode: BERMode, visitor: &mut V)
+        -> ASN1Result<()>
+        where V: Visitor<'a> + ?Sized {
+    walk_with_options(buf, ParseOptions::new(mode), visitor)
+}
+
+/// Traverses the BER/DER-encoded values in `buf` with the given
+/// [`ParseOptions`].
+///
+/// See [`walk_ber`].
+pub fn walk_with_options<'a, V>(buf: &'a [u8], options: ParseOptions,
+        visitor: &mut V) -> ASN1Result<()>
+        where V: Visitor<'a> + ?Sized {
+    let mut walk = Walk { buf, options, visitor };
+    walk.children(0, Some(buf.len()), buf.len(), 0)?;
+    Ok(())
+}
+
+struct Walk<'a, 'v, V: Visitor<'a> + ?Sized> {
+    buf: &'a [u8],
+    options: ParseOptions,
+    visitor: &'v mut V,
+}
+
+impl<'a, 'v, V: Visitor<'a> + ?Sized> Walk<'a, 'v, V> {
+    /// Traverses the values from `pos` to `end`, or to the
+    /// end-of-contents octets if `end` is `None`, which are within
+    /// `limit`, the end of the enclosing value. Returns the position after
+    /// them.
+    fn children(&mut self, mut pos: usize, end: Option<usize>,
+            limit: usize, depth: usize) -> ASN1Result<usize> {
+        if depth > self.options.max_depth() {
+            return Err(ASN1Error::new(ASN1ErrorKind::StackOverflow));
+        }
+        let limit = end.unwrap_or(limit);
+        loop {
+            if end == Some(pos) {
+                return Ok(pos);
+            }
+            let mut reader = BERReaderImpl::with_pos(
+                &self.buf[..limit], pos, self.options);
+            let (tag, pcbit) = reader.read_identifier()?;
+            let length = reader.read_length()?;
+            let contents = reader.pos;
+            if end.is_none() && tag == TAG_EOC {
+                if pcbit != PCBit::Primitive || length != Some(0) {
+                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                }
+                return Ok(contents);
+            }
+            pos = match length {
+                Some(length) => {
+                    let elem_end = contents.checked_add(length).ok_or(
+                        ASN1Error::new(ASN1ErrorKind::IntegerOverflow))?;
+                    if elem_end > limit {
+                        return Err(ASN1Error::new(ASN1ErrorKind::Eof));
+                    }
+                    if pcbit == PCBit::Constructed {
+                        if self.options.mode() == BERMode::Cer {
+                            return Err(
+                                ASN1Error::new(ASN1ErrorKind::Invalid));
+                        }
+                        self.visitor.visit(Event::BeginConstructed {
+                            tag,
+                            offset: pos,
+                            len: Some(length),
+                        })?;
+                        self.children(contents, Some(elem_end), elem_end,
+                            depth + 1)?;
+                        self.visitor.visit(Event::EndConstructed { tag })?;
+                    } else {
+                        self.visitor.visit(Event::Primitive {
+                            tag,
+                            offset: pos,
+                            content: &self.buf[contents..elem_end],
+                        })?;
+                    }
+                    elem_end
+                },
+                None => {
+                    if pcbit != PCBit::Constructed ||
+                            self.options.mode() == BERMode::Der {
+                        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                    }
+                    self.visitor.visit(Event::BeginConstructed {
+                        tag,
+                        offset: pos,
+                        len: None,
+                    })?;
+                    let elem_end =
+                        self.children(contents, None, limit, depth + 1)?;
+                    self.visitor.visit(Event::EndConstructed { tag })?;
+                    elem_end
+                },
+            };
+        }
+    }
+}
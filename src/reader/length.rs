@@ -0,0 +1,178 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![forbid(missing_docs)]
+
+use core::cmp;
+
+use super::super::PCBit;
+use super::{ASN1Error, ASN1ErrorKind, ASN1Result, BERMode};
+use super::{LeniencyFlags, ParseOptions};
+
+/// The result of [`tlv_length`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum TlvLength {
+    /// The buffer starts with a complete TLV of this many bytes,
+    /// including its identifier and length octets.
+    Complete(usize),
+    /// The buffer ends before the first TLV does. The TLV is at least
+    /// `needs_at_least` bytes long, so there's no point in looking again
+    /// before that many bytes are buffered.
+    Incomplete {
+        /// A lower bound of the length of the TLV
+        needs_at_least: usize,
+    },
+}
+
+/// Determines the length of the first TLV in a possibly partial buffer.
+///
+/// Only the identifier and length octets are examined, plus the
+/// end-of-contents octets of the indefinite length form; the contents
+/// aren't validated. This is meant for message-oriented protocols
+/// such as LDAP, to know when a whole message has arrived before
+/// calling the parser.
+///
+/// It fails if a header is malformed, in [`BERMode::Der`] and
+/// [`BERMode::Cer`] if a length isn't in its shortest form, and in
+/// [`BERMode::Cer`] if a constructed value has a definite length.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::{BERMode, TlvLength};
+/// let data = &[48, 6, 2, 1, 10, 1, 1, 255, 5, 0];
+/// assert_eq!(yasna::tlv_length(&data[..4], BERMode::Der).unwrap(),
+///     TlvLength::Incomplete { needs_at_least: 8 });
+/// assert_eq!(yasna::tlv_length(data, BERMode::Der).unwrap(),
+///     TlvLength::Complete(8));
+/// ```
+pub fn tlv_length(buf: &[u8], mode: BERMode) -> ASN1Result<TlvLength> {
+    tlv_length_with_options(buf, ParseOptions::new(mode))
+}
+
+/// Same as [`tlv_length`], but accepts non-minimal lengths if `options`
+/// relax [`LeniencyFlags::NON_MINIMAL_LENGTH`].
+pub(super) fn tlv_length_with_options(buf: &[u8], options: ParseOptions)
+        -> ASN1Result<TlvLength> {
+    let mode = options.mode();
+    let mut pos = 0;
+    // Number of enclosing indefinite length values
+    let mut open = 0usize;
+    // Returns `Incomplete` if the TLV needs `$needed` bytes, plus the
+    // end-of-contents octets of the `$open` values still open after it.
+    // At least one more byte is needed in any case.
+    macro_rules! incomplete {
+        ($needed:expr, $open:expr) => {
+            return Ok(TlvLength::Incomplete {
+                needs_at_least: cmp::max(
+                    ($needed).saturating_add(2 * $open), buf.len() + 1),
+            })
+        };
+    }
+    loop {
+        let (pcbit, is_eoc, header_pos) = match read_identifier(buf, pos)? {
+            Some(identifier) => identifier,
+            None if open > 0 => incomplete!(pos, open),
+            None => incomplete!(pos + 2, 0),
+        };
+        // The open values, excluding the one this header may close
+        let outer = if is_eoc { open.saturating_sub(1) } else { open };
+        pos = header_pos;
+        let length = match buf.get(pos) {
+            Some(&b) => b as usize,
+            None => incomplete!(pos + 1, outer),
+        };
+        pos += 1;
+        if is_eoc {
+            if length != 0 || open == 0 {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            open -= 1;
+        } else if length == 128 {
+            if pcbit != PCBit::Constructed || mode == BERMode::Der {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            open += 1;
+            continue;
+        } else if length == 255 {
+            return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+        } else {
+            let length = if length < 128 {
+                length
+            } else {
+                let num_bytes = length & 127;
+                let bytes = match buf.get(pos..pos + num_bytes) {
+                    Some(bytes) => bytes,
+                    None => incomplete!(pos + num_bytes, open),
+                };
+                pos += num_bytes;
+                let mut length: usize = 0;
+                for &b in bytes {
+                    length = length.checked_mul(256).ok_or(
+                        ASN1Error::new(ASN1ErrorKind::IntegerOverflow))?
+                        + b as usize;
+                }
+                if options.enforces(LeniencyFlags::NON_MINIMAL_LENGTH) &&
+                        (length < 128 || bytes[0] == 0) {
+                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                }
+                length
+            };
+            if pcbit == PCBit::Constructed && mode == BERMode::Cer {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            pos = pos.checked_add(length).ok_or(
+                ASN1Error::new(ASN1ErrorKind::IntegerOverflow))?;
+        }
+        if pos > buf.len() {
+            incomplete!(pos, open);
+        }
+        if open == 0 {
+            return Ok(TlvLength::Complete(pos));
+        }
+    }
+}
+
+/// Reads the identifier octets at `pos`. Returns the P/C bit, whether
+/// the identifier is that of end-of-contents, and the position after it.
+fn read_identifier(buf: &[u8], mut pos: usize)
+        -> ASN1Result<Option<(PCBit, bool, usize)>> {
+    let first = match buf.get(pos) {
+        Some(&b) => b,
+        None => return Ok(None),
+    };
+    pos += 1;
+    let pcbit = if first & 32 == 0 {
+        PCBit::Primitive
+    } else {
+        PCBit::Constructed
+    };
+    if first & 31 == 31 {
+        let mut tag_number: u64 = 0;
+        loop {
+            let b = match buf.get(pos) {
+                Some(&b) => b,
+                None => return Ok(None),
+            };
+            pos += 1;
+            if tag_number == 0 && b == 128 {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            tag_number = tag_number.checked_mul(128).ok_or(
+                ASN1Error::new(ASN1ErrorKind::IntegerOverflow))?
+                + (b & 127) as u64;
+            if b & 128 == 0 {
+                break;
+            }
+        }
+        if tag_number < 31 {
+            return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+        }
+    }
+    Ok(Some((pcbit, first == 0, pos)))
+}
@@ -0,0 +1,82 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![forbid(missing_docs)]
+
+use super::{ASN1Error, ASN1ErrorKind, ASN1Result, BERReader};
+use super::{parse_with_options, ParseOptions, BER_READER_STACK_DEPTH};
+
+/// Resource limits fixed at compile time, for [`parse_limited`].
+///
+/// The limits are associated constants, so that a parser's bounds are
+/// part of its type. This is only a typed front end to [`ParseOptions`]:
+/// the reader is not specialized for the bounds, and checks the depth
+/// against [`ParseOptions::max_depth`] as usual.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::{BERMode, Limits, ParseOptions};
+/// struct Sensor;
+/// impl Limits for Sensor {
+///     const MAX_DEPTH: usize = 2;
+///     const MAX_SIZE: usize = 64;
+/// }
+/// let options = ParseOptions::new(BERMode::Der);
+/// let data = &[48, 3, 2, 1, 10];
+/// let i = yasna::parse_limited::<Sensor, _, _>(data, options, |reader| {
+///     reader.read_sequence(|reader| reader.next().read_i64())
+/// }).unwrap();
+/// assert_eq!(i, 10);
+/// // Too deeply nested
+/// let data = &[48, 7, 48, 5, 48, 3, 2, 1, 10];
+/// assert!(yasna::parse_limited::<Sensor, _, _>(data, options, |reader| {
+///     reader.read_sequence(|reader| {
+///         reader.next().read_sequence(|reader| {
+///             reader.next().read_sequence(|reader| reader.next().read_i64())
+///         })
+///     })
+/// }).is_err());
+/// ```
+pub trait Limits {
+    /// The maximum depth of a value, the outermost value being at depth
+    /// 0. Deeper values are rejected with
+    /// [`ASN1ErrorKind::StackOverflow`].
+    const MAX_DEPTH: usize;
+    /// The maximum length of the whole input, in bytes. Longer inputs
+    /// are rejected with [`ASN1ErrorKind::LimitExceeded`] before parsing.
+    const MAX_SIZE: usize;
+}
+
+/// The limits of [`parse_with_options`](super::parse_with_options) with
+/// the default options: a depth of 100, and no size limit.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct DefaultLimits;
+
+impl Limits for DefaultLimits {
+    const MAX_DEPTH: usize = BER_READER_STACK_DEPTH;
+    const MAX_SIZE: usize = !0;
+}
+
+/// Parses BER/DER-encoded data with the given [`ParseOptions`], within
+/// the [`Limits`] of `L`.
+///
+/// This is meant for embedded targets, where the bounds are known when
+/// building. The input length is checked against [`Limits::MAX_SIZE`],
+/// then it is parsed with [`parse_with_options`], with
+/// [`ParseOptions::max_depth`] lowered to [`Limits::MAX_DEPTH`].
+pub fn parse_limited<'a, L, T, F>(buf: &'a [u8], options: ParseOptions,
+        callback: F) -> ASN1Result<T>
+        where L: Limits,
+        F: for<'b> FnOnce(BERReader<'a, 'b>) -> ASN1Result<T> {
+    if buf.len() > L::MAX_SIZE {
+        return Err(ASN1Error::new(ASN1ErrorKind::LimitExceeded));
+    }
+    let max_depth = core::cmp::min(L::MAX_DEPTH, options.max_depth());
+    parse_with_options(buf, options.with_max_depth(max_depth), callback)
+}
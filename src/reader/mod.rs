@@ -11,23 +11,58 @@
 
 use alloc::vec::Vec;
 use alloc::string::String;
-use alloc::borrow::ToOwned;
+use alloc::borrow::{Cow, ToOwned};
+use core::cmp;
+use core::ops::BitOr;
+use core::mem::size_of;
 
 mod error;
+mod length;
+mod search;
+mod events;
+mod dump;
+mod pattern;
+mod limits;
+mod real;
+mod teletex;
+mod push;
+mod multiple;
+#[cfg(feature = "std")]
+mod stream;
 
 #[cfg(feature = "num-bigint")]
-use num_bigint::{BigInt,BigUint,Sign};
+use num_bigint::{BigInt,BigUint};
 #[cfg(feature = "bit-vec")]
 use bit_vec::BitVec;
+#[cfg(feature = "uuid")]
+use uuid::Uuid;
 
-use super::{PCBit,Tag,TAG_CLASSES};
+use super::{PCBit,Tag,TagClass,TAG_CLASSES};
 use super::tags::{TAG_EOC,TAG_BOOLEAN,TAG_INTEGER,TAG_OCTETSTRING};
 use super::tags::{TAG_NULL,TAG_OID,TAG_UTF8STRING,TAG_SEQUENCE,TAG_SET,TAG_ENUM};
+use super::tags::{TAG_REAL,TAG_TELETEXSTRING,TAG_UNIVERSALSTRING};
+use super::tags::{TAG_GENERALSTRING,TAG_GRAPHICSTRING};
 use super::tags::{TAG_NUMERICSTRING,TAG_PRINTABLESTRING,TAG_VISIBLESTRING,TAG_IA5STRING,TAG_BMPSTRING};
-use super::models::{ObjectIdentifier,TaggedDerValue};
+use super::models::{AsnDateTime,ObjectIdentifier,TaggedDerValue};
+use super::models::{OidIri,RelativeOidIri};
+use super::models::{Date,TimeOfDay,DateTime,Duration};
+use super::models::{External,ExternalEncoding};
 #[cfg(feature = "time")]
 use super::models::{UTCTime,GeneralizedTime};
 pub use self::error::*;
+pub use self::length::{tlv_length, TlvLength};
+use self::length::tlv_length_with_options;
+pub use self::search::{find_oid, OidLocation};
+pub use self::events::{walk_ber, walk_with_options, Event, Visitor};
+pub use self::dump::dump_ber;
+pub use self::pattern::{match_pattern, Pattern};
+pub use self::limits::{parse_limited, DefaultLimits, Limits};
+pub use self::push::{PushParser, PushStatus};
+pub use self::multiple::{parse_ber_multiple, parse_der_multiple, ParseMultiple};
+#[cfg(feature = "std")]
+pub use self::stream::{parse_ber_from_reader, parse_from_reader_with_options};
+#[cfg(feature = "tokio")]
+pub use self::stream::{parse_ber_async, parse_async_with_options};
 
 /// Parses DER/BER-encoded data.
 ///
@@ -36,7 +71,39 @@ pub use self::error::*;
 pub fn parse_ber_general<'a, T, F>(buf: &'a [u8], mode: BERMode, callback: F)
         -> ASN1Result<T>
         where F: for<'b> FnOnce(BERReader<'a, 'b>) -> ASN1Result<T> {
-    let mut reader_impl = BERReaderImpl::new(buf, mode);
+    parse_with_options(buf, ParseOptions::new(mode), callback)
+}
+
+/// Parses BER/DER-encoded data with the given [`ParseOptions`].
+///
+/// # Examples
+///
+/// ```
+/// use yasna::{BERMode, ParseOptions, Profile};
+/// # use yasna::models::AsnDateTime;
+/// # struct UnixTime(i64, u32);
+/// # impl AsnDateTime for UnixTime {
+/// #     fn from_unix_time(secs: i64, nanos: u32) -> Option<Self> {
+/// #         Some(UnixTime(secs, nanos))
+/// #     }
+/// #     fn to_unix_time(&self) -> (i64, u32) { (self.0, self.1) }
+/// # }
+/// // 20200101000000Z as GeneralizedTime, which RFC 5280 reserves
+/// // for 2050 and later
+/// let data = &[24, 15, 50, 48, 50, 48, 48, 49, 48, 49,
+///     48, 48, 48, 48, 48, 48, 90];
+/// assert!(yasna::parse_der(data, |reader| {
+///     reader.read_generalized_time_as::<UnixTime>()
+/// }).is_ok());
+/// let options = ParseOptions::new(BERMode::Der).with_profile(Profile::Rfc5280);
+/// assert!(yasna::parse_with_options(data, options, |reader| {
+///     reader.read_generalized_time_as::<UnixTime>()
+/// }).is_err());
+/// ```
+pub fn parse_with_options<'a, T, F>(buf: &'a [u8], options: ParseOptions,
+        callback: F) -> ASN1Result<T>
+        where F: for<'b> FnOnce(BERReader<'a, 'b>) -> ASN1Result<T> {
+    let mut reader_impl = BERReaderImpl::new(buf, options);
     let result;
     {
         result = callback(BERReader::new(&mut reader_impl))?;
@@ -100,22 +167,634 @@ pub fn parse_der<'a, T, F>(buf: &'a [u8], callback: F)
     parse_ber_general(buf, BERMode::Der, callback)
 }
 
+/// Parses BER-encoded data at the beginning of `buf`.
+///
+/// Unlike [`parse_ber`], it doesn't fail if data follows what
+/// `callback` reads, and returns the number of bytes consumed along with
+/// the result. This helps reading a value out of a larger buffer and
+/// going on with the rest.
+///
+/// # Examples
+///
+/// ```
+/// use yasna;
+/// let data = &[48, 128, 2, 1, 10, 0, 0, 1, 1, 255];
+/// let (asn, len) = yasna::parse_ber_prefix(data, |reader| {
+///     reader.read_sequence(|reader| reader.next().read_i64())
+/// }).unwrap();
+/// assert_eq!((asn, len), (10, 7));
+/// let asn = yasna::parse_ber(&data[len..], |reader| {
+///     reader.read_bool()
+/// }).unwrap();
+/// assert!(asn);
+/// ```
+pub fn parse_ber_prefix<'a, T, F>(buf: &'a [u8], callback: F)
+        -> ASN1Result<(T, usize)>
+        where F: for<'b> FnOnce(BERReader<'a, 'b>) -> ASN1Result<T> {
+    parse_prefix(buf, ParseOptions::new(BERMode::Ber), callback)
+}
+
+/// Parses DER-encoded data at the beginning of `buf`.
+///
+/// Unlike [`parse_der`], it doesn't fail if data follows what
+/// `callback` reads, and returns the number of bytes consumed along with
+/// the result. See [`parse_ber_prefix`].
+///
+/// # Examples
+///
+/// ```
+/// use yasna;
+/// let data = &[2, 1, 10, 2, 1, 20, 2, 1, 30];
+/// let mut rest = &data[..];
+/// let mut v = Vec::new();
+/// while !rest.is_empty() {
+///     let (asn, len) = yasna::parse_der_prefix(rest, |reader| {
+///         reader.read_i64()
+///     }).unwrap();
+///     v.push(asn);
+///     rest = &rest[len..];
+/// }
+/// assert_eq!(v, vec![10, 20, 30]);
+/// ```
+pub fn parse_der_prefix<'a, T, F>(buf: &'a [u8], callback: F)
+        -> ASN1Result<(T, usize)>
+        where F: for<'b> FnOnce(BERReader<'a, 'b>) -> ASN1Result<T> {
+    parse_prefix(buf, ParseOptions::new(BERMode::Der), callback)
+}
+
+fn parse_prefix<'a, T, F>(buf: &'a [u8], options: ParseOptions, callback: F)
+        -> ASN1Result<(T, usize)>
+        where F: for<'b> FnOnce(BERReader<'a, 'b>) -> ASN1Result<T> {
+    let mut reader_impl = BERReaderImpl::new(buf, options);
+    let result = callback(BERReader::new(&mut reader_impl))?;
+    Ok((result, reader_impl.pos))
+}
+
 /// Used by [`BERReader`] to determine whether or not to enforce
-/// DER restrictions when parsing.
+/// DER or CER restrictions when parsing.
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum BERMode {
     /// Use BER (Basic Encoding Rules).
     Ber,
     /// Use DER (Distinguished Encoding Rules).
     Der,
+    /// Use CER (Canonical Encoding Rules).
+    ///
+    /// Constructed values must have the indefinite length, and strings
+    /// longer than 1000 octets must be split into segments of 1000
+    /// octets. The other restrictions are those of DER.
+    Cer,
+}
+
+impl BERMode {
+    /// Tells whether the mode only accepts the canonical encoding of a
+    /// value, i.e. is DER or CER.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::BERMode;
+    /// assert!(!BERMode::Ber.is_canonical());
+    /// assert!(BERMode::Der.is_canonical());
+    /// assert!(BERMode::Cer.is_canonical());
+    /// ```
+    pub fn is_canonical(self) -> bool {
+        self != BERMode::Ber
+    }
+}
+
+/// Additional rules a document must follow, on top of those of the
+/// [`BERMode`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Profile {
+    /// No additional rules.
+    Generic,
+    /// The rules of RFC 5280 (X.509 certificates and CRLs), meant to be
+    /// used with [`BERMode::Der`]:
+    ///
+    /// - UTCTime must be of the form `YYMMDDHHMMSSZ`.
+    /// - GeneralizedTime must be of the form `YYYYMMDDHHMMSSZ`, without
+    ///   fractional seconds, and is only allowed from the year 2050 on.
+    /// - Certificate serial numbers, read as
+    ///   [`SerialNumber`](crate::models::x509::SerialNumber),
+    ///   must be positive and at most 20 octets long.
+    Rfc5280,
+}
+
+/// A set of universal types, for [`ParseOptions::with_allowed_types`].
+///
+/// Only the universal tag numbers from 1 to 63 can be allowed, which
+/// covers every type defined by X.680.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::AllowedTypes;
+/// use yasna::tags::{TAG_INTEGER, TAG_REAL, TAG_SEQUENCE};
+/// let types = AllowedTypes::new().allow(TAG_SEQUENCE).allow(TAG_INTEGER);
+/// assert!(types.contains(TAG_INTEGER));
+/// assert!(!types.contains(TAG_REAL));
+/// assert_eq!(types, AllowedTypes::from_tags(&[TAG_INTEGER, TAG_SEQUENCE]));
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct AllowedTypes {
+    bits: u64,
+}
+
+impl AllowedTypes {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        AllowedTypes { bits: 0 }
+    }
+
+    /// Creates the set of the universal types among `tags`.
+    pub fn from_tags(tags: &[Tag]) -> Self {
+        tags.iter().fold(AllowedTypes::new(), |types, &tag| types.allow(tag))
+    }
+
+    /// Adds the type of `tag`. Tags other than universal tags from 1 to
+    /// 63 are ignored.
+    pub fn allow(mut self, tag: Tag) -> Self {
+        if tag.tag_class == TagClass::Universal && tag.tag_number < 64 {
+            self.bits |= 1 << tag.tag_number;
+        }
+        self
+    }
+
+    /// Removes the type of `tag`.
+    pub fn forbid(mut self, tag: Tag) -> Self {
+        if tag.tag_class == TagClass::Universal && tag.tag_number < 64 {
+            self.bits &= !(1 << tag.tag_number);
+        }
+        self
+    }
+
+    /// Returns whether the set contains the type of `tag`. Tags of other
+    /// classes, and end-of-contents, are always considered contained.
+    pub fn contains(&self, tag: Tag) -> bool {
+        if tag.tag_class != TagClass::Universal || tag == TAG_EOC {
+            return true;
+        }
+        tag.tag_number < 64 && self.bits & (1 << tag.tag_number) != 0
+    }
+}
+
+/// Rules of DER and CER which [`ParseOptions::with_leniency`] relaxes.
+///
+/// Real-world encoders, notably in hardware, commonly break some of them.
+/// Each flag accepts the encodings breaking one rule, while the others
+/// are still enforced. They have no effect in [`BERMode::Ber`].
+///
+/// # Examples
+///
+/// ```
+/// use yasna::LeniencyFlags;
+/// let flags = LeniencyFlags::NON_MINIMAL_LENGTH | LeniencyFlags::BOOLEAN;
+/// assert!(flags.contains(LeniencyFlags::BOOLEAN));
+/// assert!(!flags.contains(LeniencyFlags::SET_ORDER));
+/// assert!(LeniencyFlags::all().contains(flags));
+/// ```
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash)]
+pub struct LeniencyFlags {
+    bits: u8,
+}
+
+impl LeniencyFlags {
+    /// Accepts lengths not encoded in the fewest octets.
+    pub const NON_MINIMAL_LENGTH: LeniencyFlags = LeniencyFlags { bits: 1 };
+    /// Accepts BOOLEAN values other than 0x00 and 0xFF as true.
+    pub const BOOLEAN: LeniencyFlags = LeniencyFlags { bits: 2 };
+    /// Accepts BIT STRING values whose unused bits aren't zero.
+    pub const BIT_STRING_PADDING: LeniencyFlags = LeniencyFlags { bits: 4 };
+    /// Accepts the elements of SET and SET OF values in any order.
+    pub const SET_ORDER: LeniencyFlags = LeniencyFlags { bits: 8 };
+    /// Accepts DEFAULT components encoded with their default value.
+    pub const DEFAULT_VALUE: LeniencyFlags = LeniencyFlags { bits: 16 };
+
+    /// Creates an empty set, relaxing nothing.
+    pub fn empty() -> Self {
+        LeniencyFlags { bits: 0 }
+    }
+
+    /// Creates the set of all the flags.
+    pub fn all() -> Self {
+        LeniencyFlags { bits: 31 }
+    }
+
+    /// Returns whether the set contains all the flags in `other`.
+    pub fn contains(&self, other: LeniencyFlags) -> bool {
+        self.bits & other.bits == other.bits
+    }
+}
+
+impl BitOr for LeniencyFlags {
+    type Output = LeniencyFlags;
+
+    fn bitor(self, other: LeniencyFlags) -> LeniencyFlags {
+        LeniencyFlags { bits: self.bits | other.bits }
+    }
+}
+
+/// Options controlling how [`parse_with_options`] reads a document.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::{BERMode, ParseOptions, Profile};
+/// let options = ParseOptions::new(BERMode::Der).with_profile(Profile::Rfc5280);
+/// assert_eq!(options.mode(), BERMode::Der);
+/// assert_eq!(options.profile(), Profile::Rfc5280);
+/// assert_eq!(options, ParseOptions::rfc5280());
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct ParseOptions {
+    mode: BERMode,
+    profile: Profile,
+    allowed_types: Option<AllowedTypes>,
+    max_depth: usize,
+    max_length: usize,
+    max_alloc: usize,
+    leniency: LeniencyFlags,
+}
+
+impl ParseOptions {
+    /// Creates options for parsing in `mode`, without any profile.
+    pub fn new(mode: BERMode) -> Self {
+        ParseOptions {
+            mode,
+            profile: Profile::Generic,
+            allowed_types: None,
+            max_depth: BER_READER_STACK_DEPTH,
+            max_length: !0,
+            max_alloc: !0,
+            leniency: LeniencyFlags { bits: 0 },
+        }
+    }
+
+    /// Creates options for validating X.509 structures: DER with
+    /// [`Profile::Rfc5280`].
+    pub fn rfc5280() -> Self {
+        ParseOptions::new(BERMode::Der).with_profile(Profile::Rfc5280)
+    }
+
+    /// Sets the profile.
+    pub fn with_profile(mut self, profile: Profile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Restricts the universal types a document may contain.
+    ///
+    /// A value whose identifier has a universal tag outside `types` is
+    /// rejected as soon as its identifier is read, whether or not it
+    /// would be decoded. Values of other classes are not checked, so an
+    /// IMPLICITly tagged REAL is not caught by forbidding REAL.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::{AllowedTypes, BERMode, ParseOptions};
+    /// use yasna::tags::{TAG_INTEGER, TAG_SEQUENCE};
+    /// let options = ParseOptions::new(BERMode::Der).with_allowed_types(
+    ///     AllowedTypes::from_tags(&[TAG_SEQUENCE, TAG_INTEGER]));
+    /// // SEQUENCE { INTEGER 1, REAL 0 }
+    /// let data = &[48, 5, 2, 1, 1, 9, 0];
+    /// let result = yasna::parse_with_options(data, options, |reader| {
+    ///     reader.read_sequence(|reader| {
+    ///         reader.next().read_i64()?;
+    ///         reader.next().read_der()
+    ///     })
+    /// });
+    /// assert!(result.is_err());
+    /// ```
+    pub fn with_allowed_types(mut self, types: AllowedTypes) -> Self {
+        self.allowed_types = Some(types);
+        self
+    }
+
+    /// Sets the maximum depth of a value, the outermost value being at
+    /// depth 0. Deeper values are rejected with
+    /// [`ASN1ErrorKind::StackOverflow`].
+    ///
+    /// The default is 100. As values are read recursively, raising it
+    /// far beyond that risks overflowing the stack.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::{ASN1ErrorKind, BERMode, ParseOptions};
+    /// let options = ParseOptions::new(BERMode::Der).with_max_depth(1);
+    /// let data = &[48, 5, 48, 3, 2, 1, 10];
+    /// let err = yasna::parse_with_options(data, options, |reader| {
+    ///     reader.read_sequence(|reader| {
+    ///         reader.next().read_sequence(|reader| reader.next().read_i64())
+    ///     })
+    /// }).unwrap_err();
+    /// assert_eq!(err.kind(), ASN1ErrorKind::StackOverflow);
+    /// ```
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Sets the maximum length of the contents of a value, as declared in
+    /// its header. Longer values are rejected with
+    /// [`ASN1ErrorKind::LimitExceeded`] as soon as their length is read.
+    ///
+    /// There is no limit by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::{ASN1ErrorKind, BERMode, ParseOptions};
+    /// let options = ParseOptions::new(BERMode::Der).with_max_length(4);
+    /// let data = &[4, 5, 1, 2, 3, 4, 5];
+    /// let err = yasna::parse_with_options(data, options, |reader| {
+    ///     reader.read_bytes()
+    /// }).unwrap_err();
+    /// assert_eq!(err.kind(), ASN1ErrorKind::LimitExceeded);
+    /// ```
+    pub fn with_max_length(mut self, max_length: usize) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Sets the maximum number of bytes a parse may copy out of the
+    /// input. Once it is used up, reading further values is rejected with
+    /// [`ASN1ErrorKind::LimitExceeded`] before they are copied.
+    ///
+    /// This accounts for the values read by
+    /// [`read_bytes`](BERReader::read_bytes) and the string types built
+    /// on it, [`read_bitvec_bytes`](BERReader::read_bitvec_bytes),
+    /// [`read_bigint_bytes`](BERReader::read_bigint_bytes),
+    /// [`read_oid`](BERReader::read_oid),
+    /// [`read_relative_oid`](BERReader::read_relative_oid),
+    /// [`read_der`](BERReader::read_der) and
+    /// [`read_tagged_der`](BERReader::read_tagged_der).
+    ///
+    /// There is no limit by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::{ASN1ErrorKind, BERMode, ParseOptions};
+    /// let options = ParseOptions::new(BERMode::Der).with_max_alloc(4);
+    /// let data = &[48, 8, 4, 2, 1, 2, 4, 2, 3, 4];
+    /// let (a, b) = yasna::parse_with_options(data, options, |reader| {
+    ///     reader.read_sequence(|reader| {
+    ///         Ok((reader.next().read_bytes()?, reader.next().read_bytes()?))
+    ///     })
+    /// }).unwrap();
+    /// assert_eq!((a, b), (vec![1, 2], vec![3, 4]));
+    /// let options = options.with_max_alloc(3);
+    /// let err = yasna::parse_with_options(data, options, |reader| {
+    ///     reader.read_sequence(|reader| {
+    ///         Ok((reader.next().read_bytes()?, reader.next().read_bytes()?))
+    ///     })
+    /// }).unwrap_err();
+    /// assert_eq!(err.kind(), ASN1ErrorKind::LimitExceeded);
+    /// ```
+    pub fn with_max_alloc(mut self, max_alloc: usize) -> Self {
+        self.max_alloc = max_alloc;
+        self
+    }
+
+    /// Accepts DEFAULT components encoded with their default value in
+    /// DER and CER, which otherwise reject them.
+    ///
+    /// Some encoders, such as old versions of OpenSSL and Java, encode
+    /// them anyway. This affects [`BERReaderSeq::read_default`] and
+    /// [`BERReaderSet::read_default`] only, and the rest of the document
+    /// is still checked to be canonical.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::{BERMode, ParseOptions};
+    /// // SEQUENCE { version INTEGER DEFAULT 0 }, with version = 0
+    /// let data = &[48, 3, 2, 1, 0];
+    /// let read = |reader: yasna::BERReader| {
+    ///     reader.read_sequence(|reader| {
+    ///         reader.read_default(0, |reader| reader.read_i64())
+    ///     })
+    /// };
+    /// assert!(yasna::parse_der(data, read).is_err());
+    /// let options = ParseOptions::new(BERMode::Der).with_lenient_default(true);
+    /// assert_eq!(yasna::parse_with_options(data, options, read), Ok(0));
+    /// ```
+    pub fn with_lenient_default(mut self, lenient_default: bool) -> Self {
+        let flag = LeniencyFlags::DEFAULT_VALUE.bits;
+        if lenient_default {
+            self.leniency.bits |= flag;
+        } else {
+            self.leniency.bits &= !flag;
+        }
+        self
+    }
+
+    /// Relaxes the rules of DER and CER in `flags`, so that encodings
+    /// breaking them are accepted. It replaces the flags set before,
+    /// including by [`with_lenient_default`](Self::with_lenient_default).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::{BERMode, LeniencyFlags, ParseOptions};
+    /// // BOOLEAN with a length in two octets, and a value of 0x01
+    /// let data = &[1, 129, 1, 1];
+    /// assert!(yasna::parse_der(data, |reader| reader.read_bool()).is_err());
+    /// let options = ParseOptions::new(BERMode::Der).with_leniency(
+    ///     LeniencyFlags::NON_MINIMAL_LENGTH | LeniencyFlags::BOOLEAN);
+    /// let asn = yasna::parse_with_options(data, options, |reader| {
+    ///     reader.read_bool()
+    /// }).unwrap();
+    /// assert!(asn);
+    /// ```
+    pub fn with_leniency(mut self, flags: LeniencyFlags) -> Self {
+        self.leniency = flags;
+        self
+    }
+
+    /// Returns the mode.
+    pub fn mode(&self) -> BERMode {
+        self.mode
+    }
+
+    /// Returns the profile.
+    pub fn profile(&self) -> Profile {
+        self.profile
+    }
+
+    /// Returns the allowed universal types, if restricted.
+    pub fn allowed_types(&self) -> Option<AllowedTypes> {
+        self.allowed_types
+    }
+
+    /// Returns the maximum depth of a value.
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    /// Returns the maximum length of the contents of a value.
+    pub fn max_length(&self) -> usize {
+        self.max_length
+    }
+
+    /// Returns the maximum number of bytes a parse may copy out of the
+    /// input.
+    pub fn max_alloc(&self) -> usize {
+        self.max_alloc
+    }
+
+    /// Returns whether DEFAULT components equal to their default value
+    /// are accepted in DER and CER.
+    pub fn lenient_default(&self) -> bool {
+        self.leniency.contains(LeniencyFlags::DEFAULT_VALUE)
+    }
+
+    /// Returns the rules of DER and CER relaxed.
+    pub fn leniency(&self) -> LeniencyFlags {
+        self.leniency
+    }
+
+    /// Tells whether the canonical rule `flag` is enforced.
+    fn enforces(&self, flag: LeniencyFlags) -> bool {
+        self.mode.is_canonical() && !self.leniency.contains(flag)
+    }
+}
+
+/// How [`BERReader::read_teletex_string`] interprets the contents of a
+/// TeletexString.
+///
+/// TeletexString is meant to contain T.61 text, but real-world encoders
+/// often put Latin-1 or UTF-8 text in it instead.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TeletexDecoding {
+    /// Decodes T.61 strictly. Characters with a diacritical mark are
+    /// decoded to the letter followed by a combining character, and
+    /// escape sequences are rejected.
+    T61,
+    /// Decodes each byte as a Latin-1 (ISO 8859-1) character, which
+    /// never fails.
+    Latin1,
+    /// Decodes UTF-8, failing on invalid sequences.
+    Utf8,
+    /// Decodes UTF-8 if valid, and Latin-1 otherwise.
+    Utf8OrLatin1,
+}
+
+/// How [`BERReader::read_general_string`] and
+/// [`BERReader::read_graphic_string`] interpret their contents.
+///
+/// GeneralString and GraphicString may switch between character sets
+/// with escape sequences, which yasna doesn't support. In practice, e.g.
+/// in Kerberos, they contain ASCII or UTF-8 text.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum StringDecoding {
+    /// Decodes ASCII, failing on bytes beyond 0x7F.
+    Ascii,
+    /// Decodes UTF-8, failing on invalid sequences.
+    Utf8,
+}
+
+/// Decodes the subidentifiers making up an OBJECT IDENTIFIER or a
+/// RELATIVE-OID, of which there is at least one.
+fn decode_subidentifiers(buf: &[u8]) -> ASN1Result<Vec<u64>> {
+    if buf.len() == 0 || buf[buf.len()-1] >= 128 {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+    let mut subids = Vec::new();
+    let mut subid : u64 = 0;
+    for &b in buf.iter() {
+        if b == 128 && subid == 0 {
+            return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+        }
+        subid = subid.checked_mul(128)
+            .ok_or(ASN1Error::new(
+                ASN1ErrorKind::IntegerOverflow))? + ((b & 127) as u64);
+        if (b & 128) == 0 {
+            subids.push(subid);
+            subid = 0;
+        }
+    }
+    Ok(subids)
+}
+
+/// Decodes the contents of GeneralString or GraphicString.
+fn decode_string(bytes: Vec<u8>, decoding: StringDecoding)
+        -> ASN1Result<String> {
+    if decoding == StringDecoding::Ascii && !bytes.is_ascii() {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+    String::from_utf8(bytes).map_err(|_| {
+        ASN1Error::new(ASN1ErrorKind::Invalid)
+    })
+}
+
+/// Decodes the contents of an INTEGER as `i64`.
+fn decode_i64(buf: &[u8]) -> ASN1Result<i64> {
+    if buf.len() == 0 {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    } else if buf.len() == 1 {
+        return Ok(buf[0] as i8 as i64);
+    }
+    let mut x = ((buf[0] as i8 as i64) << 8) + (buf[1] as i64);
+    if -128 <= x && x < 128 {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+    if buf.len() > 8 {
+        return Err(ASN1Error::new(
+            ASN1ErrorKind::IntegerOverflow));
+    }
+    for &b in buf[2..].iter() {
+        x = (x << 8) | (b as i64);
+    }
+    return Ok(x);
+}
+
+/// Decodes the contents of an INTEGER as `u64`.
+fn decode_u64(buf: &[u8]) -> ASN1Result<u64> {
+    if buf.len() == 0 {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    } else if buf[0] >= 128 {
+        return Err(ASN1Error::new(ASN1ErrorKind::IntegerOverflow));
+    } else if buf.len() == 1 {
+        return Ok(buf[0] as u64);
+    }
+    let mut x = ((buf[0] as u64) << 8) + (buf[1] as u64);
+    if x < 128 {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+    if buf.len() > 9 || (buf.len() == 9 && buf[0] != 0) {
+        return Err(ASN1Error::new(
+            ASN1ErrorKind::IntegerOverflow));
+    }
+    for &b in buf[2..].iter() {
+        x = (x << 8) | (b as u64);
+    }
+    return Ok(x);
+}
+
+/// Tells whether `byte` is allowed in PrintableString.
+fn is_printable_char(byte: u8) -> bool {
+    byte == b' ' ||
+        (b'\'' <= byte && byte <= b':' && byte != b'*') ||
+        byte == b'=' || byte == b'?' ||
+        (b'A' <= byte && byte <= b'Z') ||
+        (b'a' <= byte && byte <= b'z')
 }
 
 #[derive(Debug)]
 struct BERReaderImpl<'a> {
     buf: &'a [u8],
     pos: usize,
-    mode: BERMode,
+    options: ParseOptions,
     depth: usize,
+    allocated: usize,
 }
 
 const PC_BITS : [PCBit; 2] = [PCBit::Primitive, PCBit::Constructed];
@@ -128,22 +807,27 @@ enum Contents<'a, 'b> where 'a: 'b {
 
 const BER_READER_STACK_DEPTH : usize = 100;
 
+/// The number of contents octets of the segments of a string in CER.
+const CER_SEGMENT_LEN : usize = 1000;
+
 impl<'a> BERReaderImpl<'a> {
-    fn new(buf: &'a [u8], mode: BERMode) -> Self {
+    fn new(buf: &'a [u8], options: ParseOptions) -> Self {
         return BERReaderImpl {
             buf,
             pos: 0,
-            mode,
+            options,
             depth: 0,
+            allocated: 0,
         };
     }
 
-    fn with_pos(buf: &'a [u8], pos: usize, mode: BERMode) -> Self {
+    fn with_pos(buf: &'a [u8], pos: usize, options: ParseOptions) -> Self {
         return BERReaderImpl {
             buf,
             pos,
-            mode,
+            options,
             depth: 0,
+            allocated: 0,
         };
     }
 
@@ -157,6 +841,18 @@ impl<'a> BERReaderImpl<'a> {
         }
     }
 
+    /// Charges `len` bytes to be copied out of the input to the budget of
+    /// [`ParseOptions::max_alloc`].
+    fn reserve(&mut self, len: usize) -> ASN1Result<()> {
+        match self.allocated.checked_add(len) {
+            Some(allocated) if allocated <= self.options.max_alloc => {
+                self.allocated = allocated;
+                Ok(())
+            },
+            _ => Err(ASN1Error::new(ASN1ErrorKind::LimitExceeded)),
+        }
+    }
+
     fn end_of_buf(&mut self) -> ASN1Result<()> {
         if self.pos != self.buf.len() {
             return Err(ASN1Error::new(ASN1ErrorKind::Extra));
@@ -185,6 +881,9 @@ impl<'a> BERReaderImpl<'a> {
             tag_number = 0;
             loop {
                 let b = self.read_u8()? as u64;
+                if tag_number == 0 && b == 128 {
+                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                }
                 let x =
                     tag_number.checked_mul(128).ok_or(
                         ASN1Error::new(ASN1ErrorKind::IntegerOverflow))?;
@@ -201,10 +900,19 @@ impl<'a> BERReaderImpl<'a> {
             tag_class,
             tag_number,
         };
+        if let Some(types) = self.options.allowed_types {
+            if !types.contains(tag) {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+        }
         return Ok((tag, pcbit));
     }
 
     fn lookahead_tag(&self) -> ASN1Result<Tag> {
+        self.lookahead_identifier().map(|(tag, _)| tag)
+    }
+
+    fn lookahead_identifier(&self) -> ASN1Result<(Tag, PCBit)> {
         let mut pos = self.pos;
         let mut read_u8 = || {
             if pos < self.buf.len() {
@@ -217,11 +925,15 @@ impl<'a> BERReaderImpl<'a> {
         };
         let tagbyte = read_u8()?;
         let tag_class = TAG_CLASSES[(tagbyte >> 6) as usize];
+        let pcbit = PC_BITS[((tagbyte >> 5) & 1) as usize];
         let mut tag_number = (tagbyte & 31) as u64;
         if tag_number == 31 {
             tag_number = 0;
             loop {
                 let b = read_u8()? as u64;
+                if tag_number == 0 && b == 128 {
+                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                }
                 let x =
                     tag_number.checked_mul(128).ok_or(
                         ASN1Error::new(ASN1ErrorKind::IntegerOverflow))?;
@@ -238,7 +950,7 @@ impl<'a> BERReaderImpl<'a> {
             tag_class,
             tag_number,
         };
-        return Ok(tag);
+        return Ok((tag, pcbit));
     }
 
     fn read_length(&mut self) -> ASN1Result<Option<usize>> {
@@ -250,6 +962,9 @@ impl<'a> BERReaderImpl<'a> {
             return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
         }
         if (lbyte & 128) == 0 {
+            if lbyte > self.options.max_length {
+                return Err(ASN1Error::new(ASN1ErrorKind::LimitExceeded));
+            }
             return Ok(Some(lbyte));
         }
         let mut length : usize = 0;
@@ -257,16 +972,25 @@ impl<'a> BERReaderImpl<'a> {
             let x = length.checked_mul(256).ok_or(
                 ASN1Error::new(ASN1ErrorKind::Eof))?;
             length = x + (self.read_u8()? as usize);
+            if self.options.enforces(LeniencyFlags::NON_MINIMAL_LENGTH) &&
+                    length == 0 {
+                // Leading zero octet
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
         }
-        if self.mode == BERMode::Der && length < 128 {
+        if self.options.enforces(LeniencyFlags::NON_MINIMAL_LENGTH) &&
+                length < 128 {
             return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
         }
+        if length > self.options.max_length {
+            return Err(ASN1Error::new(ASN1ErrorKind::LimitExceeded));
+        }
         return Ok(Some(length));
     }
 
     fn read_general<T, F>(&mut self, tag: Tag, callback: F) -> ASN1Result<T>
             where F: for<'b> FnOnce(Contents<'a, 'b>) -> ASN1Result<T> {
-        if self.depth > BER_READER_STACK_DEPTH {
+        if self.depth > self.options.max_depth {
             return Err(ASN1Error::new(ASN1ErrorKind::StackOverflow));
         }
         let old_pos = self.pos;
@@ -287,13 +1011,17 @@ impl<'a> BERReaderImpl<'a> {
                 if old_buf.len() < limit {
                     return Err(ASN1Error::new(ASN1ErrorKind::Eof));
                 }
+                if pcbit == PCBit::Constructed &&
+                        self.options.mode == BERMode::Cer {
+                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                }
                 self.buf = &old_buf[..limit];
             },
             None => {
                 if pcbit != PCBit::Constructed {
                     return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
                 }
-                if self.mode == BERMode::Der {
+                if self.options.mode == BERMode::Der {
                     return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
                 }
             },
@@ -350,12 +1078,21 @@ impl<'a> BERReaderImpl<'a> {
                 if self.buf.len() < limit {
                     return Err(ASN1Error::new(ASN1ErrorKind::Eof));
                 }
+                if pcbit == PCBit::Constructed {
+                    if self.options.mode == BERMode::Cer {
+                        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                    }
+                    if self.options.allowed_types.is_some() {
+                        self.check_allowed_types(limit)?;
+                    }
+                }
                 self.pos = limit;
             } else {
                 if skip_depth == 0 {
                     data_pos = Some(self.pos);
                 }
-                if pcbit != PCBit::Constructed || self.mode == BERMode::Der {
+                if pcbit != PCBit::Constructed ||
+                        self.options.mode == BERMode::Der {
                     return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
                 }
                 skip_depth += 1;
@@ -364,23 +1101,127 @@ impl<'a> BERReaderImpl<'a> {
         return Ok((skip_tag.unwrap().0, skip_tag.unwrap().1, data_pos.unwrap()));
     }
 
-    fn read_with_buffer<'b, T, F>(&'b mut self, callback: F)
-            -> ASN1Result<(T, &'a [u8])>
-            where F: FnOnce(&mut Self) -> ASN1Result<T> {
-        let old_pos = self.pos;
-        let result = callback(self)?;
-        let new_pos = self.pos;
-        let buf = &self.buf[old_pos..new_pos];
+    /// Checks the types of the values from here to `limit`, which
+    /// `skip_general` would otherwise jump over.
+    fn check_allowed_types(&self, limit: usize) -> ASN1Result<()> {
+        if self.depth > self.options.max_depth {
+            return Err(ASN1Error::new(ASN1ErrorKind::StackOverflow));
+        }
+        let mut reader = BERReaderImpl::with_pos(
+            &self.buf[..limit], self.pos, self.options);
+        reader.depth = self.depth + 1;
+        while reader.pos < limit {
+            reader.skip_general()?;
+        }
+        Ok(())
+    }
+
+    /// Reads the INTEGER elements of a SEQUENCE OF, up to its end, with
+    /// `decode`. `length` is the length of the SEQUENCE OF, if definite.
+    fn read_integers<T>(&mut self, length: Option<usize>,
+            decode: fn(&[u8]) -> ASN1Result<T>) -> ASN1Result<Vec<T>> {
+        if self.depth > self.options.max_depth {
+            return Err(ASN1Error::new(ASN1ErrorKind::StackOverflow));
+        }
+        // Each element takes at least 3 bytes. The elements of an
+        // indefinite-length SEQUENCE OF are only counted as they are read.
+        let budget = self.options.max_alloc.saturating_sub(self.allocated);
+        let capacity = cmp::min(length.unwrap_or(0) / 3,
+            budget / cmp::max(size_of::<T>(), 1));
+        let mut values = Vec::with_capacity(capacity);
+        while self.pos < self.buf.len() && self.buf[self.pos] != 0 {
+            let (tag, pcbit) = self.read_identifier()?;
+            if tag != TAG_INTEGER || pcbit != PCBit::Primitive {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            let length = match self.read_length()? {
+                Some(length) => length,
+                None => return Err(ASN1Error::new(ASN1ErrorKind::Invalid)),
+            };
+            if self.buf.len() - self.pos < length {
+                return Err(ASN1Error::new(ASN1ErrorKind::Eof));
+            }
+            let value = decode(&self.buf[self.pos..self.pos + length])?;
+            self.reserve(size_of::<T>())?;
+            values.push(value);
+            self.pos += length;
+        }
+        Ok(values)
+    }
+
+    /// Parses `buf`, which was nested in the current value, with the same
+    /// options, depth and allocation budget.
+    fn parse_nested<T, F>(&mut self, buf: &[u8], callback: F)
+            -> ASN1Result<T>
+            where F: for<'c, 'd> FnOnce(BERReader<'c, 'd>) -> ASN1Result<T> {
+        let mut reader = BERReaderImpl::new(buf, self.options);
+        reader.depth = self.depth + 1;
+        reader.allocated = self.allocated;
+        let result = callback(BERReader::new(&mut reader))?;
+        reader.end_of_buf()?;
+        self.allocated = reader.allocated;
+        Ok(result)
+    }
+
+    fn read_with_buffer<'b, T, F>(&'b mut self, callback: F)
+            -> ASN1Result<(T, &'a [u8])>
+            where F: FnOnce(&mut Self) -> ASN1Result<T> {
+        let old_pos = self.pos;
+        let result = callback(self)?;
+        let new_pos = self.pos;
+        let buf = &self.buf[old_pos..new_pos];
         return Ok((result, buf));
     }
 
+    /// Reads the segments of a constructed string with `read_segment`,
+    /// which returns the number of contents octets of each.
+    fn read_segments<F>(&mut self, mut read_segment: F) -> ASN1Result<()>
+            where F: FnMut(&mut Self) -> ASN1Result<usize> {
+        match self.options.mode {
+            BERMode::Ber => {
+                while self.read_optional(&mut read_segment)?.is_some() {}
+            },
+            BERMode::Der => {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            },
+            BERMode::Cer => {
+                // The segments are primitive, with 1000 contents octets
+                // except the last one, and there are at least two.
+                let mut segments = 0;
+                let mut last_len = 0;
+                loop {
+                    if let Ok((_, PCBit::Constructed)) =
+                            self.lookahead_identifier() {
+                        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                    }
+                    let len = match self.read_optional(&mut read_segment)? {
+                        Some(len) => len,
+                        None => break,
+                    };
+                    if segments > 0 && last_len != CER_SEGMENT_LEN {
+                        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                    }
+                    segments += 1;
+                    last_len = len;
+                }
+                if segments < 2 || last_len == 0 {
+                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                }
+            },
+        }
+        Ok(())
+    }
+
     fn read_optional<T, F>(&mut self, callback: F) -> ASN1Result<Option<T>>
             where F: FnOnce(&mut Self) -> ASN1Result<T> {
         let old_pos = self.pos;
         match callback(self) {
             Ok(result) => Ok(Some(result)),
+            // A value too deep is there, though it is rejected before
+            // anything is read.
             Err(e) =>
-                if old_pos == self.pos {
+                if old_pos == self.pos &&
+                        e.kind() != ASN1ErrorKind::StackOverflow {
                     Ok(None)
                 } else {
                     Err(e)
@@ -390,6 +1231,27 @@ impl<'a> BERReaderImpl<'a> {
 
 }
 
+/// Checks the contents of a valid UTCTime against the profile.
+fn check_utctime_profile(options: ParseOptions, bytes: &[u8])
+        -> ASN1Result<()> {
+    if options.profile == Profile::Rfc5280 &&
+            !(bytes.len() == 13 && bytes[12] == b'Z') {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+    Ok(())
+}
+
+/// Checks the contents of a valid GeneralizedTime against the profile.
+fn check_generalized_time_profile(options: ParseOptions, bytes: &[u8])
+        -> ASN1Result<()> {
+    if options.profile == Profile::Rfc5280 &&
+            !(bytes.len() == 15 && bytes[14] == b'Z' &&
+                bytes[..4] >= b"2050"[..]) {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+    Ok(())
+}
+
 /// A reader object for BER/DER-encoded ASN.1 data.
 ///
 /// The two main sources of `BERReaderSeq` are:
@@ -428,9 +1290,66 @@ impl<'a, 'b> BERReader<'a, 'b> {
         self.inner.read_general(tag, callback)
     }
 
+    /// Reads a value borrowed from the input with `callback`, and charges
+    /// `unit` bytes per byte of it to the budget of
+    /// [`ParseOptions::max_alloc`], before the caller copies it.
+    fn read_charged<F>(self, unit: usize, callback: F)
+            -> ASN1Result<&'a [u8]>
+            where F: for<'c> FnOnce(BERReader<'a, 'c>)
+                -> ASN1Result<&'a [u8]> {
+        let BERReader { inner, implicit_tag } = self;
+        let buf = callback(BERReader { inner: &mut *inner, implicit_tag })?;
+        inner.reserve(buf.len().saturating_mul(unit))?;
+        Ok(buf)
+    }
+
     /// Tells which format we are parsing, BER or DER.
     pub fn mode(&self) -> BERMode {
-        self.inner.mode
+        self.inner.options.mode
+    }
+
+    /// Returns the options of the current parse.
+    ///
+    /// Custom [`BERDecodable`](crate::BERDecodable) implementations may
+    /// use it to apply rules of the [`Profile`] themselves.
+    pub fn options(&self) -> ParseOptions {
+        self.inner.options
+    }
+
+    /// Reads the next value in `mode`, with `callback`, regardless of the
+    /// mode of the current parse.
+    ///
+    /// This is for a field allowed to hold BER inside a DER document, or
+    /// conversely. The mode of the current parse applies again once
+    /// `callback` returns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::BERMode;
+    /// // SEQUENCE { INTEGER 10, [0] indefinite-length OCTET STRING }
+    /// let data = &[48, 11, 2, 1, 10, 160, 128, 4, 2, 72, 105, 0, 0];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_sequence(|reader| {
+    ///         let i = reader.next().read_i64()?;
+    ///         let s = reader.next().with_mode(BERMode::Ber, |reader| {
+    ///             reader.read_tagged(yasna::Tag::context(0), |reader| {
+    ///                 reader.read_bytes()
+    ///             })
+    ///         })?;
+    ///         Ok((i, s))
+    ///     })
+    /// }).unwrap();
+    /// assert_eq!(asn, (10, b"Hi".to_vec()));
+    /// ```
+    pub fn with_mode<T, F>(self, mode: BERMode, callback: F) -> ASN1Result<T>
+            where F: for<'c> FnOnce(BERReader<'a, 'c>) -> ASN1Result<T> {
+        let BERReader { inner, implicit_tag } = self;
+        let old_mode = inner.options.mode;
+        inner.options.mode = mode;
+        let result = callback(BERReader { inner: &mut *inner, implicit_tag });
+        inner.options.mode = old_mode;
+        result
     }
 
     /// Reads an ASN.1 BOOLEAN value as `bool`.
@@ -446,7 +1365,7 @@ impl<'a, 'b> BERReader<'a, 'b> {
     /// assert_eq!(asn, true);
     /// ```
     pub fn read_bool(self) -> ASN1Result<bool> {
-        let mode = self.mode();
+        let options = self.options();
         self.read_general(TAG_BOOLEAN, |contents| {
             let buf = match contents {
                 Contents::Primitive(buf) => buf,
@@ -458,7 +1377,7 @@ impl<'a, 'b> BERReader<'a, 'b> {
                 return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
             }
             let b = buf[0];
-            if mode == BERMode::Der && b != 0 && b != 255 {
+            if options.enforces(LeniencyFlags::BOOLEAN) && b != 0 && b != 255 {
                 return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
             }
             return Ok(b != 0);
@@ -467,29 +1386,12 @@ impl<'a, 'b> BERReader<'a, 'b> {
 
     fn read_integer(self, tag: Tag) -> ASN1Result<i64> {
         self.read_general(tag, |contents| {
-            let buf = match contents {
-                Contents::Primitive(buf) => buf,
+            match contents {
+                Contents::Primitive(buf) => decode_i64(buf),
                 Contents::Constructed(_) => {
-                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                    Err(ASN1Error::new(ASN1ErrorKind::Invalid))
                 },
-            };
-            if buf.len() == 0 {
-                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
-            } else if buf.len() == 1 {
-                return Ok(buf[0] as i8 as i64);
-            }
-            let mut x = ((buf[0] as i8 as i64) << 8) + (buf[1] as i64);
-            if -128 <= x && x < 128 {
-                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
             }
-            if buf.len() > 8 {
-                return Err(ASN1Error::new(
-                    ASN1ErrorKind::IntegerOverflow));
-            }
-            for &b in buf[2..].iter() {
-                x = (x << 8) | (b as i64);
-            }
-            return Ok(x);
         })
     }
 
@@ -535,36 +1437,33 @@ impl<'a, 'b> BERReader<'a, 'b> {
 
     /// Reads an ASN.1 INTEGER value as `u64`.
     ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// use yasna::ASN1ErrorKind;
+    /// let data = &[2, 9, 0, 255, 255, 255, 255, 255, 255, 255, 255];
+    /// let asn = yasna::parse_der(data, |reader| reader.read_u64()).unwrap();
+    /// assert_eq!(asn, 18446744073709551615);
+    /// let err = yasna::parse_der(&[2, 1, 255], |reader| {
+    ///     reader.read_u64()
+    /// }).unwrap_err();
+    /// assert_eq!(err.kind(), ASN1ErrorKind::IntegerOverflow);
+    /// ```
+    ///
     /// # Errors
     ///
-    /// Except parse errors, it can raise integer overflow errors.
+    /// Except parse errors, it can raise integer overflow errors, which
+    /// includes negative values. An encoding which isn't the shortest
+    /// one is invalid, in BER as well as in DER.
     pub fn read_u64(self) -> ASN1Result<u64> {
         self.read_general(TAG_INTEGER, |contents| {
-            let buf = match contents {
-                Contents::Primitive(buf) => buf,
+            match contents {
+                Contents::Primitive(buf) => decode_u64(buf),
                 Contents::Constructed(_) => {
-                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                    Err(ASN1Error::new(ASN1ErrorKind::Invalid))
                 },
-            };
-            if buf.len() == 0 {
-                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
-            } else if buf[0] >= 128 {
-                return Err(ASN1Error::new(ASN1ErrorKind::IntegerOverflow));
-            } else if buf.len() == 1 {
-                return Ok(buf[0] as u64);
-            }
-            let mut x = ((buf[0] as u64) << 8) + (buf[1] as u64);
-            if x < 128 {
-                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
-            }
-            if buf.len() > 9 || (buf.len() == 9 && buf[0] != 0) {
-                return Err(ASN1Error::new(
-                    ASN1ErrorKind::IntegerOverflow));
             }
-            for &b in buf[2..].iter() {
-                x = (x << 8) | (b as u64);
-            }
-            return Ok(x);
         })
     }
 
@@ -584,9 +1483,20 @@ impl<'a, 'b> BERReader<'a, 'b> {
 
     /// Reads an ASN.1 INTEGER value as `u32`.
     ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[2, 5, 0, 255, 255, 255, 255];
+    /// let asn = yasna::parse_der(data, |reader| reader.read_u32()).unwrap();
+    /// assert_eq!(asn, 4294967295);
+    /// ```
+    ///
     /// # Errors
     ///
-    /// Except parse errors, it can raise integer overflow errors.
+    /// Except parse errors, it can raise integer overflow errors, which
+    /// includes negative values. An encoding which isn't the shortest
+    /// one is invalid, in BER as well as in DER.
     pub fn read_u32(self) -> ASN1Result<u32> {
         let val = self.read_u64()?;
         if val < (1 << 32) {
@@ -652,6 +1562,51 @@ impl<'a, 'b> BERReader<'a, 'b> {
         }
     }
 
+    /// Reads an ASN.1 REAL value as `f64`.
+    ///
+    /// The binary encodings in bases 2, 8 and 16, the decimal encodings
+    /// (ISO 6093 NR1, NR2 and NR3) and the special values PLUS-INFINITY,
+    /// MINUS-INFINITY, NOT-A-NUMBER and minus zero are supported. Values
+    /// which can't be represented exactly are rounded.
+    ///
+    /// In DER, the restrictions of X.690 11.3 apply: binary encodings must
+    /// use base 2, no scaling factor, an odd mantissa and the shortest
+    /// exponent, and decimal encodings must use the canonical NR3 form.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// // 0.75 = 3 * 2^-2
+    /// let data = &[9, 3, 128, 254, 3];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_real()
+    /// }).unwrap();
+    /// assert_eq!(asn, 0.75);
+    /// // "-125E-2" in decimal (NR3)
+    /// let data = b"\x09\x09\x03-125.E-2";
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_real()
+    /// }).unwrap();
+    /// assert_eq!(asn, -1.25);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it can raise integer overflow errors if the
+    /// exponent of a binary encoding is longer than 8 octets.
+    pub fn read_real(self) -> ASN1Result<f64> {
+        let mode = self.inner.options.mode;
+        self.read_general(TAG_REAL, |contents| {
+            match contents {
+                Contents::Primitive(buf) => real::decode_real(buf, mode),
+                Contents::Constructed(_) => {
+                    Err(ASN1Error::new(ASN1ErrorKind::Invalid))
+                },
+            }
+        })
+    }
+
     #[cfg(feature = "num-bigint")]
     /// Reads an ASN.1 INTEGER value as `BigInt`.
     ///
@@ -671,26 +1626,14 @@ impl<'a, 'b> BERReader<'a, 'b> {
     ///
     /// # Features
     ///
-    /// This method is enabled by `num` feature.
+    /// This method is enabled by `num-bigint` feature.
     ///
     /// ```toml
     /// [dependencies]
-    /// yasna = { version = "*", features = ["num"] }
+    /// yasna = { version = "*", features = ["num-bigint"] }
     /// ```
     pub fn read_bigint(self) -> ASN1Result<BigInt> {
-        let (mut bytes, non_negative) = self.read_bigint_bytes()?;
-        let sign = if non_negative {
-            Sign::Plus
-        } else {
-            let mut carry: usize = 1;
-            for b in bytes.iter_mut().rev() {
-                let bval = 255 - (*b as usize);
-                *b = (bval + carry) as u8;
-                carry = (bval + carry) >> 8;
-            }
-            Sign::Minus
-        };
-        Ok(BigInt::from_bytes_be(sign, &bytes))
+        super::bigint::read(self)
     }
 
     /// Reads an ASN.1 INTEGER value as `Vec<u8>` and a sign bit.
@@ -715,6 +1658,34 @@ impl<'a, 'b> BERReader<'a, 'b> {
     /// # }
     /// ```
     pub fn read_bigint_bytes(self) -> ASN1Result<(Vec<u8>, bool)> {
+        let bytes = self.read_charged(1, |reader| {
+            reader.read_integer_bytes()
+        })?;
+        Ok((bytes.to_vec(), bytes[0] & 128 == 0))
+    }
+
+    /// Reads an ASN.1 INTEGER value as its contents octets, borrowed from
+    /// the input.
+    ///
+    /// The number given is in big endian byte ordering, in two's
+    /// complement, and has at least one byte.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[2, 3, 0, 255, 1];
+    /// let bytes = yasna::parse_der(data, |reader| {
+    ///     reader.read_integer_bytes()
+    /// }).unwrap();
+    /// assert_eq!(bytes, &[0, 255, 1]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it fails with [`ASN1ErrorKind::Invalid`] if
+    /// the contents are empty or not minimally encoded.
+    pub fn read_integer_bytes(self) -> ASN1Result<&'a [u8]> {
         self.read_general(TAG_INTEGER, |contents| {
             let buf = match contents {
                 Contents::Primitive(buf) => buf,
@@ -725,55 +1696,48 @@ impl<'a, 'b> BERReader<'a, 'b> {
             if buf.len() == 0 {
                 return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
             } else if buf.len() == 1 {
-                return Ok((buf.to_vec(), buf[0] & 128 == 0));
+                return Ok(buf);
             }
             let x2 = ((buf[0] as i8 as i32) << 8) + (buf[1] as i32);
             if -128 <= x2 && x2 < 128 {
                 return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
             }
-            let sign = 0 <= x2;
-            Ok((buf.to_vec(), sign))
+            Ok(buf)
         })
     }
 
     #[cfg(feature = "num-bigint")]
     /// Reads an ASN.1 INTEGER value as `BigUint`.
     ///
+    /// # Examples
+    ///
+    /// ```
+    /// # fn main() {
+    /// use yasna;
+    /// use num_bigint::BigUint;
+    /// let data = &[2, 5, 0, 128, 0, 0, 1];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_biguint()
+    /// }).unwrap();
+    /// assert_eq!(&asn, &BigUint::parse_bytes(b"2147483649", 10).unwrap());
+    /// # }
+    /// ```
+    ///
     /// # Errors
     ///
-    /// Except parse errors, it can raise integer overflow errors.
+    /// Except parse errors, it fails with [`ASN1ErrorKind::IntegerOverflow`]
+    /// if the value is negative.
     ///
     /// # Features
     ///
-    /// This method is enabled by `num` feature.
+    /// This method is enabled by `num-bigint` feature.
     ///
     /// ```toml
     /// [dependencies]
-    /// yasna = { version = "*", features = ["num"] }
+    /// yasna = { version = "*", features = ["num-bigint"] }
     /// ```
     pub fn read_biguint(self) -> ASN1Result<BigUint> {
-        self.read_general(TAG_INTEGER, |contents| {
-            let buf = match contents {
-                Contents::Primitive(buf) => buf,
-                Contents::Constructed(_) => {
-                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
-                },
-            };
-            if buf.len() == 0 {
-                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
-            } else if buf[0] >= 128 {
-                return Err(ASN1Error::new(ASN1ErrorKind::IntegerOverflow));
-            } else if buf.len() == 1 {
-                return Ok(BigUint::from(buf[0]));
-            }
-            let x2 = ((buf[0] as i8 as i32) << 8) + (buf[1] as i32);
-            if -128 <= x2 && x2 < 128 {
-                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
-            } else if x2 < 0 {
-                return Err(ASN1Error::new(ASN1ErrorKind::IntegerOverflow));
-            }
-            return Ok(BigUint::from_bytes_be(buf));
-        })
+        super::bigint::read(self)
     }
 
     fn read_bitvec_impl(self, unused_bits: &mut usize, bytes: &mut Vec<u8>)
@@ -782,47 +1746,51 @@ impl<'a, 'b> BERReader<'a, 'b> {
         if *unused_bits != 0 {
             return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
         }
-        let mode = self.inner.mode;
-        self.read_general(TAG_BITSTRING, |contents| {
-            match contents {
-                Contents::Primitive(buf) => {
-                    if buf.len() == 0 {
-                        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
-                    }
-                    if buf[0] >= 8 {
-                        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
-                    }
-                    if buf[0] > 0 {
-                        if buf.len() == 1 {
+        let options = self.inner.options;
+        let mode = options.mode;
+        let buf = self.read_charged(1, |reader| {
+            reader.read_general(TAG_BITSTRING, |contents| {
+                match contents {
+                    Contents::Primitive(buf) => {
+                        if buf.len() == 0 {
                             return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
                         }
-                        if mode == BERMode::Der &&
-                            (buf[buf.len()-1] & ((1<<buf[0]) - 1)) != 0 {
+                        if buf[0] >= 8 {
                             return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
                         }
-                    }
-                    bytes.extend_from_slice(&buf[1..]);
-                    *unused_bits = buf[0] as usize;
-                    return Ok(());
-                },
-                Contents::Constructed(inner) => {
-                    if mode == BERMode::Der {
-                        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
-                    }
-                    loop {
-                        let result = inner.read_optional(|inner| {
+                        if buf[0] > 0 {
+                            if buf.len() == 1 {
+                                return Err(
+                                    ASN1Error::new(ASN1ErrorKind::Invalid));
+                            }
+                            if options.enforces(
+                                    LeniencyFlags::BIT_STRING_PADDING) &&
+                                (buf[buf.len()-1] & ((1<<buf[0]) - 1)) != 0 {
+                                return Err(
+                                    ASN1Error::new(ASN1ErrorKind::Invalid));
+                            }
+                        }
+                        if mode == BERMode::Cer && buf.len() > CER_SEGMENT_LEN {
+                            return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                        }
+                        *unused_bits = buf[0] as usize;
+                        return Ok(&buf[1..]);
+                    },
+                    Contents::Constructed(inner) => {
+                        inner.read_segments(|inner| {
+                            let start = bytes.len();
                             BERReader::new(inner)
-                                .read_bitvec_impl(unused_bits, bytes)
+                                .read_bitvec_impl(unused_bits, bytes)?;
+                            // With the unused bits octet
+                            Ok(bytes.len() - start + 1)
                         })?;
-                        match result {
-                            Some(()) => {},
-                            None => { break; },
-                        }
-                    }
-                    return Ok(());
-                },
-            };
-        })
+                        return Ok(&[]);
+                    },
+                };
+            })
+        })?;
+        bytes.extend_from_slice(buf);
+        Ok(())
     }
 
     #[cfg(feature = "bit-vec")]
@@ -891,30 +1859,93 @@ impl<'a, 'b> BERReader<'a, 'b> {
         return Ok((bytes, len));
     }
 
-    fn read_bytes_impl(self, vec: &mut Vec<u8>) -> ASN1Result<()> {
-        self.read_general(TAG_OCTETSTRING, |contents| {
+    /// Reads an ASN.1 BITSTRING value, and parses its contents as another
+    /// encoded value.
+    ///
+    /// This is how SubjectPublicKeyInfo and signatures carry nested
+    /// values. The BITSTRING must have no unused bits. Otherwise it works
+    /// as [`read_octetstring_der`](Self::read_octetstring_der).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[3, 6, 0, 48, 3, 2, 1, 10];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_bitstring_der(|reader| {
+    ///         reader.read_sequence(|reader| reader.next().read_i64())
+    ///     })
+    /// }).unwrap();
+    /// assert_eq!(asn, 10);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// It fails with [`ASN1ErrorKind::Invalid`] if the BITSTRING has
+    /// unused bits, and as [`read_octetstring_der`] does otherwise.
+    ///
+    /// [`read_octetstring_der`]: Self::read_octetstring_der
+    pub fn read_bitstring_der<T, F>(self, callback: F) -> ASN1Result<T>
+            where F: for<'c, 'd> FnOnce(BERReader<'c, 'd>) -> ASN1Result<T> {
+        use super::tags::TAG_BITSTRING;
+        let mode = self.inner.options.mode;
+        let BERReader { inner, implicit_tag } = self;
+        let tag = implicit_tag.unwrap_or(TAG_BITSTRING);
+        let bytes = inner.read_general(tag, |contents| {
             match contents {
                 Contents::Primitive(buf) => {
-                    vec.extend(buf);
-                    return Ok(());
+                    if buf.is_empty() || buf[0] != 0 {
+                        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                    }
+                    if mode == BERMode::Cer && buf.len() > CER_SEGMENT_LEN {
+                        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                    }
+                    Ok(Cow::Borrowed(&buf[1..]))
                 },
                 Contents::Constructed(inner) => {
-                    if inner.mode == BERMode::Der {
+                    let mut unused_bits = 0;
+                    let mut bytes = Vec::new();
+                    inner.read_segments(|inner| {
+                        let start = bytes.len();
+                        BERReader::new(inner)
+                            .read_bitvec_impl(&mut unused_bits, &mut bytes)?;
+                        // With the unused bits octet
+                        Ok(bytes.len() - start + 1)
+                    })?;
+                    if unused_bits != 0 {
                         return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
                     }
-                    loop {
-                        let result = inner.read_optional(|inner| {
-                            BERReader::new(inner).read_bytes_impl(vec)
-                        })?;
-                        match result {
-                            Some(()) => {},
-                            None => { break; },
-                        }
-                    }
-                    return Ok(());
+                    Ok(Cow::Owned(bytes))
                 },
-            };
-        })
+            }
+        })?;
+        inner.parse_nested(&bytes, callback)
+    }
+
+    fn read_bytes_impl(self, vec: &mut Vec<u8>) -> ASN1Result<()> {
+        let mode = self.inner.options.mode;
+        let buf = self.read_charged(1, |reader| {
+            reader.read_general(TAG_OCTETSTRING, |contents| {
+                match contents {
+                    Contents::Primitive(buf) => {
+                        if mode == BERMode::Cer && buf.len() > CER_SEGMENT_LEN {
+                            return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                        }
+                        return Ok(buf);
+                    },
+                    Contents::Constructed(inner) => {
+                        inner.read_segments(|inner| {
+                            let start = vec.len();
+                            BERReader::new(inner).read_bytes_impl(vec)?;
+                            Ok(vec.len() - start)
+                        })?;
+                        return Ok(&[]);
+                    },
+                };
+            })
+        })?;
+        vec.extend(buf);
+        Ok(())
     }
 
     /// Reads an ASN.1 OCTETSTRING value as `Vec<u8>`.
@@ -935,84 +1966,456 @@ impl<'a, 'b> BERReader<'a, 'b> {
         return Ok(ret);
     }
 
-    /// Reads the ASN.1 NULL value.
+    /// Reads an ASN.1 OCTETSTRING value, borrowed from the input.
+    ///
+    /// Unlike [`read_bytes`](Self::read_bytes), it doesn't copy the
+    /// contents, which makes it suited to large payloads.
     ///
     /// # Examples
     ///
     /// ```
     /// use yasna;
-    /// let data = &[5, 0];
+    /// let data = &[4, 6, 72, 101, 108, 108, 111, 33];
     /// let asn = yasna::parse_der(data, |reader| {
-    ///     reader.read_null()
+    ///     reader.read_bytes_borrowed()
     /// }).unwrap();
-    /// assert_eq!(asn, ());
+    /// assert_eq!(asn, b"Hello!");
     /// ```
-    pub fn read_null(self) -> ASN1Result<()> {
-        self.read_general(TAG_NULL, |contents| {
-            let buf = match contents {
-                Contents::Primitive(buf) => buf,
+    ///
+    /// # Errors
+    ///
+    /// The contents of a constructed encoding, which BER and CER allow,
+    /// aren't contiguous in the input, so it fails with
+    /// [`ASN1ErrorKind::Invalid`] on them. Use
+    /// [`read_bytes`](Self::read_bytes) to accept them.
+    pub fn read_bytes_borrowed(self) -> ASN1Result<&'a [u8]> {
+        let mode = self.inner.options.mode;
+        self.read_general(TAG_OCTETSTRING, |contents| {
+            match contents {
+                Contents::Primitive(buf) => {
+                    if mode == BERMode::Cer && buf.len() > CER_SEGMENT_LEN {
+                        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                    }
+                    Ok(buf)
+                },
                 Contents::Constructed(_) => {
-                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                    Err(ASN1Error::new(ASN1ErrorKind::Invalid))
                 },
-            };
-            if buf.len() != 0 {
-                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
             }
-            return Ok(());
         })
     }
 
-    /// Reads an ASN.1 object identifier.
+    /// Reads an ASN.1 OCTETSTRING value, borrowing it from the input
+    /// where possible.
+    ///
+    /// A primitive encoding is borrowed like
+    /// [`read_bytes_borrowed`](Self::read_bytes_borrowed), and only a
+    /// constructed one, which BER and CER allow, is reassembled into an
+    /// owned buffer.
     ///
     /// # Examples
     ///
     /// ```
+    /// use std::borrow::Cow;
     /// use yasna;
-    /// let data = &[6, 8, 42, 134, 72, 134, 247, 13, 1, 1];
+    /// let data = &[4, 2, 72, 105];
+    /// let asn = yasna::parse_ber(data, |reader| {
+    ///     reader.read_bytes_cow()
+    /// }).unwrap();
+    /// assert_eq!(asn, Cow::Borrowed(b"Hi"));
+    ///
+    /// let data = &[36, 128, 4, 1, 72, 4, 1, 105, 0, 0];
+    /// let asn = yasna::parse_ber(data, |reader| {
+    ///     reader.read_bytes_cow()
+    /// }).unwrap();
+    /// assert_eq!(asn, Cow::<[u8]>::Owned(b"Hi".to_vec()));
+    /// ```
+    pub fn read_bytes_cow(self) -> ASN1Result<Cow<'a, [u8]>> {
+        let mode = self.inner.options.mode;
+        self.read_general(TAG_OCTETSTRING, |contents| {
+            match contents {
+                Contents::Primitive(buf) => {
+                    if mode == BERMode::Cer && buf.len() > CER_SEGMENT_LEN {
+                        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                    }
+                    Ok(Cow::Borrowed(buf))
+                },
+                Contents::Constructed(inner) => {
+                    let mut vec = Vec::new();
+                    inner.read_segments(|inner| {
+                        let start = vec.len();
+                        BERReader::new(inner).read_bytes_impl(&mut vec)?;
+                        Ok(vec.len() - start)
+                    })?;
+                    Ok(Cow::Owned(vec))
+                },
+            }
+        })
+    }
+
+    /// Reads an ASN.1 OCTETSTRING value, and parses its contents as
+    /// another encoded value.
+    ///
+    /// This is how X.509 extensions, PKCS#8 private keys and CMS carry
+    /// nested values. `callback` is called back with a [`BERReader`] over
+    /// the contents, which are parsed with the same options as the outer
+    /// value, and must be consumed entirely.
+    ///
+    /// The contents are borrowed from the input unless the OCTET STRING
+    /// is constructed, as in [`read_bytes_cow`](Self::read_bytes_cow).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[4, 5, 48, 3, 2, 1, 10];
     /// let asn = yasna::parse_der(data, |reader| {
-    ///     reader.read_oid()
+    ///     reader.read_octetstring_der(|reader| {
+    ///         reader.read_sequence(|reader| reader.next().read_i64())
+    ///     })
     /// }).unwrap();
-    /// assert_eq!(&*asn.components(), &[1, 2, 840, 113549, 1, 1]);
+    /// assert_eq!(asn, 10);
     /// ```
-    pub fn read_oid(self) -> ASN1Result<ObjectIdentifier> {
-        self.read_general(TAG_OID, |contents| {
+    ///
+    /// # Errors
+    ///
+    /// Besides the errors of `callback`, it fails with
+    /// [`ASN1ErrorKind::Extra`] if the contents are not consumed entirely.
+    /// The nesting counts towards [`ParseOptions::max_depth`], and the
+    /// allocations of the inner parse towards
+    /// [`ParseOptions::max_alloc`].
+    pub fn read_octetstring_der<T, F>(self, callback: F) -> ASN1Result<T>
+            where F: for<'c, 'd> FnOnce(BERReader<'c, 'd>) -> ASN1Result<T> {
+        let BERReader { inner, implicit_tag } = self;
+        let bytes = BERReader { inner: &mut *inner, implicit_tag }
+            .read_bytes_cow()?;
+        inner.parse_nested(&bytes, callback)
+    }
+
+    /// Reads the ASN.1 NULL value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[5, 0];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_null()
+    /// }).unwrap();
+    /// assert_eq!(asn, ());
+    /// ```
+    pub fn read_null(self) -> ASN1Result<()> {
+        self.read_general(TAG_NULL, |contents| {
             let buf = match contents {
                 Contents::Primitive(buf) => buf,
                 Contents::Constructed(_) => {
                     return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
                 },
             };
-            let mut components = Vec::new();
-            if buf.len() == 0 || buf[buf.len()-1] >= 128 {
+            if buf.len() != 0 {
                 return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
             }
-            let mut subid : u64 = 0;
-            for &b in buf.iter() {
-                if b == 128 {
-                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            return Ok(());
+        })
+    }
+
+    /// Reads an ASN.1 object identifier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[6, 8, 42, 134, 72, 134, 247, 13, 1, 1];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_oid()
+    /// }).unwrap();
+    /// assert_eq!(&*asn.components(), &[1, 2, 840, 113549, 1, 1]);
+    /// ```
+    pub fn read_oid(self) -> ASN1Result<ObjectIdentifier> {
+        let buf = self.read_charged(2 * size_of::<u64>(), |reader| {
+            reader.read_general(TAG_OID, |contents| {
+                match contents {
+                    Contents::Primitive(buf) => Ok(buf),
+                    Contents::Constructed(_) => {
+                        Err(ASN1Error::new(ASN1ErrorKind::Invalid))
+                    },
                 }
-                subid = subid.checked_mul(128)
-                    .ok_or(ASN1Error::new(
-                        ASN1ErrorKind::IntegerOverflow))? + ((b & 127) as u64);
-                if (b & 128) == 0 {
-                    if components.len() == 0 {
-                        let id0 = if subid < 40 {
-                            0
-                        } else if subid < 80 {
-                            1
-                        } else {
-                            2
-                        };
-                        let id1 = subid - 40 * id0;
-                        components.push(id0);
-                        components.push(id1);
-                    } else {
-                        components.push(subid);
-                    }
-                    subid = 0;
+            })
+        })?;
+        let subids = decode_subidentifiers(buf)?;
+        let mut components = Vec::with_capacity(subids.len() + 1);
+        // The first subidentifier encodes the first two components.
+        let subid = subids[0];
+        let id0 = if subid < 40 {
+            0
+        } else if subid < 80 {
+            1
+        } else {
+            2
+        };
+        let id1 = subid - 40 * id0;
+        components.push(id0);
+        components.push(id1);
+        components.extend_from_slice(&subids[1..]);
+        return Ok(ObjectIdentifier::new(components));
+    }
+
+    /// Reads an ASN.1 relative object identifier (RELATIVE-OID).
+    ///
+    /// The components are relative to some object identifier known from
+    /// the context, and are returned as they are, as an
+    /// [`ObjectIdentifier`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[13, 4, 1, 134, 72, 5];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_relative_oid()
+    /// }).unwrap();
+    /// assert_eq!(&*asn.components(), &[1, 840, 5]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it fails with [`ASN1ErrorKind::Invalid`] if
+    /// the contents are empty or a component isn't minimally encoded, and
+    /// with [`ASN1ErrorKind::IntegerOverflow`] if a component exceeds
+    /// `u64`.
+    pub fn read_relative_oid(self) -> ASN1Result<ObjectIdentifier> {
+        use super::tags::TAG_RELATIVE_OID;
+        let buf = self.read_charged(size_of::<u64>(), |reader| {
+            reader.read_general(TAG_RELATIVE_OID, |contents| {
+                match contents {
+                    Contents::Primitive(buf) => Ok(buf),
+                    Contents::Constructed(_) => {
+                        Err(ASN1Error::new(ASN1ErrorKind::Invalid))
+                    },
                 }
-            }
-            return Ok(ObjectIdentifier::new(components));
+            })
+        })?;
+        Ok(ObjectIdentifier::new(decode_subidentifiers(buf)?))
+    }
+
+    /// Reads an ASN.1 OID-IRI.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[31, 35, 4, 47, 73, 83, 79];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_oid_iri()
+    /// }).unwrap();
+    /// assert_eq!(asn.as_str(), "/ISO");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it fails with [`ASN1ErrorKind::Invalid`] if
+    /// the contents aren't valid UTF-8 or aren't an OID-IRI as accepted by
+    /// [`OidIri::new`].
+    pub fn read_oid_iri(self) -> ASN1Result<OidIri> {
+        use super::tags::TAG_OID_INTL_RESID;
+        self.read_tagged_implicit(TAG_OID_INTL_RESID, |reader| {
+            let bytes = reader.read_bytes()?;
+            String::from_utf8(bytes).ok().and_then(OidIri::new)
+                .ok_or(ASN1Error::new(ASN1ErrorKind::Invalid))
+        })
+    }
+
+    /// Reads an ASN.1 RELATIVE-OID-IRI.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[31, 36, 9, 69, 120, 97, 109, 112, 108, 101, 47, 49];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_relative_oid_iri()
+    /// }).unwrap();
+    /// assert_eq!(asn.as_str(), "Example/1");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it fails with [`ASN1ErrorKind::Invalid`] if
+    /// the contents aren't valid UTF-8 or aren't a RELATIVE-OID-IRI as
+    /// accepted by [`RelativeOidIri::new`].
+    pub fn read_relative_oid_iri(self) -> ASN1Result<RelativeOidIri> {
+        use super::tags::TAG_RELATIVE_OID_INTL_RESID;
+        self.read_tagged_implicit(TAG_RELATIVE_OID_INTL_RESID, |reader| {
+            let bytes = reader.read_bytes()?;
+            String::from_utf8(bytes).ok().and_then(RelativeOidIri::new)
+                .ok_or(ASN1Error::new(ASN1ErrorKind::Invalid))
+        })
+    }
+
+    /// Reads an ASN.1 DATE.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[31, 31, 8, 50, 48, 50, 52, 48, 50, 50, 57];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_date()
+    /// }).unwrap();
+    /// assert_eq!(asn.day(), 29);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it fails with [`ASN1ErrorKind::Invalid`] if
+    /// the contents aren't accepted by [`Date::parse`].
+    pub fn read_date(self) -> ASN1Result<Date> {
+        use super::tags::TAG_DATE;
+        self.read_tagged_implicit(TAG_DATE, |reader| {
+            let bytes = reader.read_bytes()?;
+            Date::parse(&bytes).ok_or(ASN1Error::new(ASN1ErrorKind::Invalid))
+        })
+    }
+
+    /// Reads an ASN.1 TIME-OF-DAY.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[31, 32, 6, 49, 50, 51, 52, 53, 54];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_time_of_day()
+    /// }).unwrap();
+    /// assert_eq!(asn.minute(), 34);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it fails with [`ASN1ErrorKind::Invalid`] if
+    /// the contents aren't accepted by [`TimeOfDay::parse`].
+    pub fn read_time_of_day(self) -> ASN1Result<TimeOfDay> {
+        use super::tags::TAG_TIME_OF_DAY;
+        self.read_tagged_implicit(TAG_TIME_OF_DAY, |reader| {
+            let bytes = reader.read_bytes()?;
+            TimeOfDay::parse(&bytes).ok_or(ASN1Error::new(ASN1ErrorKind::Invalid))
+        })
+    }
+
+    /// Reads an ASN.1 DATE-TIME.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[
+    ///     31, 33, 14, 50, 48, 50, 52, 48, 50, 50, 57, 49, 50,
+    ///     51, 52, 53, 54];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_date_time()
+    /// }).unwrap();
+    /// assert_eq!(asn.time().hour(), 12);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it fails with [`ASN1ErrorKind::Invalid`] if
+    /// the contents aren't accepted by [`DateTime::parse`].
+    pub fn read_date_time(self) -> ASN1Result<DateTime> {
+        use super::tags::TAG_DATE_TIME;
+        self.read_tagged_implicit(TAG_DATE_TIME, |reader| {
+            let bytes = reader.read_bytes()?;
+            DateTime::parse(&bytes).ok_or(ASN1Error::new(ASN1ErrorKind::Invalid))
+        })
+    }
+
+    /// Reads an ASN.1 DURATION.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[31, 34, 7, 80, 49, 68, 84, 49, 50, 72];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_duration()
+    /// }).unwrap();
+    /// assert_eq!(asn.hours(), 12);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it fails with [`ASN1ErrorKind::Invalid`] if
+    /// the contents aren't accepted by [`Duration::parse`].
+    pub fn read_duration(self) -> ASN1Result<Duration> {
+        use super::tags::TAG_DURATION;
+        self.read_tagged_implicit(TAG_DURATION, |reader| {
+            let bytes = reader.read_bytes()?;
+            Duration::parse(&bytes).ok_or(ASN1Error::new(ASN1ErrorKind::Invalid))
+        })
+    }
+
+    /// Reads an ASN.1 EXTERNAL value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// use yasna::models::ExternalEncoding;
+    /// let data = &[40, 10, 6, 3, 85, 4, 3, 129, 3, 1, 2, 3];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_external()
+    /// }).unwrap();
+    /// let oid = asn.direct_reference.unwrap();
+    /// assert_eq!(&*oid.components(), &[2, 5, 4, 3]);
+    /// assert_eq!(asn.encoding, ExternalEncoding::OctetAligned(vec![1, 2, 3]));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it fails with [`ASN1ErrorKind::Invalid`] if
+    /// the data-value-descriptor isn't valid UTF-8.
+    pub fn read_external(self) -> ASN1Result<External> {
+        use super::tags::TAG_EXT;
+        self.read_tagged_implicit(TAG_EXT, |reader| {
+            reader.read_sequence(|reader| {
+                let direct_reference = reader.read_optional(|reader| {
+                    reader.read_oid()
+                })?;
+                let indirect_reference = reader.read_optional(|reader| {
+                    reader.read_i64()
+                })?;
+                let data_value_descriptor = reader.read_optional(|reader| {
+                    reader.read_object_descriptor()
+                })?;
+                let reader = reader.next();
+                let tag = reader.lookahead_tag()?;
+                let encoding = match (tag.tag_class, tag.tag_number) {
+                    (TagClass::ContextSpecific, 0) => {
+                        let value = reader.read_tagged(tag, |reader| {
+                            reader.read_tagged_der()
+                        })?;
+                        ExternalEncoding::SingleAsn1Type(value)
+                    },
+                    (TagClass::ContextSpecific, 1) => {
+                        let bytes = reader.read_tagged_implicit(tag, |reader| {
+                            reader.read_bytes()
+                        })?;
+                        ExternalEncoding::OctetAligned(bytes)
+                    },
+                    (TagClass::ContextSpecific, 2) => {
+                        let (bytes, len) = reader.read_tagged_implicit(tag,
+                            |reader| reader.read_bitvec_bytes())?;
+                        ExternalEncoding::Arbitrary(bytes, len)
+                    },
+                    _ => return Err(ASN1Error::new(ASN1ErrorKind::Invalid)),
+                };
+                Ok(External {
+                    direct_reference,
+                    indirect_reference,
+                    data_value_descriptor,
+                    encoding,
+                })
+            })
         })
     }
 
@@ -1041,6 +2444,66 @@ impl<'a, 'b> BERReader<'a, 'b> {
         })
     }
 
+    /// Reads an ASN.1 UTF8String, borrowed from the input.
+    ///
+    /// The contents are validated in place, without copying.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[12, 6, 207, 129, 206, 191, 206, 186];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_utf8string_borrowed()
+    /// }).unwrap();
+    /// assert_eq!(asn, "ροκ");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Like [`read_bytes_borrowed`](Self::read_bytes_borrowed), it fails
+    /// with [`ASN1ErrorKind::Invalid`] on a constructed encoding.
+    pub fn read_utf8string_borrowed(self) -> ASN1Result<&'a str> {
+        self.read_tagged_implicit(TAG_UTF8STRING, |reader| {
+            let bytes = reader.read_bytes_borrowed()?;
+            match core::str::from_utf8(bytes) {
+                Ok(string) => Ok(string),
+                Err(_) => Err(ASN1Error::new(ASN1ErrorKind::Invalid)),
+            }
+        })
+    }
+
+    /// Reads an ASN.1 UTF8String, borrowing it from the input where
+    /// possible.
+    ///
+    /// See [`read_bytes_cow`](Self::read_bytes_cow).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::borrow::Cow;
+    /// use yasna;
+    /// let data = &[12, 2, 72, 105];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_utf8string_cow()
+    /// }).unwrap();
+    /// assert_eq!(asn, Cow::Borrowed("Hi"));
+    /// ```
+    pub fn read_utf8string_cow(self) -> ASN1Result<Cow<'a, str>> {
+        self.read_tagged_implicit(TAG_UTF8STRING, |reader| {
+            match reader.read_bytes_cow()? {
+                Cow::Borrowed(bytes) => match core::str::from_utf8(bytes) {
+                    Ok(string) => Ok(Cow::Borrowed(string)),
+                    Err(_) => Err(ASN1Error::new(ASN1ErrorKind::Invalid)),
+                },
+                Cow::Owned(bytes) => match String::from_utf8(bytes) {
+                    Ok(string) => Ok(Cow::Owned(string)),
+                    Err(_) => Err(ASN1Error::new(ASN1ErrorKind::Invalid)),
+                },
+            }
+        })
+    }
+
     /// Reads an ASN.1 SEQUENCE value.
     ///
     /// This function uses the loan pattern: `callback` is called back with
@@ -1075,6 +2538,36 @@ impl<'a, 'b> BERReader<'a, 'b> {
         })
     }
 
+    /// Reads an ASN.1 SEQUENCE value with an extension marker.
+    ///
+    /// Same as [`read_sequence`](Self::read_sequence), except that the
+    /// elements left after `callback` returns are skipped instead of
+    /// failing with [`ASN1ErrorKind::Extra`]. This lets a type ending in
+    /// `...` accept values of later versions, which append components to
+    /// it. The skipped elements must still be well-formed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[48, 8, 2, 1, 10, 1, 1, 255, 5, 0];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_sequence_extensible(|reader| {
+    ///         reader.next().read_i64()
+    ///     })
+    /// }).unwrap();
+    /// assert_eq!(asn, 10);
+    /// ```
+    pub fn read_sequence_extensible<T, F>(self, callback: F) -> ASN1Result<T>
+            where F: for<'c> FnOnce(
+                &mut BERReaderSeq<'a, 'c>) -> ASN1Result<T> {
+        self.read_sequence(|reader| {
+            let result = callback(reader)?;
+            reader.read_remaining_blob()?;
+            Ok(result)
+        })
+    }
+
     /// Reads an ASN.1 SEQUENCE OF value.
     ///
     /// This function uses the loan pattern: `callback` is called back with
@@ -1144,12 +2637,77 @@ impl<'a, 'b> BERReader<'a, 'b> {
         return Ok(collection);
     }
 
+    /// Reads an ASN.1 SEQUENCE OF INTEGER value as `Vec<i64>`.
+    ///
+    /// Same as collecting [`read_i64`](Self::read_i64) with
+    /// [`collect_sequence_of`](Self::collect_sequence_of), but decodes
+    /// the elements in a single loop, which is faster for long sequences.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[48, 7, 2, 1, 10, 2, 2, 255, 127];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_sequence_of_i64()
+    /// }).unwrap();
+    /// assert_eq!(asn, vec![10, -129]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it can raise integer overflow errors.
+    pub fn read_sequence_of_i64(self) -> ASN1Result<Vec<i64>> {
+        self.read_sequence_of_integers(decode_i64)
+    }
+
+    /// Reads an ASN.1 SEQUENCE OF INTEGER value as `Vec<u64>`.
+    ///
+    /// See [`read_sequence_of_i64`](Self::read_sequence_of_i64) and
+    /// [`read_u64`](Self::read_u64).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[48, 7, 2, 1, 10, 2, 2, 0, 255];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_sequence_of_u64()
+    /// }).unwrap();
+    /// assert_eq!(asn, vec![10, 255]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it can raise integer overflow errors, which
+    /// includes negative values.
+    pub fn read_sequence_of_u64(self) -> ASN1Result<Vec<u64>> {
+        self.read_sequence_of_integers(decode_u64)
+    }
+
+    fn read_sequence_of_integers<T>(self, decode: fn(&[u8]) -> ASN1Result<T>)
+            -> ASN1Result<Vec<T>> {
+        // Looks ahead for the length. Errors are left to `read_sequence`.
+        let mut lookahead = BERReaderImpl::with_pos(
+            self.inner.buf, self.inner.pos, self.inner.options);
+        let length = lookahead.read_identifier()
+            .and_then(|_| lookahead.read_length()).unwrap_or(None);
+        self.read_sequence(|reader| {
+            reader.inner.read_integers(length, decode)
+        })
+    }
+
     /// Reads an ASN.1 SET value.
     ///
     /// This function uses the loan pattern: `callback` is called back with
     /// a [`BERReaderSet`], from which the contents of the
     /// SET are read.
     ///
+    /// The components are looked up by tag with [`BERReaderSet::next`],
+    /// so they may be read in any order, regardless of the order in which
+    /// they are encoded. In DER and CER, it fails if they aren't encoded
+    /// in ascending order of their tags, as X.690 10.3 requires.
+    ///
     /// For SET OF values, use `read_set_of` instead.
     ///
     /// # Examples
@@ -1190,18 +2748,21 @@ impl<'a, 'b> BERReader<'a, 'b> {
                     break;
                 }
             }
-            if inner.mode == BERMode::Der {
+            if inner.options.enforces(LeniencyFlags::SET_ORDER) {
                 for i in 1..elements.len() {
                     if elements[i] <= elements[i-1] {
                         return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
                     }
                 }
             }
-            let mut new_impl = BERReaderImpl::new(&[], inner.mode);
+            let mut new_impl = BERReaderImpl::new(&[], inner.options);
+            new_impl.depth = inner.depth;
+            new_impl.allocated = inner.allocated;
             let result = callback(&mut BERReaderSet {
                 impl_ref: &mut new_impl,
                 elements: &mut elements,
             })?;
+            inner.allocated = new_impl.allocated;
             if elements.len() > 0 {
                 return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
             }
@@ -1218,8 +2779,9 @@ impl<'a, 'b> BERReader<'a, 'b> {
     /// This function doesn't return values. Instead, use mutable values to
     /// maintain read values. `collect_set_of` can be an alternative.
     ///
-    /// This function doesn't sort the elements. In DER, it is assumed that
-    /// the elements occur in an order determined by DER encodings of them.
+    /// This function doesn't sort the elements. In DER, it fails if the
+    /// elements don't occur in ascending order of their encodings, as
+    /// X.690 11.6 requires.
     ///
     /// For SET values, use `read_set` instead.
     ///
@@ -1254,7 +2816,8 @@ impl<'a, 'b> BERReader<'a, 'b> {
                     })
             })? {
                 if let Some(last_buf) = last_buf {
-                    if inner.mode == BERMode::Der && buf < last_buf {
+                    if inner.options.enforces(LeniencyFlags::SET_ORDER) &&
+                            buf < last_buf {
                         return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
                     }
                 }
@@ -1272,8 +2835,9 @@ impl<'a, 'b> BERReader<'a, 'b> {
     ///
     /// If you don't like `Vec`, you can use `read_set_of` instead.
     ///
-    /// This function doesn't sort the elements. In DER, it is assumed that
-    /// the elements occur in an order determined by DER encodings of them.
+    /// This function doesn't sort the elements. In DER, it fails if the
+    /// elements don't occur in ascending order of their encodings, as
+    /// X.690 11.6 requires.
     ///
     /// # Examples
     ///
@@ -1333,23 +2897,49 @@ impl<'a, 'b> BERReader<'a, 'b> {
     /// }).unwrap();
     /// assert_eq!(&asn, "Co., Ltd.");
     /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it fails with [`ASN1ErrorKind::Invalid`] if a
+    /// character other than letters, digits, space and `'()+,-./:=?`
+    /// appears.
     pub fn read_printable_string(self) -> ASN1Result<String> {
         self.read_tagged_implicit(TAG_PRINTABLESTRING, |reader| {
             let bytes = reader.read_bytes()?;
-            for &byte in bytes.iter() {
-                if !(
-                    byte == b' ' ||
-                    (b'\'' <= byte && byte <= b':' && byte != b'*') ||
-                    byte == b'=' ||
-                    (b'A' <= byte && byte <= b'Z') ||
-                    (b'a' <= byte && byte <= b'z')) {
-                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
-                }
+            if !bytes.iter().all(|&byte| is_printable_char(byte)) {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
             }
             return Ok(String::from_utf8(bytes).unwrap());
         })
     }
 
+    /// Reads an ASN.1 PrintableString, borrowed from the input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[19, 9, 67, 111, 46, 44, 32, 76, 116, 100, 46];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_printable_string_borrowed()
+    /// }).unwrap();
+    /// assert_eq!(asn, "Co., Ltd.");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Like [`read_bytes_borrowed`](Self::read_bytes_borrowed), it fails
+    /// with [`ASN1ErrorKind::Invalid`] on a constructed encoding.
+    pub fn read_printable_string_borrowed(self) -> ASN1Result<&'a str> {
+        self.read_tagged_implicit(TAG_PRINTABLESTRING, |reader| {
+            let bytes = reader.read_bytes_borrowed()?;
+            if !bytes.iter().all(|&byte| is_printable_char(byte)) {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            Ok(core::str::from_utf8(bytes).unwrap())
+        })
+    }
+
     /// Reads an ASN.1 IA5String.
     ///
     /// # Examples
@@ -1378,6 +2968,33 @@ impl<'a, 'b> BERReader<'a, 'b> {
         })
     }
 
+    /// Reads an ASN.1 IA5String, borrowed from the input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[22, 9, 0x41, 0x53, 0x43, 0x49, 0x49, 0x20, 0x70, 0x6C, 0x7A];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_ia5_string_borrowed()
+    /// }).unwrap();
+    /// assert_eq!(asn, "ASCII plz");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Like [`read_bytes_borrowed`](Self::read_bytes_borrowed), it fails
+    /// with [`ASN1ErrorKind::Invalid`] on a constructed encoding.
+    pub fn read_ia5_string_borrowed(self) -> ASN1Result<&'a str> {
+        self.read_tagged_implicit(TAG_IA5STRING, |reader| {
+            let bytes = reader.read_bytes_borrowed()?;
+            if !bytes.is_ascii() {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            Ok(core::str::from_utf8(bytes).unwrap())
+        })
+    }
+
     /// Reads an ASN.1 BMPString.
     ///
     /// # Examples
@@ -1390,6 +3007,12 @@ impl<'a, 'b> BERReader<'a, 'b> {
     /// }).unwrap();
     /// assert_eq!(&asn, "£π quux");
     /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it fails with [`ASN1ErrorKind::Invalid`] if
+    /// the length is odd, or if the UTF-16 code units contain an unpaired
+    /// surrogate.
     pub fn read_bmp_string(self) -> ASN1Result<String> {
         self.read_tagged_implicit(TAG_BMPSTRING, |reader| {
             let bytes = reader.read_bytes()?;
@@ -1400,7 +3023,200 @@ impl<'a, 'b> BERReader<'a, 'b> {
 
             let utf16 : Vec<u16> = bytes.chunks(2).map(|c| (c[0] as u16) * 256 + c[1] as u16).collect();
 
-            Ok(String::from_utf16_lossy(&utf16))
+            String::from_utf16(&utf16).map_err(|_| {
+                ASN1Error::new(ASN1ErrorKind::Invalid)
+            })
+        })
+    }
+
+    /// Reads an ASN.1 TeletexString (T61String), interpreting it as
+    /// specified by `decoding`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::TeletexDecoding;
+    /// // "Müller" in T.61, where the diaeresis precedes the letter
+    /// let data = &[20, 7, 77, 200, 117, 108, 108, 101, 114];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_teletex_string(TeletexDecoding::T61)
+    /// }).unwrap();
+    /// assert_eq!(&asn, "Mu\u{308}ller");
+    /// // "Müller" in Latin-1
+    /// let data = &[20, 6, 77, 252, 108, 108, 101, 114];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_teletex_string(TeletexDecoding::Utf8OrLatin1)
+    /// }).unwrap();
+    /// assert_eq!(&asn, "Müller");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it fails with [`ASN1ErrorKind::Invalid`] if
+    /// the contents can't be decoded.
+    pub fn read_teletex_string(self, decoding: TeletexDecoding)
+            -> ASN1Result<String> {
+        self.read_tagged_implicit(TAG_TELETEXSTRING, |reader| {
+            let bytes = reader.read_bytes()?;
+            let latin1 = |bytes: &[u8]| {
+                bytes.iter().map(|&b| b as char).collect::<String>()
+            };
+            let string = match decoding {
+                TeletexDecoding::T61 => teletex::decode_t61(&bytes),
+                TeletexDecoding::Latin1 => Some(latin1(&bytes)),
+                TeletexDecoding::Utf8 => String::from_utf8(bytes).ok(),
+                TeletexDecoding::Utf8OrLatin1 => {
+                    String::from_utf8(bytes).or_else(|e| {
+                        Ok::<_, ()>(latin1(e.as_bytes()))
+                    }).ok()
+                },
+            };
+            string.ok_or(ASN1Error::new(ASN1ErrorKind::Invalid))
+        })
+    }
+
+    /// Reads an ASN.1 GeneralString as raw bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[27, 4, 0x4B, 0x52, 0x42, 0xFF];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_general_string_bytes()
+    /// }).unwrap();
+    /// assert_eq!(&asn, &[0x4B, 0x52, 0x42, 0xFF]);
+    /// ```
+    pub fn read_general_string_bytes(self) -> ASN1Result<Vec<u8>> {
+        self.read_tagged_implicit(TAG_GENERALSTRING, |reader| {
+            reader.read_bytes()
+        })
+    }
+
+    /// Reads an ASN.1 GeneralString, interpreting it as specified by
+    /// `decoding`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::StringDecoding;
+    /// let data = &[27, 11, 69, 88, 65, 77, 80, 76, 69, 46, 67, 79, 77];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_general_string(StringDecoding::Ascii)
+    /// }).unwrap();
+    /// assert_eq!(&asn, "EXAMPLE.COM");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it fails with [`ASN1ErrorKind::Invalid`] if
+    /// the contents can't be decoded.
+    pub fn read_general_string(self, decoding: StringDecoding)
+            -> ASN1Result<String> {
+        decode_string(self.read_general_string_bytes()?, decoding)
+    }
+
+    /// Reads an ASN.1 GraphicString as raw bytes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[25, 2, 0x41, 0xE9];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_graphic_string_bytes()
+    /// }).unwrap();
+    /// assert_eq!(&asn, &[0x41, 0xE9]);
+    /// ```
+    pub fn read_graphic_string_bytes(self) -> ASN1Result<Vec<u8>> {
+        self.read_tagged_implicit(TAG_GRAPHICSTRING, |reader| {
+            reader.read_bytes()
+        })
+    }
+
+    /// Reads an ASN.1 GraphicString, interpreting it as specified by
+    /// `decoding`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::StringDecoding;
+    /// let data = &[25, 3, 0x41, 0xC3, 0xA9];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_graphic_string(StringDecoding::Utf8)
+    /// }).unwrap();
+    /// assert_eq!(&asn, "Aé");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it fails with [`ASN1ErrorKind::Invalid`] if
+    /// the contents can't be decoded.
+    pub fn read_graphic_string(self, decoding: StringDecoding)
+            -> ASN1Result<String> {
+        decode_string(self.read_graphic_string_bytes()?, decoding)
+    }
+
+    /// Reads an ASN.1 ObjectDescriptor.
+    ///
+    /// ObjectDescriptor is a GraphicString, which is decoded as UTF-8 as
+    /// with [`StringDecoding::Utf8`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[7, 6, 80, 75, 67, 83, 32, 49];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_object_descriptor()
+    /// }).unwrap();
+    /// assert_eq!(&asn, "PKCS 1");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it fails with [`ASN1ErrorKind::Invalid`] if
+    /// the contents aren't valid UTF-8.
+    pub fn read_object_descriptor(self) -> ASN1Result<String> {
+        use super::tags::TAG_OBJECT_DESCRIPTOR;
+        let bytes = self.read_tagged_implicit(TAG_OBJECT_DESCRIPTOR, |reader| {
+            reader.read_bytes()
+        })?;
+        decode_string(bytes, StringDecoding::Utf8)
+    }
+
+    /// Reads an ASN.1 UniversalString.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[28, 8, 0, 0, 0, 0xA3, 0, 1, 0xF6, 0x00];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_universal_string()
+    /// }).unwrap();
+    /// assert_eq!(&asn, "£\u{1F600}");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it fails with [`ASN1ErrorKind::Invalid`] if
+    /// the length isn't a multiple of 4, or if a code point isn't a
+    /// Unicode scalar value.
+    pub fn read_universal_string(self) -> ASN1Result<String> {
+        self.read_tagged_implicit(TAG_UNIVERSALSTRING, |reader| {
+            let bytes = reader.read_bytes()?;
+
+            if bytes.len() % 4 != 0 {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+
+            bytes.chunks(4).map(|c| {
+                let code = ((c[0] as u32) << 24) | ((c[1] as u32) << 16) |
+                    ((c[2] as u32) << 8) | c[3] as u32;
+                core::char::from_u32(code).ok_or(
+                    ASN1Error::new(ASN1ErrorKind::Invalid))
+            }).collect()
         })
     }
 
@@ -1420,6 +3236,13 @@ impl<'a, 'b> BERReader<'a, 'b> {
     /// assert_eq!(asn.datetime().unix_timestamp(), 378820800);
     /// ```
     ///
+    /// # Errors
+    ///
+    /// Except parse errors, it fails with [`ASN1ErrorKind::Invalid`] if
+    /// the value isn't of the form `YYMMDDhhmm[ss](Z|(+|-)hhmm)` or
+    /// doesn't specify a correct datetime. In DER, the seconds must be
+    /// present and the time must be in UTC, i.e. end in `Z`.
+    ///
     /// # Features
     ///
     /// This method is enabled by `time` feature.
@@ -1430,14 +3253,16 @@ impl<'a, 'b> BERReader<'a, 'b> {
     /// ```
     pub fn read_utctime(self) -> ASN1Result<UTCTime> {
         use super::tags::TAG_UTCTIME;
-        let mode = self.inner.mode;
+        let options = self.inner.options;
+        let mode = options.mode;
         self.read_tagged_implicit(TAG_UTCTIME, |reader| {
             let bytes = reader.read_bytes()?;
             let datetime = UTCTime::parse(&bytes).ok_or_else(
                 || ASN1Error::new(ASN1ErrorKind::Invalid))?;
-            if mode == BERMode::Der && &datetime.to_bytes() != &bytes {
+            if mode.is_canonical() && datetime.to_bytes() != bytes {
                 return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
             }
+            check_utctime_profile(options, &bytes)?;
             return Ok(datetime);
         })
     }
@@ -1458,6 +3283,15 @@ impl<'a, 'b> BERReader<'a, 'b> {
     /// assert_eq!(asn.datetime().unix_timestamp(), 500159309);
     /// ```
     ///
+    /// # Errors
+    ///
+    /// Except parse errors, it fails with [`ASN1ErrorKind::Invalid`] if
+    /// the value isn't of the form `YYYYMMDDhh[mm[ss]][(.|,)f+][Z|(+|-)hh[mm]]`
+    /// or doesn't specify a correct datetime. In DER, the minutes and
+    /// seconds must be present, the time must be in UTC, i.e. end in `Z`,
+    /// and the fraction, if any, must be separated by `.` and have no
+    /// trailing zeros.
+    ///
     /// # Features
     ///
     /// This method is enabled by `time` feature.
@@ -1468,39 +3302,261 @@ impl<'a, 'b> BERReader<'a, 'b> {
     /// ```
     pub fn read_generalized_time(self) -> ASN1Result<GeneralizedTime> {
         use super::tags::TAG_GENERALIZEDTIME;
-        let mode = self.inner.mode;
+        let options = self.inner.options;
+        let mode = options.mode;
         self.read_tagged_implicit(TAG_GENERALIZEDTIME, |reader| {
             let bytes = reader.read_bytes()?;
             let datetime = GeneralizedTime::parse(&bytes).ok_or_else(
                 || ASN1Error::new(ASN1ErrorKind::Invalid))?;
-            if mode == BERMode::Der && &datetime.to_bytes() != &bytes {
+            if mode.is_canonical() && datetime.to_bytes() != bytes {
                 return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
             }
+            check_generalized_time_profile(options, &bytes)?;
             return Ok(datetime);
         })
     }
 
-    /// Reads an ASN.1 VisibleString.
+    /// Reads an ASN.1 UTCTime as any type implementing [`AsnDateTime`].
+    ///
+    /// The string is interpreted as in [`read_utctime`](Self::read_utctime).
+    /// It returns `Invalid` if `T` can't represent the time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// # use yasna::models::AsnDateTime;
+    /// # #[derive(Debug, PartialEq)]
+    /// # struct UnixTime(i64, u32);
+    /// # impl AsnDateTime for UnixTime {
+    /// #     fn from_unix_time(secs: i64, nanos: u32) -> Option<Self> {
+    /// #         Some(UnixTime(secs, nanos))
+    /// #     }
+    /// #     fn to_unix_time(&self) -> (i64, u32) { (self.0, self.1) }
+    /// # }
+    /// let data = &[
+    ///     23, 15, 56, 50, 48, 49, 48, 50, 48,
+    ///     55, 48, 48, 45, 48, 53, 48, 48];
+    /// let asn: UnixTime = yasna::parse_ber(data, |reader| {
+    ///     reader.read_utctime_as()
+    /// }).unwrap();
+    /// assert_eq!(asn, UnixTime(378820800, 0));
+    /// ```
+    pub fn read_utctime_as<T: AsnDateTime>(self) -> ASN1Result<T> {
+        use super::tags::TAG_UTCTIME;
+        use super::models::{parse_utctime, format_utctime};
+        let options = self.inner.options;
+        let mode = options.mode;
+        self.read_tagged_implicit(TAG_UTCTIME, |reader| {
+            let bytes = reader.read_bytes()?;
+            let secs = parse_utctime(&bytes).ok_or_else(
+                || ASN1Error::new(ASN1ErrorKind::Invalid))?;
+            check_utctime_profile(options, &bytes)?;
+            if mode.is_canonical() && format_utctime(secs, 0) != Some(bytes) {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            T::from_unix_time(secs, 0).ok_or_else(
+                || ASN1Error::new(ASN1ErrorKind::Invalid))
+        })
+    }
+
+    /// Reads an ASN.1 GeneralizedTime as any type implementing
+    /// [`AsnDateTime`].
+    ///
+    /// The string is interpreted as in
+    /// [`read_generalized_time`](Self::read_generalized_time), except that
+    /// leap seconds are rejected and fractions of a second are truncated
+    /// to nanoseconds. It returns `Invalid` if `T` can't represent the
+    /// time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// # use yasna::models::AsnDateTime;
+    /// # #[derive(Debug, PartialEq)]
+    /// # struct UnixTime(i64, u32);
+    /// # impl AsnDateTime for UnixTime {
+    /// #     fn from_unix_time(secs: i64, nanos: u32) -> Option<Self> {
+    /// #         Some(UnixTime(secs, nanos))
+    /// #     }
+    /// #     fn to_unix_time(&self) -> (i64, u32) { (self.0, self.1) }
+    /// # }
+    /// let data = &[
+    ///     24, 17, 49, 57, 56, 53, 49, 49, 48, 54,
+    ///     50, 49, 46, 49, 52, 49, 53, 57, 90];
+    /// let asn: UnixTime = yasna::parse_ber(data, |reader| {
+    ///     reader.read_generalized_time_as()
+    /// }).unwrap();
+    /// assert_eq!(asn, UnixTime(500159309, 724_000_000));
+    /// ```
+    pub fn read_generalized_time_as<T: AsnDateTime>(self) -> ASN1Result<T> {
+        use super::tags::TAG_GENERALIZEDTIME;
+        use super::models::{parse_generalized_time, is_der_generalized_time};
+        let options = self.inner.options;
+        let mode = options.mode;
+        self.read_tagged_implicit(TAG_GENERALIZEDTIME, |reader| {
+            let bytes = reader.read_bytes()?;
+            let (secs, nanos) = parse_generalized_time(&bytes).ok_or_else(
+                || ASN1Error::new(ASN1ErrorKind::Invalid))?;
+            check_generalized_time_profile(options, &bytes)?;
+            if mode.is_canonical() && !is_der_generalized_time(&bytes) {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            T::from_unix_time(secs, nanos).ok_or_else(
+                || ASN1Error::new(ASN1ErrorKind::Invalid))
+        })
+    }
+
+    /// Reads an ASN.1 VisibleString.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[26, 3, 72, 105, 33];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_visible_string()
+    /// }).unwrap();
+    /// assert_eq!(&asn, "Hi!");
+    /// ```
+    pub fn read_visible_string(self) -> ASN1Result<String> {
+        self.read_tagged_implicit(TAG_VISIBLESTRING, |reader| {
+            let bytes = reader.read_bytes()?;
+            for &byte in bytes.iter() {
+                if !(b' ' <= byte && byte <= b'~') {
+                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                }
+            }
+            return Ok(String::from_utf8(bytes).unwrap());
+        })
+    }
+
+    #[cfg(feature = "uuid")]
+    /// Reads a UUID encoded as a 16-byte ASN.1 OCTET STRING.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// use uuid::Uuid;
+    /// let data = &[4, 16, 248, 29, 79, 174, 125, 236, 17, 208,
+    ///     167, 101, 0, 160, 201, 30, 107, 246];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_uuid()
+    /// }).unwrap();
+    /// assert_eq!(asn, Uuid::parse_str(
+    ///     "f81d4fae-7dec-11d0-a765-00a0c91e6bf6").unwrap());
+    /// ```
+    ///
+    /// # Features
+    ///
+    /// This method is enabled by `uuid` feature.
+    ///
+    /// ```toml
+    /// [dependencies]
+    /// yasna = { version = "*", features = ["uuid"] }
+    /// ```
+    pub fn read_uuid(self) -> ASN1Result<Uuid> {
+        let bytes = self.read_bytes()?;
+        Uuid::from_slice(&bytes)
+            .map_err(|_| ASN1Error::new(ASN1ErrorKind::Invalid))
+    }
+
+    #[cfg(feature = "uuid")]
+    /// Reads a UUID encoded as an ASN.1 INTEGER, i.e. as the unsigned
+    /// 128-bit integer used in the `2.25` OID arc of X.667.
+    ///
+    /// # Errors
+    ///
+    /// Except parse errors, it can raise integer overflow errors
+    /// for negative values and values of 2^128 or above.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// use uuid::Uuid;
+    /// let data = &[2, 17, 0, 248, 29, 79, 174, 125, 236, 17, 208,
+    ///     167, 101, 0, 160, 201, 30, 107, 246];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_uuid_integer()
+    /// }).unwrap();
+    /// assert_eq!(asn, Uuid::parse_str(
+    ///     "f81d4fae-7dec-11d0-a765-00a0c91e6bf6").unwrap());
+    /// ```
+    ///
+    /// # Features
+    ///
+    /// This method is enabled by `uuid` feature.
+    ///
+    /// ```toml
+    /// [dependencies]
+    /// yasna = { version = "*", features = ["uuid"] }
+    /// ```
+    pub fn read_uuid_integer(self) -> ASN1Result<Uuid> {
+        let (bytes, non_negative) = self.read_bigint_bytes()?;
+        if !non_negative {
+            return Err(ASN1Error::new(ASN1ErrorKind::IntegerOverflow));
+        }
+        let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+        let bytes = &bytes[start..];
+        if bytes.len() > 16 {
+            return Err(ASN1Error::new(ASN1ErrorKind::IntegerOverflow));
+        }
+        let mut uuid = [0; 16];
+        uuid[16 - bytes.len()..].copy_from_slice(bytes);
+        Ok(Uuid::from_bytes(uuid))
+    }
+
+    #[cfg(feature = "uuid")]
+    /// Reads a UUID encoded as an ASN.1 RELATIVE-OID with a single arc,
+    /// whose value is the UUID as an unsigned 128-bit integer.
     ///
     /// # Examples
     ///
     /// ```
     /// use yasna;
-    /// let data = &[26, 3, 72, 105, 33];
+    /// use uuid::Uuid;
+    /// let data = &[13, 19, 131, 240, 157, 167, 235, 207, 222, 224, 199,
+    ///     161, 167, 178, 192, 148, 140, 200, 249, 215, 118];
     /// let asn = yasna::parse_der(data, |reader| {
-    ///     reader.read_visible_string()
+    ///     reader.read_uuid_relative_oid()
     /// }).unwrap();
-    /// assert_eq!(&asn, "Hi!");
+    /// assert_eq!(asn, Uuid::parse_str(
+    ///     "f81d4fae-7dec-11d0-a765-00a0c91e6bf6").unwrap());
     /// ```
-    pub fn read_visible_string(self) -> ASN1Result<String> {
-        self.read_tagged_implicit(TAG_VISIBLESTRING, |reader| {
-            let bytes = reader.read_bytes()?;
-            for &byte in bytes.iter() {
-                if !(b' ' <= byte && byte <= b'~') {
+    ///
+    /// # Features
+    ///
+    /// This method is enabled by `uuid` feature.
+    ///
+    /// ```toml
+    /// [dependencies]
+    /// yasna = { version = "*", features = ["uuid"] }
+    /// ```
+    pub fn read_uuid_relative_oid(self) -> ASN1Result<Uuid> {
+        use super::tags::TAG_RELATIVE_OID;
+        self.read_general(TAG_RELATIVE_OID, |contents| {
+            let buf = match contents {
+                Contents::Primitive(buf) => buf,
+                Contents::Constructed(_) => {
                     return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
-                }
+                },
+            };
+            match buf.split_last() {
+                Some((&last, rest)) if last < 128 &&
+                    rest.iter().all(|&b| b >= 128) &&
+                    rest.first() != Some(&128) => {},
+                _ => return Err(ASN1Error::new(ASN1ErrorKind::Invalid)),
             }
-            return Ok(String::from_utf8(bytes).unwrap());
+            let mut arc : u128 = 0;
+            for &b in buf {
+                arc = arc.checked_mul(128).ok_or_else(
+                    || ASN1Error::new(ASN1ErrorKind::IntegerOverflow))?
+                    | (b & 127) as u128;
+            }
+            Ok(Uuid::from_u128(arc))
         })
     }
 
@@ -1582,6 +3638,121 @@ impl<'a, 'b> BERReader<'a, 'b> {
         self.inner.lookahead_tag()
     }
 
+    /// Reads an ASN.1 CHOICE value, dispatching on its tag.
+    ///
+    /// `tags` lists the tags of the alternatives. `callback` is called back
+    /// with the index of the one matching the next value in `tags`, and
+    /// reads it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::{self, Tag};
+    /// use yasna::tags::TAG_INTEGER;
+    /// // Time ::= CHOICE { seconds INTEGER, never [0] IMPLICIT NULL }
+    /// let data = &[48, 5, 2, 1, 10, 128, 0];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.collect_sequence_of(|reader| {
+    ///         reader.read_choice(&[TAG_INTEGER, Tag::context(0)],
+    ///                 |index, reader| match index {
+    ///             0 => reader.read_i64().map(Some),
+    ///             _ => reader.read_tagged_implicit(Tag::context(0), |reader| {
+    ///                 reader.read_null()
+    ///             }).map(|_| None),
+    ///         })
+    ///     })
+    /// }).unwrap();
+    /// assert_eq!(asn, vec![Some(10), None]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except errors from `callback`, it fails with
+    /// [`ASN1ErrorKind::Invalid`] without consuming anything if the tag of
+    /// the next value isn't in `tags`, and with
+    /// [`ASN1ErrorKind::Eof`] if there is no value left.
+    pub fn read_choice<T, F>(self, tags: &[Tag], callback: F)
+            -> ASN1Result<T>
+            where F: FnOnce(usize, BERReader<'a, 'b>) -> ASN1Result<T> {
+        let tag = self.lookahead_tag()?;
+        match tags.iter().position(|&t| t == tag) {
+            Some(index) => callback(index, self),
+            None => Err(ASN1Error::new(ASN1ErrorKind::Invalid)),
+        }
+    }
+
+    /// Peeks the tag and the primitive/constructed flag of the next
+    /// value, without consuming it.
+    ///
+    /// Unlike [`lookahead_tag`](Self::lookahead_tag), it tells apart
+    /// primitive and constructed encodings, e.g. to branch on a CHOICE
+    /// between `[0] IMPLICIT OCTET STRING` and `[0] IMPLICIT SEQUENCE`
+    /// in DER. The tag is the one in the data, even within
+    /// [`read_tagged_implicit`](Self::read_tagged_implicit).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::{PCBit, Tag};
+    /// let data = &[160, 3, 2, 1, 10];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     let (tag, pcbit) = reader.peek_tag()?;
+    ///     assert_eq!(pcbit, PCBit::Constructed);
+    ///     reader.read_tagged_implicit(tag, |reader| {
+    ///         reader.read_sequence(|reader| reader.next().read_i64())
+    ///     })
+    /// }).unwrap();
+    /// assert_eq!(asn, 10);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// It fails with [`ASN1ErrorKind::Eof`] if there is no value left, and
+    /// with other errors if the identifier is malformed.
+    pub fn peek_tag(&self) -> ASN1Result<(Tag, PCBit)> {
+        self.inner.lookahead_identifier()
+    }
+
+    /// Reads the next value whatever its tag, as for an ANY or open type.
+    ///
+    /// `callback` is called back with the tag and the primitive/constructed
+    /// flag of the value, and a reader for it which reads the value as
+    /// [`read_tagged_implicit`](Self::read_tagged_implicit) would with
+    /// that tag. The type of the value is thus up to `callback`, e.g. for
+    /// `[1] EXPLICIT INTEGER`, it calls
+    /// [`read_tagged`](Self::read_tagged) with the tag, and for
+    /// `[1] IMPLICIT INTEGER`, [`read_i64`](Self::read_i64) directly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::{PCBit, Tag};
+    /// let data = &[48, 6, 129, 1, 10, 2, 1, 20];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.collect_sequence_of(|reader| {
+    ///         reader.read_tagged_any(|tag, pcbit, reader| {
+    ///             assert_eq!(pcbit, PCBit::Primitive);
+    ///             Ok((tag, reader.read_i64()?))
+    ///         })
+    ///     })
+    /// }).unwrap();
+    /// assert_eq!(asn, vec![(Tag::context(1), 10),
+    ///     (yasna::tags::TAG_INTEGER, 20)]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Except errors from `callback`, it fails with
+    /// [`ASN1ErrorKind::Eof`] if there is no value left.
+    pub fn read_tagged_any<T, F>(self, callback: F) -> ASN1Result<T>
+            where F: FnOnce(Tag, PCBit, BERReader<'a, 'b>) -> ASN1Result<T> {
+        let (tag, pcbit) = self.inner.lookahead_identifier()?;
+        callback(tag, pcbit, BERReader {
+            inner: self.inner,
+            implicit_tag: Some(tag),
+        })
+    }
+
     pub fn read_with_buffer<T, F>(self, callback: F)
             -> ASN1Result<(T, &'a [u8])>
             where F: for<'c> FnOnce(BERReader<'a, 'c>) -> ASN1Result<T> {
@@ -1594,6 +3765,57 @@ impl<'a, 'b> BERReader<'a, 'b> {
         })
     }
 
+    /// Reads a value and feeds its encoding into `digest`.
+    ///
+    /// This function uses the loan pattern: `callback` is called back with
+    /// a [`BERReader`], from which the value is read. Once `callback`
+    /// returns, the encoding of the value (identifier and length
+    /// included), as found in the input, is passed to `digest`. This
+    /// allows verifiers to hash a signed structure while parsing it,
+    /// instead of locating it again afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// # struct Sha256(Vec<u8>);
+    /// # impl digest::Update for Sha256 {
+    /// #     fn update(&mut self, data: &[u8]) { self.0.extend_from_slice(data); }
+    /// # }
+    /// # impl Sha256 { fn new() -> Self { Sha256(Vec::new()) } }
+    /// let data = &[48, 6, 2, 1, 10, 1, 1, 255];
+    /// let mut hasher = Sha256::new();
+    /// let (i, b) = yasna::parse_der(data, |reader| {
+    ///     reader.read_sequence(|reader| {
+    ///         let i = reader.next().read_digested(&mut hasher, |reader| {
+    ///             reader.read_i64()
+    ///         })?;
+    ///         let b = reader.next().read_bool()?;
+    ///         Ok((i, b))
+    ///     })
+    /// }).unwrap();
+    /// assert_eq!((i, b), (10, true));
+    /// # assert_eq!(hasher.0, vec![2, 1, 10]);
+    /// ```
+    ///
+    /// # Features
+    ///
+    /// This method is enabled by `digest` feature.
+    ///
+    /// ```toml
+    /// [dependencies]
+    /// yasna = { version = "*", features = ["digest"] }
+    /// ```
+    #[cfg(feature = "digest")]
+    pub fn read_digested<T, D, F>(self, digest: &mut D, callback: F)
+            -> ASN1Result<T>
+            where D: digest::Update,
+            F: for<'c> FnOnce(BERReader<'a, 'c>) -> ASN1Result<T> {
+        let (result, buf) = self.read_with_buffer(callback)?;
+        digest.update(buf);
+        Ok(result)
+    }
+
     /// Read an arbitrary (tag, value) pair as a TaggedDerValue.
     /// The length is not included in the returned payload. If the
     /// payload has indefinite-length encoding, the EOC bytes are
@@ -1611,12 +3833,42 @@ impl<'a, 'b> BERReader<'a, 'b> {
     /// ```
     pub fn read_tagged_der(self) -> ASN1Result<TaggedDerValue> {
         let (tag, pcbit, data_pos) = self.inner.skip_general()?;
+        self.inner.reserve(self.inner.pos - data_pos)?;
         Ok(TaggedDerValue::from_tag_pc_and_bytes(
                 tag,
                 pcbit,
                 self.inner.buf[data_pos..self.inner.pos].to_vec()))
     }
 
+    /// Skips the next value, without decoding or allocating.
+    ///
+    /// Only the identifiers and lengths are read: the contents are jumped
+    /// over, except within indefinite-length values, whose end must be
+    /// searched for.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[48, 9, 48, 4, 2, 2, 1, 0, 1, 1, 255];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_sequence(|reader| {
+    ///         reader.next().skip_value()?;
+    ///         reader.next().read_bool()
+    ///     })
+    /// }).unwrap();
+    /// assert!(asn);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// It fails if the identifiers or lengths are malformed, or if the
+    /// contents are truncated. The contents of definite-length values
+    /// aren't validated.
+    pub fn skip_value(self) -> ASN1Result<()> {
+        self.inner.skip_general().map(|_| ())
+    }
+
     /// Reads a DER object as raw bytes. Tag and length are included
     /// in the returned buffer. For indefinite length encoding, EOC bytes
     /// are included in the returned buffer as well.
@@ -1630,12 +3882,49 @@ impl<'a, 'b> BERReader<'a, 'b> {
     /// assert_eq!(res, data);
     /// ```
     pub fn read_der(self) -> ASN1Result<Vec<u8>> {
+        self.read_charged(1, |reader| reader.read_der_slice())
+            .map(|buf| buf.to_owned())
+    }
+
+    /// Same as [`read_der`](Self::read_der), but borrows the encoding
+    /// from the input instead of copying it.
+    ///
+    /// The value is only checked to be well-formed, so this is suited to
+    /// hashing the exact bytes that were signed, or to deferring the
+    /// decoding of an ANY value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[48, 8, 48, 3, 1, 1, 255, 3, 1, 0];
+    /// let (tbs, signature) = yasna::parse_der(data, |reader| {
+    ///     reader.read_sequence(|reader| {
+    ///         let tbs = reader.next().read_der_slice()?;
+    ///         let signature = reader.next().read_bitvec_bytes()?;
+    ///         Ok((tbs, signature))
+    ///     })
+    /// }).unwrap();
+    /// assert_eq!(tbs, &[48, 3, 1, 1, 255]);
+    /// assert_eq!(signature, (vec![], 0));
+    /// ```
+    pub fn read_der_slice(self) -> ASN1Result<&'a [u8]> {
         Ok(self.inner.read_with_buffer(|inner| {
             inner.skip_general()
-        })?.1.to_owned())
+        })?.1)
     }
 }
 
+/// A position in a SEQUENCE, to which [`BERReaderSeq::rollback`] returns.
+///
+/// It is obtained from [`BERReaderSeq::checkpoint`].
+#[derive(Debug, Clone, Copy)]
+pub struct Checkpoint<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    depth: usize,
+}
+
 /// A reader object for a sequence of BER/DER-encoded ASN.1 data.
 ///
 /// The main source of this object is the [`read_sequence`] method from
@@ -1665,7 +3954,7 @@ pub struct BERReaderSeq<'a, 'b> where 'a: 'b {
 impl<'a, 'b> BERReaderSeq<'a, 'b> {
     /// Tells which format we are parsing, BER or DER.
     pub fn mode(&self) -> BERMode {
-        self.inner.mode
+        self.inner.options.mode
     }
 
     /// Generates a new [`BERReader`].
@@ -1673,6 +3962,189 @@ impl<'a, 'b> BERReaderSeq<'a, 'b> {
         BERReader::new(self.inner)
     }
 
+    /// Remembers the current position, to return to it with
+    /// [`rollback`](Self::rollback).
+    ///
+    /// This allows speculative parsing of several elements, where
+    /// [`read_optional`](Self::read_optional) only backtracks on a
+    /// failure before anything is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[48, 6, 2, 1, 10, 2, 1, 20];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_sequence(|reader| {
+    ///         let checkpoint = reader.checkpoint();
+    ///         let i = reader.next().read_i64()?;
+    ///         if let Ok(b) = reader.next().read_bool() {
+    ///             return Ok((i, b));
+    ///         }
+    ///         reader.rollback(checkpoint);
+    ///         let i = reader.next().read_i64()?;
+    ///         let j = reader.next().read_i64()?;
+    ///         Ok((i + j, false))
+    ///     })
+    /// }).unwrap();
+    /// assert_eq!(asn, (30, false));
+    /// ```
+    pub fn checkpoint(&self) -> Checkpoint<'a> {
+        Checkpoint {
+            buf: self.inner.buf,
+            pos: self.inner.pos,
+            depth: self.inner.depth,
+        }
+    }
+
+    /// Returns to the position remembered by
+    /// [`checkpoint`](Self::checkpoint), discarding what was read since,
+    /// even by a failed read.
+    ///
+    /// `checkpoint` must have been obtained from this reader. The
+    /// allocations charged against [`ParseOptions::max_alloc`] in the
+    /// meantime aren't refunded.
+    pub fn rollback(&mut self, checkpoint: Checkpoint<'a>) {
+        self.inner.buf = checkpoint.buf;
+        self.inner.pos = checkpoint.pos;
+        self.inner.depth = checkpoint.depth;
+    }
+
+    /// Tells whether the SEQUENCE has elements left to read.
+    ///
+    /// It doesn't check that the next element is well-formed, so reading
+    /// it may still fail.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[48, 6, 2, 1, 10, 2, 1, 20];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_sequence(|reader| {
+    ///         let mut v = Vec::new();
+    ///         while reader.has_next() {
+    ///             v.push(reader.next().read_i64()?);
+    ///         }
+    ///         return Ok(v);
+    ///     })
+    /// }).unwrap();
+    /// assert_eq!(asn, vec![10, 20]);
+    /// ```
+    pub fn has_next(&self) -> bool {
+        // At the end of an indefinite-length SEQUENCE, the end-of-contents
+        // octets follow. A zero identifier octet isn't valid otherwise.
+        let inner = &*self.inner;
+        inner.pos < inner.buf.len() && inner.buf[inner.pos] != 0
+    }
+
+    /// Tells whether all the elements of the SEQUENCE have been read.
+    ///
+    /// This is the negation of [`has_next`](Self::has_next).
+    pub fn is_empty(&self) -> bool {
+        !self.has_next()
+    }
+
+    /// Returns the length in bytes of the elements left to read, without
+    /// the end-of-contents octets of an indefinite-length SEQUENCE.
+    ///
+    /// # Errors
+    ///
+    /// The remaining elements are scanned to find their end, and it fails
+    /// if they are malformed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[48, 128, 2, 1, 10, 5, 0, 0, 0];
+    /// let asn = yasna::parse_ber(data, |reader| {
+    ///     reader.read_sequence(|reader| {
+    ///         let before = reader.remaining_len()?;
+    ///         reader.next().read_i64()?;
+    ///         let after = reader.remaining_len()?;
+    ///         reader.next().read_null()?;
+    ///         return Ok((before, after, reader.remaining_len()?));
+    ///     })
+    /// }).unwrap();
+    /// assert_eq!(asn, (5, 2, 0));
+    /// ```
+    pub fn remaining_len(&self) -> ASN1Result<usize> {
+        let mut reader = BERReaderImpl::with_pos(
+            self.inner.buf, self.inner.pos, self.inner.options);
+        reader.depth = self.inner.depth;
+        while reader.pos < reader.buf.len() && reader.buf[reader.pos] != 0 {
+            reader.skip_general()?;
+        }
+        Ok(reader.pos - self.inner.pos)
+    }
+
+    /// Consumes the elements left in the SEQUENCE, and returns their
+    /// encoding, without the end-of-contents octets of an
+    /// indefinite-length SEQUENCE.
+    ///
+    /// This keeps unknown extensions, e.g. to encode them back later with
+    /// [`DERWriter::write_der`](crate::DERWriter::write_der).
+    ///
+    /// # Errors
+    ///
+    /// The remaining elements are scanned to find their end, and it fails
+    /// if they are malformed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[48, 128, 2, 1, 10, 1, 1, 255, 5, 0, 0, 0];
+    /// let asn = yasna::parse_ber(data, |reader| {
+    ///     reader.read_sequence(|reader| {
+    ///         let i = reader.next().read_i64()?;
+    ///         let rest = reader.read_remaining_blob()?;
+    ///         Ok((i, rest))
+    ///     })
+    /// }).unwrap();
+    /// assert_eq!(asn, (10, &[1, 1, 255, 5, 0][..]));
+    /// ```
+    pub fn read_remaining_blob(&mut self) -> ASN1Result<&'a [u8]> {
+        let start = self.inner.pos;
+        while self.has_next() {
+            self.inner.skip_general()?;
+        }
+        Ok(&self.inner.buf[start..self.inner.pos])
+    }
+
+    /// Calls `callback` with a reader for each of the elements left in the
+    /// SEQUENCE, until it is exhausted.
+    ///
+    /// Unlike [`BERReader::read_sequence_of`], it doesn't stop at an
+    /// element `callback` fails to read, but returns the error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let data = &[48, 9, 1, 1, 255, 2, 1, 10, 2, 1, 20];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_sequence(|reader| {
+    ///         let b = reader.next().read_bool()?;
+    ///         let mut v = Vec::new();
+    ///         reader.for_each_remaining(|reader| {
+    ///             v.push(reader.read_i64()?);
+    ///             return Ok(());
+    ///         })?;
+    ///         return Ok((b, v));
+    ///     })
+    /// }).unwrap();
+    /// assert_eq!(asn, (true, vec![10, 20]));
+    /// ```
+    pub fn for_each_remaining<F>(&mut self, mut callback: F) -> ASN1Result<()>
+            where F: for<'c> FnMut(BERReader<'a, 'c>) -> ASN1Result<()> {
+        while self.has_next() {
+            callback(self.next())?;
+        }
+        Ok(())
+    }
+
     /// Tries to read an ASN.1 value. If it fails at the first tag,
     /// it doesn't consume buffer and returns `None`.
     ///
@@ -1706,7 +4178,8 @@ impl<'a, 'b> BERReaderSeq<'a, 'b> {
     /// but uses `default` if it fails.
     ///
     /// `T: Eq` is required because it fails in DER mode if the read value
-    /// is equal to `default`.
+    /// is equal to `default`, unless
+    /// [`ParseOptions::with_lenient_default`] is set.
     ///
     /// Used to parse DEFAULT elements.
     ///
@@ -1732,7 +4205,9 @@ impl<'a, 'b> BERReaderSeq<'a, 'b> {
             T: Eq {
         match self.read_optional(callback)? {
             Some(result) => {
-                if self.inner.mode == BERMode::Der && result == default {
+                let options = self.inner.options;
+                if options.enforces(LeniencyFlags::DEFAULT_VALUE) &&
+                        result == default {
                     return Err(
                         ASN1Error::new(ASN1ErrorKind::Invalid));
                 }
@@ -1742,6 +4217,77 @@ impl<'a, 'b> BERReaderSeq<'a, 'b> {
         }
     }
 
+    /// Reads an ASN.1 value if the next element has the tag `tag`.
+    /// Otherwise, including at the end of the SEQUENCE, it doesn't
+    /// consume buffer and returns `None`.
+    ///
+    /// Unlike [`read_optional`](Self::read_optional), the presence of the
+    /// value is decided by its tag alone: `callback` is only called if the
+    /// tag matches, and its failure is returned as is, instead of being
+    /// taken for the absence of the value.
+    ///
+    /// Used to parse OPTIONAL elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::{self, ASN1ErrorKind, Tag};
+    /// let read = |reader: &mut yasna::BERReaderSeq| {
+    ///     reader.read_optional_tagged(Tag::context(0), |reader| {
+    ///         reader.read_tagged(Tag::context(0), |reader| reader.read_i64())
+    ///     })
+    /// };
+    /// let asn = yasna::parse_der(&[48, 5, 160, 3, 2, 1, 10], |reader| {
+    ///     reader.read_sequence(read)
+    /// }).unwrap();
+    /// assert_eq!(asn, Some(10));
+    /// let asn = yasna::parse_der(&[48, 0], |reader| {
+    ///     reader.read_sequence(read)
+    /// }).unwrap();
+    /// assert_eq!(asn, None);
+    /// // Present, but corrupt
+    /// let err = yasna::parse_der(&[48, 4, 160, 2, 5, 0], |reader| {
+    ///     reader.read_sequence(read)
+    /// }).unwrap_err();
+    /// assert_eq!(err.kind(), ASN1ErrorKind::Invalid);
+    /// ```
+    pub fn read_optional_tagged<T, F>(&mut self, tag: Tag, callback: F)
+            -> ASN1Result<Option<T>>
+            where F: for<'c> FnOnce(BERReader<'a, 'c>) -> ASN1Result<T> {
+        match self.inner.lookahead_tag() {
+            Ok(next_tag) if next_tag == tag => callback(self.next()).map(Some),
+            Ok(_) => Ok(None),
+            Err(ref e) if e.kind() == ASN1ErrorKind::Eof => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Peeks the tag and the primitive/constructed flag of the next
+    /// element, without consuming it.
+    ///
+    /// See [`BERReader::peek_tag`]. At the end of a definite-length
+    /// SEQUENCE, it fails with [`ASN1ErrorKind::Eof`], and at the end of
+    /// an indefinite-length one, it returns the end-of-contents tag
+    /// [`TAG_EOC`](crate::tags::TAG_EOC).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::PCBit;
+    /// use yasna::tags::TAG_BOOLEAN;
+    /// let data = &[48, 3, 1, 1, 255];
+    /// let asn = yasna::parse_der(data, |reader| {
+    ///     reader.read_sequence(|reader| {
+    ///         assert_eq!(reader.peek_tag()?, (TAG_BOOLEAN, PCBit::Primitive));
+    ///         reader.next().read_bool()
+    ///     })
+    /// }).unwrap();
+    /// assert!(asn);
+    /// ```
+    pub fn peek_tag(&self) -> ASN1Result<(Tag, PCBit)> {
+        self.inner.lookahead_identifier()
+    }
+
     pub fn read_with_buffer<T, F>(&mut self, callback: F)
             -> ASN1Result<(T, &'a [u8])>
             where F: for<'c> FnOnce(
@@ -1781,20 +4327,25 @@ pub struct BERReaderSet<'a, 'b> where 'a: 'b {
 impl<'a, 'b> BERReaderSet<'a, 'b> {
     /// Tells which format we are parsing, BER or DER.
     pub fn mode(&self) -> BERMode {
-        self.impl_ref.mode
+        self.impl_ref.options.mode
     }
 
     /// Generates a new [`BERReader`].
     ///
     /// This method needs `tag_hint` to determine the position of the data.
+    /// The reader is for the element with one of these tags, wherever it
+    /// is in the SET, and it fails if there isn't one left.
     pub fn next<'c>(&'c mut self, tag_hint: &[Tag])
             -> ASN1Result<BERReader<'a, 'c>> {
         if let Some(elem_pos) = self.elements.iter().position(|&(tag,_,_)| {
             tag_hint.contains(&tag)
         }) {
             let (_, buf, pos) = self.elements.remove(elem_pos);
-            *self.impl_ref = BERReaderImpl::with_pos(
-                buf, pos, self.impl_ref.mode);
+            let mut reader_impl = BERReaderImpl::with_pos(
+                buf, pos, self.impl_ref.options);
+            reader_impl.depth = self.impl_ref.depth;
+            reader_impl.allocated = self.impl_ref.allocated;
+            *self.impl_ref = reader_impl;
             return Ok(BERReader::new(self.impl_ref))
         } else {
             return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
@@ -1832,9 +4383,12 @@ impl<'a, 'b> BERReaderSet<'a, 'b> {
         }) {
             let (_, buf, pos) = self.elements.remove(elem_pos);
             let mut reader_impl = BERReaderImpl::with_pos(
-                buf, pos, self.impl_ref.mode);
+                buf, pos, self.impl_ref.options);
+            reader_impl.depth = self.impl_ref.depth;
+            reader_impl.allocated = self.impl_ref.allocated;
             let result = callback(BERReader::new(&mut reader_impl))?;
             reader_impl.end_of_buf()?;
+            self.impl_ref.allocated = reader_impl.allocated;
             return Ok(Some(result));
         } else {
             return Ok(None);
@@ -1845,7 +4399,8 @@ impl<'a, 'b> BERReaderSet<'a, 'b> {
     /// but uses `default` if it fails.
     ///
     /// `T: Eq` is required because it fails in DER mode if the read value
-    /// is equal to `default`.
+    /// is equal to `default`, unless
+    /// [`ParseOptions::with_lenient_default`] is set.
     ///
     /// Used to parse DEFAULT elements.
     ///
@@ -1871,10 +4426,11 @@ impl<'a, 'b> BERReaderSet<'a, 'b> {
             -> ASN1Result<T>
             where F: for<'c> FnOnce(BERReader<'a, 'c>) -> ASN1Result<T>,
             T: Eq {
-        let mode = self.impl_ref.mode;
+        let options = self.impl_ref.options;
         match self.read_optional(tag_hint, callback)? {
             Some(result) => {
-                if mode == BERMode::Der && result == default {
+                if options.enforces(LeniencyFlags::DEFAULT_VALUE) &&
+                        result == default {
                     return Err(
                         ASN1Error::new(ASN1ErrorKind::Invalid));
                 }
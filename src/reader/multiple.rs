@@ -0,0 +1,119 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![forbid(missing_docs)]
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use super::{ASN1Result, BERMode, BERReader, BERReaderImpl, ParseOptions};
+use super::parse_with_options;
+
+/// Parses each of the BER-encoded values concatenated in `buf`.
+///
+/// The returned iterator calls `callback` with a [`BERReader`] for each
+/// top-level value in turn, which must read it entirely, until `buf` is
+/// exhausted. This suits bundles of certificates, for example. It stops
+/// after yielding an error.
+///
+/// # Examples
+///
+/// ```
+/// use yasna;
+/// let data = &[2, 1, 10, 48, 128, 2, 1, 20, 0, 0];
+/// let asn = yasna::parse_ber_multiple(data, |reader| {
+///     reader.read_der_slice()
+/// }).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(asn, vec![&data[..3], &data[3..]]);
+/// ```
+pub fn parse_ber_multiple<'a, T, F>(buf: &'a [u8], callback: F)
+        -> ParseMultiple<'a, T, F>
+        where F: for<'b> FnMut(BERReader<'a, 'b>) -> ASN1Result<T> {
+    ParseMultiple::new(buf, ParseOptions::new(BERMode::Ber), callback)
+}
+
+/// Parses each of the DER-encoded values concatenated in `buf`.
+///
+/// See [`parse_ber_multiple`].
+///
+/// # Examples
+///
+/// ```
+/// use yasna;
+/// let data = &[2, 1, 10, 2, 1, 20, 2, 1, 30];
+/// let asn = yasna::parse_der_multiple(data, |reader| {
+///     reader.read_i64()
+/// }).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(asn, vec![10, 20, 30]);
+/// ```
+pub fn parse_der_multiple<'a, T, F>(buf: &'a [u8], callback: F)
+        -> ParseMultiple<'a, T, F>
+        where F: for<'b> FnMut(BERReader<'a, 'b>) -> ASN1Result<T> {
+    ParseMultiple::new(buf, ParseOptions::new(BERMode::Der), callback)
+}
+
+/// An iterator over the values concatenated in a buffer, returned by
+/// [`parse_ber_multiple`] and [`parse_der_multiple`].
+pub struct ParseMultiple<'a, T, F> {
+    buf: &'a [u8],
+    options: ParseOptions,
+    callback: F,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T, F> ParseMultiple<'a, T, F>
+        where F: for<'b> FnMut(BERReader<'a, 'b>) -> ASN1Result<T> {
+    /// Creates an iterator parsing each value in `buf` with `callback`,
+    /// with the given [`ParseOptions`].
+    pub fn new(buf: &'a [u8], options: ParseOptions, callback: F) -> Self {
+        ParseMultiple {
+            buf,
+            options,
+            callback,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the data not parsed yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.buf
+    }
+}
+
+impl<'a, T, F> fmt::Debug for ParseMultiple<'a, T, F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ParseMultiple")
+            .field("buf", &self.buf)
+            .field("options", &self.options)
+            .finish()
+    }
+}
+
+impl<'a, T, F> Iterator for ParseMultiple<'a, T, F>
+        where F: for<'b> FnMut(BERReader<'a, 'b>) -> ASN1Result<T> {
+    type Item = ASN1Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.buf.is_empty() {
+            return None;
+        }
+        let mut reader = BERReaderImpl::new(self.buf, self.options);
+        let len = match reader.skip_general() {
+            Ok(_) => reader.pos,
+            Err(e) => {
+                self.buf = &[];
+                return Some(Err(e));
+            },
+        };
+        let (value, rest) = self.buf.split_at(len);
+        let result = parse_with_options(value, self.options,
+            &mut self.callback);
+        self.buf = if result.is_ok() { rest } else { &[] };
+        Some(result)
+    }
+}
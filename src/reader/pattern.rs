@@ -0,0 +1,288 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![forbid(missing_docs)]
+
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::super::{construct_der, PCBit, Tag};
+use super::super::models::ObjectIdentifier;
+use super::super::tags::{TAG_OID, TAG_SEQUENCE, TAG_SET};
+use super::{ASN1Result, BERMode, BERReaderImpl, ParseOptions};
+
+/// The expected shape of BER/DER-encoded data, for [`match_pattern`].
+///
+/// Patterns describe values by their tags and, optionally, their
+/// contents, and mark the values to be captured. The helper functions
+/// build the common cases.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::{BERMode, Pattern};
+/// use yasna::models::ObjectIdentifier;
+/// use yasna::tags::TAG_BOOLEAN;
+/// // An Extension of type basicConstraints
+/// let pattern = Pattern::sequence(vec![
+///     Pattern::oid(&ObjectIdentifier::from_slice(&[2, 5, 29, 19])),
+///     Pattern::optional(Pattern::Tagged(TAG_BOOLEAN)),
+///     Pattern::capture(Pattern::Any),
+/// ]);
+/// let data = &[48, 12, 6, 3, 85, 29, 19, 4, 5, 48, 3, 1, 1, 255];
+/// let captures = yasna::match_pattern(data, BERMode::Der, &pattern)
+///     .unwrap().unwrap();
+/// assert_eq!(captures, vec![&data[7..]]);
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Pattern {
+    /// Matches any value.
+    Any,
+    /// Matches a value with this tag, whatever its contents.
+    Tagged(Tag),
+    /// Matches a primitive value with this tag and these contents octets.
+    Exact(Tag, Vec<u8>),
+    /// Matches a constructed value with this tag, whose elements match
+    /// the patterns in order.
+    Constructed(Tag, Vec<Pattern>),
+    /// Among the elements of a constructed value, matches the next
+    /// element if it matches the pattern, or nothing.
+    Optional(Box<Pattern>),
+    /// Among the elements of a constructed value, matches as many of
+    /// the next elements as match the pattern.
+    Repeat(Box<Pattern>),
+    /// Among the elements of a constructed value, matches all the
+    /// remaining elements.
+    Rest,
+    /// Matches what the pattern matches, and captures the encoding of
+    /// the value(s).
+    Capture(Box<Pattern>),
+}
+
+impl Pattern {
+    /// Matches a SEQUENCE whose elements match `elements`.
+    pub fn sequence(elements: Vec<Pattern>) -> Self {
+        Pattern::Constructed(TAG_SEQUENCE, elements)
+    }
+
+    /// Matches a SET whose elements, in encoding order, match `elements`.
+    pub fn set(elements: Vec<Pattern>) -> Self {
+        Pattern::Constructed(TAG_SET, elements)
+    }
+
+    /// Matches an explicitly tagged value: `[n] EXPLICIT`.
+    pub fn explicit(tag: Tag, pattern: Pattern) -> Self {
+        Pattern::Constructed(tag, vec![pattern])
+    }
+
+    /// Matches the OBJECT IDENTIFIER `oid`.
+    pub fn oid(oid: &ObjectIdentifier) -> Self {
+        let encoded = construct_der(|writer| writer.write_oid(oid));
+        let header_len = if encoded[1] < 128 {
+            2
+        } else {
+            2 + (encoded[1] & 127) as usize
+        };
+        Pattern::Exact(TAG_OID, encoded[header_len..].to_vec())
+    }
+
+    /// See [`Pattern::Optional`].
+    pub fn optional(pattern: Pattern) -> Self {
+        Pattern::Optional(Box::new(pattern))
+    }
+
+    /// See [`Pattern::Repeat`].
+    pub fn repeat(pattern: Pattern) -> Self {
+        Pattern::Repeat(Box::new(pattern))
+    }
+
+    /// See [`Pattern::Capture`].
+    pub fn capture(pattern: Pattern) -> Self {
+        Pattern::Capture(Box::new(pattern))
+    }
+}
+
+/// Matches `buf`, which must hold a single value, against `pattern`.
+///
+/// Returns the captured encodings in the order of the patterns, or
+/// `None` if the value doesn't match. A [`Pattern::Capture`] around
+/// [`Pattern::Repeat`] or [`Pattern::Rest`] captures the matched elements
+/// as one slice. Matching is greedy: `Optional` and `Repeat` take every
+/// element they can, without reconsidering if the following patterns
+/// fail.
+///
+/// The structure of the data is validated as far as it is traversed,
+/// and an error is returned if it is malformed.
+pub fn match_pattern<'a>(buf: &'a [u8], mode: BERMode, pattern: &Pattern)
+        -> ASN1Result<Option<Vec<&'a [u8]>>> {
+    let matcher = Matcher {
+        buf,
+        options: ParseOptions::new(mode),
+    };
+    let element = matcher.element(0, buf.len())?;
+    matcher.end_of_buf(element.range.end, buf.len())?;
+    let mut captures = Vec::new();
+    if matcher.matches(&element, pattern, &mut captures)? {
+        Ok(Some(captures))
+    } else {
+        Ok(None)
+    }
+}
+
+struct Element {
+    tag: Tag,
+    pcbit: PCBit,
+    /// The whole encoding
+    range: Range<usize>,
+    /// The contents octets, without end-of-contents octets
+    contents: Range<usize>,
+}
+
+struct Matcher<'a> {
+    buf: &'a [u8],
+    options: ParseOptions,
+}
+
+impl<'a> Matcher<'a> {
+    /// Reads the value at `pos`, which must end before `limit`.
+    fn element(&self, pos: usize, limit: usize) -> ASN1Result<Element> {
+        let mut reader = BERReaderImpl::with_pos(
+            &self.buf[..limit], pos, self.options);
+        let (tag, pcbit) = reader.read_identifier()?;
+        let definite = reader.read_length()?.is_some();
+        let mut reader = BERReaderImpl::with_pos(
+            &self.buf[..limit], pos, self.options);
+        let (_, _, contents) = reader.skip_general()?;
+        let end = reader.pos;
+        Ok(Element {
+            tag,
+            pcbit,
+            range: pos..end,
+            contents: contents..if definite { end } else { end - 2 },
+        })
+    }
+
+    fn end_of_buf(&self, pos: usize, limit: usize) -> ASN1Result<()> {
+        BERReaderImpl::with_pos(&self.buf[..limit], pos, self.options)
+            .end_of_buf()
+    }
+
+    fn children(&self, element: &Element) -> ASN1Result<Vec<Element>> {
+        let mut children = Vec::new();
+        let mut pos = element.contents.start;
+        while pos < element.contents.end {
+            let child = self.element(pos, element.contents.end)?;
+            pos = child.range.end;
+            children.push(child);
+        }
+        Ok(children)
+    }
+
+    fn matches(&self, element: &Element, pattern: &Pattern,
+            captures: &mut Vec<&'a [u8]>) -> ASN1Result<bool> {
+        Ok(match *pattern {
+            Pattern::Any => true,
+            Pattern::Tagged(tag) => element.tag == tag,
+            Pattern::Exact(tag, ref contents) => {
+                element.tag == tag && element.pcbit == PCBit::Primitive &&
+                    self.buf[element.contents.clone()] == contents[..]
+            },
+            Pattern::Constructed(tag, ref patterns) => {
+                if element.tag != tag || element.pcbit != PCBit::Constructed {
+                    return Ok(false);
+                }
+                let children = self.children(element)?;
+                let mut rest = &children[..];
+                for pattern in patterns {
+                    match self.match_elements(rest, pattern, captures)? {
+                        Some(count) => rest = &rest[count..],
+                        None => return Ok(false),
+                    }
+                }
+                rest.is_empty()
+            },
+            Pattern::Capture(ref pattern) => {
+                let index = captures.len();
+                if !self.matches(element, pattern, captures)? {
+                    return Ok(false);
+                }
+                captures.insert(index, &self.buf[element.range.clone()]);
+                true
+            },
+            Pattern::Optional(ref pattern) |
+            Pattern::Repeat(ref pattern) => {
+                self.matches(element, pattern, captures)?
+            },
+            Pattern::Rest => true,
+        })
+    }
+
+    /// Matches the first elements of `elements` against `pattern`, and
+    /// returns the number of elements matched.
+    fn match_elements(&self, elements: &[Element], pattern: &Pattern,
+            captures: &mut Vec<&'a [u8]>) -> ASN1Result<Option<usize>> {
+        let count = match *pattern {
+            Pattern::Optional(ref pattern) => {
+                match elements.first() {
+                    Some(element) => {
+                        self.try_match(element, pattern, captures)? as usize
+                    },
+                    None => 0,
+                }
+            },
+            Pattern::Repeat(ref pattern) => {
+                let mut count = 0;
+                for element in elements {
+                    if !self.try_match(element, pattern, captures)? {
+                        break;
+                    }
+                    count += 1;
+                }
+                count
+            },
+            Pattern::Rest => elements.len(),
+            Pattern::Capture(ref inner) => {
+                let index = captures.len();
+                let count = match self.match_elements(
+                        elements, inner, captures)? {
+                    Some(count) => count,
+                    None => return Ok(None),
+                };
+                let start = elements.first().map_or(0, |e| e.range.start);
+                let end = if count > 0 {
+                    elements[count - 1].range.end
+                } else {
+                    start
+                };
+                captures.insert(index, &self.buf[start..end]);
+                count
+            },
+            _ => {
+                match elements.first() {
+                    Some(element) if self.matches(
+                            element, pattern, captures)? => 1,
+                    _ => return Ok(None),
+                }
+            },
+        };
+        Ok(Some(count))
+    }
+
+    /// Like `matches`, but leaves `captures` unchanged on failure.
+    fn try_match(&self, element: &Element, pattern: &Pattern,
+            captures: &mut Vec<&'a [u8]>) -> ASN1Result<bool> {
+        let len = captures.len();
+        let matched = self.matches(element, pattern, captures)?;
+        if !matched {
+            captures.truncate(len);
+        }
+        Ok(matched)
+    }
+}
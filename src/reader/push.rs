@@ -0,0 +1,121 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![forbid(missing_docs)]
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use super::{ASN1Error, ASN1ErrorKind, ASN1Result, BERMode, BERReader};
+use super::{ParseOptions, TlvLength, parse_with_options};
+use super::tlv_length_with_options;
+
+/// The result of [`PushParser::feed`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PushStatus<T> {
+    /// The value isn't complete yet.
+    NeedMoreData,
+    /// A value was complete, and parsed to this.
+    Complete(T),
+    /// The value was malformed, or failed to parse. The data buffered
+    /// so far is discarded.
+    Error(ASN1Error),
+}
+
+/// A parser for BER/DER-encoded values arriving in arbitrary chunks.
+///
+/// Chunks are buffered until they make up a whole value, which is then
+/// parsed with the callback given on construction. Data after the value
+/// is kept for the next one, so a stream of values can be fed as it
+/// comes.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::{BERMode, PushParser, PushStatus};
+/// let mut parser = PushParser::new(BERMode::Der, |reader| {
+///     reader.read_sequence(|reader| reader.next().read_i64())
+/// });
+/// assert_eq!(parser.feed(&[48, 3, 2]), PushStatus::NeedMoreData);
+/// assert_eq!(parser.feed(&[1, 10, 48, 3]), PushStatus::Complete(10));
+/// assert_eq!(parser.feed(&[2, 1, 20]), PushStatus::Complete(20));
+/// assert_eq!(parser.buffered(), 0);
+/// ```
+pub struct PushParser<F> {
+    options: ParseOptions,
+    callback: F,
+    buf: Vec<u8>,
+}
+
+impl<F> fmt::Debug for PushParser<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PushParser")
+            .field("options", &self.options)
+            .field("buf", &self.buf)
+            .finish()
+    }
+}
+
+impl<T, F> PushParser<F>
+        where F: for<'a, 'b> FnMut(BERReader<'a, 'b>) -> ASN1Result<T> {
+    /// Creates a parser reading each value with `callback`.
+    pub fn new(mode: BERMode, callback: F) -> Self {
+        PushParser::with_options(ParseOptions::new(mode), callback)
+    }
+
+    /// Creates a parser reading each value with `callback`, with the
+    /// given [`ParseOptions`].
+    ///
+    /// Values longer than [`ParseOptions::max_alloc`] are rejected with
+    /// [`ASN1ErrorKind::LimitExceeded`] as soon as their header tells so.
+    pub fn with_options(options: ParseOptions, callback: F) -> Self {
+        PushParser {
+            options,
+            callback,
+            buf: Vec::new(),
+        }
+    }
+
+    /// Appends `chunk` to the data, and parses the next value if it is
+    /// complete.
+    ///
+    /// At most one value is parsed per call. After
+    /// [`Complete`](PushStatus::Complete), the next value may already be
+    /// buffered, which feeding an empty chunk tells.
+    pub fn feed(&mut self, chunk: &[u8]) -> PushStatus<T> {
+        self.buf.extend_from_slice(chunk);
+        let len = match tlv_length_with_options(&self.buf, self.options) {
+            Ok(TlvLength::Complete(len)) => len,
+            Ok(TlvLength::Incomplete { needs_at_least })
+                    if needs_at_least <= self.options.max_alloc() => {
+                return PushStatus::NeedMoreData;
+            },
+            Ok(TlvLength::Incomplete { .. }) => {
+                self.buf.clear();
+                return PushStatus::Error(
+                    ASN1Error::new(ASN1ErrorKind::LimitExceeded));
+            },
+            Err(e) => {
+                self.buf.clear();
+                return PushStatus::Error(e);
+            },
+        };
+        let result = parse_with_options(&self.buf[..len], self.options,
+            &mut self.callback);
+        self.buf.drain(..len);
+        match result {
+            Ok(value) => PushStatus::Complete(value),
+            Err(e) => PushStatus::Error(e),
+        }
+    }
+
+    /// Returns the number of bytes buffered, of values yet to be parsed.
+    pub fn buffered(&self) -> usize {
+        self.buf.len()
+    }
+}
@@ -0,0 +1,240 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use alloc::string::String;
+use core::{cmp, str};
+
+use super::{ASN1Error, ASN1ErrorKind, ASN1Result, BERMode};
+
+/// Decodes the contents octets of a REAL (X.690 8.5, and 11.3 for DER).
+pub(super) fn decode_real(buf: &[u8], mode: BERMode) -> ASN1Result<f64> {
+    let first = match buf.first() {
+        Some(&first) => first,
+        None => return Ok(0.0),
+    };
+    if first & 0x80 != 0 {
+        decode_binary(buf, mode)
+    } else if first & 0x40 != 0 {
+        if buf.len() != 1 {
+            return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+        }
+        match first {
+            0x40 => Ok(infinity()),
+            0x41 => Ok(-infinity()),
+            0x42 => Ok(f64::from_bits(0x7ff8_0000_0000_0000)),
+            0x43 => Ok(-0.0),
+            _ => Err(ASN1Error::new(ASN1ErrorKind::Invalid)),
+        }
+    } else {
+        decode_decimal(buf, mode)
+    }
+}
+
+fn decode_binary(buf: &[u8], mode: BERMode) -> ASN1Result<f64> {
+    let first = buf[0];
+    let negative = first & 0x40 != 0;
+    // log2 of the base
+    let base_bits = match (first >> 4) & 3 {
+        0 => 1,
+        1 => 3,
+        2 => 4,
+        _ => return Err(ASN1Error::new(ASN1ErrorKind::Invalid)),
+    };
+    let scale = ((first >> 2) & 3) as i64;
+    let (exponent, mantissa) = match first & 3 {
+        3 => {
+            let len = *buf.get(1).ok_or(
+                ASN1Error::new(ASN1ErrorKind::Invalid))? as usize;
+            if len == 0 || (mode.is_canonical() && len <= 3) {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            if buf.len() < 2 + len {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            (&buf[2..2 + len], &buf[2 + len..])
+        },
+        len => {
+            let len = len as usize + 1;
+            if buf.len() < 1 + len {
+                return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+            }
+            (&buf[1..1 + len], &buf[1 + len..])
+        },
+    };
+    if mantissa.is_empty() {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+    if mode.is_canonical() {
+        let redundant = exponent.len() > 1 &&
+            ((exponent[0] == 0 && exponent[1] < 128) ||
+             (exponent[0] == 255 && exponent[1] >= 128));
+        if base_bits != 1 || scale != 0 || redundant ||
+                mantissa[mantissa.len() - 1] & 1 == 0 {
+            return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+        }
+    }
+    if exponent.len() > 8 {
+        return Err(ASN1Error::new(ASN1ErrorKind::IntegerOverflow));
+    }
+    let mut e = exponent[0] as i8 as i64;
+    for &b in &exponent[1..] {
+        e = (e << 8) | b as i64;
+    }
+    // The top 57 to 64 significant bits of the mantissa, the lowest one
+    // set if any bit below them is, so that it is rounded only once.
+    let mut m = 0u64;
+    let mut dropped = 0i64;
+    let mut sticky = false;
+    for &b in mantissa {
+        if m >> 56 == 0 {
+            m = (m << 8) | b as u64;
+        } else {
+            dropped += 8;
+            sticky |= b != 0;
+        }
+    }
+    let exp = e.saturating_mul(base_bits).saturating_add(scale)
+        .saturating_add(dropped);
+    let m = scale_by_power_of_two(m | sticky as u64, exp);
+    Ok(if negative { -m } else { m })
+}
+
+/// Returns `m * 2^exp`, rounded to the nearest, ties to even.
+fn scale_by_power_of_two(m: u64, exp: i64) -> f64 {
+    if m == 0 {
+        return 0.0;
+    }
+    // Beyond these bounds, the result is infinite or zero whatever `m`.
+    if exp > 4000 {
+        return infinity();
+    } else if exp < -4000 {
+        return 0.0;
+    }
+    // The position of the last bit kept: 53 bits in all, but no lower
+    // than that of the least subnormal number.
+    let bits = 64 - m.leading_zeros() as i64;
+    let last = cmp::max(exp + bits - 53, -1074);
+    let shift = last - exp;
+    let (mut m, mut exp) = if shift <= 0 {
+        (m as f64, exp)
+    } else if shift > 64 {
+        // Less than half the least subnormal number
+        return 0.0;
+    } else {
+        let wide = m as u128;
+        let mut rounded = (wide >> shift) as u64;
+        let rem = wide & ((1 << shift) - 1);
+        let half = 1 << (shift - 1);
+        if rem > half || (rem == half && rounded & 1 == 1) {
+            rounded += 1;
+        }
+        (rounded as f64, last)
+    };
+    // Now `m * 2^exp` is exact, unless it overflows.
+    while exp > 1000 {
+        m *= power_of_two(1000);
+        exp -= 1000;
+    }
+    while exp < -1000 {
+        m *= power_of_two(-1000);
+        exp += 1000;
+    }
+    m * power_of_two(exp as i32)
+}
+
+fn infinity() -> f64 {
+    f64::from_bits(0x7ff0_0000_0000_0000)
+}
+
+/// Returns `2^exp` for `-1022 <= exp <= 1023`.
+fn power_of_two(exp: i32) -> f64 {
+    f64::from_bits(((exp + 1023) as u64) << 52)
+}
+
+fn decode_decimal(buf: &[u8], mode: BERMode) -> ASN1Result<f64> {
+    let form = buf[0];
+    let s = str::from_utf8(&buf[1..]).map_err(|_| {
+        ASN1Error::new(ASN1ErrorKind::Invalid)
+    })?;
+    if mode.is_canonical() && (form != 3 || !is_canonical_nr3(s)) {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+    // ISO 6093: leading spaces, an optional sign, digits with an optional
+    // decimal mark (NR2 and NR3), and an exponent (NR3)
+    let s = s.trim_start_matches(' ');
+    let (sign, s) = match s.as_bytes().first() {
+        Some(b'-') => ("-", &s[1..]),
+        Some(b'+') => ("", &s[1..]),
+        _ => ("", s),
+    };
+    let (mantissa, exponent) = match s.find(&['E', 'e'][..]) {
+        Some(i) => (&s[..i], Some(&s[i + 1..])),
+        None => (s, None),
+    };
+    let (int_part, frac_part) = match mantissa.find(&['.', ','][..]) {
+        Some(i) => (&mantissa[..i], Some(&mantissa[i + 1..])),
+        None => (mantissa, None),
+    };
+    let digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+    let valid_form = match form {
+        1 => frac_part.is_none() && exponent.is_none(),
+        2 => frac_part.is_some() && exponent.is_none(),
+        3 => exponent.is_some(),
+        _ => false,
+    };
+    let frac_part = frac_part.unwrap_or("");
+    if !valid_form || !digits(int_part) || !digits(frac_part) ||
+            int_part.len() + frac_part.len() == 0 {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+    let exponent = exponent.unwrap_or("0");
+    let exp_digits = match exponent.as_bytes().first() {
+        Some(b'-') | Some(b'+') => &exponent[1..],
+        _ => exponent,
+    };
+    if exp_digits.is_empty() || !digits(exp_digits) {
+        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+    }
+    // Rebuild the number in the syntax of `f64::from_str`.
+    let mut normalized = String::with_capacity(buf.len() + 2);
+    normalized.push_str(sign);
+    normalized.push_str(if int_part.is_empty() { "0" } else { int_part });
+    normalized.push('.');
+    normalized.push_str(frac_part);
+    normalized.push('e');
+    normalized.push_str(exponent);
+    normalized.parse::<f64>().map_err(|_| {
+        ASN1Error::new(ASN1ErrorKind::Invalid)
+    })
+}
+
+/// Checks the NR3 form required by DER: `[-]M.E[-]N` where the integer
+/// `M` has neither leading nor trailing zeros, and the exponent `N` has
+/// no leading zeros and is written `+0` if zero.
+fn is_canonical_nr3(s: &str) -> bool {
+    let s = match s.as_bytes().first() {
+        Some(b'-') => &s[1..],
+        _ => s,
+    };
+    let (mantissa, exponent) = match s.find(".E") {
+        Some(i) => (&s[..i], &s[i + 2..]),
+        None => return false,
+    };
+    let mantissa_ok = !mantissa.is_empty() &&
+        mantissa.bytes().all(|b| b.is_ascii_digit()) &&
+        !mantissa.starts_with('0') && !mantissa.ends_with('0');
+    let exponent_ok = exponent == "+0" || {
+        let digits = match exponent.as_bytes().first() {
+            Some(b'-') => &exponent[1..],
+            _ => exponent,
+        };
+        !digits.is_empty() && digits.bytes().all(|b| b.is_ascii_digit()) &&
+            !digits.starts_with('0')
+    };
+    mantissa_ok && exponent_ok
+}
@@ -0,0 +1,156 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![forbid(missing_docs)]
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::super::{construct_der, PCBit};
+use super::super::models::ObjectIdentifier;
+use super::super::tags::{TAG_EOC, TAG_OID, TAG_SEQUENCE};
+use super::{ASN1Error, ASN1ErrorKind, ASN1Result, BERMode};
+use super::{BERReaderImpl, ParseOptions};
+
+/// An occurrence of an OBJECT IDENTIFIER found by [`find_oid`].
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct OidLocation {
+    /// The index of the element within its parent, preceded by those of
+    /// its ancestors. The first index counts top-level elements.
+    pub path: Vec<usize>,
+    /// The byte range of the OBJECT IDENTIFIER, identifier and length
+    /// octets included
+    pub range: Range<usize>,
+    /// The byte range of the enclosing element, if it is a SEQUENCE
+    pub sequence: Option<Range<usize>>,
+}
+
+/// Finds every occurrence of `oid` in `buf`.
+///
+/// `buf` is a sequence of BER/DER-encoded values, which are traversed
+/// in full. Only the structure is validated: the contents of primitive
+/// values are not parsed, nor searched (e.g. DER wrapped in an OCTET
+/// STRING).
+///
+/// The enclosing SEQUENCE is usually what is looked for, e.g. an
+/// `Extension` or an `AttributeTypeAndValue`; its range is given in
+/// [`OidLocation::sequence`].
+///
+/// # Examples
+///
+/// ```
+/// use yasna::BERMode;
+/// use yasna::models::ObjectIdentifier;
+/// // SEQUENCE { SEQUENCE { OID 2.5.4.3, UTF8String "a" } }
+/// let data = &[48, 10, 48, 8, 6, 3, 85, 4, 3, 12, 1, 97];
+/// let cn = ObjectIdentifier::from_slice(&[2, 5, 4, 3]);
+/// let found = yasna::find_oid(data, BERMode::Der, &cn).unwrap();
+/// assert_eq!(found.len(), 1);
+/// assert_eq!(found[0].path, vec![0, 0, 0]);
+/// assert_eq!(found[0].range, 4..9);
+/// assert_eq!(found[0].sequence, Some(2..12));
+/// ```
+pub fn find_oid(buf: &[u8], mode: BERMode, oid: &ObjectIdentifier)
+        -> ASN1Result<Vec<OidLocation>> {
+    let encoded = construct_der(|writer| writer.write_oid(oid));
+    // The contents follow a one-byte length, as OIDs are short
+    let target = match encoded.get(1) {
+        Some(&length) if length < 128 => &encoded[2..],
+        _ => return Ok(Vec::new()),
+    };
+    let mut search = Search {
+        buf,
+        options: ParseOptions::new(mode),
+        target,
+        path: Vec::new(),
+        found: Vec::new(),
+    };
+    search.children(0, Some(buf.len()), 0)?;
+    Ok(search.found)
+}
+
+struct Search<'a> {
+    buf: &'a [u8],
+    options: ParseOptions,
+    target: &'a [u8],
+    path: Vec<usize>,
+    found: Vec<OidLocation>,
+}
+
+impl<'a> Search<'a> {
+    /// Searches the values from `pos` to `end`, or to the end-of-contents
+    /// octets if `end` is `None`. Returns the position after them.
+    fn children(&mut self, mut pos: usize, end: Option<usize>, depth: usize)
+            -> ASN1Result<usize> {
+        if depth > self.options.max_depth() {
+            return Err(ASN1Error::new(ASN1ErrorKind::StackOverflow));
+        }
+        let limit = end.unwrap_or(self.buf.len());
+        let mut index = 0;
+        loop {
+            if end == Some(pos) {
+                return Ok(pos);
+            }
+            let mut reader = BERReaderImpl::with_pos(
+                &self.buf[..limit], pos, self.options);
+            let (tag, pcbit) = reader.read_identifier()?;
+            let length = reader.read_length()?;
+            let contents = reader.pos;
+            if end.is_none() && tag == TAG_EOC {
+                if pcbit != PCBit::Primitive || length != Some(0) {
+                    return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                }
+                return Ok(contents);
+            }
+            let first = self.found.len();
+            self.path.push(index);
+            let elem_end = match length {
+                Some(length) => {
+                    let elem_end = contents.checked_add(length).ok_or(
+                        ASN1Error::new(ASN1ErrorKind::IntegerOverflow))?;
+                    if elem_end > limit {
+                        return Err(ASN1Error::new(ASN1ErrorKind::Eof));
+                    }
+                    if pcbit == PCBit::Constructed {
+                        if self.options.mode == BERMode::Cer {
+                            return Err(
+                                ASN1Error::new(ASN1ErrorKind::Invalid));
+                        }
+                        self.children(contents, Some(elem_end), depth + 1)?;
+                    } else if tag == TAG_OID &&
+                            &self.buf[contents..elem_end] == self.target {
+                        self.found.push(OidLocation {
+                            path: self.path.clone(),
+                            range: pos..elem_end,
+                            sequence: None,
+                        });
+                    }
+                    elem_end
+                },
+                None => {
+                    if pcbit != PCBit::Constructed ||
+                            self.options.mode == BERMode::Der {
+                        return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+                    }
+                    self.children(contents, None, depth + 1)?
+                },
+            };
+            if tag == TAG_SEQUENCE && pcbit == PCBit::Constructed {
+                let depth = self.path.len() + 1;
+                for location in self.found[first..].iter_mut() {
+                    if location.path.len() == depth {
+                        location.sequence = Some(pos..elem_end);
+                    }
+                }
+            }
+            self.path.pop();
+            index += 1;
+            pos = elem_end;
+        }
+    }
+}
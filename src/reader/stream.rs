@@ -0,0 +1,169 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![forbid(missing_docs)]
+
+use alloc::vec::Vec;
+use core::cmp;
+use std::io::{self, Read};
+#[cfg(feature = "tokio")]
+use tokio1::io::{AsyncRead, AsyncReadExt};
+
+use super::{ASN1Error, ASN1ErrorKind, ASN1Result, BERMode, BERReader};
+use super::{ParseOptions, TlvLength, parse_with_options};
+use super::tlv_length_with_options;
+
+/// How much the buffer grows at a time, so that a length announced in a
+/// header isn't allocated before the data arrives.
+const CHUNK_SIZE: usize = 8192;
+
+/// Reads a single BER/DER-encoded value from `reader` and parses it.
+///
+/// The bytes are fetched as the headers tell how many more are needed,
+/// and nothing after the value is read, so that the rest is left in
+/// `reader`, e.g. the next message on a socket. Only the value itself is
+/// buffered; to process a CMS message too large for that, see
+/// [`cms::stream_content`](crate::cms::stream_content).
+///
+/// Malformed input is reported as an `io::Error` of kind `InvalidData`,
+/// and an input ending within the value as one of kind `UnexpectedEof`.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::BERMode;
+/// let data = &[48, 128, 2, 1, 10, 0, 0, 1, 1, 255];
+/// let mut reader = &data[..];
+/// let i = yasna::parse_ber_from_reader(&mut reader, BERMode::Ber, |reader| {
+///     reader.read_sequence(|reader| reader.next().read_i64())
+/// }).unwrap();
+/// assert_eq!(i, 10);
+/// assert_eq!(reader, &[1, 1, 255]);
+/// ```
+///
+/// # Features
+///
+/// This function is enabled by `std` feature.
+///
+/// ```toml
+/// [dependencies]
+/// yasna = { version = "*", features = ["std"] }
+/// ```
+pub fn parse_ber_from_reader<R, T, F>(reader: R, mode: BERMode, callback: F)
+        -> io::Result<T>
+        where R: Read,
+        F: for<'a, 'b> FnOnce(BERReader<'a, 'b>) -> ASN1Result<T> {
+    parse_from_reader_with_options(reader, ParseOptions::new(mode), callback)
+}
+
+/// Reads a single BER/DER-encoded value from `reader` and parses it with
+/// the given [`ParseOptions`].
+///
+/// See [`parse_ber_from_reader`]. The value is buffered only if it is no
+/// longer than [`ParseOptions::max_alloc`], and fails with
+/// [`ASN1ErrorKind::LimitExceeded`] otherwise.
+///
+/// # Features
+///
+/// This function is enabled by `std` feature.
+///
+/// ```toml
+/// [dependencies]
+/// yasna = { version = "*", features = ["std"] }
+/// ```
+pub fn parse_from_reader_with_options<R, T, F>(mut reader: R,
+        options: ParseOptions, callback: F) -> io::Result<T>
+        where R: Read,
+        F: for<'a, 'b> FnOnce(BERReader<'a, 'b>) -> ASN1Result<T> {
+    let mut buf = Vec::new();
+    while let Some(len) = fetch_len(&buf, options)? {
+        let start = buf.len();
+        buf.resize(len, 0);
+        reader.read_exact(&mut buf[start..])?;
+    }
+    Ok(parse_with_options(&buf, options, callback)?)
+}
+
+/// Reads a single BER/DER-encoded value from `reader` asynchronously,
+/// and parses it.
+///
+/// This is the asynchronous version of [`parse_ber_from_reader`]. The
+/// data is awaited as the headers tell how many more bytes are needed,
+/// and the value is parsed once complete.
+///
+/// # Examples
+///
+/// ```
+/// # extern crate tokio1 as tokio;
+/// use yasna::BERMode;
+/// // Reads a SEQUENCE { INTEGER }, e.g. from a `TcpStream`.
+/// async fn read_message<R>(reader: R) -> std::io::Result<i64>
+///         where R: tokio::io::AsyncRead + Unpin {
+///     yasna::parse_ber_async(reader, BERMode::Der, |reader| {
+///         reader.read_sequence(|reader| reader.next().read_i64())
+///     }).await
+/// }
+/// ```
+///
+/// # Features
+///
+/// This function is enabled by `tokio` feature.
+///
+/// ```toml
+/// [dependencies]
+/// yasna = { version = "*", features = ["tokio"] }
+/// ```
+#[cfg(feature = "tokio")]
+pub async fn parse_ber_async<R, T, F>(reader: R, mode: BERMode, callback: F)
+        -> io::Result<T>
+        where R: AsyncRead + Unpin,
+        F: for<'a, 'b> FnOnce(BERReader<'a, 'b>) -> ASN1Result<T> {
+    parse_async_with_options(reader, ParseOptions::new(mode), callback).await
+}
+
+/// Reads a single BER/DER-encoded value from `reader` asynchronously,
+/// and parses it with the given [`ParseOptions`].
+///
+/// See [`parse_ber_async`] and [`parse_from_reader_with_options`].
+///
+/// # Features
+///
+/// This function is enabled by `tokio` feature.
+///
+/// ```toml
+/// [dependencies]
+/// yasna = { version = "*", features = ["tokio"] }
+/// ```
+#[cfg(feature = "tokio")]
+pub async fn parse_async_with_options<R, T, F>(mut reader: R,
+        options: ParseOptions, callback: F) -> io::Result<T>
+        where R: AsyncRead + Unpin,
+        F: for<'a, 'b> FnOnce(BERReader<'a, 'b>) -> ASN1Result<T> {
+    let mut buf = Vec::new();
+    while let Some(len) = fetch_len(&buf, options)? {
+        let start = buf.len();
+        buf.resize(len, 0);
+        reader.read_exact(&mut buf[start..]).await?;
+    }
+    Ok(parse_with_options(&buf, options, callback)?)
+}
+
+/// Returns the length `buf` should be read up to, or `None` if it holds
+/// a whole value.
+fn fetch_len(buf: &[u8], options: ParseOptions) -> ASN1Result<Option<usize>> {
+    let needs_at_least = match tlv_length_with_options(buf, options)? {
+        TlvLength::Complete(_) => return Ok(None),
+        TlvLength::Incomplete { needs_at_least } => needs_at_least,
+    };
+    if needs_at_least > options.max_alloc() {
+        return Err(ASN1Error::new(ASN1ErrorKind::LimitExceeded));
+    }
+    // The value is at least `needs_at_least` bytes long, so reading up
+    // to there never goes beyond it.
+    Ok(Some(cmp::min(needs_at_least, buf.len() + CHUNK_SIZE)))
+}
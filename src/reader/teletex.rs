@@ -0,0 +1,76 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use alloc::string::String;
+
+/// Characters of the supplementary set of T.61, from 0xA0 to 0xFF.
+/// Non-spacing diacritical marks (0xC1 to 0xCF) are given as the
+/// corresponding combining characters. `'\0'` marks unassigned codes.
+const SUPPLEMENTARY: [char; 96] = [
+    // 0xA0
+    '\0', '\u{A1}', '\u{A2}', '\u{A3}', '$', '\u{A5}', '#', '\u{A7}',
+    '\u{A4}', '\0', '\0', '\u{AB}', '\0', '\0', '\0', '\0',
+    // 0xB0
+    '\u{B0}', '\u{B1}', '\u{B2}', '\u{B3}', '\u{D7}', '\u{B5}', '\u{B6}',
+    '\u{B7}', '\u{F7}', '\0', '\0', '\u{BB}', '\u{BC}', '\u{BD}', '\u{BE}',
+    '\u{BF}',
+    // 0xC0
+    '\0', '\u{300}', '\u{301}', '\u{302}', '\u{303}', '\u{304}', '\u{306}',
+    '\u{307}', '\u{308}', '\u{308}', '\u{30A}', '\u{327}', '\0', '\u{30B}',
+    '\u{328}', '\u{30C}',
+    // 0xD0
+    '\0', '\0', '\0', '\0', '\0', '\0', '\0', '\0',
+    '\0', '\0', '\0', '\0', '\0', '\0', '\0', '\0',
+    // 0xE0
+    '\u{2126}', '\u{C6}', '\u{110}', '\u{AA}', '\u{126}', '\0', '\u{132}',
+    '\u{13F}', '\u{141}', '\u{D8}', '\u{152}', '\u{BA}', '\u{DE}', '\u{166}',
+    '\u{14A}', '\u{149}',
+    // 0xF0
+    '\u{138}', '\u{E6}', '\u{111}', '\u{F0}', '\u{127}', '\u{131}', '\u{133}',
+    '\u{140}', '\u{142}', '\u{F8}', '\u{153}', '\u{DF}', '\u{FE}', '\u{167}',
+    '\u{14B}', '\0',
+];
+
+/// Decodes T.61 (the Teletex repertoire of X.208 / X.690) text.
+///
+/// Returns `None` on unassigned codes, control characters other than
+/// LF, FF and CR (including the escape sequences switching character
+/// sets), and diacritical marks not followed by a letter.
+pub(super) fn decode_t61(bytes: &[u8]) -> Option<String> {
+    let mut string = String::with_capacity(bytes.len());
+    let mut iter = bytes.iter();
+    while let Some(&b) = iter.next() {
+        match b {
+            b'\n' | b'\x0c' | b'\r' => string.push(b as char),
+            b'#' | b'$' | b'\\' | b'^' | b'`' | b'{' | b'}' | b'~' => {
+                return None;
+            },
+            0x20..=0x7e => string.push(b as char),
+            0xc1..=0xcf => {
+                let mark = SUPPLEMENTARY[(b - 0xa0) as usize];
+                match iter.next() {
+                    Some(&base) if mark != '\0' &&
+                            (base as char).is_ascii_alphabetic() => {
+                        string.push(base as char);
+                        string.push(mark);
+                    },
+                    _ => return None,
+                }
+            },
+            0xa0..=0xff => {
+                let c = SUPPLEMENTARY[(b - 0xa0) as usize];
+                if c == '\0' {
+                    return None;
+                }
+                string.push(c);
+            },
+            _ => return None,
+        }
+    }
+    Some(string)
+}
@@ -531,6 +531,134 @@ fn test_general_read_u8_err(mode: BERMode) {
     }
 }
 
+#[test]
+fn test_der_read_real() {
+    test_general_read_real(BERMode::Der);
+}
+
+#[test]
+fn test_ber_read_real() {
+    test_general_read_real(BERMode::Ber);
+}
+
+fn test_general_read_real(mode: BERMode) {
+    let inf = 1.0 / 0.0;
+    let tests : &[(f64, &[u8], bool)] = &[
+        (0.0, &[9, 0], true),
+        (inf, &[9, 1, 64], true),
+        (-inf, &[9, 1, 65], true),
+        (1.0, &[9, 3, 128, 0, 1], true),
+        (-1.0, &[9, 3, 192, 0, 1], true),
+        (6.0, &[9, 3, 128, 1, 3], true),
+        (0.75, &[9, 3, 128, 254, 3], true),
+        (2.0, &[9, 3, 128, 0, 2], false),
+        (8.0, &[9, 3, 144, 1, 1], false),
+        (0.0625, &[9, 3, 160, 255, 1], false),
+        (2.0, &[9, 3, 132, 0, 1], false),
+        (65793.0, &[9, 5, 128, 0, 1, 1, 1], true),
+        (inf, &[9, 4, 129, 4, 0, 1], true),
+        (5e-324, &[9, 4, 129, 251, 206, 1], true),
+        (2.0, &[9, 4, 129, 0, 1, 1], false),
+        (2.0, &[9, 7, 131, 4, 0, 0, 0, 1, 1], false),
+        (12.0, b"\x09\x04\x01 12", false),
+        (-1.5, b"\x09\x05\x02-1,5", false),
+        (120.0, b"\x09\x06\x0312.E1", true),
+        (1.0, b"\x09\x06\x031.E+0", true),
+        (-0.25, b"\x09\x08\x03-25.E-2", true),
+        (100.0, b"\x09\x06\x0310.E1", false),
+        (1.0, b"\x09\x05\x031.E0", false),
+        (10.0, b"\x09\x06\x031.E01", false),
+        (0.5, b"\x09\x06\x03 .5E0", false),
+    ];
+    for &(evalue, data, der) in tests {
+        let result = parse_ber_general(data, mode, |reader| {
+            reader.read_real()
+        });
+        if mode == BERMode::Ber || der {
+            assert_eq!(result.unwrap(), evalue);
+        } else {
+            result.unwrap_err();
+        }
+    }
+
+    let nan = parse_ber_general(&[9, 1, 66], mode, |reader| {
+        reader.read_real()
+    }).unwrap();
+    assert!(nan.is_nan());
+    let zero = parse_ber_general(&[9, 1, 67], mode, |reader| {
+        reader.read_real()
+    }).unwrap();
+    assert!(zero == 0.0 && zero.is_sign_negative());
+
+    let tests : &[&[u8]] = &[
+        &[9, 1, 68], &[9, 2, 64, 0], &[9, 1, 128], &[9, 2, 128, 0],
+        &[9, 3, 176, 0, 1], &[9, 3, 131, 0, 1], &[9, 2, 131, 4],
+        &[41, 0], &[2, 1, 0], b"\x09\x03\x041.", b"\x09\x03\x011.",
+        b"\x09\x02\x021", b"\x09\x04\x03inf", b"\x09\x03\x031E",
+        &[9, 12, 131, 9, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1],
+    ];
+    for &data in tests {
+        parse_ber_general(data, mode, |reader| {
+            reader.read_real()
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_ber_read_real_long_mantissa() {
+    use alloc::vec;
+    let read = |data: &[u8]| parse_ber(data, |reader| reader.read_real());
+    // 2^1600 * 2^-1600, and 2^1600 * 2^-2674 which is subnormal
+    let mut data = vec![9, 129, 204, 129, 0xF9, 0xC0, 1];
+    data.resize(data.len() + 200, 0);
+    assert_eq!(read(&data).unwrap(), 1.0);
+    data[4..6].copy_from_slice(&[0xF5, 0x8E]);
+    assert_eq!(read(&data).unwrap(), 5e-324);
+    // Leading zeros
+    assert_eq!(read(&[9, 5, 128, 0, 0, 0, 1]).unwrap(), 1.0);
+    // 2^53 + 1 rounds to even, 2^53 + 1 + 2^-16 up
+    let two53 = 9007199254740992.0;
+    assert_eq!(read(&[9, 9, 128, 0, 32, 0, 0, 0, 0, 0, 1]).unwrap(), two53);
+    assert_eq!(read(&[9, 11, 128, 240, 32, 0, 0, 0, 0, 0, 1, 0, 0])
+        .unwrap(), two53);
+    assert_eq!(read(&[9, 11, 128, 240, 32, 0, 0, 0, 0, 0, 1, 0, 1])
+        .unwrap(), two53 + 2.0);
+    // 2^64 + 1
+    assert_eq!(read(&[9, 11, 128, 0, 1, 0, 0, 0, 0, 0, 0, 0, 1]).unwrap(),
+        18446744073709551616.0);
+    // 0.5, 0.75 and 1.25 times the least subnormal number
+    assert_eq!(read(&[9, 4, 129, 251, 205, 1]).unwrap(), 0.0);
+    assert_eq!(read(&[9, 4, 129, 251, 204, 3]).unwrap(), 5e-324);
+    assert_eq!(read(&[9, 4, 129, 251, 204, 5]).unwrap(), 5e-324);
+}
+
+#[test]
+fn test_der_read_integer_bytes() {
+    let tests : &[&[u8]] = &[
+        &[2, 1, 0], &[2, 1, 255], &[2, 2, 0, 128], &[2, 2, 255, 127],
+        &[2, 9, 1, 0, 0, 0, 0, 0, 0, 0, 0],
+    ];
+    for &data in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_integer_bytes()
+        }).unwrap();
+        assert_eq!(value, &data[2..]);
+        let (bytes, nonnegative) = parse_der(data, |reader| {
+            reader.read_bigint_bytes()
+        }).unwrap();
+        assert_eq!(bytes, &data[2..]);
+        assert_eq!(nonnegative, data[2] < 128);
+    }
+
+    let tests : &[&[u8]] = &[
+        &[2, 0], &[2, 2, 0, 127], &[2, 2, 255, 128], &[34, 3, 2, 1, 0],
+        &[4, 1, 0],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| reader.read_integer_bytes()).unwrap_err();
+    }
+}
+
 #[cfg(feature = "num-bigint")]
 #[test]
 fn test_der_read_bigint_ok() {
@@ -790,232 +918,1401 @@ fn test_ber_read_bytes_err() {
 }
 
 #[test]
-fn test_der_read_null_ok() {
-    let value = parse_der(&[5, 0], |reader| {
-        reader.read_null()
-    }).unwrap();
-    assert_eq!(value, ());
-}
+fn test_ber_read_bytes_borrowed() {
+    let tests : &[(&[u8], &[u8])] = &[
+        (&[], &[4, 0]),
+        (&[1, 0, 255], &[4, 3, 1, 0, 255]),
+        (&[1, 0, 255], &[4, 129, 3, 1, 0, 255]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_ber(data, |reader| {
+            reader.read_bytes_borrowed()
+        }).unwrap();
+        assert_eq!(value, evalue);
+        assert!(core::ptr::eq(value.as_ptr(),
+            data[data.len() - value.len()..].as_ptr()));
+    }
 
-#[test]
-fn test_der_read_null_err() {
     let tests : &[&[u8]] = &[
-        &[], &[5], &[0, 0], &[0, 1, 0], &[2, 1, 0], &[37, 0], &[69, 0],
-        &[5, 128, 0], &[37, 128, 0], &[5, 1, 0], &[5, 2, 0, 0],
-        &[5, 1], &[5, 0, 1],
+        &[36, 0], &[36, 3, 4, 1, 0], &[36, 128, 4, 1, 0, 0, 0], &[5, 0],
+        &[4, 4, 0],
     ];
     for &data in tests {
-        parse_der(data, |reader| {
-            reader.read_null()
+        parse_ber(data, |reader| {
+            reader.read_bytes_borrowed()
         }).unwrap_err();
     }
+    parse_der(&[4, 129, 3, 1, 0, 255], |reader| {
+        reader.read_bytes_borrowed()
+    }).unwrap_err();
 }
 
 #[test]
-fn test_ber_read_null_ok() {
-    let value = parse_ber(&[5, 0], |reader| {
-        reader.read_null()
+fn test_ber_read_cow() {
+    use alloc::borrow::Cow;
+    let data: &[u8] = &[4, 3, 1, 2, 3];
+    let value = parse_der(data, |reader| reader.read_bytes_cow()).unwrap();
+    match value {
+        Cow::Borrowed(bytes) => assert_eq!(bytes.as_ptr(), data[2..].as_ptr()),
+        Cow::Owned(_) => panic!("primitive OCTET STRING was copied"),
+    }
+
+    let data: &[u8] = &[36, 128, 4, 1, 1, 36, 4, 4, 2, 2, 3, 0, 0];
+    let value = parse_ber(data, |reader| reader.read_bytes_cow()).unwrap();
+    match value {
+        Cow::Owned(bytes) => assert_eq!(bytes, [1, 2, 3]),
+        Cow::Borrowed(_) => panic!("constructed OCTET STRING was borrowed"),
+    }
+    parse_der(&[36, 3, 4, 1, 1], |reader| reader.read_bytes_cow())
+        .unwrap_err();
+
+    let value = parse_der(&[12, 3, 227, 129, 130], |reader| {
+        reader.read_utf8string_cow()
     }).unwrap();
-    assert_eq!(value, ());
+    assert_eq!(value, Cow::Borrowed("\u{3042}"));
+    let value = parse_ber(&[44, 128, 4, 1, 227, 4, 2, 129, 130, 0, 0],
+        |reader| reader.read_utf8string_cow()).unwrap();
+    assert_eq!(value, Cow::<str>::Owned("\u{3042}".to_owned()));
+    parse_ber(&[44, 128, 4, 1, 227, 4, 1, 129, 0, 0], |reader| {
+        reader.read_utf8string_cow()
+    }).unwrap_err();
+    parse_ber(&[12, 1, 128], |reader| {
+        reader.read_utf8string_cow()
+    }).unwrap_err();
 }
 
 #[test]
-fn test_ber_read_null_err() {
+fn test_ber_read_octetstring_der() {
+    use alloc::vec;
+    let value = parse_der(&[4, 5, 48, 3, 1, 1, 255], |reader| {
+        reader.read_octetstring_der(|reader| {
+            reader.read_sequence(|reader| reader.next().read_bool())
+        })
+    }).unwrap();
+    assert!(value);
+
+    let data: &[u8] = &[36, 128, 4, 2, 48, 3, 4, 3, 1, 1, 255, 0, 0];
+    let value = parse_ber(data, |reader| {
+        reader.read_octetstring_der(|reader| {
+            reader.read_sequence(|reader| reader.next().read_bool())
+        })
+    }).unwrap();
+    assert!(value);
+
+    let value = parse_der(&[48, 5, 128, 3, 5, 0, 0], |reader| {
+        reader.read_sequence(|reader| {
+            reader.next().read_tagged_implicit(Tag::context(0), |reader| {
+                reader.read_octetstring_der(|reader| reader.read_null())
+            })
+        })
+    });
+    assert_eq!(value, Err(ASN1Error::new(ASN1ErrorKind::Extra)));
+
+    // The contents are parsed in the outer mode.
+    parse_der(&[4, 4, 1, 129, 1, 255], |reader| {
+        reader.read_octetstring_der(|reader| reader.read_bool())
+    }).unwrap_err();
+    parse_ber(&[4, 4, 1, 129, 1, 255], |reader| {
+        reader.read_octetstring_der(|reader| reader.read_bool())
+    }).unwrap();
+
+    let options = ParseOptions::new(BERMode::Der).with_max_depth(1);
+    let value = parse_with_options(&[4, 4, 48, 2, 5, 0], options, |reader| {
+        reader.read_octetstring_der(|reader| {
+            reader.read_sequence(|reader| reader.next().read_null())
+        })
+    });
+    assert_eq!(value, Err(ASN1Error::new(ASN1ErrorKind::StackOverflow)));
+    // The outer and inner allocations share the budget.
+    let data: &[u8] = &[36, 6, 4, 4, 4, 2, 1, 2];
+    let options = ParseOptions::new(BERMode::Ber).with_max_alloc(6);
+    let value = parse_with_options(data, options, |reader| {
+        reader.read_octetstring_der(|reader| reader.read_bytes())
+    });
+    assert_eq!(value, Ok(vec![1, 2]));
+    let options = ParseOptions::new(BERMode::Ber).with_max_alloc(5);
+    let value = parse_with_options(data, options, |reader| {
+        reader.read_octetstring_der(|reader| reader.read_bytes())
+    });
+    assert_eq!(value, Err(ASN1Error::new(ASN1ErrorKind::LimitExceeded)));
+}
+
+#[test]
+fn test_ber_read_bitstring_der() {
+    let value = parse_der(&[3, 6, 0, 48, 3, 1, 1, 255], |reader| {
+        reader.read_bitstring_der(|reader| {
+            reader.read_sequence(|reader| reader.next().read_bool())
+        })
+    }).unwrap();
+    assert!(value);
+
+    let data: &[u8] = &[35, 128, 3, 3, 0, 48, 3, 3, 4, 0, 1, 1, 255, 0, 0];
+    let value = parse_ber(data, |reader| {
+        reader.read_bitstring_der(|reader| {
+            reader.read_sequence(|reader| reader.next().read_bool())
+        })
+    }).unwrap();
+    assert!(value);
+
     let tests : &[&[u8]] = &[
-        &[], &[5], &[0, 0], &[0, 1, 0], &[2, 1, 0], &[37, 0], &[69, 0],
-        &[5, 128, 0], &[37, 128, 0], &[5, 1, 0], &[5, 2, 0, 0],
-        &[5, 1], &[5, 0, 1],
+        &[3, 0], &[3, 3, 1, 5, 0], &[4, 3, 0, 5, 0], &[3, 4, 0, 5, 0, 0],
+        &[35, 128, 3, 2, 0, 5, 3, 2, 1, 0, 0, 0],
     ];
     for &data in tests {
         parse_ber(data, |reader| {
-            reader.read_null()
+            reader.read_bitstring_der(|reader| reader.read_null())
         }).unwrap_err();
     }
 }
 
 #[test]
-fn test_der_read_sequence_ok() {
-    let tests : &[((i64, bool), &[u8])] = &[
-        ((10, true), &[48, 6, 2, 1, 10, 1, 1, 255]),
-        ((266, true), &[48, 7, 2, 2, 1, 10, 1, 1, 255]),
+fn test_der_read_utf8string() {
+    let tests : &[(&str, &[u8])] = &[
+        ("", &[12, 0]),
+        ("yasna", &[12, 5, 121, 97, 115, 110, 97]),
+        ("\u{3042}", &[12, 3, 227, 129, 130]),
     ];
     for &(evalue, data) in tests {
         let value = parse_der(data, |reader| {
-            reader.read_sequence(|reader| {
-                let i = reader.next().read_i64()?;
-                let b = reader.next().read_bool()?;
-                return Ok((i, b));
-            })
+            reader.read_utf8string()
         }).unwrap();
         assert_eq!(value, evalue);
     }
 
-    let tests : &[((), &[u8])] = &[
-        ((), &[48, 0]),
+    let tests : &[&[u8]] = &[
+        &[4, 1, 97], &[12, 1, 128], &[12, 2, 227, 129], &[12, 2, 192, 128],
+        &[44, 3, 4, 1, 97], &[44, 128, 4, 1, 97, 0, 0],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| {
+            reader.read_utf8string()
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_ber_read_utf8string() {
+    let tests : &[(&str, &[u8])] = &[
+        ("a", &[44, 3, 4, 1, 97]),
+        ("\u{3042}", &[44, 128, 4, 1, 227, 4, 2, 129, 130, 0, 0]),
     ];
     for &(evalue, data) in tests {
-        let value = parse_der(data, |reader| {
-            reader.read_sequence(|_| {
-                Ok(())
-            })
+        let value = parse_ber(data, |reader| {
+            reader.read_utf8string()
         }).unwrap();
         assert_eq!(value, evalue);
     }
+    parse_ber(&[44, 128, 4, 1, 227, 4, 1, 129, 0, 0], |reader| {
+        reader.read_utf8string()
+    }).unwrap_err();
 }
 
 #[test]
-fn test_der_read_sequence_err() {
-    let tests : &[&[u8]] = &[
-        &[], &[48], &[0, 0], &[0, 1, 0],
-        &[49, 6, 2, 1, 10, 1, 1, 255],
-        &[16, 6, 2, 1, 10, 1, 1, 255],
-        &[112, 6, 2, 1, 10, 1, 1, 255],
-        &[48, 6, 2, 1, 10, 1, 1, 255, 0],
-        &[48, 6, 2, 2, 1, 10, 1, 1, 255],
-        &[48, 7, 2, 1, 10, 1, 1, 255, 0],
-        &[48, 7, 2, 1, 10, 1, 1, 255],
-        &[48, 8, 48, 6, 2, 1, 10, 1, 1, 255],
-        &[49, 128, 2, 1, 10, 1, 1, 255, 0, 0],
-        &[16, 128, 2, 1, 10, 1, 1, 255, 0, 0],
-        &[112, 128, 2, 1, 10, 1, 1, 255, 0, 0],
-        &[48, 128, 2, 1, 10, 1, 1, 255, 0, 0, 0],
-        &[48, 128, 48, 6, 2, 1, 10, 1, 1, 255, 0, 0],
-        &[48, 10, 48, 128, 2, 1, 10, 1, 1, 255, 0, 0],
-        &[48, 128, 48, 128, 2, 1, 10, 1, 1, 255, 0, 0, 0, 0],
-        &[48, 128, 2, 1, 10, 1, 1, 255, 0, 0],
-        &[48, 128, 2, 2, 1, 10, 1, 1, 255, 0, 0],
+fn test_der_read_ia5_string() {
+    let tests : &[(&str, &[u8])] = &[
+        ("", &[22, 0]),
+        ("a@example.com", b"\x16\x0da@example.com"),
+        ("\0\x7f", &[22, 2, 0, 127]),
     ];
-    for &data in tests {
-        parse_der(data, |reader| {
-            reader.read_sequence(|reader| {
-                let i = reader.next().read_i64()?;
-                let b = reader.next().read_bool()?;
-                return Ok((i, b));
-            })
-        }).unwrap_err();
+    for &(evalue, data) in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_ia5_string()
+        }).unwrap();
+        assert_eq!(value, evalue);
+        let value = parse_ber(data, |reader| {
+            reader.read_ia5_string()
+        }).unwrap();
+        assert_eq!(value, evalue);
     }
-}
+    let value = parse_ber(&[54, 6, 4, 1, 97, 4, 1, 98], |reader| {
+        reader.read_ia5_string()
+    }).unwrap();
+    assert_eq!(value, "ab");
 
-#[test]
-fn test_der_read_tagged_der_err() {
     let tests : &[&[u8]] = &[
-        &[], &[48], &[0, 0], &[0, 1, 0],
-        &[48, 6, 2, 1, 10, 1, 1, 255, 0],
-        &[48, 6, 2, 2, 1, 10, 1, 1, 255],
-        &[48, 7, 2, 1, 10, 1, 1, 255],
-        &[48, 128, 2, 1, 10, 1, 1, 255, 0, 0, 0],
-
-        // Otherwise valid indefinite-length encodings
-        &[48, 128, 48, 6, 2, 1, 10, 1, 1, 255, 0, 0],
-        &[48, 128, 48, 128, 2, 1, 10, 1, 1, 255, 0, 0, 0, 0],
-        &[48, 128, 2, 1, 10, 1, 1, 255, 0, 0],
-        &[48, 128, 2, 2, 1, 10, 1, 1, 255, 0, 0],
+        &[12, 1, 97], &[22, 1, 128], &[22, 2, 195, 169], &[22, 1, 255],
+        &[54, 3, 4, 1, 97],
     ];
     for &data in tests {
         parse_der(data, |reader| {
-            reader.read_tagged_der()
+            reader.read_ia5_string()
         }).unwrap_err();
     }
 }
 
 #[test]
-fn test_ber_read_sequence_ok() {
-    let tests : &[((i64, bool),
-                  TaggedDerValue,
-                  &[u8])] = &[
-        // Definite-length encoding
-        ((10, true),
-            TaggedDerValue::from_tag_and_bytes(
-                TAG_SEQUENCE,
-                [2, 1, 10, 1, 1, 255].to_vec()),
-            &[48, 6, 2, 1, 10, 1, 1, 255]),
-        // Definite-length encoding
-        ((266, true),
-            TaggedDerValue::from_tag_and_bytes(
-                TAG_SEQUENCE,
-                [2, 2, 1, 10, 1, 1, 255].to_vec()),
-            &[48, 7, 2, 2, 1, 10, 1, 1, 255]),
-        // Indefinite-length encoding
-        ((10, true),
-            TaggedDerValue::from_tag_and_bytes(
-                TAG_SEQUENCE,
-                [2, 1, 10, 1, 1, 255, 0, 0].to_vec()),
-            &[48, 128, 2, 1, 10, 1, 1, 255, 0, 0]),
-        // Indefinite-length encoding
-        ((266, true),
-            TaggedDerValue::from_tag_and_bytes(
-                TAG_SEQUENCE,
-                [2, 2, 1, 10, 1, 1, 255, 0, 0].to_vec()),
-            &[48, 128, 2, 2, 1, 10, 1, 1, 255, 0, 0]),
+fn test_der_read_printable_string() {
+    let tests : &[(&str, &[u8])] = &[
+        ("", &[19, 0]),
+        ("Co., Ltd.", b"\x13\x09Co., Ltd."),
+        ("'()+,-./:=? ", b"\x13\x0c'()+,-./:=? "),
+        ("AZaz09", b"\x13\x06AZaz09"),
     ];
-    for &(evalue, ref ervalue, data) in tests {
-        let value = parse_ber(data, |reader| {
-            reader.read_sequence(|reader| {
-                let i = reader.next().read_i64()?;
-                let b = reader.next().read_bool()?;
-                return Ok((i, b));
-            })
+    for &(evalue, data) in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_printable_string()
         }).unwrap();
         assert_eq!(value, evalue);
-        let rvalue = parse_ber(data, |reader| {
-            reader.read_tagged_der()
-        }).unwrap();
-        assert_eq!(&rvalue, ervalue);
     }
 
-    let tests : &[((), &[u8])] = &[
-        ((), &[48, 0]),
-        ((), &[48, 128, 0, 0]),
+    let tests : &[&[u8]] = &[
+        b"\x0c\x01a", b"\x13\x01*", b"\x13\x01@", b"\x13\x01&",
+        b"\x13\x01_", b"\x13\x01\"", b"\x13\x01\x80", b"\x13\x01;",
     ];
-    for &(evalue, data) in tests {
-        let value = parse_ber(data, |reader| {
-            reader.read_sequence(|_| {
-                Ok(())
-            })
-        }).unwrap();
-        assert_eq!(value, evalue);
+    for &data in tests {
+        parse_der(data, |reader| {
+            reader.read_printable_string()
+        }).unwrap_err();
     }
 }
 
 #[test]
-fn test_ber_read_sequence_err() {
+fn test_ber_read_strings_borrowed() {
+    let data: &[u8] = &[48, 15, 12, 3, 227, 129, 130, 19, 2, 65, 90,
+        22, 4, 97, 64, 98, 46];
+    let (u, p, i) = parse_der(data, |reader| {
+        reader.read_sequence(|reader| {
+            let u = reader.next().read_utf8string_borrowed()?;
+            let p = reader.next().read_printable_string_borrowed()?;
+            let i = reader.next().read_ia5_string_borrowed()?;
+            Ok((u, p, i))
+        })
+    }).unwrap();
+    assert_eq!(u, "\u{3042}");
+    assert_eq!(p, "AZ");
+    assert_eq!(i, "a@b.");
+    assert_eq!(u.as_ptr(), data[4..].as_ptr());
+
+    let value = parse_der(&[128, 1, 97], |reader| {
+        reader.read_tagged_implicit(Tag::context(0), |reader| {
+            reader.read_ia5_string_borrowed()
+        })
+    }).unwrap();
+    assert_eq!(value, "a");
+
     let tests : &[&[u8]] = &[
-        &[], &[48], &[0, 0], &[0, 1, 0],
-        &[49, 6, 2, 1, 10, 1, 1, 255],
-        &[16, 6, 2, 1, 10, 1, 1, 255],
-        &[112, 6, 2, 1, 10, 1, 1, 255],
-        &[48, 6, 2, 1, 10, 1, 1, 255, 0],
-        &[48, 6, 2, 2, 1, 10, 1, 1, 255],
-        &[48, 7, 2, 1, 10, 1, 1, 255, 0],
-        &[48, 7, 2, 1, 10, 1, 1, 255],
-        &[48, 8, 48, 6, 2, 1, 10, 1, 1, 255],
-        &[49, 128, 2, 1, 10, 1, 1, 255, 0, 0],
-        &[16, 128, 2, 1, 10, 1, 1, 255, 0, 0],
-        &[112, 128, 2, 1, 10, 1, 1, 255, 0, 0],
-        &[48, 128, 2, 1, 10, 1, 1, 255, 0, 0, 0],
-        &[48, 128, 48, 6, 2, 1, 10, 1, 1, 255, 0, 0],
+        &[12, 1, 128], &[19, 1, 42], &[22, 1, 128], &[4, 1, 97],
+        &[44, 3, 4, 1, 97], &[54, 3, 4, 1, 97],
     ];
     for &data in tests {
         parse_ber(data, |reader| {
-            reader.read_sequence(|reader| {
-                let i = reader.next().read_i64()?;
-                let b = reader.next().read_bool()?;
-                return Ok((i, b));
-            })
+            match data[0] & 31 {
+                12 => reader.read_utf8string_borrowed(),
+                19 => reader.read_printable_string_borrowed(),
+                _ => reader.read_ia5_string_borrowed(),
+            }
         }).unwrap_err();
     }
 }
 
 #[test]
-fn test_der_read_set_ok() {
-    let tests : &[((i64, Vec<u8>, i64, Vec<u8>), &[u8])] = &[
-        ((456789, b"Foo".to_vec(), 456790, b"Bar".to_vec()), &[
-        49, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111]),
+fn test_der_read_numeric_string() {
+    let tests : &[(&str, &[u8])] = &[
+        ("", &[18, 0]),
+        ("0123 456789", b"\x12\x0b0123 456789"),
     ];
-    for &(ref evalue, data) in tests {
+    for &(evalue, data) in tests {
         let value = parse_der(data, |reader| {
-            reader.read_set(|reader| {
+            reader.read_numeric_string()
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+
+    let tests : &[&[u8]] = &[
+        b"\x13\x011", b"\x12\x01a", b"\x12\x01+", b"\x12\x01-",
+        b"\x12\x01.", b"\x12\x01\x80", b"\x32\x03\x04\x011",
+    ];
+    for &data in tests {
+        parse_der(data, |reader| {
+            reader.read_numeric_string()
+        }).unwrap_err();
+    }
+    let value = parse_ber(b"\x32\x06\x04\x011\x04\x01 ", |reader| {
+        reader.read_numeric_string()
+    }).unwrap();
+    assert_eq!(value, "1 ");
+}
+
+#[test]
+fn test_der_read_visible_string() {
+    let tests : &[(&str, &[u8])] = &[
+        ("", &[26, 0]),
+        ("Hi!", &[26, 3, 72, 105, 33]),
+        (" ~", &[26, 2, 32, 126]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_visible_string()
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+
+    let tests : &[&[u8]] = &[
+        &[22, 1, 65], &[26, 1, 31], &[26, 1, 127], &[26, 1, 10],
+        &[26, 2, 195, 169], &[58, 3, 4, 1, 65],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| {
+            reader.read_visible_string()
+        }).unwrap_err();
+    }
+    let value = parse_ber(&[58, 128, 4, 1, 65, 0, 0], |reader| {
+        reader.read_visible_string()
+    }).unwrap();
+    assert_eq!(value, "A");
+}
+
+#[test]
+fn test_der_read_bmp_string() {
+    let tests : &[(&str, &[u8])] = &[
+        ("", &[30, 0]),
+        ("a\u{3042}", &[30, 4, 0, 97, 48, 66]),
+        ("\u{1f600}", &[30, 4, 216, 61, 222, 0]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_bmp_string()
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+
+    let tests : &[&[u8]] = &[
+        &[30, 1, 0], &[30, 3, 0, 97, 0], &[30, 2, 216, 61],
+        &[30, 2, 222, 0], &[30, 4, 222, 0, 216, 61], &[30, 4, 216, 61, 0, 97],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| {
+            reader.read_bmp_string()
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_der_read_universal_string() {
+    let tests : &[(&str, &[u8])] = &[
+        ("", &[28, 0]),
+        ("a", &[28, 4, 0, 0, 0, 97]),
+        ("\u{10FFFF}", &[28, 4, 0, 16, 255, 255]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_universal_string()
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+
+    let tests : &[&[u8]] = &[
+        &[28, 1, 97], &[28, 3, 0, 0, 97], &[28, 5, 0, 0, 0, 97, 0],
+        &[28, 4, 0, 17, 0, 0], &[28, 4, 0, 0, 216, 0], &[28, 4, 255, 0, 0, 0],
+        &[30, 4, 0, 0, 0, 97],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| {
+            reader.read_universal_string()
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_der_read_teletex_string() {
+    use crate::TeletexDecoding;
+    let tests : &[(&[u8], TeletexDecoding, &str)] = &[
+        (&[20, 0], TeletexDecoding::T61, ""),
+        (&[20, 3, 65, 32, 122], TeletexDecoding::T61, "A z"),
+        (&[20, 3, 200, 117, 251], TeletexDecoding::T61, "u\u{308}\u{DF}"),
+        (&[20, 2, 164, 166], TeletexDecoding::T61, "$#"),
+        (&[20, 2, 77, 252], TeletexDecoding::Latin1, "M\u{FC}"),
+        (&[20, 3, 77, 195, 188], TeletexDecoding::Utf8, "M\u{FC}"),
+        (&[20, 3, 77, 195, 188], TeletexDecoding::Utf8OrLatin1, "M\u{FC}"),
+        (&[20, 2, 77, 252], TeletexDecoding::Utf8OrLatin1, "M\u{FC}"),
+    ];
+    for &(data, decoding, evalue) in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_teletex_string(decoding)
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+
+    let tests : &[(&[u8], TeletexDecoding)] = &[
+        (&[19, 1, 65], TeletexDecoding::Latin1),
+        (&[20, 1, 36], TeletexDecoding::T61),
+        (&[20, 1, 27], TeletexDecoding::T61),
+        (&[20, 1, 200], TeletexDecoding::T61),
+        (&[20, 2, 200, 49], TeletexDecoding::T61),
+        (&[20, 1, 208], TeletexDecoding::T61),
+        (&[20, 2, 77, 252], TeletexDecoding::Utf8),
+    ];
+    for &(data, decoding) in tests {
+        parse_der(data, |reader| {
+            reader.read_teletex_string(decoding)
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_der_read_general_string() {
+    use crate::StringDecoding;
+    let data = &[27, 4, 75, 82, 66, 255];
+    let value = parse_der(data, |reader| {
+        reader.read_general_string_bytes()
+    }).unwrap();
+    assert_eq!(value, &[75, 82, 66, 255]);
+    parse_der(data, |reader| {
+        reader.read_general_string(StringDecoding::Utf8)
+    }).unwrap_err();
+
+    let tests : &[(&[u8], StringDecoding, &str)] = &[
+        (&[27, 0], StringDecoding::Ascii, ""),
+        (&[27, 3, 75, 82, 66], StringDecoding::Ascii, "KRB"),
+        (&[27, 3, 65, 195, 169], StringDecoding::Utf8, "A\u{E9}"),
+    ];
+    for &(data, decoding, evalue) in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_general_string(decoding)
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+
+    parse_der(&[27, 3, 65, 195, 169], |reader| {
+        reader.read_general_string(StringDecoding::Ascii)
+    }).unwrap_err();
+    parse_der(&[25, 1, 65], |reader| {
+        reader.read_general_string(StringDecoding::Ascii)
+    }).unwrap_err();
+}
+
+#[test]
+fn test_der_read_graphic_string() {
+    use crate::StringDecoding;
+    let value = parse_der(&[25, 2, 65, 233], |reader| {
+        reader.read_graphic_string_bytes()
+    }).unwrap();
+    assert_eq!(value, &[65, 233]);
+
+    let tests : &[(&[u8], StringDecoding, &str)] = &[
+        (&[25, 0], StringDecoding::Utf8, ""),
+        (&[25, 2, 65, 66], StringDecoding::Ascii, "AB"),
+        (&[25, 3, 65, 195, 169], StringDecoding::Utf8, "A\u{E9}"),
+    ];
+    for &(data, decoding, evalue) in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_graphic_string(decoding)
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+
+    parse_der(&[25, 2, 65, 233], |reader| {
+        reader.read_graphic_string(StringDecoding::Utf8)
+    }).unwrap_err();
+    parse_der(&[27, 1, 65], |reader| {
+        reader.read_graphic_string(StringDecoding::Ascii)
+    }).unwrap_err();
+}
+
+#[test]
+fn test_der_read_object_descriptor() {
+    let tests : &[(&[u8], &str)] = &[
+        (&[7, 0], ""),
+        (&[7, 3, 97, 98, 99], "abc"),
+        (&[7, 2, 195, 169], "\u{E9}"),
+    ];
+    for &(data, evalue) in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_object_descriptor()
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+
+    let tests : &[&[u8]] = &[&[7, 1, 255], &[25, 1, 97], &[39, 3, 4, 1, 97]];
+    for &data in tests {
+        parse_der(data, |reader| reader.read_object_descriptor()).unwrap_err();
+    }
+}
+
+#[test]
+fn test_ber_read_segmented_strings() {
+    use alloc::vec;
+    use crate::{StringDecoding, TeletexDecoding};
+    // A constructed string is a sequence of OCTET STRING segments, which
+    // may split a character.
+    let segmented = |tag: u8, s: &[u8]| {
+        let mut data = vec![tag | 32, 128];
+        for &b in s {
+            data.extend_from_slice(&[4, 1, b]);
+        }
+        data.extend_from_slice(&[36, 0, 0, 0]);
+        data
+    };
+    let tests : &[(u8, &[u8], &str)] = &[
+        (12, "\u{E9}t\u{E9}".as_bytes(), "\u{E9}t\u{E9}"),
+        (18, b"0 1", "0 1"),
+        (19, b"ab?", "ab?"),
+        (20, b"ab", "ab"),
+        (22, b"a@", "a@"),
+        (25, b"ab", "ab"),
+        (26, b"a~", "a~"),
+        (27, b"ab", "ab"),
+        (28, &[0, 0, 0, 97], "a"),
+        (30, &[0, 97, 0, 98], "ab"),
+        (7, b"ab", "ab"),
+    ];
+    for &(tag, contents, evalue) in tests {
+        let data = segmented(tag, contents);
+        let read = |reader: BERReader| match tag {
+            7 => reader.read_object_descriptor(),
+            12 => reader.read_utf8string(),
+            18 => reader.read_numeric_string(),
+            19 => reader.read_printable_string(),
+            20 => reader.read_teletex_string(TeletexDecoding::T61),
+            22 => reader.read_ia5_string(),
+            25 => reader.read_graphic_string(StringDecoding::Ascii),
+            26 => reader.read_visible_string(),
+            27 => reader.read_general_string(StringDecoding::Ascii),
+            28 => reader.read_universal_string(),
+            _ => reader.read_bmp_string(),
+        };
+        assert_eq!(parse_ber(&data, read).unwrap(), evalue);
+        parse_der(&data, read).unwrap_err();
+    }
+}
+
+#[test]
+fn test_der_read_null_ok() {
+    let value = parse_der(&[5, 0], |reader| {
+        reader.read_null()
+    }).unwrap();
+    assert_eq!(value, ());
+}
+
+#[test]
+fn test_der_read_null_err() {
+    let tests : &[&[u8]] = &[
+        &[], &[5], &[0, 0], &[0, 1, 0], &[2, 1, 0], &[37, 0], &[69, 0],
+        &[5, 128, 0], &[37, 128, 0], &[5, 1, 0], &[5, 2, 0, 0],
+        &[5, 1], &[5, 0, 1],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| {
+            reader.read_null()
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_ber_read_null_ok() {
+    let value = parse_ber(&[5, 0], |reader| {
+        reader.read_null()
+    }).unwrap();
+    assert_eq!(value, ());
+}
+
+#[test]
+fn test_ber_read_null_err() {
+    let tests : &[&[u8]] = &[
+        &[], &[5], &[0, 0], &[0, 1, 0], &[2, 1, 0], &[37, 0], &[69, 0],
+        &[5, 128, 0], &[37, 128, 0], &[5, 1, 0], &[5, 2, 0, 0],
+        &[5, 1], &[5, 0, 1],
+    ];
+    for &data in tests {
+        parse_ber(data, |reader| {
+            reader.read_null()
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_der_read_sequence_ok() {
+    let tests : &[((i64, bool), &[u8])] = &[
+        ((10, true), &[48, 6, 2, 1, 10, 1, 1, 255]),
+        ((266, true), &[48, 7, 2, 2, 1, 10, 1, 1, 255]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_sequence(|reader| {
+                let i = reader.next().read_i64()?;
+                let b = reader.next().read_bool()?;
+                return Ok((i, b));
+            })
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+
+    let tests : &[((), &[u8])] = &[
+        ((), &[48, 0]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_sequence(|_| {
+                Ok(())
+            })
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+}
+
+#[test]
+fn test_der_read_sequence_err() {
+    let tests : &[&[u8]] = &[
+        &[], &[48], &[0, 0], &[0, 1, 0],
+        &[49, 6, 2, 1, 10, 1, 1, 255],
+        &[16, 6, 2, 1, 10, 1, 1, 255],
+        &[112, 6, 2, 1, 10, 1, 1, 255],
+        &[48, 6, 2, 1, 10, 1, 1, 255, 0],
+        &[48, 6, 2, 2, 1, 10, 1, 1, 255],
+        &[48, 7, 2, 1, 10, 1, 1, 255, 0],
+        &[48, 7, 2, 1, 10, 1, 1, 255],
+        &[48, 8, 48, 6, 2, 1, 10, 1, 1, 255],
+        &[49, 128, 2, 1, 10, 1, 1, 255, 0, 0],
+        &[16, 128, 2, 1, 10, 1, 1, 255, 0, 0],
+        &[112, 128, 2, 1, 10, 1, 1, 255, 0, 0],
+        &[48, 128, 2, 1, 10, 1, 1, 255, 0, 0, 0],
+        &[48, 128, 48, 6, 2, 1, 10, 1, 1, 255, 0, 0],
+        &[48, 10, 48, 128, 2, 1, 10, 1, 1, 255, 0, 0],
+        &[48, 128, 48, 128, 2, 1, 10, 1, 1, 255, 0, 0, 0, 0],
+        &[48, 128, 2, 1, 10, 1, 1, 255, 0, 0],
+        &[48, 128, 2, 2, 1, 10, 1, 1, 255, 0, 0],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| {
+            reader.read_sequence(|reader| {
+                let i = reader.next().read_i64()?;
+                let b = reader.next().read_bool()?;
+                return Ok((i, b));
+            })
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_der_read_tagged_der_err() {
+    let tests : &[&[u8]] = &[
+        &[], &[48], &[0, 0], &[0, 1, 0],
+        &[48, 6, 2, 1, 10, 1, 1, 255, 0],
+        &[48, 6, 2, 2, 1, 10, 1, 1, 255],
+        &[48, 7, 2, 1, 10, 1, 1, 255],
+        &[48, 128, 2, 1, 10, 1, 1, 255, 0, 0, 0],
+
+        // Otherwise valid indefinite-length encodings
+        &[48, 128, 48, 6, 2, 1, 10, 1, 1, 255, 0, 0],
+        &[48, 128, 48, 128, 2, 1, 10, 1, 1, 255, 0, 0, 0, 0],
+        &[48, 128, 2, 1, 10, 1, 1, 255, 0, 0],
+        &[48, 128, 2, 2, 1, 10, 1, 1, 255, 0, 0],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| {
+            reader.read_tagged_der()
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_ber_read_sequence_ok() {
+    let tests : &[((i64, bool),
+                  TaggedDerValue,
+                  &[u8])] = &[
+        // Definite-length encoding
+        ((10, true),
+            TaggedDerValue::from_tag_and_bytes(
+                TAG_SEQUENCE,
+                [2, 1, 10, 1, 1, 255].to_vec()),
+            &[48, 6, 2, 1, 10, 1, 1, 255]),
+        // Definite-length encoding
+        ((266, true),
+            TaggedDerValue::from_tag_and_bytes(
+                TAG_SEQUENCE,
+                [2, 2, 1, 10, 1, 1, 255].to_vec()),
+            &[48, 7, 2, 2, 1, 10, 1, 1, 255]),
+        // Indefinite-length encoding
+        ((10, true),
+            TaggedDerValue::from_tag_and_bytes(
+                TAG_SEQUENCE,
+                [2, 1, 10, 1, 1, 255, 0, 0].to_vec()),
+            &[48, 128, 2, 1, 10, 1, 1, 255, 0, 0]),
+        // Indefinite-length encoding
+        ((266, true),
+            TaggedDerValue::from_tag_and_bytes(
+                TAG_SEQUENCE,
+                [2, 2, 1, 10, 1, 1, 255, 0, 0].to_vec()),
+            &[48, 128, 2, 2, 1, 10, 1, 1, 255, 0, 0]),
+    ];
+    for &(evalue, ref ervalue, data) in tests {
+        let value = parse_ber(data, |reader| {
+            reader.read_sequence(|reader| {
+                let i = reader.next().read_i64()?;
+                let b = reader.next().read_bool()?;
+                return Ok((i, b));
+            })
+        }).unwrap();
+        assert_eq!(value, evalue);
+        let rvalue = parse_ber(data, |reader| {
+            reader.read_tagged_der()
+        }).unwrap();
+        assert_eq!(&rvalue, ervalue);
+    }
+
+    let tests : &[((), &[u8])] = &[
+        ((), &[48, 0]),
+        ((), &[48, 128, 0, 0]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_ber(data, |reader| {
+            reader.read_sequence(|_| {
+                Ok(())
+            })
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+}
+
+#[test]
+fn test_ber_read_sequence_err() {
+    let tests : &[&[u8]] = &[
+        &[], &[48], &[0, 0], &[0, 1, 0],
+        &[49, 6, 2, 1, 10, 1, 1, 255],
+        &[16, 6, 2, 1, 10, 1, 1, 255],
+        &[112, 6, 2, 1, 10, 1, 1, 255],
+        &[48, 6, 2, 1, 10, 1, 1, 255, 0],
+        &[48, 6, 2, 2, 1, 10, 1, 1, 255],
+        &[48, 7, 2, 1, 10, 1, 1, 255, 0],
+        &[48, 7, 2, 1, 10, 1, 1, 255],
+        &[48, 8, 48, 6, 2, 1, 10, 1, 1, 255],
+        &[49, 128, 2, 1, 10, 1, 1, 255, 0, 0],
+        &[16, 128, 2, 1, 10, 1, 1, 255, 0, 0],
+        &[112, 128, 2, 1, 10, 1, 1, 255, 0, 0],
+        &[48, 128, 2, 1, 10, 1, 1, 255, 0, 0, 0],
+        &[48, 128, 48, 6, 2, 1, 10, 1, 1, 255, 0, 0],
+    ];
+    for &data in tests {
+        parse_ber(data, |reader| {
+            reader.read_sequence(|reader| {
+                let i = reader.next().read_i64()?;
+                let b = reader.next().read_bool()?;
+                return Ok((i, b));
+            })
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_der_read_sequence_of_ok() {
+    let tests : &[(&[i64], &[u8])] = &[
+        (&[], &[48, 0]),
+        (&[10], &[48, 3, 2, 1, 10]),
+        (&[128, 10, 10], &[48, 10, 2, 2, 0, 128, 2, 1, 10, 2, 1, 10]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_der(data, |reader| {
+            reader.collect_sequence_of(|reader| reader.read_i64())
+        }).unwrap();
+        assert_eq!(value, evalue);
+        let value = parse_der(data, |reader| {
+            reader.read_sequence_of_i64()
+        }).unwrap();
+        assert_eq!(value, evalue);
+        let mut value = Vec::new();
+        parse_der(data, |reader| {
+            reader.read_sequence_of(|reader| {
+                value.push(reader.read_i64()?);
+                Ok(())
+            })
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+}
+
+#[test]
+fn test_der_read_sequence_of_err() {
+    let tests : &[&[u8]] = &[
+        &[], &[48], &[49, 3, 2, 1, 10], &[16, 3, 2, 1, 10],
+        &[48, 3, 2, 1], &[48, 4, 2, 1, 10, 5], &[48, 5, 2, 1, 10, 5, 0],
+        &[48, 4, 2, 2, 0, 10], &[48, 128, 2, 1, 10, 0, 0],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| {
+            reader.collect_sequence_of(|reader| reader.read_i64())
+        }).unwrap_err();
+        parse_der(data, |reader| {
+            reader.read_sequence_of_i64()
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_ber_read_sequence_of_ok() {
+    let tests : &[(&[i64], &[u8])] = &[
+        (&[], &[48, 128, 0, 0]),
+        (&[10, -1], &[48, 128, 2, 1, 10, 2, 1, 255, 0, 0]),
+        (&[10], &[48, 3, 2, 1, 10]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_ber(data, |reader| {
+            reader.collect_sequence_of(|reader| reader.read_i64())
+        }).unwrap();
+        assert_eq!(value, evalue);
+        let value = parse_ber(data, |reader| {
+            reader.read_sequence_of_i64()
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+    parse_ber(&[48, 128, 2, 1, 10, 5, 0, 0, 0], |reader| {
+        reader.collect_sequence_of(|reader| reader.read_i64())
+    }).unwrap_err();
+    parse_ber(&[48, 128, 2, 1, 10, 5, 0, 0, 0], |reader| {
+        reader.read_sequence_of_i64()
+    }).unwrap_err();
+}
+
+#[test]
+fn test_ber_read_sequence_of_i64_capacity() {
+    use alloc::vec;
+    // Preallocated from the length
+    let value = parse_ber(&[48, 9, 2, 1, 1, 2, 1, 2, 2, 1, 3], |reader| {
+        reader.read_sequence_of_i64()
+    }).unwrap();
+    assert_eq!(value.capacity(), 3);
+
+    // Not from the rest of the input when the length is indefinite
+    let mut data = vec![48, 130, 15, 171, 48, 128, 2, 1, 1, 0, 0,
+        4, 130, 15, 160];
+    data.resize(data.len() + 4000, 0);
+    let value = parse_ber(&data, |reader| {
+        reader.read_sequence(|reader| {
+            let value = reader.next().read_sequence_of_i64()?;
+            reader.next().read_bytes()?;
+            Ok(value)
+        })
+    }).unwrap();
+    assert_eq!(value, &[1]);
+    assert!(value.capacity() < 100);
+}
+
+#[test]
+fn test_ber_read_sequence_of_u64() {
+    use alloc::vec;
+    let data = &[48, 128, 2, 1, 0, 2, 9, 0, 255, 255, 255, 255, 255, 255,
+        255, 255, 0, 0];
+    let value = parse_ber(data, |reader| {
+        reader.read_sequence_of_u64()
+    }).unwrap();
+    assert_eq!(value, vec![0, !0]);
+    let value = parse_ber(&[48, 3, 2, 1, 255], |reader| {
+        reader.read_sequence_of_u64()
+    });
+    assert_eq!(value, Err(ASN1Error::new(ASN1ErrorKind::IntegerOverflow)));
+    let value = parse_ber(&[48, 4, 34, 2, 2, 0], |reader| {
+        reader.read_sequence_of_u64()
+    });
+    assert_eq!(value, Err(ASN1Error::new(ASN1ErrorKind::Invalid)));
+
+    // The elements count towards the allocation budget.
+    let data = &[48, 9, 2, 1, 1, 2, 1, 2, 2, 1, 3];
+    let options = ParseOptions::new(BERMode::Der).with_max_alloc(24);
+    let value = parse_with_options(data, options, |reader| {
+        reader.read_sequence_of_u64()
+    });
+    assert_eq!(value, Ok(vec![1, 2, 3]));
+    let options = options.with_max_alloc(23);
+    let value = parse_with_options(data, options, |reader| {
+        reader.read_sequence_of_u64()
+    });
+    assert_eq!(value, Err(ASN1Error::new(ASN1ErrorKind::LimitExceeded)));
+}
+
+#[test]
+fn test_ber_seq_remaining() {
+    let tests : &[(&[u8], &[usize])] = &[
+        (&[48, 0], &[]),
+        (&[48, 5, 2, 1, 10, 5, 0], &[5, 2]),
+        (&[48, 128, 0, 0], &[]),
+        (&[48, 128, 2, 1, 10, 5, 0, 0, 0], &[5, 2]),
+        (&[48, 128, 48, 128, 0, 0, 5, 0, 0, 0], &[6, 2]),
+        (&[48, 9, 48, 128, 2, 1, 10, 0, 0, 5, 0], &[9, 2]),
+    ];
+    for &(data, elens) in tests {
+        let lens = parse_ber(data, |reader| {
+            reader.read_sequence(|reader| {
+                let mut lens = Vec::new();
+                while reader.has_next() {
+                    assert!(!reader.is_empty());
+                    lens.push(reader.remaining_len()?);
+                    reader.next().skip_value()?;
+                }
+                assert!(reader.is_empty());
+                assert_eq!(reader.remaining_len()?, 0);
+                Ok(lens)
+            })
+        }).unwrap();
+        assert_eq!(lens, elens);
+    }
+
+    let err = parse_ber(&[48, 3, 4, 2, 1], |reader| {
+        reader.read_sequence(|reader| {
+            assert!(reader.has_next());
+            reader.remaining_len()
+        })
+    }).unwrap_err();
+    assert_eq!(err.kind(), ASN1ErrorKind::Eof);
+}
+
+#[test]
+fn test_ber_for_each_remaining() {
+    let read = |reader: &mut BERReaderSeq| {
+        let b = reader.next().read_bool()?;
+        let mut v = Vec::new();
+        reader.for_each_remaining(|reader| {
+            v.push(reader.read_i64()?);
+            Ok(())
+        })?;
+        Ok((b, v))
+    };
+    let tests : &[(&[u8], bool, &[i64])] = &[
+        (&[48, 3, 1, 1, 0], false, &[]),
+        (&[48, 6, 1, 1, 255, 2, 1, 10], true, &[10]),
+        (&[48, 128, 1, 1, 255, 2, 1, 10, 2, 1, 20, 0, 0], true, &[10, 20]),
+    ];
+    for &(data, eb, ev) in tests {
+        assert_eq!(parse_ber(data, |reader| reader.read_sequence(read))
+            .unwrap(), (eb, ev.to_vec()));
+    }
+
+    let tests : &[(&[u8], ASN1ErrorKind)] = &[
+        (&[48, 5, 1, 1, 255, 5, 0], ASN1ErrorKind::Invalid),
+        (&[48, 6, 1, 1, 255, 2, 2, 10], ASN1ErrorKind::Eof),
+        (&[48, 0], ASN1ErrorKind::Eof),
+    ];
+    for &(data, kind) in tests {
+        let err = parse_ber(data, |reader| reader.read_sequence(read))
+            .unwrap_err();
+        assert_eq!(err.kind(), kind);
+    }
+}
+
+#[test]
+fn test_ber_read_optional_tagged() {
+    let read = |reader: &mut BERReaderSeq| {
+        let a = reader.read_optional_tagged(Tag::context(0), |reader| {
+            reader.read_tagged_implicit(Tag::context(0), |reader| {
+                reader.read_i64()
+            })
+        })?;
+        let b = reader.read_optional_tagged(TAG_SEQUENCE, |reader| {
+            reader.read_sequence(|reader| reader.next().read_bool())
+        })?;
+        Ok((a, b))
+    };
+    let tests : &[(&[u8], Option<i64>, Option<bool>)] = &[
+        (&[48, 0], None, None),
+        (&[48, 3, 128, 1, 10], Some(10), None),
+        (&[48, 5, 48, 3, 1, 1, 255], None, Some(true)),
+        (&[48, 128, 128, 1, 10, 48, 128, 1, 1, 0, 0, 0, 0, 0],
+            Some(10), Some(false)),
+        (&[48, 128, 0, 0], None, None),
+    ];
+    for &(data, a, b) in tests {
+        assert_eq!(parse_ber(data, |reader| reader.read_sequence(read))
+            .unwrap(), (a, b));
+    }
+
+    let tests : &[(&[u8], ASN1ErrorKind)] = &[
+        // Present but corrupt
+        (&[48, 2, 128, 0], ASN1ErrorKind::Invalid),
+        (&[48, 4, 48, 2, 5, 0], ASN1ErrorKind::Invalid),
+        // Neither of the optional values
+        (&[48, 2, 5, 0], ASN1ErrorKind::Extra),
+    ];
+    for &(data, kind) in tests {
+        let err = parse_ber(data, |reader| reader.read_sequence(read))
+            .unwrap_err();
+        assert_eq!(err.kind(), kind);
+    }
+}
+
+#[test]
+fn test_ber_read_sequence_extensible() {
+    let tests : &[(i64, &[u8])] = &[
+        (10, &[48, 3, 2, 1, 10]),
+        (10, &[48, 8, 2, 1, 10, 1, 1, 255, 5, 0]),
+        (10, &[48, 128, 2, 1, 10, 48, 128, 5, 0, 0, 0, 0, 0]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_ber(data, |reader| {
+            reader.read_sequence_extensible(|reader| reader.next().read_i64())
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+
+    let tests : &[&[u8]] = &[
+        &[48, 6, 2, 1, 10, 1, 2, 255], &[48, 4, 2, 1, 10, 0],
+        &[48, 128, 2, 1, 10, 5, 0, 0], &[49, 3, 2, 1, 10],
+    ];
+    for &data in tests {
+        parse_ber(data, |reader| {
+            reader.read_sequence_extensible(|reader| reader.next().read_i64())
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_ber_seq_rollback() {
+    // The first attempt fails deep within a nested SEQUENCE.
+    let data: &[u8] = &[48, 128, 48, 128, 2, 1, 10, 0, 0, 2, 1, 20, 0, 0];
+    let value = parse_ber(data, |reader| {
+        reader.read_sequence(|reader| {
+            let checkpoint = reader.checkpoint();
+            reader.next().read_sequence(|reader| {
+                reader.next().read_bool()
+            }).unwrap_err();
+            reader.rollback(checkpoint);
+            let i = reader.next().read_sequence(|reader| {
+                reader.next().read_i64()
+            })?;
+            let checkpoint = reader.checkpoint();
+            let j = reader.next().read_i64()?;
+            reader.rollback(checkpoint);
+            let k = reader.next().read_i64()?;
+            Ok((i, j, k))
+        })
+    }).unwrap();
+    assert_eq!(value, (10, 20, 20));
+}
+
+#[test]
+fn test_ber_read_remaining_blob() {
+    let tests : &[(&[u8], &[u8])] = &[
+        (&[48, 3, 2, 1, 10], &[]),
+        (&[48, 8, 2, 1, 10, 1, 1, 255, 5, 0], &[1, 1, 255, 5, 0]),
+        (&[48, 128, 2, 1, 10, 0, 0], &[]),
+        (&[48, 128, 2, 1, 10, 48, 128, 5, 0, 0, 0, 0, 0],
+            &[48, 128, 5, 0, 0, 0]),
+    ];
+    for &(data, erest) in tests {
+        let rest = parse_ber(data, |reader| {
+            reader.read_sequence(|reader| {
+                reader.next().read_i64()?;
+                let rest = reader.read_remaining_blob()?;
+                assert!(!reader.has_next());
+                assert!(reader.read_remaining_blob()?.is_empty());
+                Ok(rest)
+            })
+        }).unwrap();
+        assert_eq!(rest, erest);
+    }
+
+    let tests : &[&[u8]] = &[
+        &[48, 6, 2, 1, 10, 1, 2, 255], &[48, 128, 2, 1, 10, 48, 128, 0, 0],
+    ];
+    for &data in tests {
+        parse_ber(data, |reader| {
+            reader.read_sequence(|reader| {
+                reader.next().read_i64()?;
+                reader.read_remaining_blob()
+            })
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_der_read_set_ok() {
+    let tests : &[((i64, Vec<u8>, i64, Vec<u8>), &[u8])] = &[
+        ((456789, b"Foo".to_vec(), 456790, b"Bar".to_vec()), &[
+        49, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111]),
+    ];
+    for &(ref evalue, data) in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_set(|reader| {
+                let a = reader.next(&[Tag::context(28)])?
+                    .read_tagged_implicit(Tag::context(28), |reader| {
+                    reader.read_i64()
+                })?;
+                let b = reader.next(&[Tag::context(345678)])?
+                    .read_tagged(Tag::context(345678), |reader| {
+                    reader.read_bytes()
+                })?;
+                let c = reader.next(&[Tag::context(27)])?
+                    .read_tagged(Tag::context(27), |reader| {
+                    reader.read_i64()
+                })?;
+                let d = reader.next(&[Tag::context(345677)])?
+                    .read_tagged(Tag::context(345677), |reader| {
+                    reader.read_bytes()
+                })?;
+                return Ok((a, b, c, d));
+            })
+        }).unwrap();
+        assert_eq!(&value, evalue);
+    }
+}
+
+#[test]
+fn test_der_read_set_err() {
+    let tests : &[&[u8]] = &[
+        &[], &[49], &[0, 0], &[0, 1, 0],
+        &[17, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
+        &[113, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
+        &[48, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
+        &[49, 33, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
+        &[49, 33, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111, 0],
+        &[49, 31, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
+        &[49, 31, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111],
+        &[49, 22, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114],
+        &[49, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111,
+        191, 149, 140, 79, 5, 4, 3, 70, 111, 111],
+        &[49, 128, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+            77, 5, 4, 3, 66, 97, 114, 191, 149,
+            140, 78, 5, 4, 3, 70, 111, 111, 0, 0],
+        &[49, 32, 156, 3, 6, 248, 85, 187, 5, 2, 3, 6, 248, 86, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
+        &[49, 32, 187, 5, 2, 3, 6, 248, 86, 191, 149, 140, 77, 5, 4, 3, 66, 97,
+        114, 156, 3, 6, 248, 85, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
+        &[49, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        78, 5, 4, 3, 70, 111, 111, 191, 149, 140, 77, 5, 4, 3, 66, 97, 114],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| {
+            reader.read_set(|reader| {
+                let a = reader.next(&[Tag::context(28)])?
+                    .read_tagged_implicit(Tag::context(28), |reader| {
+                    reader.read_i64()
+                })?;
+                let b = reader.next(&[Tag::context(345678)])?
+                    .read_tagged(Tag::context(345678), |reader| {
+                    reader.read_bytes()
+                })?;
+                let c = reader.next(&[Tag::context(27)])?
+                    .read_tagged(Tag::context(27), |reader| {
+                    reader.read_i64()
+                })?;
+                let d = reader.next(&[Tag::context(345677)])?
+                    .read_tagged(Tag::context(345677), |reader| {
+                    reader.read_bytes()
+                })?;
+                return Ok((a, b, c, d));
+            })
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_ber_read_set_ok() {
+    let tests : &[((i64, Vec<u8>, i64, Vec<u8>), &[u8])] = &[
+        ((456789, b"Foo".to_vec(), 456790, b"Bar".to_vec()), &[
+        49, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111]),
+        ((456789, b"Foo".to_vec(), 456790, b"Bar".to_vec()), &[
+        49, 128, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+            77, 5, 4, 3, 66, 97, 114, 191, 149,
+            140, 78, 5, 4, 3, 70, 111, 111, 0, 0]),
+        ((456789, b"Foo".to_vec(), 456790, b"Bar".to_vec()), &[
+        49, 32, 156, 3, 6, 248, 85, 187, 5, 2, 3, 6, 248, 86, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111]),
+        ((456789, b"Foo".to_vec(), 456790, b"Bar".to_vec()), &[
+        49, 32, 187, 5, 2, 3, 6, 248, 86, 191, 149, 140, 77, 5, 4, 3, 66, 97,
+        114, 156, 3, 6, 248, 85, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111]),
+        ((456789, b"Foo".to_vec(), 456790, b"Bar".to_vec()), &[
+        49, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        78, 5, 4, 3, 70, 111, 111, 191, 149, 140, 77, 5, 4, 3, 66, 97, 114]),
+    ];
+    for &(ref evalue, data) in tests {
+        let value = parse_ber(data, |reader| {
+            reader.read_set(|reader| {
+                let a = reader.next(&[Tag::context(28)])?
+                    .read_tagged_implicit(Tag::context(28), |reader| {
+                    reader.read_i64()
+                })?;
+                let b = reader.next(&[Tag::context(345678)])?
+                    .read_tagged(Tag::context(345678), |reader| {
+                    reader.read_bytes()
+                })?;
+                let c = reader.next(&[Tag::context(27)])?
+                    .read_tagged(Tag::context(27), |reader| {
+                    reader.read_i64()
+                })?;
+                let d = reader.next(&[Tag::context(345677)])?
+                    .read_tagged(Tag::context(345677), |reader| {
+                    reader.read_bytes()
+                })?;
+                return Ok((a, b, c, d));
+            })
+        }).unwrap();
+        assert_eq!(&value, evalue);
+    }
+}
+
+#[test]
+fn test_ber_read_set_err() {
+    let tests : &[&[u8]] = &[
+        &[], &[49], &[0, 0], &[0, 1, 0],
+        &[17, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
+        &[113, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
+        &[48, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
+        &[49, 33, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
+        &[49, 33, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111, 0],
+        &[49, 31, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
+        &[49, 31, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111],
+        &[49, 22, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114],
+        &[49, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
+        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111,
+        191, 149, 140, 79, 5, 4, 3, 70, 111, 111],
+    ];
+    for &data in tests {
+        parse_ber(data, |reader| {
+            reader.read_set(|reader| {
+                let a = reader.next(&[Tag::context(28)])?
+                    .read_tagged_implicit(Tag::context(28), |reader| {
+                    reader.read_i64()
+                })?;
+                let b = reader.next(&[Tag::context(345678)])?
+                    .read_tagged(Tag::context(345678), |reader| {
+                    reader.read_bytes()
+                })?;
+                let c = reader.next(&[Tag::context(27)])?
+                    .read_tagged(Tag::context(27), |reader| {
+                    reader.read_i64()
+                })?;
+                let d = reader.next(&[Tag::context(345677)])?
+                    .read_tagged(Tag::context(345677), |reader| {
+                    reader.read_bytes()
+                })?;
+                return Ok((a, b, c, d));
+            })
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_der_read_set_of_ok() {
+    use std::collections::HashSet;
+    let tests : &[(&[i64], &[u8])] = &[
+        (&[-129, -128, 127, 128], &[
+            49, 14, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127]),
+        (&[-128, 127, 128], &[
+            49, 10, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128]),
+        (&[-129, -128, 127, 128, 32768], &[
+            49, 19, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127,
+            2, 3, 0, 128, 0]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_der(data, |reader| {
+            reader.collect_set_of(|reader| {
+                reader.read_i64()
+            })
+        }).unwrap();
+        let value_set = value.iter().collect::<HashSet<_>>();
+        let evalue_set = evalue.iter().collect::<HashSet<_>>();
+        assert_eq!(value_set, evalue_set);
+    }
+}
+
+#[test]
+fn test_der_read_set_of_err() {
+    let tests : &[&[u8]] = &[
+        &[], &[49], &[0, 0], &[0, 1, 0],
+        &[17, 14, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
+        &[113, 14, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
+        &[48, 14, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
+        &[49, 15, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
+        &[49, 15, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127, 0],
+        &[49, 13, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
+        &[49, 13, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255],
+        &[49, 128, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127, 0, 0],
+        &[49, 14, 2, 1, 128, 2, 1, 127, 2, 2, 0, 128, 2, 2, 255, 127],
+        &[49, 14, 2, 1, 127, 2, 2, 0, 128, 2, 1, 128, 2, 2, 255, 127],
+        &[49, 14, 2, 1, 127, 2, 1, 128, 2, 2, 255, 127, 2, 2, 0, 128],
+        &[49, 14, 2, 2, 255, 127, 2, 1, 128, 2, 2, 0, 128, 2, 1, 127],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| {
+            reader.read_set(|reader| {
+                let a = reader.next(&[Tag::context(28)])?
+                    .read_tagged_implicit(Tag::context(28), |reader| {
+                    reader.read_i64()
+                })?;
+                let b = reader.next(&[Tag::context(345678)])?
+                    .read_tagged(Tag::context(345678), |reader| {
+                    reader.read_bytes()
+                })?;
+                let c = reader.next(&[Tag::context(27)])?
+                    .read_tagged(Tag::context(27), |reader| {
+                    reader.read_i64()
+                })?;
+                let d = reader.next(&[Tag::context(345677)])?
+                    .read_tagged(Tag::context(345677), |reader| {
+                    reader.read_bytes()
+                })?;
+                return Ok((a, b, c, d));
+            })
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_ber_read_set_of_ok() {
+    use std::collections::HashSet;
+    let tests : &[(&[i64], &[u8])] = &[
+        (&[-129, -128, 127, 128], &[
+            49, 14, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127]),
+        (&[-128, 127, 128], &[
+            49, 10, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128]),
+        (&[-129, -128, 127, 128, 32768], &[
+            49, 19, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127,
+            2, 3, 0, 128, 0]),
+        (&[-129, -128, 127, 128], &[
+            49, 128, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127,
+            0, 0]),
+        (&[-129, -128, 127, 128], &[
+            49, 14, 2, 1, 128, 2, 1, 127, 2, 2, 0, 128, 2, 2, 255, 127]),
+        (&[-129, -128, 127, 128], &[
+            49, 14, 2, 1, 127, 2, 2, 0, 128, 2, 1, 128, 2, 2, 255, 127]),
+        (&[-129, -128, 127, 128], &[
+            49, 14, 2, 1, 127, 2, 1, 128, 2, 2, 255, 127, 2, 2, 0, 128]),
+        (&[-129, -128, 127, 128], &[
+            49, 14, 2, 2, 255, 127, 2, 1, 128, 2, 2, 0, 128, 2, 1, 127]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_ber(data, |reader| {
+            reader.collect_set_of(|reader| {
+                reader.read_i64()
+            })
+        }).unwrap();
+        let value_set = value.iter().collect::<HashSet<_>>();
+        let evalue_set = evalue.iter().collect::<HashSet<_>>();
+        assert_eq!(value_set, evalue_set);
+    }
+}
+
+#[test]
+fn test_ber_read_set_of_err() {
+    let tests : &[&[u8]] = &[
+        &[], &[49], &[0, 0], &[0, 1, 0],
+        &[17, 14, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
+        &[113, 14, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
+        &[48, 14, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
+        &[49, 15, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
+        &[49, 15, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127, 0],
+        &[49, 13, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
+        &[49, 13, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255],
+    ];
+    for &data in tests {
+        parse_ber(data, |reader| {
+            reader.read_set(|reader| {
                 let a = reader.next(&[Tag::context(28)])?
                     .read_tagged_implicit(Tag::context(28), |reader| {
                     reader.read_i64()
@@ -1034,633 +2331,1821 @@ fn test_der_read_set_ok() {
                 })?;
                 return Ok((a, b, c, d));
             })
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_der_read_set_of_order() {
+    let tests : &[(&[u8], bool)] = &[
+        (&[49, 6, 2, 1, 10, 2, 1, 10], true),
+        (&[49, 7, 2, 1, 10, 2, 2, 0, 128], true),
+        (&[49, 7, 2, 2, 0, 128, 2, 1, 10], false),
+        (&[49, 6, 2, 1, 11, 2, 1, 10], false),
+        (&[49, 9, 2, 1, 1, 2, 1, 3, 2, 1, 2], false),
+        (&[49, 5, 1, 1, 0, 5, 0], true),
+        (&[49, 5, 5, 0, 1, 1, 0], false),
+    ];
+    for &(data, sorted) in tests {
+        let result = parse_der(data, |reader| {
+            reader.collect_set_of(|reader| reader.read_tagged_der())
+        });
+        assert_eq!(result.is_ok(), sorted);
+        // BER doesn't require the order.
+        parse_ber(data, |reader| {
+            reader.collect_set_of(|reader| reader.read_tagged_der())
+        }).unwrap();
+    }
+}
+
+#[test]
+fn test_der_read_tagged_ok() {
+    let tests : &[(i64, &[u8])] = &[
+        (10, &[163, 3, 2, 1, 10]),
+        (266, &[163, 4, 2, 2, 1, 10]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_tagged(Tag::context(3), |reader| {
+                reader.read_i64()
+            })
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+
+    let tests : &[((i64, bool), &[u8])] = &[
+        ((10, false), &[163, 8, 48, 6, 2, 1, 10, 1, 1, 0]),
+        ((266, false), &[163, 9, 48, 7, 2, 2, 1, 10, 1, 1, 0]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_tagged(Tag::context(3), |reader| {
+                reader.read_sequence(|reader| {
+                    let i = reader.next().read_i64()?;
+                    let b = reader.next().read_bool()?;
+                    return Ok((i, b));
+                })
+            })
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+}
+
+#[test]
+fn test_der_read_tagged_err() {
+    let tests : &[&[u8]] = &[
+        &[], &[163], &[0, 0], &[0, 1, 0], &[160, 3, 2, 1, 10],
+        &[35, 3, 2, 1, 10], &[131, 3, 2, 1, 10],
+        &[131, 1, 10],
+        &[163, 128, 2, 1, 10, 0, 0],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| {
+            reader.read_tagged(Tag::context(3), |reader| {
+                reader.read_sequence(|reader| {
+                    let i = reader.next().read_i64()?;
+                    let b = reader.next().read_bool()?;
+                    return Ok((i, b));
+                })
+            })
+        }).unwrap_err();
+    }
+
+    let tests : &[&[u8]] = &[
+        &[], &[163], &[0, 0], &[0, 1, 0],
+        &[160, 8, 48, 6, 2, 1, 10, 1, 1, 0],
+        &[35, 8, 48, 6, 2, 1, 10, 1, 1, 0],
+        &[131, 8, 48, 6, 2, 1, 10, 1, 1, 0],
+        &[163, 8, 48, 6, 2, 1, 10, 1, 1, 0, 0],
+        &[163, 8, 48, 6, 2, 1, 10, 1, 1],
+        &[163, 9, 48, 7, 2, 2, 1, 10, 1, 1, 0, 0],
+        &[163, 9, 48, 7, 2, 2, 1, 10, 1, 1],
+        &[163, 6, 2, 1, 10, 1, 1, 0],
+        &[163, 7, 2, 2, 1, 10, 1, 1, 0],
+        &[163, 128, 48, 6, 2, 1, 10, 1, 1, 0, 0, 0, 0],
+        &[163, 128, 48, 6, 2, 1, 10, 1, 1, 0, 0, 0],
+        &[163, 128, 48, 6, 2, 1, 10, 1, 1, 0, 0],
+        &[163, 128, 48, 7, 2, 2, 1, 10, 1, 1, 0, 0, 0, 0],
+        &[163, 128, 48, 7, 2, 2, 1, 10, 1, 1, 0, 0, 0],
+        &[163, 128, 48, 7, 2, 2, 1, 10, 1, 1, 0, 0],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| {
+            reader.read_tagged(Tag::context(3), |reader| {
+                reader.read_i64()
+            })
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_ber_read_tagged_ok() {
+    let tests : &[(i64, &[u8])] = &[
+        (10, &[163, 3, 2, 1, 10]),
+        (266, &[163, 4, 2, 2, 1, 10]),
+        (10, &[163, 128, 2, 1, 10, 0, 0]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_ber(data, |reader| {
+            reader.read_tagged(Tag::context(3), |reader| {
+                reader.read_i64()
+            })
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+
+    let tests : &[((i64, bool), &[u8])] = &[
+        ((10, false), &[163, 8, 48, 6, 2, 1, 10, 1, 1, 0]),
+        ((266, false), &[163, 9, 48, 7, 2, 2, 1, 10, 1, 1, 0]),
+        ((10, false), &[163, 128, 48, 6, 2, 1, 10, 1, 1, 0, 0, 0]),
+        ((266, false), &[163, 128, 48, 7, 2, 2, 1, 10, 1, 1, 0, 0, 0]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_ber(data, |reader| {
+            reader.read_tagged(Tag::context(3), |reader| {
+                reader.read_sequence(|reader| {
+                    let i = reader.next().read_i64()?;
+                    let b = reader.next().read_bool()?;
+                    return Ok((i, b));
+                })
+            })
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+}
+
+#[test]
+fn test_ber_read_tagged_err() {
+    let tests : &[&[u8]] = &[
+        &[], &[163], &[0, 0], &[0, 1, 0], &[160, 3, 2, 1, 10],
+        &[35, 3, 2, 1, 10], &[131, 3, 2, 1, 10],
+        &[131, 1, 10],
+    ];
+    for &data in tests {
+        parse_ber(data, |reader| {
+            reader.read_tagged(Tag::context(3), |reader| {
+                reader.read_sequence(|reader| {
+                    let i = reader.next().read_i64()?;
+                    let b = reader.next().read_bool()?;
+                    return Ok((i, b));
+                })
+            })
+        }).unwrap_err();
+    }
+
+    let tests : &[&[u8]] = &[
+        &[], &[163], &[0, 0], &[0, 1, 0],
+        &[160, 8, 48, 6, 2, 1, 10, 1, 1, 0],
+        &[35, 8, 48, 6, 2, 1, 10, 1, 1, 0],
+        &[131, 8, 48, 6, 2, 1, 10, 1, 1, 0],
+        &[163, 8, 48, 6, 2, 1, 10, 1, 1, 0, 0],
+        &[163, 8, 48, 6, 2, 1, 10, 1, 1],
+        &[163, 9, 48, 7, 2, 2, 1, 10, 1, 1, 0, 0],
+        &[163, 9, 48, 7, 2, 2, 1, 10, 1, 1],
+        &[163, 6, 2, 1, 10, 1, 1, 0],
+        &[163, 7, 2, 2, 1, 10, 1, 1, 0],
+        &[163, 128, 48, 6, 2, 1, 10, 1, 1, 0, 0, 0, 0],
+        &[163, 128, 48, 6, 2, 1, 10, 1, 1, 0, 0],
+        &[163, 128, 48, 7, 2, 2, 1, 10, 1, 1, 0, 0, 0, 0],
+        &[163, 128, 48, 7, 2, 2, 1, 10, 1, 1, 0, 0],
+    ];
+    for &data in tests {
+        parse_ber(data, |reader| {
+            reader.read_tagged(Tag::context(3), |reader| {
+                reader.read_i64()
+            })
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_der_read_tagged_implicit_ok() {
+    let tests : &[(i64, &[u8])] = &[
+        (10, &[131, 1, 10]),
+        (266, &[131, 2, 1, 10]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_tagged_implicit(Tag::context(3), |reader| {
+                reader.read_i64()
+            })
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+
+    let tests : &[((i64, bool), &[u8])] = &[
+        ((10, false), &[163, 6, 2, 1, 10, 1, 1, 0]),
+        ((266, false), &[163, 7, 2, 2, 1, 10, 1, 1, 0]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_tagged_implicit(Tag::context(3), |reader| {
+                reader.read_sequence(|reader| {
+                    let i = reader.next().read_i64()?;
+                    let b = reader.next().read_bool()?;
+                    return Ok((i, b));
+                })
+            })
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+}
+
+#[test]
+fn test_der_read_tagged_implicit_err() {
+    let tests : &[&[u8]] = &[
+        &[], &[131], &[0, 0], &[0, 1, 0], &[128, 3, 2, 1, 10],
+        &[3, 3, 2, 1, 10], &[163, 3, 2, 1, 10],
+        &[163, 3, 2, 1, 10],
+        &[131, 128, 1, 10, 0, 0],
+        &[163, 128, 2, 1, 10, 0, 0],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| {
+            reader.read_tagged(Tag::context(3), |reader| {
+                reader.read_sequence(|reader| {
+                    let i = reader.next().read_i64()?;
+                    let b = reader.next().read_bool()?;
+                    return Ok((i, b));
+                })
+            })
+        }).unwrap_err();
+    }
+
+    let tests : &[&[u8]] = &[
+        &[], &[163], &[0, 0], &[0, 1, 0],
+        &[160, 6, 2, 1, 10, 1, 1, 0],
+        &[35, 6, 2, 1, 10, 1, 1, 0],
+        &[131, 6, 2, 1, 10, 1, 1, 0],
+        &[163, 6, 2, 1, 10, 1, 1, 0, 0],
+        &[163, 6, 2, 1, 10, 1, 1],
+        &[163, 7, 2, 2, 1, 10, 1, 1, 0, 0],
+        &[163, 7, 2, 2, 1, 10, 1, 1],
+        &[163, 8, 48, 6, 2, 1, 10, 1, 1, 0],
+        &[163, 9, 48, 7, 2, 2, 1, 10, 1, 1, 0],
+        &[163, 128, 2, 1, 10, 1, 1, 0, 0, 0, 0],
+        &[163, 128, 2, 1, 10, 1, 1, 0, 0, 0],
+        &[163, 128, 2, 1, 10, 1, 1, 0, 0],
+        &[163, 128, 2, 2, 1, 10, 1, 1, 0, 0, 0, 0],
+        &[163, 128, 2, 2, 1, 10, 1, 1, 0, 0, 0],
+        &[163, 128, 2, 2, 1, 10, 1, 1, 0, 0],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| {
+            reader.read_tagged(Tag::context(3), |reader| {
+                reader.read_i64()
+            })
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_ber_read_tagged_implicit_ok() {
+    let tests : &[(i64, &[u8])] = &[
+        (10, &[131, 1, 10]),
+        (266, &[131, 2, 1, 10]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_ber(data, |reader| {
+            reader.read_tagged_implicit(Tag::context(3), |reader| {
+                reader.read_i64()
+            })
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+
+    let tests : &[((i64, bool), &[u8])] = &[
+        ((10, false), &[163, 6, 2, 1, 10, 1, 1, 0]),
+        ((266, false), &[163, 7, 2, 2, 1, 10, 1, 1, 0]),
+        ((10, false), &[163, 128, 2, 1, 10, 1, 1, 0, 0, 0]),
+        ((266, false), &[163, 128, 2, 2, 1, 10, 1, 1, 0, 0, 0]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_ber(data, |reader| {
+            reader.read_tagged_implicit(Tag::context(3), |reader| {
+                reader.read_sequence(|reader| {
+                    let i = reader.next().read_i64()?;
+                    let b = reader.next().read_bool()?;
+                    return Ok((i, b));
+                })
+            })
+        }).unwrap();
+        assert_eq!(value, evalue);
+    }
+}
+
+#[test]
+fn test_ber_read_tagged_implicit_err() {
+    let tests : &[&[u8]] = &[
+        &[], &[131], &[0, 0], &[0, 1, 0], &[128, 3, 2, 1, 10],
+        &[3, 3, 2, 1, 10], &[163, 3, 2, 1, 10],
+        &[163, 3, 2, 1, 10],
+        &[131, 128, 1, 10, 0, 0],
+        &[163, 128, 2, 1, 10, 0, 0],
+    ];
+    for &data in tests {
+        parse_ber(data, |reader| {
+            reader.read_tagged(Tag::context(3), |reader| {
+                reader.read_sequence(|reader| {
+                    let i = reader.next().read_i64()?;
+                    let b = reader.next().read_bool()?;
+                    return Ok((i, b));
+                })
+            })
+        }).unwrap_err();
+    }
+
+    let tests : &[&[u8]] = &[
+        &[], &[163], &[0, 0], &[0, 1, 0],
+        &[160, 6, 2, 1, 10, 1, 1, 0],
+        &[35, 6, 2, 1, 10, 1, 1, 0],
+        &[131, 6, 2, 1, 10, 1, 1, 0],
+        &[163, 6, 2, 1, 10, 1, 1, 0, 0],
+        &[163, 6, 2, 1, 10, 1, 1],
+        &[163, 7, 2, 2, 1, 10, 1, 1, 0, 0],
+        &[163, 7, 2, 2, 1, 10, 1, 1],
+        &[163, 8, 48, 6, 2, 1, 10, 1, 1, 0],
+        &[163, 9, 48, 7, 2, 2, 1, 10, 1, 1, 0],
+        &[163, 128, 2, 1, 10, 1, 1, 0, 0, 0, 0],
+        &[163, 128, 2, 1, 10, 1, 1, 0, 0],
+        &[163, 128, 2, 2, 1, 10, 1, 1, 0, 0, 0, 0],
+        &[163, 128, 2, 2, 1, 10, 1, 1, 0, 0],
+    ];
+    for &data in tests {
+        parse_ber(data, |reader| {
+            reader.read_tagged(Tag::context(3), |reader| {
+                reader.read_i64()
+            })
+        }).unwrap_err();
+    }
+}
+
+#[test]
+fn test_ber_read_overflow() {
+    let data = [33, 136, 255, 255, 255, 255, 255, 255, 255, 255];
+
+    let r: Result<bool, _> = parse_ber(&data, |reader| {
+        reader.read_bool()
+    });
+
+    let err = r.unwrap_err();
+
+    assert_eq!(err.kind(), ASN1ErrorKind::IntegerOverflow);
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn test_der_read_uuid_forms() {
+    use uuid::Uuid;
+    let tests : &[(u128, &[u8], &[u8])] = &[
+        (0, &[2, 1, 0], &[13, 1, 0]),
+        (1, &[2, 1, 1], &[13, 1, 1]),
+        (128, &[2, 2, 0, 128], &[13, 2, 129, 0]),
+        (u128::max_value(),
+            &[2, 17, 0, 255, 255, 255, 255, 255, 255, 255, 255,
+                255, 255, 255, 255, 255, 255, 255, 255],
+            &[13, 19, 131, 255, 255, 255, 255, 255, 255, 255, 255,
+                255, 255, 255, 255, 255, 255, 255, 255, 255, 127]),
+    ];
+    for &(evalue, integer, relative_oid) in tests {
+        let value = parse_der(integer, |reader| {
+            reader.read_uuid_integer()
         }).unwrap();
-        assert_eq!(&value, evalue);
+        assert_eq!(value, Uuid::from_u128(evalue));
+        let value = parse_der(relative_oid, |reader| {
+            reader.read_uuid_relative_oid()
+        }).unwrap();
+        assert_eq!(value, Uuid::from_u128(evalue));
+    }
+
+    let tests : &[&[u8]] = &[
+        &[2, 1, 255], &[2, 2, 0, 1],
+        &[2, 17, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| {
+            reader.read_uuid_integer()
+        }).unwrap_err();
+    }
+
+    let tests : &[&[u8]] = &[
+        &[13, 0], &[13, 1, 128], &[13, 2, 128, 1], &[13, 2, 1, 1],
+        &[45, 3, 13, 1, 1],
+        &[13, 19, 132, 128, 128, 128, 128, 128, 128, 128, 128,
+            128, 128, 128, 128, 128, 128, 128, 128, 128, 0],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| {
+            reader.read_uuid_relative_oid()
+        }).unwrap_err();
     }
+
+    parse_der(&[4, 15, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0], |reader| {
+        reader.read_uuid()
+    }).unwrap_err();
 }
 
 #[test]
-fn test_der_read_set_err() {
+fn test_der_read_oid_ok() {
+    let tests : &[(&[u64], &[u8])] = &[
+        (&[1, 2, 840, 113549], &[6, 6, 42, 134, 72, 134, 247, 13]),
+        (&[2, 999, 16384], &[6, 5, 136, 55, 129, 128, 0]),
+    ];
+    for &(evalue, data) in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_oid()
+        }).unwrap();
+        assert_eq!(value.components(), evalue);
+    }
+}
+
+#[test]
+fn test_der_read_oid_err() {
     let tests : &[&[u8]] = &[
-        &[], &[49], &[0, 0], &[0, 1, 0],
-        &[17, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
-        &[113, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
-        &[48, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
-        &[49, 33, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
-        &[49, 33, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111, 0],
-        &[49, 31, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
-        &[49, 31, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111],
-        &[49, 22, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114],
-        &[49, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111,
-        191, 149, 140, 79, 5, 4, 3, 70, 111, 111],
-        &[49, 128, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-            77, 5, 4, 3, 66, 97, 114, 191, 149,
-            140, 78, 5, 4, 3, 70, 111, 111, 0, 0],
-        &[49, 32, 156, 3, 6, 248, 85, 187, 5, 2, 3, 6, 248, 86, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
-        &[49, 32, 187, 5, 2, 3, 6, 248, 86, 191, 149, 140, 77, 5, 4, 3, 66, 97,
-        114, 156, 3, 6, 248, 85, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
-        &[49, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        78, 5, 4, 3, 70, 111, 111, 191, 149, 140, 77, 5, 4, 3, 66, 97, 114],
+        &[6, 0], &[6, 1, 128], &[6, 2, 128, 1], &[6, 2, 42, 129],
     ];
     for &data in tests {
         parse_der(data, |reader| {
-            reader.read_set(|reader| {
-                let a = reader.next(&[Tag::context(28)])?
-                    .read_tagged_implicit(Tag::context(28), |reader| {
-                    reader.read_i64()
-                })?;
-                let b = reader.next(&[Tag::context(345678)])?
-                    .read_tagged(Tag::context(345678), |reader| {
-                    reader.read_bytes()
-                })?;
-                let c = reader.next(&[Tag::context(27)])?
-                    .read_tagged(Tag::context(27), |reader| {
-                    reader.read_i64()
-                })?;
-                let d = reader.next(&[Tag::context(345677)])?
-                    .read_tagged(Tag::context(345677), |reader| {
-                    reader.read_bytes()
-                })?;
-                return Ok((a, b, c, d));
-            })
+            reader.read_oid()
         }).unwrap_err();
     }
 }
 
 #[test]
-fn test_ber_read_set_ok() {
-    let tests : &[((i64, Vec<u8>, i64, Vec<u8>), &[u8])] = &[
-        ((456789, b"Foo".to_vec(), 456790, b"Bar".to_vec()), &[
-        49, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111]),
-        ((456789, b"Foo".to_vec(), 456790, b"Bar".to_vec()), &[
-        49, 128, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-            77, 5, 4, 3, 66, 97, 114, 191, 149,
-            140, 78, 5, 4, 3, 70, 111, 111, 0, 0]),
-        ((456789, b"Foo".to_vec(), 456790, b"Bar".to_vec()), &[
-        49, 32, 156, 3, 6, 248, 85, 187, 5, 2, 3, 6, 248, 86, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111]),
-        ((456789, b"Foo".to_vec(), 456790, b"Bar".to_vec()), &[
-        49, 32, 187, 5, 2, 3, 6, 248, 86, 191, 149, 140, 77, 5, 4, 3, 66, 97,
-        114, 156, 3, 6, 248, 85, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111]),
-        ((456789, b"Foo".to_vec(), 456790, b"Bar".to_vec()), &[
-        49, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        78, 5, 4, 3, 70, 111, 111, 191, 149, 140, 77, 5, 4, 3, 66, 97, 114]),
+fn test_der_read_relative_oid_ok() {
+    let tests : &[(&[u64], &[u8])] = &[
+        (&[0], &[13, 1, 0]),
+        (&[1, 840, 5], &[13, 4, 1, 134, 72, 5]),
+        (&[113549, 1, 1], &[13, 5, 134, 247, 13, 1, 1]),
     ];
-    for &(ref evalue, data) in tests {
-        let value = parse_ber(data, |reader| {
-            reader.read_set(|reader| {
-                let a = reader.next(&[Tag::context(28)])?
-                    .read_tagged_implicit(Tag::context(28), |reader| {
-                    reader.read_i64()
-                })?;
-                let b = reader.next(&[Tag::context(345678)])?
-                    .read_tagged(Tag::context(345678), |reader| {
-                    reader.read_bytes()
-                })?;
-                let c = reader.next(&[Tag::context(27)])?
-                    .read_tagged(Tag::context(27), |reader| {
-                    reader.read_i64()
-                })?;
-                let d = reader.next(&[Tag::context(345677)])?
-                    .read_tagged(Tag::context(345677), |reader| {
-                    reader.read_bytes()
-                })?;
-                return Ok((a, b, c, d));
-            })
+    for &(evalue, data) in tests {
+        let value = parse_der(data, |reader| {
+            reader.read_relative_oid()
         }).unwrap();
-        assert_eq!(&value, evalue);
+        assert_eq!(value.components(), evalue);
     }
 }
 
 #[test]
-fn test_ber_read_set_err() {
+fn test_der_read_relative_oid_err() {
     let tests : &[&[u8]] = &[
-        &[], &[49], &[0, 0], &[0, 1, 0],
-        &[17, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
-        &[113, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
-        &[48, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
-        &[49, 33, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
-        &[49, 33, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111, 0],
-        &[49, 31, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111],
-        &[49, 31, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111],
-        &[49, 22, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114],
-        &[49, 32, 187, 5, 2, 3, 6, 248, 86, 156, 3, 6, 248, 85, 191, 149, 140,
-        77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111,
-        191, 149, 140, 79, 5, 4, 3, 70, 111, 111],
+        &[13, 0], &[13, 1, 128], &[13, 2, 128, 1], &[13, 2, 42, 129],
+        &[6, 1, 42], &[45, 3, 4, 1, 1],
     ];
     for &data in tests {
-        parse_ber(data, |reader| {
-            reader.read_set(|reader| {
-                let a = reader.next(&[Tag::context(28)])?
-                    .read_tagged_implicit(Tag::context(28), |reader| {
-                    reader.read_i64()
-                })?;
-                let b = reader.next(&[Tag::context(345678)])?
-                    .read_tagged(Tag::context(345678), |reader| {
-                    reader.read_bytes()
-                })?;
-                let c = reader.next(&[Tag::context(27)])?
-                    .read_tagged(Tag::context(27), |reader| {
-                    reader.read_i64()
-                })?;
-                let d = reader.next(&[Tag::context(345677)])?
-                    .read_tagged(Tag::context(345677), |reader| {
-                    reader.read_bytes()
-                })?;
-                return Ok((a, b, c, d));
-            })
+        parse_der(data, |reader| {
+            reader.read_relative_oid()
         }).unwrap_err();
     }
 }
 
 #[test]
-fn test_der_read_set_of_ok() {
-    use std::collections::HashSet;
-    let tests : &[(&[i64], &[u8])] = &[
-        (&[-129, -128, 127, 128], &[
-            49, 14, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127]),
-        (&[-128, 127, 128], &[
-            49, 10, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128]),
-        (&[-129, -128, 127, 128, 32768], &[
-            49, 19, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127,
-            2, 3, 0, 128, 0]),
+fn test_der_read_oid_iri() {
+    let value = parse_der(&[31, 35, 4, 47, 73, 83, 79], |reader| {
+        reader.read_oid_iri()
+    }).unwrap();
+    assert_eq!(value.as_str(), "/ISO");
+    let value = parse_der(&[31, 36, 3, 49, 47, 50], |reader| {
+        reader.read_relative_oid_iri()
+    }).unwrap();
+    assert_eq!(value.as_str(), "1/2");
+
+    let tests : &[&[u8]] = &[
+        &[31, 35, 0], &[31, 35, 3, 73, 83, 79], &[31, 35, 2, 47, 255],
+        &[31, 35, 2, 47, 47], &[31, 36, 1, 47], &[12, 4, 47, 73, 83, 79],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| reader.read_oid_iri()).unwrap_err();
+        parse_der(data, |reader| reader.read_relative_oid_iri()).unwrap_err();
+    }
+}
+
+#[test]
+fn test_der_read_date_and_time() {
+    use crate::models::{Date, TimeOfDay, DateTime, Duration};
+    let value = parse_der(b"\x1f\x1f\x0800010203", |reader| {
+        reader.read_date()
+    }).unwrap();
+    assert_eq!(value, Date::new(1, 2, 3).unwrap());
+    let value = parse_der(b"\x1f\x20\x06000000", |reader| {
+        reader.read_time_of_day()
+    }).unwrap();
+    assert_eq!(value, TimeOfDay::new(0, 0, 0).unwrap());
+    let value = parse_der(b"\x1f\x21\x0e20000229235959", |reader| {
+        reader.read_date_time()
+    }).unwrap();
+    assert_eq!(value, DateTime::new(Date::new(2000, 2, 29).unwrap(),
+        TimeOfDay::new(23, 59, 59).unwrap()));
+    let value = parse_der(b"\x1f\x22\x04PT5M", |reader| {
+        reader.read_duration()
+    }).unwrap();
+    assert_eq!(value, Duration::new(0, 0, 0, 0, 5, 0));
+
+    parse_der(b"\x1f\x1f\x0800010230", |reader| reader.read_date())
+        .unwrap_err();
+    parse_der(b"\x1f\x20\x07000000Z", |reader| reader.read_time_of_day())
+        .unwrap_err();
+    parse_der(b"\x1f\x21\x0820000229", |reader| reader.read_date_time())
+        .unwrap_err();
+    parse_der(b"\x1f\x22\x02P5", |reader| reader.read_duration())
+        .unwrap_err();
+    parse_der(b"\x1f\x20\x0800010203", |reader| reader.read_date())
+        .unwrap_err();
+}
+
+#[test]
+fn test_der_read_external() {
+    use alloc::vec;
+    use crate::models::{ExternalEncoding, ObjectIdentifier, TaggedDerValue};
+    use super::super::tags::TAG_INTEGER;
+    let data = &[40, 14, 2, 1, 3, 7, 3, 97, 98, 99, 160, 4, 2, 2, 1, 0];
+    let value = parse_der(data, |reader| reader.read_external()).unwrap();
+    assert_eq!(value.direct_reference, None);
+    assert_eq!(value.indirect_reference, Some(3));
+    assert_eq!(value.data_value_descriptor, Some("abc".to_owned()));
+    assert_eq!(value.encoding, ExternalEncoding::SingleAsn1Type(
+        TaggedDerValue::from_tag_and_bytes(TAG_INTEGER, vec![1, 0])));
+
+    let data = &[40, 9, 6, 3, 85, 4, 3, 130, 2, 6, 64];
+    let value = parse_der(data, |reader| reader.read_external()).unwrap();
+    assert_eq!(value.direct_reference,
+        Some(ObjectIdentifier::from_slice(&[2, 5, 4, 3])));
+    assert_eq!(value.encoding, ExternalEncoding::Arbitrary(vec![64], 2));
+
+    let tests : &[&[u8]] = &[
+        &[40, 0], &[40, 3, 2, 1, 3], &[40, 4, 131, 2, 1, 2],
+        &[40, 3, 129, 1, 1, 0], &[40, 6, 7, 1, 255, 129, 1, 1],
+        &[48, 3, 129, 1, 1], &[40, 5, 129, 1, 1, 5, 0],
+    ];
+    for &data in tests {
+        parse_der(data, |reader| reader.read_external()).unwrap_err();
+    }
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn test_der_read_utctime() {
+    let tests : &[(&[u8], i64)] = &[
+        (b"\x17\x0d820102120000Z", 378820800),
+        (b"\x17\x0d491231235959Z", 2524607999),
+        (b"\x17\x0d500101000000Z", -631152000),
+        (b"\x17\x0d000229123456Z", 951827696),
+    ];
+    for &(data, evalue) in tests {
+        let value = parse_der(data, |reader| reader.read_utctime()).unwrap();
+        assert_eq!(value.datetime().unix_timestamp(), evalue);
+        let value = parse_ber(data, |reader| reader.read_utctime()).unwrap();
+        assert_eq!(value.datetime().unix_timestamp(), evalue);
+    }
+
+    // Valid in BER only
+    let tests : &[(&[u8], i64)] = &[
+        (b"\x17\x0b8201021200Z", 378820800),
+        (b"\x17\x0f8201020700-0500", 378820800),
+        (b"\x17\x11820102070000-0500", 378820800),
+    ];
+    for &(data, evalue) in tests {
+        parse_der(data, |reader| reader.read_utctime()).unwrap_err();
+        let value = parse_ber(data, |reader| reader.read_utctime()).unwrap();
+        assert_eq!(value.datetime().unix_timestamp(), evalue);
+    }
+
+    let tests : &[&[u8]] = &[
+        b"\x17\x00", b"\x17\x0c820102120000", b"\x17\x0d820102120060Z",
+        b"\x17\x0d821302120000Z", b"\x17\x0d010229120000Z",
+        b"\x17\x0d82010212000aZ", b"\x17\x0e8201021200001Z",
+        b"\x18\x0d820102120000Z",
+    ];
+    for &data in tests {
+        parse_ber(data, |reader| reader.read_utctime()).unwrap_err();
+    }
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn test_der_read_generalized_time() {
+    let tests : &[(&[u8], i64, u32)] = &[
+        (b"\x18\x0f19851106210627Z", 500159187, 0),
+        (b"\x18\x1119851106210627.3Z", 500159187, 300_000_000),
+        (b"\x18\x0f20500101000000Z", 2524608000, 0),
+        (b"\x18\x1900000101000000.000000001Z", -62167219200, 1),
+    ];
+    for &(data, evalue, enano) in tests {
+        for &mode in &[BERMode::Der, BERMode::Ber] {
+            let value = parse_ber_general(data, mode, |reader| {
+                reader.read_generalized_time()
+            }).unwrap();
+            assert_eq!(value.datetime().unix_timestamp(), evalue);
+            assert_eq!(value.datetime().nanosecond(), enano);
+        }
+    }
+
+    // Valid in BER only
+    let tests : &[(&[u8], i64, u32)] = &[
+        (b"\x18\x0b1985110621Z", 500158800, 0),
+        (b"\x18\x0d198511062106Z", 500159160, 0),
+        (b"\x18\x0d1985110621.5Z", 500160600, 0),
+        (b"\x18\x1219851106210627.30Z", 500159187, 300_000_000),
+        (b"\x18\x1119851106210627,3Z", 500159187, 300_000_000),
+        (b"\x18\x1319851106160627-0500", 500159187, 0),
+    ];
+    for &(data, evalue, enano) in tests {
+        parse_der(data, |reader| reader.read_generalized_time())
+            .unwrap_err();
+        let value = parse_ber(data, |reader| {
+            reader.read_generalized_time()
+        }).unwrap();
+        assert_eq!(value.datetime().unix_timestamp(), evalue);
+        assert_eq!(value.datetime().nanosecond(), enano);
+    }
+
+    let tests : &[&[u8]] = &[
+        b"\x18\x00", b"\x18\x0e19851106210627", b"\x18\x0f19851306210627Z",
+        b"\x18\x0f19851106250627Z", b"\x18\x1019851106210627.Z",
+        b"\x18\x1119851106210627+25", b"\x17\x0f19851106210627Z",
+    ];
+    for &data in tests {
+        parse_ber(data, |reader| reader.read_generalized_time()).unwrap_err();
+    }
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn test_der_read_time_as() {
+    use time::OffsetDateTime;
+    let tests : &[&[u8]] = &[
+        b"\x17\x0d820102120000Z", b"\x17\x0d491231235959Z",
+        b"\x17\x0d000229123456Z",
     ];
-    for &(evalue, data) in tests {
+    for &data in tests {
+        let datetime = parse_der(data, |reader| reader.read_utctime()).unwrap();
         let value = parse_der(data, |reader| {
-            reader.collect_set_of(|reader| {
-                reader.read_i64()
-            })
+            reader.read_utctime_as::<OffsetDateTime>()
         }).unwrap();
-        let value_set = value.iter().collect::<HashSet<_>>();
-        let evalue_set = evalue.iter().collect::<HashSet<_>>();
-        assert_eq!(value_set, evalue_set);
+        assert_eq!(&value, datetime.datetime());
     }
-}
 
-#[test]
-fn test_der_read_set_of_err() {
     let tests : &[&[u8]] = &[
-        &[], &[49], &[0, 0], &[0, 1, 0],
-        &[17, 14, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
-        &[113, 14, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
-        &[48, 14, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
-        &[49, 15, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
-        &[49, 15, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127, 0],
-        &[49, 13, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
-        &[49, 13, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255],
-        &[49, 128, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127, 0, 0],
-        &[49, 14, 2, 1, 128, 2, 1, 127, 2, 2, 0, 128, 2, 2, 255, 127],
-        &[49, 14, 2, 1, 127, 2, 2, 0, 128, 2, 1, 128, 2, 2, 255, 127],
-        &[49, 14, 2, 1, 127, 2, 1, 128, 2, 2, 255, 127, 2, 2, 0, 128],
-        &[49, 14, 2, 2, 255, 127, 2, 1, 128, 2, 2, 0, 128, 2, 1, 127],
+        b"\x18\x0f19851106210627Z", b"\x18\x1119851106210627.3Z",
+        b"\x18\x1900000101000000.000000001Z",
+        b"\x18\x1a19851106210627.3000000001Z",
     ];
     for &data in tests {
-        parse_der(data, |reader| {
-            reader.read_set(|reader| {
-                let a = reader.next(&[Tag::context(28)])?
-                    .read_tagged_implicit(Tag::context(28), |reader| {
-                    reader.read_i64()
-                })?;
-                let b = reader.next(&[Tag::context(345678)])?
-                    .read_tagged(Tag::context(345678), |reader| {
-                    reader.read_bytes()
-                })?;
-                let c = reader.next(&[Tag::context(27)])?
-                    .read_tagged(Tag::context(27), |reader| {
-                    reader.read_i64()
-                })?;
-                let d = reader.next(&[Tag::context(345677)])?
-                    .read_tagged(Tag::context(345677), |reader| {
-                    reader.read_bytes()
-                })?;
-                return Ok((a, b, c, d));
-            })
-        }).unwrap_err();
+        let datetime = parse_der(data, |reader| {
+            reader.read_generalized_time()
+        }).unwrap();
+        let value = parse_der(data, |reader| {
+            reader.read_generalized_time_as::<OffsetDateTime>()
+        }).unwrap();
+        assert_eq!(&value, datetime.datetime());
+    }
+
+    let tests : &[&[u8]] = &[
+        b"\x17\x0b8201021200Z", b"\x18\x0f19851106210660Z",
+        b"\x18\x1219851106210627.30Z", b"\x18\x1119851106210627,3Z",
+    ];
+    for &data in tests {
+        if data[0] == 23 {
+            parse_der(data, |reader| {
+                reader.read_utctime_as::<OffsetDateTime>()
+            }).unwrap_err();
+        } else {
+            parse_der(data, |reader| {
+                reader.read_generalized_time_as::<OffsetDateTime>()
+            }).unwrap_err();
+        }
     }
 }
 
+#[cfg(feature = "chrono")]
 #[test]
-fn test_ber_read_set_of_ok() {
-    use std::collections::HashSet;
-    let tests : &[(&[i64], &[u8])] = &[
-        (&[-129, -128, 127, 128], &[
-            49, 14, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127]),
-        (&[-128, 127, 128], &[
-            49, 10, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128]),
-        (&[-129, -128, 127, 128, 32768], &[
-            49, 19, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127,
-            2, 3, 0, 128, 0]),
-        (&[-129, -128, 127, 128], &[
-            49, 128, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127,
-            0, 0]),
-        (&[-129, -128, 127, 128], &[
-            49, 14, 2, 1, 128, 2, 1, 127, 2, 2, 0, 128, 2, 2, 255, 127]),
-        (&[-129, -128, 127, 128], &[
-            49, 14, 2, 1, 127, 2, 2, 0, 128, 2, 1, 128, 2, 2, 255, 127]),
-        (&[-129, -128, 127, 128], &[
-            49, 14, 2, 1, 127, 2, 1, 128, 2, 2, 255, 127, 2, 2, 0, 128]),
-        (&[-129, -128, 127, 128], &[
-            49, 14, 2, 2, 255, 127, 2, 1, 128, 2, 2, 0, 128, 2, 1, 127]),
+fn test_der_read_time_as_chrono() {
+    use chrono::{DateTime, TimeZone, Utc};
+    let value = parse_der(b"\x18\x1119851106210627.3Z", |reader| {
+        reader.read_generalized_time_as::<DateTime<Utc>>()
+    }).unwrap();
+    let expected = Utc.with_ymd_and_hms(1985, 11, 6, 21, 6, 27).unwrap()
+        + chrono::Duration::milliseconds(300);
+    assert_eq!(value, expected);
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn test_der_read_time_rfc5280() {
+    let options = ParseOptions::rfc5280();
+    let tests : &[(&[u8], bool)] = &[
+        (b"\x17\x0d820102120000Z", true),
+        (b"\x18\x0f19851106210627Z", false),
+        (b"\x18\x0f20500101000000Z", true),
+        (b"\x18\x1120500101000000.3Z", false),
     ];
-    for &(evalue, data) in tests {
-        let value = parse_ber(data, |reader| {
-            reader.collect_set_of(|reader| {
-                reader.read_i64()
-            })
-        }).unwrap();
-        let value_set = value.iter().collect::<HashSet<_>>();
-        let evalue_set = evalue.iter().collect::<HashSet<_>>();
-        assert_eq!(value_set, evalue_set);
+    for &(data, ok) in tests {
+        let result = parse_with_options(data, options, |reader| {
+            if data[0] == 23 {
+                reader.read_utctime().map(|_| ())
+            } else {
+                reader.read_generalized_time().map(|_| ())
+            }
+        });
+        assert_eq!(result.is_ok(), ok);
+        let result = parse_with_options(data, options, |reader| {
+            if data[0] == 23 {
+                reader.read_utctime_as::<time::OffsetDateTime>().map(|_| ())
+            } else {
+                reader.read_generalized_time_as::<time::OffsetDateTime>()
+                    .map(|_| ())
+            }
+        });
+        assert_eq!(result.is_ok(), ok);
     }
+
+    let options = ParseOptions::new(BERMode::Ber)
+        .with_profile(Profile::Rfc5280);
+    parse_ber(b"\x17\x0b8201021200Z", |reader| reader.read_utctime())
+        .unwrap();
+    parse_with_options(b"\x17\x0b8201021200Z", options, |reader| {
+        reader.read_utctime()
+    }).unwrap_err();
 }
 
 #[test]
-fn test_ber_read_set_of_err() {
+fn test_der_read_choice() {
+    use super::super::tags::{TAG_INTEGER, TAG_UTF8STRING};
+    let tags = &[TAG_INTEGER, TAG_UTF8STRING, Tag::context(3)];
+    let read = |reader: BERReader| {
+        reader.read_choice(tags, |index, reader| match index {
+            0 => reader.read_i64().map(|i| (0, i)),
+            1 => reader.read_utf8string().map(|s| (1, s.len() as i64)),
+            _ => reader.read_tagged(Tag::context(3), |reader| {
+                reader.read_i64()
+            }).map(|i| (2, i)),
+        })
+    };
+    let tests : &[(&[u8], (usize, i64))] = &[
+        (&[2, 1, 10], (0, 10)),
+        (&[12, 2, 97, 98], (1, 2)),
+        (&[163, 3, 2, 1, 255], (2, -1)),
+    ];
+    for &(data, evalue) in tests {
+        assert_eq!(parse_der(data, read).unwrap(), evalue);
+    }
+
     let tests : &[&[u8]] = &[
-        &[], &[49], &[0, 0], &[0, 1, 0],
-        &[17, 14, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
-        &[113, 14, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
-        &[48, 14, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
-        &[49, 15, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
-        &[49, 15, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127, 0],
-        &[49, 13, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255, 127],
-        &[49, 13, 2, 1, 127, 2, 1, 128, 2, 2, 0, 128, 2, 2, 255],
+        &[], &[1, 1, 0], &[131, 1, 0], &[162, 3, 2, 1, 10], &[2, 1],
+        &[12, 1, 255],
     ];
     for &data in tests {
-        parse_ber(data, |reader| {
-            reader.read_set(|reader| {
-                let a = reader.next(&[Tag::context(28)])?
-                    .read_tagged_implicit(Tag::context(28), |reader| {
-                    reader.read_i64()
-                })?;
-                let b = reader.next(&[Tag::context(345678)])?
-                    .read_tagged(Tag::context(345678), |reader| {
-                    reader.read_bytes()
-                })?;
-                let c = reader.next(&[Tag::context(27)])?
-                    .read_tagged(Tag::context(27), |reader| {
-                    reader.read_i64()
-                })?;
-                let d = reader.next(&[Tag::context(345677)])?
-                    .read_tagged(Tag::context(345677), |reader| {
-                    reader.read_bytes()
-                })?;
-                return Ok((a, b, c, d));
-            })
-        }).unwrap_err();
+        parse_der(data, read).unwrap_err();
     }
+    // A mismatch doesn't consume the value, for read_optional.
+    let value = parse_der(&[48, 3, 1, 1, 255], |reader| {
+        reader.read_sequence(|reader| {
+            let choice = reader.read_optional(read)?;
+            let b = reader.next().read_bool()?;
+            Ok((choice, b))
+        })
+    }).unwrap();
+    assert_eq!(value, (None, true));
 }
 
 #[test]
-fn test_der_read_tagged_ok() {
-    let tests : &[(i64, &[u8])] = &[
-        (10, &[163, 3, 2, 1, 10]),
-        (266, &[163, 4, 2, 2, 1, 10]),
+fn test_der_peek_tag() {
+    use super::super::tags::{TAG_BOOLEAN, TAG_EOC, TAG_OCTETSTRING};
+    let context0 = Tag::context(0);
+    let data = &[48, 8, 128, 1, 1, 160, 3, 1, 1, 255];
+    let value = parse_der(data, |reader| {
+        assert_eq!(reader.peek_tag()?, (TAG_SEQUENCE, PCBit::Constructed));
+        reader.read_sequence(|reader| {
+            assert_eq!(reader.peek_tag()?, (context0, PCBit::Primitive));
+            // Peeking twice doesn't consume anything
+            assert_eq!(reader.peek_tag()?, (context0, PCBit::Primitive));
+            let i = reader.next().read_tagged_implicit(context0, |reader| {
+                reader.read_u8()
+            })?;
+            assert_eq!(reader.peek_tag()?, (context0, PCBit::Constructed));
+            let b = reader.next().read_tagged(context0, |reader| {
+                assert_eq!(reader.peek_tag()?, (TAG_BOOLEAN, PCBit::Primitive));
+                reader.read_bool()
+            })?;
+            assert_eq!(reader.peek_tag().unwrap_err().kind(),
+                ASN1ErrorKind::Eof);
+            Ok((i, b))
+        })
+    }).unwrap();
+    assert_eq!(value, (1, true));
+
+    parse_ber(&[36, 128, 4, 0, 0, 0], |reader| {
+        reader.read_tagged_implicit(TAG_OCTETSTRING, |reader| {
+            assert_eq!(reader.peek_tag()?,
+                (TAG_OCTETSTRING, PCBit::Constructed));
+            reader.read_bytes()
+        })
+    }).unwrap();
+    parse_ber(&[48, 128, 0, 0], |reader| {
+        reader.read_sequence(|reader| {
+            assert_eq!(reader.peek_tag()?, (TAG_EOC, PCBit::Primitive));
+            Ok(())
+        })
+    }).unwrap();
+    parse_der(&[31], |reader| reader.peek_tag()).unwrap_err();
+}
+
+#[test]
+fn test_ber_skip_value() {
+    use alloc::vec;
+    let tests : &[&[u8]] = &[
+        &[5, 0], &[2, 1, 10], &[48, 4, 255, 255, 255, 255],
+        &[48, 128, 2, 1, 10, 48, 128, 0, 0, 0, 0],
+        &[31, 35, 2, 1, 2], &[36, 128, 4, 1, 1, 0, 0],
     ];
-    for &(evalue, data) in tests {
-        let value = parse_der(data, |reader| {
-            reader.read_tagged(Tag::context(3), |reader| {
-                reader.read_i64()
+    for &data in tests {
+        let mut seq = vec![48, 128];
+        seq.extend_from_slice(data);
+        seq.extend_from_slice(&[1, 1, 255, 0, 0]);
+        let value = parse_ber(&seq, |reader| {
+            reader.read_sequence(|reader| {
+                reader.next().skip_value()?;
+                reader.next().read_bool()
             })
         }).unwrap();
-        assert_eq!(value, evalue);
+        assert!(value);
     }
+    parse_der(&[48, 128, 0, 0], |reader| reader.skip_value()).unwrap_err();
 
-    let tests : &[((i64, bool), &[u8])] = &[
-        ((10, false), &[163, 8, 48, 6, 2, 1, 10, 1, 1, 0]),
-        ((266, false), &[163, 9, 48, 7, 2, 2, 1, 10, 1, 1, 0]),
+    let tests : &[&[u8]] = &[
+        &[], &[0, 0], &[2, 2, 10], &[5, 128, 0, 0], &[48, 128, 2, 1, 10],
+        &[48, 128, 0, 1],
     ];
-    for &(evalue, data) in tests {
-        let value = parse_der(data, |reader| {
-            reader.read_tagged(Tag::context(3), |reader| {
-                reader.read_sequence(|reader| {
-                    let i = reader.next().read_i64()?;
-                    let b = reader.next().read_bool()?;
-                    return Ok((i, b));
-                })
-            })
-        }).unwrap();
-        assert_eq!(value, evalue);
+    for &data in tests {
+        parse_ber(data, |reader| reader.skip_value()).unwrap_err();
     }
 }
 
 #[test]
-fn test_der_read_tagged_err() {
+fn test_ber_read_der_slice() {
     let tests : &[&[u8]] = &[
-        &[], &[163], &[0, 0], &[0, 1, 0], &[160, 3, 2, 1, 10],
-        &[35, 3, 2, 1, 10], &[131, 3, 2, 1, 10],
-        &[131, 1, 10],
-        &[163, 128, 2, 1, 10, 0, 0],
+        &[5, 0], &[48, 3, 2, 1, 10], &[48, 128, 2, 1, 10, 0, 0],
+        &[160, 128, 48, 128, 0, 0, 0, 0], &[31, 35, 2, 1, 2],
     ];
     for &data in tests {
-        parse_der(data, |reader| {
-            reader.read_tagged(Tag::context(3), |reader| {
-                reader.read_sequence(|reader| {
-                    let i = reader.next().read_i64()?;
-                    let b = reader.next().read_bool()?;
-                    return Ok((i, b));
-                })
-            })
-        }).unwrap_err();
+        let value = parse_ber(data, |reader| reader.read_der_slice()).unwrap();
+        assert_eq!(value, data);
+        let value = parse_ber(data, |reader| reader.read_der()).unwrap();
+        assert_eq!(value, data);
     }
+    parse_der(&[48, 128, 2, 1, 10, 0, 0], |reader| {
+        reader.read_der_slice()
+    }).unwrap_err();
 
     let tests : &[&[u8]] = &[
-        &[], &[163], &[0, 0], &[0, 1, 0],
-        &[160, 8, 48, 6, 2, 1, 10, 1, 1, 0],
-        &[35, 8, 48, 6, 2, 1, 10, 1, 1, 0],
-        &[131, 8, 48, 6, 2, 1, 10, 1, 1, 0],
-        &[163, 8, 48, 6, 2, 1, 10, 1, 1, 0, 0],
-        &[163, 8, 48, 6, 2, 1, 10, 1, 1],
-        &[163, 9, 48, 7, 2, 2, 1, 10, 1, 1, 0, 0],
-        &[163, 9, 48, 7, 2, 2, 1, 10, 1, 1],
-        &[163, 6, 2, 1, 10, 1, 1, 0],
-        &[163, 7, 2, 2, 1, 10, 1, 1, 0],
-        &[163, 128, 48, 6, 2, 1, 10, 1, 1, 0, 0, 0, 0],
-        &[163, 128, 48, 6, 2, 1, 10, 1, 1, 0, 0, 0],
-        &[163, 128, 48, 6, 2, 1, 10, 1, 1, 0, 0],
-        &[163, 128, 48, 7, 2, 2, 1, 10, 1, 1, 0, 0, 0, 0],
-        &[163, 128, 48, 7, 2, 2, 1, 10, 1, 1, 0, 0, 0],
-        &[163, 128, 48, 7, 2, 2, 1, 10, 1, 1, 0, 0],
+        &[], &[0, 0], &[48, 4, 2, 1, 10], &[48, 128, 2, 1, 10],
     ];
     for &data in tests {
-        parse_der(data, |reader| {
-            reader.read_tagged(Tag::context(3), |reader| {
-                reader.read_i64()
-            })
-        }).unwrap_err();
+        parse_ber(data, |reader| reader.read_der_slice()).unwrap_err();
     }
 }
 
 #[test]
-fn test_ber_read_tagged_ok() {
-    let tests : &[(i64, &[u8])] = &[
-        (10, &[163, 3, 2, 1, 10]),
-        (266, &[163, 4, 2, 2, 1, 10]),
-        (10, &[163, 128, 2, 1, 10, 0, 0]),
+fn test_cer_read() {
+    use alloc::vec;
+    let read_seq = |reader: BERReader| {
+        reader.read_sequence(|reader| reader.next().read_i64())
+    };
+    assert_eq!(parse_ber_general(&[48, 128, 2, 1, 10, 0, 0], BERMode::Cer,
+        read_seq).unwrap(), 10);
+    // Definite length of a constructed value
+    assert_eq!(parse_ber_general(&[48, 3, 2, 1, 10], BERMode::Cer,
+        read_seq).unwrap_err().kind(), ASN1ErrorKind::Invalid);
+    // Restrictions of DER
+    assert_eq!(parse_ber_general(&[1, 1, 1], BERMode::Cer,
+        |reader| reader.read_bool()).unwrap_err().kind(),
+        ASN1ErrorKind::Invalid);
+    assert_eq!(parse_ber_general(&[4, 129, 1, 0], BERMode::Cer,
+        |reader| reader.read_bytes()).unwrap_err().kind(),
+        ASN1ErrorKind::Invalid);
+    assert_eq!(parse_ber_general(&[49, 128, 1, 1, 255, 1, 1, 0, 0, 0],
+        BERMode::Cer, |reader| reader.collect_set_of(|reader| {
+            reader.read_bool()
+        })).unwrap_err().kind(), ASN1ErrorKind::Invalid);
+    assert_eq!(tlv_length(&[48, 3, 2, 1, 10], BERMode::Cer).unwrap_err()
+        .kind(), ASN1ErrorKind::Invalid);
+    assert_eq!(tlv_length(&[48, 128, 2, 1, 10, 0, 0], BERMode::Cer).unwrap(),
+        TlvLength::Complete(7));
+
+    // Segmentation of strings
+    let octets = |lens: &[usize]| {
+        let mut data = vec![36, 128];
+        for &len in lens {
+            data.push(4);
+            if len >= 256 {
+                data.extend_from_slice(&[130, (len >> 8) as u8, len as u8]);
+            } else if len >= 128 {
+                data.extend_from_slice(&[129, len as u8]);
+            } else {
+                data.push(len as u8);
+            }
+            data.resize(data.len() + len, 1);
+        }
+        data.extend_from_slice(&[0, 0]);
+        data
+    };
+    let read_bytes = |reader: BERReader| reader.read_bytes();
+    let tests : &[(&[usize], bool)] = &[
+        (&[1000, 1], true),
+        (&[1000, 1000, 1000], true),
+        (&[1000, 1000, 999], true),
+        (&[1], false),
+        (&[1000], false),
+        (&[999, 2], false),
+        (&[1000, 999, 1], false),
+        (&[1000, 1001], false),
+        (&[1000, 0], false),
     ];
-    for &(evalue, data) in tests {
-        let value = parse_ber(data, |reader| {
-            reader.read_tagged(Tag::context(3), |reader| {
-                reader.read_i64()
+    for &(lens, valid) in tests {
+        let data = octets(lens);
+        let result = parse_ber_general(&data, BERMode::Cer, read_bytes);
+        assert_eq!(result.is_ok(), valid, "{:?}", lens);
+        if valid {
+            assert_eq!(result.unwrap().len(), lens.iter().sum::<usize>());
+        }
+        assert!(parse_ber_general(&data, BERMode::Ber, read_bytes).is_ok());
+    }
+    let mut data = vec![4, 130, 3, 232];
+    data.resize(1004, 1);
+    assert_eq!(parse_ber_general(&data, BERMode::Cer, read_bytes).unwrap()
+        .len(), 1000);
+    data[3] = 233;
+    data.push(1);
+    assert_eq!(parse_ber_general(&data, BERMode::Cer, read_bytes)
+        .unwrap_err().kind(), ASN1ErrorKind::Invalid);
+    // Nested segments
+    let mut data = octets(&[1000, 1]);
+    data.splice(2..2, vec![36, 128]);
+    data.extend_from_slice(&[0, 0]);
+    parse_ber_general(&data, BERMode::Ber, read_bytes).unwrap();
+    assert_eq!(parse_ber_general(&data, BERMode::Cer, read_bytes)
+        .unwrap_err().kind(), ASN1ErrorKind::Invalid);
+
+    // The unused bits octet counts in the segments of BIT STRING
+    let mut data = vec![35, 128, 3, 130, 3, 232, 0];
+    data.resize(1006, 1);
+    data.extend_from_slice(&[3, 2, 7, 128, 0, 0]);
+    assert_eq!(parse_ber_general(&data, BERMode::Cer, |reader| {
+        reader.read_bitvec_bytes()
+    }).unwrap().1, 999 * 8 + 1);
+    data[5] = 231;
+    data.remove(6);
+    assert_eq!(parse_ber_general(&data, BERMode::Cer, |reader| {
+        reader.read_bitvec_bytes()
+    }).unwrap_err().kind(), ASN1ErrorKind::Invalid);
+}
+
+#[test]
+fn test_parse_prefix() {
+    let tests : &[(&[u8], i64, usize)] = &[
+        (&[2, 1, 10], 10, 3),
+        (&[2, 1, 10, 2, 1, 20], 10, 3),
+        (&[2, 2, 1, 0, 0], 256, 4),
+    ];
+    for &(data, evalue, elen) in tests {
+        assert_eq!(parse_der_prefix(data, |reader| reader.read_i64())
+            .unwrap(), (evalue, elen));
+        assert_eq!(parse_ber_prefix(data, |reader| reader.read_i64())
+            .unwrap(), (evalue, elen));
+    }
+
+    let data = &[48, 128, 5, 0, 0, 0, 255];
+    assert_eq!(parse_ber_prefix(data, |reader| {
+        reader.read_sequence(|reader| reader.next().read_null())
+    }).unwrap(), ((), 6));
+    let err = parse_der_prefix(data, |reader| {
+        reader.read_sequence(|reader| reader.next().read_null())
+    }).unwrap_err();
+    assert_eq!(err.kind(), ASN1ErrorKind::Invalid);
+
+    let err = parse_der_prefix(&[2, 2, 1], |reader| reader.read_i64())
+        .unwrap_err();
+    assert_eq!(err.kind(), ASN1ErrorKind::Eof);
+}
+
+#[test]
+fn test_parse_multiple() {
+    use alloc::vec;
+    let data = &[2, 1, 10, 48, 128, 2, 1, 20, 0, 0, 5, 0];
+    let mut iter = parse_ber_multiple(data, |reader| reader.read_der_slice());
+    assert_eq!(iter.next(), Some(Ok(&data[..3])));
+    assert_eq!(iter.remaining(), &data[3..]);
+    assert_eq!(iter.next(), Some(Ok(&data[3..10])));
+    assert_eq!(iter.next(), Some(Ok(&data[10..])));
+    assert_eq!(iter.next(), None);
+    assert_eq!(parse_ber_multiple(&[], |reader| reader.read_null()).count(), 0);
+
+    // The values must be read entirely.
+    let mut iter = parse_der_multiple(&[48, 3, 2, 1, 10, 5, 0], |reader| {
+        reader.read_sequence(|_| Ok(()))
+    });
+    assert_eq!(iter.next(), Some(Err(ASN1Error::new(ASN1ErrorKind::Extra))));
+    assert_eq!(iter.next(), None);
+
+    // It stops at the first error.
+    let data = &[2, 1, 10, 48, 128, 2, 1, 20, 0, 0, 2, 1, 30];
+    let values = parse_der_multiple(data, |reader| reader.read_i64())
+        .collect::<Vec<_>>();
+    assert_eq!(values, vec![Ok(10), Err(ASN1Error::new(ASN1ErrorKind::Invalid))]);
+    let values = parse_der_multiple(&[2, 1, 10, 2, 2, 20], |reader| {
+        reader.read_i64()
+    }).collect::<Vec<_>>();
+    assert_eq!(values, vec![Ok(10), Err(ASN1Error::new(ASN1ErrorKind::Eof))]);
+}
+
+#[test]
+fn test_ber_push_parser() {
+    let data = &[48, 128, 2, 1, 10, 0, 0, 48, 3, 2, 1, 20, 48, 3, 2, 1, 30];
+    let read = |reader: BERReader| {
+        reader.read_sequence(|reader| reader.next().read_i64())
+    };
+    let mut parser = PushParser::new(BERMode::Ber, read);
+    let mut values = Vec::new();
+    for b in data.iter() {
+        match parser.feed(&[*b]) {
+            PushStatus::NeedMoreData => {},
+            PushStatus::Complete(value) => values.push(value),
+            PushStatus::Error(e) => panic!("{:?}", e),
+        }
+    }
+    assert_eq!(values, &[10, 20, 30]);
+    assert_eq!(parser.buffered(), 0);
+
+    let mut parser = PushParser::new(BERMode::Ber, read);
+    assert_eq!(parser.feed(data), PushStatus::Complete(10));
+    assert_eq!(parser.buffered(), 10);
+    assert_eq!(parser.feed(&[]), PushStatus::Complete(20));
+    assert_eq!(parser.feed(&[]), PushStatus::Complete(30));
+    assert_eq!(parser.feed(&[]), PushStatus::NeedMoreData);
+
+    let mut parser = PushParser::new(BERMode::Der, read);
+    assert_eq!(parser.feed(&data[..3]),
+        PushStatus::Error(ASN1Error::new(ASN1ErrorKind::Invalid)));
+    assert_eq!(parser.buffered(), 0);
+    assert_eq!(parser.feed(&[48, 3, 1, 1, 0, 48, 3]),
+        PushStatus::Error(ASN1Error::new(ASN1ErrorKind::Invalid)));
+    assert_eq!(parser.feed(&[2, 1, 20]), PushStatus::Complete(20));
+
+    let options = ParseOptions::new(BERMode::Der).with_max_alloc(100);
+    let mut parser = PushParser::with_options(options, read);
+    assert_eq!(parser.feed(&[48, 129, 200]),
+        PushStatus::Error(ASN1Error::new(ASN1ErrorKind::LimitExceeded)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_ber_parse_from_reader() {
+    use alloc::vec;
+    use std::io::{self, Read};
+    // Hands out a byte at a time
+    struct Trickle<'a>(&'a [u8]);
+    impl<'a> Read for Trickle<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if buf.is_empty() || self.0.is_empty() {
+                return Ok(0);
+            }
+            buf[0] = self.0[0];
+            self.0 = &self.0[1..];
+            Ok(1)
+        }
+    }
+    let read = |reader: BERReader| {
+        reader.read_sequence(|reader| reader.next().read_bytes())
+    };
+
+    let mut data = vec![48, 130, 39, 20, 4, 130, 39, 16];
+    data.resize(10008, 7);
+    data.extend_from_slice(&[5, 0]);
+    for &mode in &[BERMode::Ber, BERMode::Der] {
+        let mut reader = Trickle(&data);
+        assert_eq!(parse_ber_from_reader(&mut reader, mode, read).unwrap()
+            .len(), 10000);
+        assert_eq!(reader.0, &[5, 0]);
+    }
+
+    let data = &[48, 128, 36, 128, 4, 1, 1, 0, 0, 0, 0, 5, 0];
+    let mut reader = Trickle(data);
+    assert_eq!(parse_ber_from_reader(&mut reader, BERMode::Ber, read)
+        .unwrap(), &[1]);
+    assert_eq!(reader.0, &[5, 0]);
+    let err = parse_ber_from_reader(Trickle(data), BERMode::Der, read)
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    let err = parse_ber_from_reader(Trickle(&[48, 3, 4, 1]), BERMode::Der,
+        read).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    let err = parse_ber_from_reader(Trickle(&[48, 3, 5, 0, 0]), BERMode::Der,
+        read).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    // A header announcing 2 GB
+    let options = ParseOptions::new(BERMode::Der).with_max_alloc(1 << 20);
+    let err = parse_from_reader_with_options(
+        Trickle(&[4, 132, 128, 0, 0, 0]), options, |reader| {
+            reader.read_bytes()
+        }).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+}
+
+/// Runs `future` to completion by polling it over and over, which suits
+/// futures reading from memory.
+#[cfg(feature = "tokio")]
+fn block_on<F: core::future::Future>(future: F) -> F::Output {
+    use alloc::boxed::Box;
+    use alloc::sync::Arc;
+    use core::task::{Context, Poll};
+    use std::task::{Wake, Waker};
+    struct NoopWaker;
+    impl Wake for NoopWaker {
+        fn wake(self: Arc<Self>) {}
+    }
+    let waker = Waker::from(Arc::new(NoopWaker));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = Box::pin(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn test_ber_parse_async() {
+    use std::io;
+    let read = |reader: BERReader| {
+        reader.read_sequence(|reader| reader.next().read_i64())
+    };
+    block_on(async {
+        let data = &[48, 128, 2, 1, 10, 0, 0, 48, 3, 2, 1, 20];
+        let mut reader = &data[..];
+        assert_eq!(parse_ber_async(&mut reader, BERMode::Ber, read).await
+            .unwrap(), 10);
+        assert_eq!(parse_ber_async(&mut reader, BERMode::Der, read).await
+            .unwrap(), 20);
+        assert!(reader.is_empty());
+
+        let err = parse_ber_async(&data[..3], BERMode::Ber, read).await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        let err = parse_ber_async(&data[..], BERMode::Der, read).await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let options = ParseOptions::new(BERMode::Der).with_max_alloc(4);
+        let err = parse_async_with_options(&data[7..], options, read).await
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    });
+}
+
+#[test]
+fn test_der_read_lenient_default() {
+    use super::super::tags::TAG_BOOLEAN;
+    let seq: &[u8] = &[48, 3, 1, 1, 0];
+    let set: &[u8] = &[49, 3, 1, 1, 0];
+    let options = ParseOptions::new(BERMode::Der);
+    let lenient = options.with_lenient_default(true);
+    assert!(!options.lenient_default());
+    assert!(lenient.lenient_default());
+    for &(options, ok) in &[(options, false), (lenient, true)] {
+        let value = parse_with_options(seq, options, |reader| {
+            reader.read_sequence(|reader| {
+                reader.read_default(false, |reader| reader.read_bool())
+            })
+        });
+        assert_eq!(value.is_ok(), ok);
+        let value = parse_with_options(set, options, |reader| {
+            reader.read_set(|reader| {
+                reader.read_default(&[TAG_BOOLEAN], false, |reader| {
+                    reader.read_bool()
+                })
             })
-        }).unwrap();
-        assert_eq!(value, evalue);
+        });
+        assert_eq!(value.is_ok(), ok);
     }
+    // Other canonical checks still apply.
+    parse_with_options(&[48, 3, 1, 1, 1], lenient, |reader| {
+        reader.read_sequence(|reader| {
+            reader.read_default(false, |reader| reader.read_bool())
+        })
+    }).unwrap_err();
+}
 
-    let tests : &[((i64, bool), &[u8])] = &[
-        ((10, false), &[163, 8, 48, 6, 2, 1, 10, 1, 1, 0]),
-        ((266, false), &[163, 9, 48, 7, 2, 2, 1, 10, 1, 1, 0]),
-        ((10, false), &[163, 128, 48, 6, 2, 1, 10, 1, 1, 0, 0, 0]),
-        ((266, false), &[163, 128, 48, 7, 2, 2, 1, 10, 1, 1, 0, 0, 0]),
-    ];
-    for &(evalue, data) in tests {
-        let value = parse_ber(data, |reader| {
-            reader.read_tagged(Tag::context(3), |reader| {
-                reader.read_sequence(|reader| {
-                    let i = reader.next().read_i64()?;
-                    let b = reader.next().read_bool()?;
-                    return Ok((i, b));
-                })
+#[test]
+fn test_der_read_tagged_any() {
+    use alloc::vec;
+    use super::super::tags::{TAG_INTEGER, TAG_SEQUENCE};
+    // SEQUENCE { [0] EXPLICIT NULL, [1] IMPLICIT SEQUENCE { }, INTEGER 5 }
+    let data = &[48, 9, 160, 2, 5, 0, 161, 0, 2, 1, 5];
+    let value = parse_der(data, |reader| {
+        reader.collect_sequence_of(|reader| {
+            reader.read_tagged_any(|tag, pcbit, reader| {
+                if tag == Tag::context(0) {
+                    reader.read_tagged(tag, |reader| reader.read_null())?;
+                    Ok((tag, pcbit, None))
+                } else if tag == Tag::context(1) {
+                    reader.read_sequence(|_| Ok(()))?;
+                    Ok((tag, pcbit, None))
+                } else {
+                    Ok((tag, pcbit, Some(reader.read_i64()?)))
+                }
             })
-        }).unwrap();
-        assert_eq!(value, evalue);
-    }
+        })
+    }).unwrap();
+    assert_eq!(value, vec![
+        (Tag::context(0), PCBit::Constructed, None),
+        (Tag::context(1), PCBit::Constructed, None),
+        (TAG_INTEGER, PCBit::Primitive, Some(5)),
+    ]);
+
+    let value = parse_der(&[48, 0], |reader| {
+        reader.read_tagged_any(|tag, _, reader| {
+            reader.read_sequence(|_| Ok(()))?;
+            Ok(tag)
+        })
+    }).unwrap();
+    assert_eq!(value, TAG_SEQUENCE);
+    let value = parse_der(&[], |reader| {
+        reader.read_tagged_any(|_, _, reader| reader.read_null())
+    });
+    assert_eq!(value, Err(ASN1Error::new(ASN1ErrorKind::Eof)));
 }
 
 #[test]
-fn test_ber_read_tagged_err() {
-    let tests : &[&[u8]] = &[
-        &[], &[163], &[0, 0], &[0, 1, 0], &[160, 3, 2, 1, 10],
-        &[35, 3, 2, 1, 10], &[131, 3, 2, 1, 10],
-        &[131, 1, 10],
-    ];
-    for &data in tests {
-        parse_ber(data, |reader| {
-            reader.read_tagged(Tag::context(3), |reader| {
-                reader.read_sequence(|reader| {
-                    let i = reader.next().read_i64()?;
-                    let b = reader.next().read_bool()?;
-                    return Ok((i, b));
+fn test_ber_with_mode() {
+    // SEQUENCE { [0] { BOOLEAN 1 }, BOOLEAN 1 }
+    let data = &[48, 128, 160, 3, 1, 1, 1, 1, 1, 1, 0, 0];
+    let read = |reader: BERReader, mode| {
+        reader.read_sequence(|reader| {
+            let a = reader.next().with_mode(mode, |reader| {
+                assert_eq!(reader.mode(), mode);
+                reader.read_tagged(Tag::context(0), |reader| {
+                    reader.read_bool()
                 })
-            })
-        }).unwrap_err();
-    }
+            })?;
+            let b = reader.next().read_bool()?;
+            Ok((a, b))
+        })
+    };
+    assert_eq!(parse_ber(data, |reader| read(reader, BERMode::Ber)),
+        Ok((true, true)));
+    parse_ber(data, |reader| read(reader, BERMode::Der)).unwrap_err();
 
-    let tests : &[&[u8]] = &[
-        &[], &[163], &[0, 0], &[0, 1, 0],
-        &[160, 8, 48, 6, 2, 1, 10, 1, 1, 0],
-        &[35, 8, 48, 6, 2, 1, 10, 1, 1, 0],
-        &[131, 8, 48, 6, 2, 1, 10, 1, 1, 0],
-        &[163, 8, 48, 6, 2, 1, 10, 1, 1, 0, 0],
-        &[163, 8, 48, 6, 2, 1, 10, 1, 1],
-        &[163, 9, 48, 7, 2, 2, 1, 10, 1, 1, 0, 0],
-        &[163, 9, 48, 7, 2, 2, 1, 10, 1, 1],
-        &[163, 6, 2, 1, 10, 1, 1, 0],
-        &[163, 7, 2, 2, 1, 10, 1, 1, 0],
-        &[163, 128, 48, 6, 2, 1, 10, 1, 1, 0, 0, 0, 0],
-        &[163, 128, 48, 6, 2, 1, 10, 1, 1, 0, 0],
-        &[163, 128, 48, 7, 2, 2, 1, 10, 1, 1, 0, 0, 0, 0],
-        &[163, 128, 48, 7, 2, 2, 1, 10, 1, 1, 0, 0],
+    // The outer mode applies again afterwards.
+    let data = &[48, 8, 160, 3, 1, 1, 1, 1, 1, 255];
+    assert_eq!(parse_der(data, |reader| read(reader, BERMode::Ber)),
+        Ok((true, true)));
+    let data = &[48, 8, 160, 3, 1, 1, 255, 1, 1, 1];
+    parse_der(data, |reader| read(reader, BERMode::Ber)).unwrap_err();
+}
+
+#[test]
+fn test_der_read_leniency() {
+    use alloc::vec;
+    let strict = ParseOptions::new(BERMode::Der);
+    let tests : &[(LeniencyFlags, &[u8])] = &[
+        (LeniencyFlags::NON_MINIMAL_LENGTH, &[48, 129, 3, 1, 1, 255]),
+        (LeniencyFlags::NON_MINIMAL_LENGTH, &[48, 130, 0, 3, 1, 1, 255]),
+        (LeniencyFlags::BOOLEAN, &[48, 3, 1, 1, 1]),
+        (LeniencyFlags::SET_ORDER, &[49, 6, 1, 1, 255, 1, 1, 0]),
     ];
-    for &data in tests {
-        parse_ber(data, |reader| {
-            reader.read_tagged(Tag::context(3), |reader| {
-                reader.read_i64()
-            })
-        }).unwrap_err();
+    for &(flag, data) in tests {
+        let read = |reader: BERReader| {
+            if data[0] == 48 {
+                reader.collect_sequence_of(|reader| reader.read_bool())
+            } else {
+                reader.collect_set_of(|reader| reader.read_bool())
+            }
+        };
+        parse_with_options(data, strict, read).unwrap_err();
+        parse_with_options(data, strict.with_leniency(flag), read).unwrap();
+        parse_with_options(data, strict.with_leniency(LeniencyFlags::all()),
+            read).unwrap();
+        parse_ber(data, read).unwrap();
     }
+
+    let data = &[3, 2, 7, 255];
+    let read = |reader: BERReader| reader.read_bitvec_bytes();
+    parse_with_options(data, strict, read).unwrap_err();
+    let options = strict.with_leniency(LeniencyFlags::BIT_STRING_PADDING);
+    assert_eq!(parse_with_options(data, options, read), Ok((vec![255], 1)));
+
+    // A flag relaxes one rule only.
+    let options = strict.with_leniency(LeniencyFlags::BOOLEAN);
+    parse_with_options(&[48, 129, 3, 1, 1, 1], options, |reader| {
+        reader.collect_sequence_of(|reader| reader.read_bool())
+    }).unwrap_err();
+
+    // with_leniency replaces the DEFAULT flag, and conversely.
+    let options = strict.with_lenient_default(true)
+        .with_leniency(LeniencyFlags::BOOLEAN);
+    assert!(!options.lenient_default());
+    let options = options.with_lenient_default(true);
+    assert_eq!(options.leniency(),
+        LeniencyFlags::BOOLEAN | LeniencyFlags::DEFAULT_VALUE);
+    assert_eq!(options.with_lenient_default(false).leniency(),
+        LeniencyFlags::BOOLEAN);
+
+    let options = strict.with_leniency(LeniencyFlags::NON_MINIMAL_LENGTH);
+    assert_eq!(tlv_length_with_options(&[4, 129, 1, 0], options),
+        Ok(TlvLength::Complete(4)));
+    tlv_length(&[4, 129, 1, 0], BERMode::Der).unwrap_err();
 }
 
 #[test]
-fn test_der_read_tagged_implicit_ok() {
-    let tests : &[(i64, &[u8])] = &[
-        (10, &[131, 1, 10]),
-        (266, &[131, 2, 1, 10]),
+fn test_der_read_allowed_types() {
+    use super::super::tags::{TAG_INTEGER, TAG_REAL, TAG_TELETEXSTRING};
+    let types = AllowedTypes::from_tags(&[TAG_SEQUENCE, TAG_INTEGER]);
+    assert!(types.contains(TAG_SEQUENCE));
+    assert!(!types.contains(TAG_TELETEXSTRING));
+    assert!(!types.forbid(TAG_INTEGER).contains(TAG_INTEGER));
+    assert!(types.contains(Tag::context(0)));
+    assert!(!types.contains(Tag { tag_class: TagClass::Universal,
+        tag_number: 100 }));
+    let options = ParseOptions::new(BERMode::Ber).with_allowed_types(types);
+    assert_eq!(options.allowed_types(), Some(types));
+
+    let read = |data: &[u8], options| {
+        parse_with_options(data, options, |reader| {
+            reader.read_sequence(|reader| {
+                reader.next().read_i64()?;
+                reader.read_optional(|reader| reader.read_der())
+            })
+        })
+    };
+    let tests : &[(&[u8], bool)] = &[
+        (b"\x30\x03\x02\x01\x01", true),
+        (b"\x30\x80\x02\x01\x01\x00\x00", true),
+        (b"\x30\x06\x02\x01\x01\x80\x01\x00", true),
+        (b"\x30\x08\x02\x01\x01\xa0\x03\x02\x01\x05", true),
+        (b"\x30\x05\x02\x01\x01\x09\x00", false),
+        (b"\x30\x05\x02\x01\x01\x14\x00", false),
+        (b"\x30\x07\x02\x01\x01\xa0\x02\x09\x00", false),
+        (b"\x30\x05\x02\x01\x01\x31\x00", false),
     ];
-    for &(evalue, data) in tests {
-        let value = parse_der(data, |reader| {
-            reader.read_tagged_implicit(Tag::context(3), |reader| {
-                reader.read_i64()
+    for &(data, ok) in tests {
+        assert_eq!(read(data, options).is_ok(), ok);
+        read(data, ParseOptions::new(BERMode::Ber)).unwrap();
+    }
+    let options = options.with_allowed_types(types.allow(TAG_REAL));
+    read(b"\x30\x05\x02\x01\x01\x09\x00", options).unwrap();
+}
+
+#[test]
+fn test_der_parse_limited() {
+    struct Tiny;
+    impl Limits for Tiny {
+        const MAX_DEPTH: usize = 1;
+        const MAX_SIZE: usize = 8;
+    }
+    let options = ParseOptions::new(BERMode::Ber);
+    let read = |data| {
+        parse_limited::<Tiny, _, _>(data, options, |reader| {
+            reader.read_sequence(|reader| {
+                while reader.read_optional(|reader| {
+                    reader.read_tagged_der()
+                })?.is_some() {}
+                Ok(())
             })
-        }).unwrap();
-        assert_eq!(value, evalue);
+        })
+    };
+    read(b"\x30\x03\x02\x01\x0a").unwrap();
+    read(b"\x30\x06\x02\x01\x0a\x01\x01\xff").unwrap();
+    assert_eq!(read(b"\x30\x07\x02\x01\x0a\x01\x01\xff\x05").unwrap_err()
+        .kind(), ASN1ErrorKind::LimitExceeded);
+
+    let nested = |reader: BERReader| {
+        reader.read_sequence(|reader| {
+            reader.next().read_sequence(|reader| reader.next().read_i64())
+        })
+    };
+    let data = b"\x30\x05\x30\x03\x02\x01\x0a";
+    assert_eq!(parse_limited::<Tiny, _, _>(data, options, nested)
+        .unwrap_err().kind(), ASN1ErrorKind::StackOverflow);
+    assert_eq!(parse_limited::<DefaultLimits, _, _>(data, options, nested)
+        .unwrap(), 10);
+}
+
+#[test]
+fn test_der_parse_max_depth() {
+    let nested = |reader: BERReader| {
+        reader.read_sequence(|reader| {
+            reader.next().read_sequence(|reader| reader.next().read_i64())
+        })
+    };
+    let data = b"\x30\x05\x30\x03\x02\x01\x0a";
+    let options = ParseOptions::new(BERMode::Der);
+    assert_eq!(options.max_depth(), 100);
+    assert_eq!(parse_with_options(data, options.with_max_depth(2), nested)
+        .unwrap(), 10);
+    assert_eq!(parse_with_options(data, options.with_max_depth(1), nested)
+        .unwrap_err().kind(), ASN1ErrorKind::StackOverflow);
+    assert_eq!(parse_limited::<DefaultLimits, _, _>(data,
+        options.with_max_depth(1), nested).unwrap_err().kind(),
+        ASN1ErrorKind::StackOverflow);
+
+    // Beyond the default depth
+    let mut data = Vec::new();
+    for _ in 0..150 {
+        data.extend_from_slice(&[48, 128]);
+    }
+    for _ in 0..150 {
+        data.extend_from_slice(&[0, 0]);
+    }
+    fn read(reader: BERReader) -> ASN1Result<()> {
+        reader.read_sequence(|reader| {
+            if reader.has_next() {
+                read(reader.next())?;
+            }
+            Ok(())
+        })
     }
+    let options = ParseOptions::new(BERMode::Ber);
+    assert_eq!(parse_with_options(&data, options, read).unwrap_err().kind(),
+        ASN1ErrorKind::StackOverflow);
+    parse_with_options(&data, options.with_max_depth(200), read).unwrap();
+}
 
-    let tests : &[((i64, bool), &[u8])] = &[
-        ((10, false), &[163, 6, 2, 1, 10, 1, 1, 0]),
-        ((266, false), &[163, 7, 2, 2, 1, 10, 1, 1, 0]),
-    ];
-    for &(evalue, data) in tests {
-        let value = parse_der(data, |reader| {
-            reader.read_tagged_implicit(Tag::context(3), |reader| {
-                reader.read_sequence(|reader| {
-                    let i = reader.next().read_i64()?;
-                    let b = reader.next().read_bool()?;
-                    return Ok((i, b));
+#[test]
+fn test_der_parse_max_depth_set() {
+    let nested = |reader: BERReader| {
+        reader.read_set(|reader| {
+            reader.next(&[TAG_SET])?.read_set(|reader| {
+                reader.read_optional(&[TAG_SET], |reader| {
+                    reader.read_set_of(|reader| {
+                        reader.read_i64()?;
+                        Ok(())
+                    })
                 })
             })
-        }).unwrap();
-        assert_eq!(value, evalue);
+        })
+    };
+    let data = b"\x31\x07\x31\x05\x31\x03\x02\x01\x0a";
+    let options = ParseOptions::new(BERMode::Der);
+    assert_eq!(parse_with_options(data, options.with_max_depth(3), nested)
+        .unwrap(), Some(()));
+    assert_eq!(parse_with_options(data, options.with_max_depth(2), nested)
+        .unwrap_err().kind(), ASN1ErrorKind::StackOverflow);
+    assert_eq!(parse_with_options(data, options.with_max_depth(1), nested)
+        .unwrap_err().kind(), ASN1ErrorKind::StackOverflow);
+
+    // Far beyond the default depth
+    let mut data = Vec::new();
+    for _ in 0..5000 {
+        data.extend_from_slice(&[49, 128]);
     }
+    for _ in 0..5000 {
+        data.extend_from_slice(&[0, 0]);
+    }
+    fn read(reader: BERReader) -> ASN1Result<()> {
+        reader.read_set(|reader| {
+            reader.read_optional(&[TAG_SET], read)?;
+            Ok(())
+        })
+    }
+    let options = ParseOptions::new(BERMode::Ber);
+    assert_eq!(parse_with_options(&data, options, read).unwrap_err().kind(),
+        ASN1ErrorKind::StackOverflow);
 }
 
 #[test]
-fn test_der_read_tagged_implicit_err() {
-    let tests : &[&[u8]] = &[
-        &[], &[131], &[0, 0], &[0, 1, 0], &[128, 3, 2, 1, 10],
-        &[3, 3, 2, 1, 10], &[163, 3, 2, 1, 10],
-        &[163, 3, 2, 1, 10],
-        &[131, 128, 1, 10, 0, 0],
-        &[163, 128, 2, 1, 10, 0, 0],
-    ];
-    for &data in tests {
-        parse_der(data, |reader| {
-            reader.read_tagged(Tag::context(3), |reader| {
-                reader.read_sequence(|reader| {
-                    let i = reader.next().read_i64()?;
-                    let b = reader.next().read_bool()?;
-                    return Ok((i, b));
-                })
+fn test_ber_parse_resource_limits() {
+    let options = ParseOptions::new(BERMode::Ber);
+    assert_eq!(options.max_length(), !0);
+    assert_eq!(options.max_alloc(), !0);
+
+    // An OCTET STRING claiming 2 GB
+    let data = &[4, 132, 128, 0, 0, 0, 0];
+    assert_eq!(parse_with_options(data, options, |reader| reader.read_bytes())
+        .unwrap_err().kind(), ASN1ErrorKind::Eof);
+    let limited = options.with_max_length(1 << 20);
+    assert_eq!(parse_with_options(data, limited, |reader| reader.read_bytes())
+        .unwrap_err().kind(), ASN1ErrorKind::LimitExceeded);
+    assert_eq!(parse_with_options(data, limited, |reader| reader.skip_value())
+        .unwrap_err().kind(), ASN1ErrorKind::LimitExceeded);
+
+    // The length of indefinite-length values isn't limited
+    let data = &[36, 128, 4, 2, 1, 2, 4, 2, 3, 4, 0, 0];
+    let limited = options.with_max_length(2);
+    assert_eq!(parse_with_options(data, limited, |reader| reader.read_bytes())
+        .unwrap(), &[1, 2, 3, 4]);
+    let limited = options.with_max_alloc(4);
+    assert_eq!(parse_with_options(data, limited, |reader| reader.read_bytes())
+        .unwrap(), &[1, 2, 3, 4]);
+    let limited = options.with_max_alloc(3);
+    assert_eq!(parse_with_options(data, limited, |reader| reader.read_bytes())
+        .unwrap_err().kind(), ASN1ErrorKind::LimitExceeded);
+    assert_eq!(parse_with_options(data, limited, |reader| reader.read_der())
+        .unwrap_err().kind(), ASN1ErrorKind::LimitExceeded);
+    parse_with_options(data, limited, |reader| reader.read_der_slice())
+        .unwrap();
+
+    // The budget is shared by the elements of a SET, like a SEQUENCE
+    let data = &[49, 8, 4, 2, 1, 2, 4, 2, 3, 4];
+    let read_set = |reader: BERReader| {
+        reader.read_set(|reader| {
+            let a = reader.next(&[TAG_OCTETSTRING])?.read_bytes()?;
+            let b = reader.read_optional(&[TAG_OCTETSTRING], |reader| {
+                reader.read_bytes()
+            })?;
+            Ok((a, b))
+        })
+    };
+    let read_set_of = |reader: BERReader| {
+        reader.collect_set_of(|reader| reader.read_bytes())
+    };
+    let limited = options.with_max_alloc(4);
+    parse_with_options(data, limited, read_set).unwrap();
+    parse_with_options(data, limited, read_set_of).unwrap();
+    let limited = options.with_max_alloc(3);
+    assert_eq!(parse_with_options(data, limited, read_set)
+        .unwrap_err().kind(), ASN1ErrorKind::LimitExceeded);
+    assert_eq!(parse_with_options(data, limited, read_set_of)
+        .unwrap_err().kind(), ASN1ErrorKind::LimitExceeded);
+    let data = &[49, 10, 49, 8, 4, 2, 1, 2, 4, 2, 3, 4];
+    assert_eq!(parse_with_options(data, limited, |reader| {
+        reader.read_set(|reader| reader.next(&[TAG_SET]).and_then(read_set))
+    }).unwrap_err().kind(), ASN1ErrorKind::LimitExceeded);
+
+    let data = &[6, 3, 85, 4, 3];
+    let limited = options.with_max_alloc(47);
+    assert_eq!(parse_with_options(data, limited, |reader| reader.read_oid())
+        .unwrap_err().kind(), ASN1ErrorKind::LimitExceeded);
+    let limited = options.with_max_alloc(48);
+    parse_with_options(data, limited, |reader| reader.read_oid()).unwrap();
+
+    let data = &[2, 2, 1, 0];
+    let limited = options.with_max_alloc(1);
+    assert_eq!(parse_with_options(data, limited, |reader| reader.read_i64())
+        .unwrap(), 256);
+    assert_eq!(parse_with_options(data, limited, |reader| {
+        reader.read_bigint_bytes()
+    }).unwrap_err().kind(), ASN1ErrorKind::LimitExceeded);
+}
+
+#[cfg(feature = "digest")]
+#[test]
+fn test_der_read_digested() {
+    struct Collect(Vec<u8>);
+    impl digest::Update for Collect {
+        fn update(&mut self, data: &[u8]) {
+            self.0.extend_from_slice(data);
+        }
+    }
+    let data = &[48, 128, 160, 3, 2, 1, 10, 1, 1, 255, 0, 0];
+    let mut digest = Collect(Vec::new());
+    let b = parse_ber(data, |reader| {
+        reader.read_sequence(|reader| {
+            reader.next().read_digested(&mut digest, |reader| {
+                reader.read_tagged(Tag::context(0), |reader| reader.read_i64())
+            })?;
+            reader.next().read_digested(&mut digest, |reader| {
+                reader.read_bool()
             })
-        }).unwrap_err();
+        })
+    }).unwrap();
+    assert!(b);
+    assert_eq!(digest.0, &[160, 3, 2, 1, 10, 1, 1, 255]);
+
+    let mut digest = Collect(Vec::new());
+    parse_der(&[2, 1, 10], |reader| {
+        reader.read_digested(&mut digest, |reader| reader.read_bool())
+    }).unwrap_err();
+    assert!(digest.0.is_empty());
+}
+
+#[test]
+fn test_der_tlv_length() {
+    let tests : &[(&[u8], TlvLength)] = &[
+        (b"", TlvLength::Incomplete { needs_at_least: 2 }),
+        (b"\x30", TlvLength::Incomplete { needs_at_least: 2 }),
+        (b"\x30\x03\x02\x01", TlvLength::Incomplete { needs_at_least: 5 }),
+        (b"\x30\x03\x02\x01\x0a\xff", TlvLength::Complete(5)),
+        (b"\x1f\x81", TlvLength::Incomplete { needs_at_least: 3 }),
+        (b"\x1f\x81\x00\x00", TlvLength::Complete(4)),
+        (b"\x04\x82\x01", TlvLength::Incomplete { needs_at_least: 4 }),
+        (b"\x04\x81\x80", TlvLength::Incomplete { needs_at_least: 131 }),
+        (b"\x30\x80\x24\x80", TlvLength::Incomplete { needs_at_least: 8 }),
+        (b"\x30\x80\x02\x01\x0a", TlvLength::Incomplete { needs_at_least: 7 }),
+        (b"\x30\x80\x02\x01\x0a\x00",
+            TlvLength::Incomplete { needs_at_least: 7 }),
+        (b"\x30\x80\x24\x80\x04\x00\x00\x00\x00\x00\x01",
+            TlvLength::Complete(10)),
+    ];
+    for &(data, length) in tests {
+        assert_eq!(tlv_length(data, BERMode::Ber).unwrap(), length);
     }
 
     let tests : &[&[u8]] = &[
-        &[], &[163], &[0, 0], &[0, 1, 0],
-        &[160, 6, 2, 1, 10, 1, 1, 0],
-        &[35, 6, 2, 1, 10, 1, 1, 0],
-        &[131, 6, 2, 1, 10, 1, 1, 0],
-        &[163, 6, 2, 1, 10, 1, 1, 0, 0],
-        &[163, 6, 2, 1, 10, 1, 1],
-        &[163, 7, 2, 2, 1, 10, 1, 1, 0, 0],
-        &[163, 7, 2, 2, 1, 10, 1, 1],
-        &[163, 8, 48, 6, 2, 1, 10, 1, 1, 0],
-        &[163, 9, 48, 7, 2, 2, 1, 10, 1, 1, 0],
-        &[163, 128, 2, 1, 10, 1, 1, 0, 0, 0, 0],
-        &[163, 128, 2, 1, 10, 1, 1, 0, 0, 0],
-        &[163, 128, 2, 1, 10, 1, 1, 0, 0],
-        &[163, 128, 2, 2, 1, 10, 1, 1, 0, 0, 0, 0],
-        &[163, 128, 2, 2, 1, 10, 1, 1, 0, 0, 0],
-        &[163, 128, 2, 2, 1, 10, 1, 1, 0, 0],
+        b"\x00\x00", b"\x30\x80\x00\x01", b"\x04\x80", b"\x04\xff",
+        b"\x1f\x1e\x00",
     ];
     for &data in tests {
-        parse_der(data, |reader| {
-            reader.read_tagged(Tag::context(3), |reader| {
-                reader.read_i64()
-            })
-        }).unwrap_err();
+        tlv_length(data, BERMode::Ber).unwrap_err();
     }
+    tlv_length(b"\x30\x80", BERMode::Der).unwrap_err();
+    tlv_length(b"\x04\x81\x01", BERMode::Der).unwrap_err();
+    tlv_length(b"\x04\x89\x01\x00\x00\x00\x00\x00\x00\x00\x00",
+        BERMode::Ber).unwrap_err();
 }
 
 #[test]
-fn test_ber_read_tagged_implicit_ok() {
-    let tests : &[(i64, &[u8])] = &[
-        (10, &[131, 1, 10]),
-        (266, &[131, 2, 1, 10]),
+fn test_der_find_oid() {
+    use alloc::vec;
+    let oid = ObjectIdentifier::from_slice(&[2, 5, 29, 19]);
+    // SEQUENCE { OID, [0] { SEQUENCE { OID, BOOLEAN } }, SET { OID } }
+    let data = &[48, 24, 6, 3, 85, 29, 19, 160, 10, 48, 8, 6, 3, 85, 29, 19,
+        1, 1, 255, 49, 5, 6, 3, 85, 29, 19, 6, 3, 85, 29, 19];
+    let found = find_oid(data, BERMode::Der, &oid).unwrap();
+    let expected = vec![
+        OidLocation { path: vec![0, 0], range: 2..7, sequence: Some(0..26) },
+        OidLocation {
+            path: vec![0, 1, 0, 0],
+            range: 11..16,
+            sequence: Some(9..19),
+        },
+        OidLocation { path: vec![0, 2, 0], range: 21..26, sequence: None },
+        OidLocation { path: vec![1], range: 26..31, sequence: None },
     ];
-    for &(evalue, data) in tests {
-        let value = parse_ber(data, |reader| {
-            reader.read_tagged_implicit(Tag::context(3), |reader| {
-                reader.read_i64()
-            })
-        }).unwrap();
-        assert_eq!(value, evalue);
-    }
+    assert_eq!(found, expected);
+    let other = ObjectIdentifier::from_slice(&[2, 5, 29, 1]);
+    assert!(find_oid(data, BERMode::Der, &other).unwrap().is_empty());
 
-    let tests : &[((i64, bool), &[u8])] = &[
-        ((10, false), &[163, 6, 2, 1, 10, 1, 1, 0]),
-        ((266, false), &[163, 7, 2, 2, 1, 10, 1, 1, 0]),
-        ((10, false), &[163, 128, 2, 1, 10, 1, 1, 0, 0, 0]),
-        ((266, false), &[163, 128, 2, 2, 1, 10, 1, 1, 0, 0, 0]),
+    let data = &[48, 128, 6, 3, 85, 29, 19, 48, 128, 6, 3, 85, 29, 19, 0, 0,
+        0, 0];
+    let found = find_oid(data, BERMode::Ber, &oid).unwrap();
+    assert_eq!(found.len(), 2);
+    assert_eq!(found[0].sequence, Some(0..18));
+    assert_eq!(found[1].sequence, Some(7..16));
+    find_oid(data, BERMode::Der, &oid).unwrap_err();
+
+    let tests : &[&[u8]] = &[
+        &[48, 5, 6, 3, 85, 29], &[48, 3, 6, 3, 85, 29, 19], &[48, 128, 0],
+        &[4, 128, 0, 0], &[48, 128, 48, 128, 0, 0],
     ];
-    for &(evalue, data) in tests {
-        let value = parse_ber(data, |reader| {
-            reader.read_tagged_implicit(Tag::context(3), |reader| {
-                reader.read_sequence(|reader| {
-                    let i = reader.next().read_i64()?;
-                    let b = reader.next().read_bool()?;
-                    return Ok((i, b));
-                })
-            })
-        }).unwrap();
-        assert_eq!(value, evalue);
+    for &data in tests {
+        find_oid(data, BERMode::Ber, &oid).unwrap_err();
     }
 }
 
 #[test]
-fn test_ber_read_tagged_implicit_err() {
+fn test_ber_walk() {
+    use alloc::vec;
+    use super::super::tags::{TAG_INTEGER, TAG_NULL, TAG_SEQUENCE};
+    // SEQUENCE { INTEGER 10, [0] { NULL } }, NULL
+    let data = &[48, 128, 2, 1, 10, 160, 2, 5, 0, 0, 0, 5, 0];
+    let mut events = Vec::new();
+    walk_ber(data, BERMode::Ber, &mut |event| {
+        events.push(event);
+        Ok(())
+    }).unwrap();
+    let expected = vec![
+        Event::BeginConstructed { tag: TAG_SEQUENCE, offset: 0, len: None },
+        Event::Primitive { tag: TAG_INTEGER, offset: 2, content: &[10] },
+        Event::BeginConstructed {
+            tag: Tag::context(0),
+            offset: 5,
+            len: Some(2),
+        },
+        Event::Primitive { tag: TAG_NULL, offset: 7, content: &[] },
+        Event::EndConstructed { tag: Tag::context(0) },
+        Event::EndConstructed { tag: TAG_SEQUENCE },
+        Event::Primitive { tag: TAG_NULL, offset: 11, content: &[] },
+    ];
+    assert_eq!(events, expected);
+    walk_ber(data, BERMode::Der, &mut |_| Ok(())).unwrap_err();
+
+    // The visitor may stop the traversal.
+    let mut count = 0;
+    let result = walk_ber(data, BERMode::Ber, &mut |_| {
+        count += 1;
+        if count == 3 {
+            return Err(ASN1Error::new(ASN1ErrorKind::Extra));
+        }
+        Ok(())
+    });
+    assert_eq!(result, Err(ASN1Error::new(ASN1ErrorKind::Extra)));
+    assert_eq!(count, 3);
+
+    let options = ParseOptions::new(BERMode::Ber).with_max_depth(0);
+    let result = walk_with_options(data, options, &mut |_| Ok(()));
+    assert_eq!(result, Err(ASN1Error::new(ASN1ErrorKind::StackOverflow)));
+
     let tests : &[&[u8]] = &[
-        &[], &[131], &[0, 0], &[0, 1, 0], &[128, 3, 2, 1, 10],
-        &[3, 3, 2, 1, 10], &[163, 3, 2, 1, 10],
-        &[163, 3, 2, 1, 10],
-        &[131, 128, 1, 10, 0, 0],
-        &[163, 128, 2, 1, 10, 0, 0],
+        &[48, 5, 2, 1, 10], &[48, 3, 2, 2, 10], &[48, 128, 0],
+        &[4, 128, 0, 0], &[48, 128, 48, 128, 0, 0],
     ];
     for &data in tests {
-        parse_ber(data, |reader| {
-            reader.read_tagged(Tag::context(3), |reader| {
-                reader.read_sequence(|reader| {
-                    let i = reader.next().read_i64()?;
-                    let b = reader.next().read_bool()?;
-                    return Ok((i, b));
-                })
-            })
-        }).unwrap_err();
+        walk_ber(data, BERMode::Ber, &mut |_| Ok(())).unwrap_err();
     }
 
+    // The end-of-contents octets must be within the enclosing value.
+    let data = &[48, 2, 48, 128, 0, 0];
+    let mut events = Vec::new();
+    let result = walk_ber(data, BERMode::Ber, &mut |event| {
+        events.push(event);
+        Ok(())
+    });
+    assert_eq!(result, Err(ASN1Error::new(ASN1ErrorKind::Eof)));
+    assert_eq!(events, vec![
+        Event::BeginConstructed { tag: TAG_SEQUENCE, offset: 0, len: Some(2) },
+        Event::BeginConstructed { tag: TAG_SEQUENCE, offset: 2, len: None },
+    ]);
+}
+
+#[test]
+fn test_ber_dump() {
+    // SEQUENCE { BOOLEAN TRUE, [0] { OCTET STRING "A" }, OID 2.5.4.3,
+    //     UTF8String "x", [APPLICATION 3] 00 }
+    let data = &[48, 128, 1, 1, 255, 160, 128, 4, 1, 65, 0, 0,
+        6, 3, 85, 4, 3, 12, 1, 120, 67, 1, 0, 0, 0];
+    assert_eq!(dump_ber(data, BERMode::Ber).unwrap(), concat!(
+        "    0:d=0  hl=2 l= inf cons: SEQUENCE\n",
+        "    2:d=1  hl=2 l=   1 prim: BOOLEAN           :TRUE\n",
+        "    5:d=1  hl=2 l= inf cons: cont [ 0 ]\n",
+        "    7:d=2  hl=2 l=   1 prim: OCTET STRING      :[HEX DUMP]:41\n",
+        "   12:d=1  hl=2 l=   3 prim: OBJECT            :2.5.4.3\n",
+        "   17:d=1  hl=2 l=   1 prim: UTF8STRING        :x\n",
+        "   20:d=1  hl=2 l=   1 prim: appl [ 3 ]\n",
+    ));
+    dump_ber(data, BERMode::Der).unwrap_err();
+    assert_eq!(dump_ber(&[], BERMode::Der).unwrap(), "");
+    assert_eq!(dump_ber(&[31, 35, 0], BERMode::Der).unwrap(),
+        "    0:d=0  hl=3 l=   0 prim: [UNIVERSAL 35]\n");
+
     let tests : &[&[u8]] = &[
-        &[], &[163], &[0, 0], &[0, 1, 0],
-        &[160, 6, 2, 1, 10, 1, 1, 0],
-        &[35, 6, 2, 1, 10, 1, 1, 0],
-        &[131, 6, 2, 1, 10, 1, 1, 0],
-        &[163, 6, 2, 1, 10, 1, 1, 0, 0],
-        &[163, 6, 2, 1, 10, 1, 1],
-        &[163, 7, 2, 2, 1, 10, 1, 1, 0, 0],
-        &[163, 7, 2, 2, 1, 10, 1, 1],
-        &[163, 8, 48, 6, 2, 1, 10, 1, 1, 0],
-        &[163, 9, 48, 7, 2, 2, 1, 10, 1, 1, 0],
-        &[163, 128, 2, 1, 10, 1, 1, 0, 0, 0, 0],
-        &[163, 128, 2, 1, 10, 1, 1, 0, 0],
-        &[163, 128, 2, 2, 1, 10, 1, 1, 0, 0, 0, 0],
-        &[163, 128, 2, 2, 1, 10, 1, 1, 0, 0],
+        &[4, 255, 0], &[31, 128, 1, 0], &[31, 30, 0],
+        &[4, 137, 1, 1, 1, 1, 1, 1, 1, 1, 1], &[48, 3, 2, 1],
     ];
     for &data in tests {
-        parse_ber(data, |reader| {
-            reader.read_tagged(Tag::context(3), |reader| {
-                reader.read_i64()
-            })
-        }).unwrap_err();
+        dump_ber(data, BERMode::Ber).unwrap_err();
     }
 }
 
 #[test]
-fn test_ber_read_overflow() {
-    let data = [33, 136, 255, 255, 255, 255, 255, 255, 255, 255];
+fn test_der_match_pattern() {
+    use alloc::vec;
+    // SEQUENCE { INTEGER 1, [0] { OID 2.5.4.3 }, BOOLEAN TRUE, NULL, NULL }
+    let data = &[48, 17, 2, 1, 1, 160, 5, 6, 3, 85, 4, 3, 1, 1, 255,
+        5, 0, 5, 0];
+    let oid = ObjectIdentifier::from_slice(&[2, 5, 4, 3]);
+    let pattern = Pattern::sequence(vec![
+        Pattern::capture(Pattern::Tagged(TAG_INTEGER)),
+        Pattern::explicit(Tag::context(0), Pattern::oid(&oid)),
+        Pattern::optional(Pattern::Tagged(TAG_NULL)),
+        Pattern::capture(Pattern::Rest),
+    ]);
+    assert_eq!(match_pattern(data, BERMode::Der, &pattern).unwrap(),
+        Some(vec![&data[2..5], &data[12..]]));
 
-    let r: Result<bool, _> = parse_ber(&data, |reader| {
-        reader.read_bool()
-    });
+    let pattern = Pattern::sequence(vec![
+        Pattern::Any,
+        Pattern::capture(Pattern::explicit(Tag::context(0),
+            Pattern::capture(Pattern::Any))),
+        Pattern::Exact(TAG_BOOLEAN, vec![255]),
+        Pattern::capture(Pattern::repeat(Pattern::Tagged(TAG_NULL))),
+    ]);
+    assert_eq!(match_pattern(data, BERMode::Der, &pattern).unwrap(),
+        Some(vec![&data[5..12], &data[7..12], &data[15..]]));
 
-    let err = r.unwrap_err();
+    let patterns = vec![
+        Pattern::Tagged(TAG_SET),
+        Pattern::sequence(vec![Pattern::Any]),
+        Pattern::sequence(vec![Pattern::Tagged(TAG_NULL), Pattern::Rest]),
+        Pattern::sequence(vec![
+            Pattern::Any,
+            Pattern::explicit(Tag::context(0),
+                Pattern::oid(&ObjectIdentifier::from_slice(&[2, 5, 4, 6]))),
+            Pattern::Rest,
+        ]),
+    ];
+    for pattern in &patterns {
+        assert_eq!(match_pattern(data, BERMode::Der, pattern).unwrap(), None);
+    }
 
-    assert_eq!(err.kind(), ASN1ErrorKind::IntegerOverflow);
+    let data = &[48, 128, 4, 1, 7, 0, 0];
+    let pattern = Pattern::sequence(vec![Pattern::capture(Pattern::Any)]);
+    assert_eq!(match_pattern(data, BERMode::Ber, &pattern).unwrap(),
+        Some(vec![&data[2..5]]));
+    match_pattern(data, BERMode::Der, &pattern).unwrap_err();
+    match_pattern(&data[..6], BERMode::Ber, &pattern).unwrap_err();
+    match_pattern(&[5, 0, 5, 0], BERMode::Ber, &Pattern::Any).unwrap_err();
+}
+
+#[test]
+fn test_der_read_non_minimal_header() {
+    let mut data = [0; 132];
+    data[..4].copy_from_slice(&[4, 0x82, 0, 128]);
+    parse_ber(&data, |reader| reader.read_bytes()).unwrap();
+    parse_der(&data, |reader| reader.read_bytes()).unwrap_err();
+
+    let data = &[0x9f, 0x80, 31, 0];
+    parse_ber(data, |reader| reader.read_tagged_der()).unwrap_err();
+    parse_ber(data, |reader| reader.lookahead_tag()).unwrap_err();
 }
@@ -15,9 +15,14 @@ use alloc::string::String;
 use num_bigint::{BigInt,BigUint};
 #[cfg(feature = "bit-vec")]
 use bit_vec::BitVec;
+#[cfg(feature = "ibig")]
+use ibig::{IBig,UBig};
+#[cfg(feature = "crypto-bigint")]
+use crypto_bigint::Uint;
 
 use super::{DERWriter,construct_der};
-use super::models::ObjectIdentifier;
+use super::models::{ObjectIdentifier,OidIri,RelativeOidIri};
+use super::models::{Date,TimeOfDay,DateTime,Duration};
 #[cfg(feature = "time")]
 use super::models::{UTCTime,GeneralizedTime};
 
@@ -47,10 +52,14 @@ use super::models::{UTCTime,GeneralizedTime};
 /// - `Vec<u8>`/`[u8]` as OCTETSTRING encoder.
 /// - `BitVec` as BITSTRING encoder.
 /// - `String`/`str` as UTF8String encoder.
-/// - `i64`, `u64`, `i32`, `u32`, `i16`, `u16`, `BigInt`, `BigUint`
+/// - `i64`, `u64`, `i32`, `u32`, `i16`, `u16`, `BigInt`, `BigUint`,
+///   `IBig`, `UBig`, `crypto_bigint::Uint`
 ///   as INTEGER encoder. (`u8` is avoided because of confliction.)
 /// - `bool` as BOOLEAN encoder.
 /// - `ObjectIdentifier` as OBJECTT IDENTIFIER encoder.
+/// - `OidIri`/`RelativeOidIri` as OID-IRI/RELATIVE-OID-IRI encoder.
+/// - `Date`/`TimeOfDay`/`DateTime`/`Duration` as
+///   DATE/TIME-OF-DAY/DATE-TIME/DURATION encoder.
 /// - `UTCTime`/`GeneralizedTime` as UTCTime/GeneralizedTime encoder.
 pub trait DEREncodable {
     /// Writes the value as an DER-encoded ASN.1 value.
@@ -161,6 +170,27 @@ impl DEREncodable for BigUint {
     }
 }
 
+#[cfg(feature = "ibig")]
+impl DEREncodable for IBig {
+    fn encode_der(&self, writer: DERWriter) {
+        super::bigint::write(writer, self)
+    }
+}
+
+#[cfg(feature = "ibig")]
+impl DEREncodable for UBig {
+    fn encode_der(&self, writer: DERWriter) {
+        super::bigint::write(writer, self)
+    }
+}
+
+#[cfg(feature = "crypto-bigint")]
+impl<const LIMBS: usize> DEREncodable for Uint<LIMBS> {
+    fn encode_der(&self, writer: DERWriter) {
+        super::bigint::write(writer, self)
+    }
+}
+
 impl DEREncodable for bool {
     fn encode_der(&self, writer: DERWriter) {
         writer.write_bool(*self)
@@ -204,6 +234,42 @@ impl DEREncodable for ObjectIdentifier {
     }
 }
 
+impl DEREncodable for OidIri {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_oid_iri(self)
+    }
+}
+
+impl DEREncodable for RelativeOidIri {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_relative_oid_iri(self)
+    }
+}
+
+impl DEREncodable for Date {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_date(self)
+    }
+}
+
+impl DEREncodable for TimeOfDay {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_time_of_day(self)
+    }
+}
+
+impl DEREncodable for DateTime {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_date_time(self)
+    }
+}
+
+impl DEREncodable for Duration {
+    fn encode_der(&self, writer: DERWriter) {
+        writer.write_duration(self)
+    }
+}
+
 #[cfg(feature = "time")]
 impl DEREncodable for UTCTime {
     fn encode_der(&self, writer: DERWriter) {
@@ -0,0 +1,144 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use super::super::{PCBit, Tag};
+use super::super::models::ObjectIdentifier;
+use super::output::length_octets;
+
+/// The largest second component of an OID that can be encoded, plus one:
+/// the first subidentifier, `80 + id1`, must fit in `u64`.
+pub(crate) const OID_MAX_ID1: u64 = 18446744073709551535;
+
+/// An output of the encoders shared by [`DERWriter`](super::DERWriter)
+/// and [`SliceWriter`](super::SliceWriter).
+pub(super) trait Sink {
+    /// Appends `bytes` to the output.
+    fn extend(&mut self, bytes: &[u8]);
+}
+
+/// Writes BER identifier (tag + primitive/constructed) octets.
+pub(super) fn write_identifier<S: Sink + ?Sized>(out: &mut S, tag: Tag,
+        pc: PCBit) {
+    let first = ((tag.tag_class as u8) << 6) | ((pc as u8) << 5);
+    if tag.tag_number < 31 {
+        out.extend(&[first | tag.tag_number as u8]);
+        return;
+    }
+    let mut octets = [0; 11];
+    octets[0] = first | 31;
+    let len = write_subid(&mut octets[1..], tag.tag_number);
+    out.extend(&octets[..len + 1]);
+}
+
+/// Writes BER length octets.
+pub(super) fn write_length<S: Sink + ?Sized>(out: &mut S, length: usize) {
+    let (octets, len) = length_octets(length);
+    out.extend(&octets[..len]);
+}
+
+/// Writes the length and the contents of an INTEGER or ENUMERATED.
+pub(super) fn write_i64<S: Sink + ?Sized>(out: &mut S, val: i64) {
+    let mut shiftnum = 56;
+    while shiftnum > 0 &&
+            (val >> (shiftnum-1) == 0 || val >> (shiftnum-1) == -1) {
+        shiftnum -= 8;
+    }
+    write_int_octets(out, val as u64, shiftnum);
+}
+
+/// Writes the length and the contents of an INTEGER. A leading zero
+/// octet is added when the most significant bit is set, so that the
+/// value isn't read as negative.
+pub(super) fn write_u64<S: Sink + ?Sized>(out: &mut S, val: u64) {
+    let mut shiftnum = 64;
+    while shiftnum > 0 && val >> (shiftnum-1) == 0 {
+        shiftnum -= 8;
+    }
+    if shiftnum == 64 {
+        write_length(out, 9);
+        out.extend(&[0]);
+        write_octets(out, val, 56);
+    } else {
+        write_int_octets(out, val, shiftnum);
+    }
+}
+
+/// Writes the length, then the octets of `val` from `shiftnum` down.
+fn write_int_octets<S: Sink + ?Sized>(out: &mut S, val: u64,
+        shiftnum: usize) {
+    write_length(out, shiftnum / 8 + 1);
+    write_octets(out, val, shiftnum);
+}
+
+fn write_octets<S: Sink + ?Sized>(out: &mut S, val: u64, shiftnum: usize) {
+    let mut octets = [0; 8];
+    let len = shiftnum / 8 + 1;
+    for (i, octet) in octets[..len].iter_mut().enumerate() {
+        *octet = (val >> (shiftnum - 8 * i)) as u8;
+    }
+    out.extend(&octets[..len]);
+}
+
+/// Writes the length and the contents of a primitive BIT STRING of the
+/// first `len` bits of `bytes`.
+pub(super) fn write_bitvec_bytes<S: Sink + ?Sized>(out: &mut S,
+        bytes: &[u8], len: usize) {
+    debug_assert!(len <= 8 * bytes.len());
+    debug_assert!(8 * bytes.len() < len + 8);
+    write_length(out, 1 + bytes.len());
+    let len_diff = 8 * bytes.len() - len;
+    out.extend(&[len_diff as u8]);
+    if let Some((&last, init)) = bytes.split_last() {
+        out.extend(init);
+        let mask = !(255u16 >> (8 - len_diff)) as u8;
+        out.extend(&[last & mask]);
+    }
+}
+
+/// Writes the length and the contents of an OBJECT IDENTIFIER.
+///
+/// # Panics
+///
+/// It panics when the OID cannot be canonically encoded in BER.
+pub(super) fn write_oid<S: Sink + ?Sized>(out: &mut S,
+        oid: &ObjectIdentifier) {
+    let components = oid.components();
+    assert!(components.len() >= 2, "Invalid OID: too short");
+    let (id0, id1) = (components[0], components[1]);
+    assert!(
+        (id0 < 3) && (id1 < OID_MAX_ID1) && (id0 >= 2 || id1 < 40),
+        "Invalid OID {{{} {} ...}}", id0, id1);
+    let subid0 = id0 * 40 + id1;
+    let subids = || Some(subid0).into_iter()
+        .chain(components[2..].iter().cloned());
+    let mut octets = [0; 10];
+    let length = subids().map(|subid| write_subid(&mut octets, subid))
+        .sum::<usize>();
+    write_length(out, length);
+    for subid in subids() {
+        let len = write_subid(&mut octets, subid);
+        out.extend(&octets[..len]);
+    }
+}
+
+/// Writes `subid` in base 128 into `octets`, returning the number of
+/// octets.
+fn write_subid(octets: &mut [u8], subid: u64) -> usize {
+    let mut len = 0;
+    let mut shiftnum = 63; // ceil(64 / 7) * 7 - 7
+    while shiftnum > 0 && (subid >> shiftnum) == 0 {
+        shiftnum -= 7;
+    }
+    while shiftnum > 0 {
+        octets[len] = 128 | ((subid >> shiftnum) & 127) as u8;
+        len += 1;
+        shiftnum -= 7;
+    }
+    octets[len] = (subid & 127) as u8;
+    len + 1
+}
@@ -10,20 +10,40 @@
 #![forbid(missing_docs)]
 
 use alloc::vec::Vec;
+use core::cmp;
+
+mod encode;
+mod output;
+mod slice;
+#[cfg(feature = "std")]
+mod stream;
 
 #[cfg(feature = "num-bigint")]
 use num_bigint::{BigUint, BigInt};
 #[cfg(feature = "bit-vec")]
 use bit_vec::BitVec;
+#[cfg(feature = "uuid")]
+use uuid::Uuid;
 
-use super::{PCBit, Tag};
+use super::{BERMode, PCBit, Tag};
+use super::{tlv_length, TlvLength, ASN1Error, ASN1ErrorKind, ASN1Result};
 use super::tags::{TAG_BOOLEAN,TAG_INTEGER,TAG_OCTETSTRING};
 use super::tags::{TAG_NULL,TAG_OID,TAG_UTF8STRING,TAG_SEQUENCE,TAG_SET,TAG_ENUM,TAG_IA5STRING,TAG_BMPSTRING};
 use super::tags::{TAG_NUMERICSTRING,TAG_PRINTABLESTRING,TAG_VISIBLESTRING};
-use super::models::{ObjectIdentifier,TaggedDerValue};
+use super::tags::{TAG_UNIVERSALSTRING,TAG_REAL};
+use super::models::{AsnDateTime,ObjectIdentifier,TaggedDerValue};
+use super::models::{OidIri,RelativeOidIri};
+use super::models::{Date,TimeOfDay,DateTime,Duration};
 #[cfg(feature = "time")]
 use super::models::{UTCTime,GeneralizedTime};
 
+pub use self::slice::{encode_der_into, BufferTooSmall};
+pub use self::slice::{SliceWriter, SliceWriterSeq};
+#[cfg(feature = "std")]
+pub use self::stream::StreamWriter;
+use self::output::Output;
+pub(crate) use self::encode::OID_MAX_ID1;
+
 /// Constructs DER-encoded data as `Vec<u8>`.
 ///
 /// This function uses the loan pattern: `callback` is called back with
@@ -43,14 +63,15 @@ use super::models::{UTCTime,GeneralizedTime};
 /// ```
 pub fn construct_der<F>(callback: F) -> Vec<u8>
         where F: FnOnce(DERWriter) {
-    let mut buf = Vec::new();
+    let mut buf = Output::new();
     {
         let mut writer = DERWriterSeq {
             buf: &mut buf,
+            mode: BERMode::Der,
         };
         callback(writer.next());
     }
-    return buf;
+    return buf.finish();
 }
 
 /// Tries to construct DER-encoded data as `Vec<u8>`.
@@ -85,14 +106,15 @@ pub fn construct_der<F>(callback: F) -> Vec<u8>
 /// ```
 pub fn try_construct_der<F, E>(callback: F) -> Result<Vec<u8>, E>
         where F: FnOnce(DERWriter) -> Result<(), E> {
-    let mut buf = Vec::new();
+    let mut buf = Output::new();
     {
         let mut writer = DERWriterSeq {
             buf: &mut buf,
+            mode: BERMode::Der,
         };
         callback(writer.next())?;
     }
-    return Ok(buf);
+    return Ok(buf.finish());
 }
 
 /// Constructs DER-encoded sequence of data as `Vec<u8>`.
@@ -115,14 +137,15 @@ pub fn try_construct_der<F, E>(callback: F) -> Result<Vec<u8>, E>
 /// ```
 pub fn construct_der_seq<F>(callback: F) -> Vec<u8>
         where F: FnOnce(&mut DERWriterSeq) {
-    let mut buf = Vec::new();
+    let mut buf = Output::new();
     {
         let mut writer = DERWriterSeq {
             buf: &mut buf,
+            mode: BERMode::Der,
         };
         callback(&mut writer);
     }
-    return buf;
+    return buf.finish();
 }
 
 /// Tries to construct a DER-encoded sequence of data as `Vec<u8>`.
@@ -150,14 +173,113 @@ pub fn construct_der_seq<F>(callback: F) -> Vec<u8>
 /// ```
 pub fn try_construct_der_seq<F, E>(callback: F) -> Result<Vec<u8> , E>
         where F: FnOnce(&mut DERWriterSeq) -> Result<(), E> {
-    let mut buf = Vec::new();
+    let mut buf = Output::new();
     {
         let mut writer = DERWriterSeq {
             buf: &mut buf,
+            mode: BERMode::Der,
         };
         callback(&mut writer)?;
     }
-    return Ok(buf);
+    return Ok(buf.finish());
+}
+
+/// Constructs BER-encoded data as `Vec<u8>`, where constructed values
+/// use the indefinite length form.
+///
+/// This is the same as [`construct_der`], except that every constructed
+/// value, such as a SEQUENCE or an explicitly tagged value, is terminated
+/// by end-of-contents octets instead of being preceded by its length.
+/// Primitive values and the order of SET components are as in DER. This
+/// is useful to produce valid BER that isn't DER, e.g. for tests.
+///
+/// # Examples
+///
+/// ```
+/// use yasna;
+/// let ber = yasna::construct_ber(|writer| {
+///     writer.write_sequence(|writer| {
+///         writer.next().write_i64(10);
+///         writer.next().write_bool(true);
+///     })
+/// });
+/// assert_eq!(ber, vec![48, 128, 2, 1, 10, 1, 1, 255, 0, 0]);
+/// ```
+pub fn construct_ber<F>(callback: F) -> Vec<u8>
+        where F: FnOnce(DERWriter) {
+    let mut buf = Output::new();
+    {
+        let mut writer = DERWriterSeq {
+            buf: &mut buf,
+            mode: BERMode::Ber,
+        };
+        callback(writer.next());
+    }
+    return buf.finish();
+}
+
+/// Constructs CER-encoded data as `Vec<u8>`.
+///
+/// This is the same as [`construct_ber`], except that the values of
+/// string types with more than 1000 contents octets are split into
+/// segments of 1000 octets, as CER requires.
+///
+/// # Examples
+///
+/// ```
+/// use yasna;
+/// let cer = yasna::construct_cer(|writer| {
+///     writer.write_bytes(&[7; 1001])
+/// });
+/// assert_eq!(&cer[..6], &[36, 128, 4, 130, 3, 232]);
+/// assert_eq!(&cer[1006..], &[4, 1, 7, 0, 0]);
+/// ```
+pub fn construct_cer<F>(callback: F) -> Vec<u8>
+        where F: FnOnce(DERWriter) {
+    let mut buf = Output::new();
+    {
+        let mut writer = DERWriterSeq {
+            buf: &mut buf,
+            mode: BERMode::Cer,
+        };
+        callback(writer.next());
+    }
+    buf.finish()
+}
+
+/// Computes the length of the DER encoding constructed by `callback`,
+/// without storing it.
+///
+/// `callback` is called back with a [`DERWriter`], as in
+/// [`construct_der`], which only counts the bytes written. This allows to
+/// allocate the output exactly, or to write the length ahead of it. The
+/// components of SET and SET OF are still encoded in memory, to be
+/// sorted, and `write_digested` doesn't feed the digest.
+///
+/// # Examples
+///
+/// ```
+/// use yasna;
+/// let write = |writer: yasna::DERWriter| {
+///     writer.write_sequence(|writer| {
+///         writer.next().write_bytes(&[0; 200]);
+///         writer.next().write_bool(true);
+///     })
+/// };
+/// assert_eq!(yasna::encoded_len(write), 209);
+/// assert_eq!(yasna::construct_der(write).len(), 209);
+/// ```
+pub fn encoded_len<F>(callback: F) -> usize
+        where F: FnOnce(DERWriter) {
+    let mut buf = Output::dry_run();
+    {
+        let mut writer = DERWriterSeq {
+            buf: &mut buf,
+            mode: BERMode::Der,
+        };
+        callback(writer.next());
+    }
+    buf.len()
 }
 
 /// A writer object that accepts an ASN.1 value.
@@ -179,113 +301,86 @@ pub fn try_construct_der_seq<F, E>(callback: F) -> Result<Vec<u8> , E>
 /// ```
 #[derive(Debug)]
 pub struct DERWriter<'a> {
-    buf: &'a mut Vec<u8>,
+    buf: &'a mut Output,
     implicit_tag: Option<Tag>,
+    mode: BERMode,
 }
 
 impl<'a> DERWriter<'a> {
-    fn from_buf(buf: &'a mut Vec<u8>) -> Self {
+    fn from_buf(buf: &'a mut Output, mode: BERMode) -> Self {
         return DERWriter {
             buf,
             implicit_tag: None,
+            mode,
         }
     }
     /// Writes BER identifier (tag + primitive/constructed) octets.
     fn write_identifier(&mut self, tag: Tag, pc: PCBit) {
         let tag = if let Some(tag) = self.implicit_tag { tag } else { tag };
         self.implicit_tag = None;
-        let classid = tag.tag_class as u8;
-        let pcid = pc as u8;
-        if tag.tag_number < 31 {
-            self.buf.push(
-                (classid << 6) | (pcid << 5) | (tag.tag_number as u8));
-            return;
-        }
-        self.buf.push((classid << 6) | (pcid << 5) | 31);
-        let mut shiftnum = 63; // ceil(64 / 7) * 7 - 7
-        while (tag.tag_number >> shiftnum) == 0 {
-            shiftnum -= 7;
-        }
-        while shiftnum > 0 {
-            self.buf.push(128 | (((tag.tag_number >> shiftnum) & 127) as u8));
-            shiftnum -= 7;
-        }
-        self.buf.push((tag.tag_number & 127) as u8);
+        encode::write_identifier(self.buf, tag, pc);
     }
 
     /// Writes BER length octets.
     fn write_length(&mut self, length: usize) {
-        let length = length as u64;
-        if length < 128 {
-            self.buf.push(length as u8);
-            return;
-        }
-        let mut shiftnum = 56; // ceil(64 / 8) * 8 - 8
-        while (length >> shiftnum) == 0 {
-            shiftnum -= 8;
-        }
-        self.buf.push(128 | ((shiftnum / 8 + 1) as u8));
-        loop {
-            self.buf.push((length >> shiftnum) as u8);
-            if shiftnum == 0 {
-                break;
-            }
-            shiftnum -= 8;
-        }
+        encode::write_length(self.buf, length);
     }
 
     /// Deals with unknown length procedures.
-    /// This function first marks the current position and
-    /// allocates 3 bytes. Then it calls back `callback`.
-    /// It then calculates the length and moves the written data
-    /// to the actual position. Finally, it writes the length.
+    /// In BER and CER, this function uses the indefinite form.
+    /// Otherwise, it reserves room for the length, calls back `callback`,
+    /// and then writes the length into the room. The unused part of the
+    /// room is removed when the whole output is complete.
     fn with_length<T, F>(&mut self, callback: F) -> T
         where F: FnOnce(&mut Self) -> T {
-        let expected_length_length = 3;
-        for _ in 0..3 {
-            self.buf.push(255);
+        if self.mode != BERMode::Der {
+            self.buf.push(128);
+            let result = callback(self);
+            self.buf.extend(&[0, 0]);
+            return result;
         }
-        let start_pos = self.buf.len();
+        let opened = self.buf.open_length();
         let result = callback(self);
-        let length = (self.buf.len() - start_pos) as u64;
-        let length_length;
-        let mut shiftnum = 56; // ceil(64 / 8) * 8 - 8
-        if length < 128 {
-            length_length = 1;
+        self.buf.close_length(opened);
+        result
+    }
+
+    /// Writes the length and the contents of a SET or SET OF, whose
+    /// components are encoded in `bufs`.
+    fn write_set_contents(&mut self, bufs: &[Vec<u8>]) {
+        if self.mode != BERMode::Der {
+            self.buf.push(128);
         } else {
-            while (length >> shiftnum) == 0 {
-                shiftnum -= 8;
-            }
-            length_length = shiftnum / 8 + 2;
+            // let bufs_len = bufs.iter().map(|buf| buf.len()).sum();
+            let bufs_len =
+                bufs.iter().map(|buf| buf.len()).fold(0, |x, y| x + y);
+            self.write_length(bufs_len);
         }
-        let new_start_pos;
-        if length_length < expected_length_length {
-            let diff = expected_length_length - length_length;
-            new_start_pos = start_pos - diff;
-            self.buf.drain(new_start_pos .. start_pos);
-        } else if length_length > expected_length_length {
-            let diff = length_length - expected_length_length;
-            new_start_pos = start_pos + diff;
-            for _ in 0..diff { self.buf.insert(start_pos, 0); }
-        } else {
-            new_start_pos = start_pos;
+        for buf in bufs.iter() {
+            self.buf.extend(buf);
         }
-        let mut idx = new_start_pos - length_length;
-        if length < 128 {
-            self.buf[idx] = length as u8;
-        } else {
-            self.buf[idx] = 128 | ((shiftnum / 8 + 1) as u8);
-            idx += 1;
-            loop {
-                self.buf[idx] = (length >> shiftnum) as u8;
-                idx += 1;
-                if shiftnum == 0 {
-                    break;
-                }
-                shiftnum -= 8;
-            }
+        if self.mode != BERMode::Der {
+            self.buf.extend(&[0, 0]);
         }
-        return result;
+    }
+
+    /// Writes a value of a string type whose contents are `bytes`.
+    /// In CER, the contents longer than 1000 octets are split into
+    /// OCTET STRING segments of 1000 octets.
+    fn write_string(mut self, tag: Tag, bytes: &[u8]) {
+        if self.mode != BERMode::Cer || bytes.len() <= 1000 {
+            self.write_identifier(tag, PCBit::Primitive);
+            self.write_length(bytes.len());
+            self.buf.extend(bytes);
+            return;
+        }
+        self.write_identifier(tag, PCBit::Constructed);
+        self.with_length(|writer| {
+            for chunk in bytes.chunks(1000) {
+                DERWriter::from_buf(writer.buf, writer.mode)
+                    .write_bytes(chunk);
+            }
+        });
     }
 
     /// Writes `bool` as an ASN.1 BOOLEAN value.
@@ -306,20 +401,8 @@ impl<'a> DERWriter<'a> {
     }
 
     fn write_integer(mut self, tag: Tag, val: i64) {
-        let mut shiftnum = 56;
-        while shiftnum > 0 &&
-                (val >> (shiftnum-1) == 0 || val >> (shiftnum-1) == -1) {
-            shiftnum -= 8;
-        }
         self.write_identifier(tag, PCBit::Primitive);
-        self.write_length(shiftnum / 8 + 1);
-        loop {
-            self.buf.push((val >> shiftnum) as u8);
-            if shiftnum == 0 {
-                break;
-            }
-            shiftnum -= 8;
-        }
+        encode::write_i64(self.buf, val);
     }
 
     /// Writes `i64` as an ASN.1 ENUMERATED value.
@@ -353,24 +436,22 @@ impl<'a> DERWriter<'a> {
     }
 
     /// Writes `u64` as an ASN.1 INTEGER value.
+    ///
+    /// A leading zero octet is added when the most significant bit is set,
+    /// so that the value isn't read as negative.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_u64(0x8000_0000_0000_0000)
+    /// });
+    /// assert_eq!(der, vec![2, 9, 0, 128, 0, 0, 0, 0, 0, 0, 0]);
+    /// ```
     pub fn write_u64(mut self, val: u64) {
-        let mut shiftnum = 64;
-        while shiftnum > 0 && val >> (shiftnum-1) == 0 {
-            shiftnum -= 8;
-        }
         self.write_identifier(TAG_INTEGER, PCBit::Primitive);
-        self.write_length(shiftnum / 8 + 1);
-        if shiftnum == 64 {
-            self.buf.push(0);
-            shiftnum -= 8;
-        }
-        loop {
-            self.buf.push((val >> shiftnum) as u8);
-            if shiftnum == 0 {
-                break;
-            }
-            shiftnum -= 8;
-        }
+        encode::write_u64(self.buf, val);
     }
 
     /// Writes `i32` as an ASN.1 INTEGER value.
@@ -379,6 +460,16 @@ impl<'a> DERWriter<'a> {
     }
 
     /// Writes `u32` as an ASN.1 INTEGER value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_u32(0xFFFF_FFFF)
+    /// });
+    /// assert_eq!(der, vec![2, 5, 0, 255, 255, 255, 255]);
+    /// ```
     pub fn write_u32(self, val: u32) {
         self.write_i64(val as i64)
     }
@@ -422,24 +513,14 @@ impl<'a> DERWriter<'a> {
     ///
     /// # Features
     ///
-    /// This method is enabled by `num` feature.
+    /// This method is enabled by `num-bigint` feature.
     ///
     /// ```toml
     /// [dependencies]
     /// yasna = { version = "*", features = ["num-bigint"] }
     /// ```
     pub fn write_bigint(self, val: &BigInt) {
-        use num_bigint::Sign;
-        let (sign, mut bytes) = val.to_bytes_be();
-        if sign == Sign::Minus {
-            let mut carry: usize = 1;
-            for b in bytes.iter_mut().rev() {
-                let bval = 255 - (*b as usize);
-                *b = (bval + carry) as u8;
-                carry = (bval + carry) >> 8;
-            }
-        }
-        self.write_bigint_bytes(&bytes, sign != Sign::Minus);
+        super::bigint::write(self, val)
     }
 
     /// Writes `&[u8]` and `bool` as an ASN.1 INTEGER value.
@@ -481,7 +562,7 @@ impl<'a> DERWriter<'a> {
             } else {
                 self.write_length(bytes.len());
             }
-            self.buf.extend_from_slice(&bytes);
+            self.buf.extend(&bytes);
         } else {
             debug_assert!(bytes[0] != 0);
             if bytes[0] < 128 {
@@ -490,7 +571,7 @@ impl<'a> DERWriter<'a> {
             } else {
                 self.write_length(bytes.len());
             }
-            self.buf.extend_from_slice(&bytes);
+            self.buf.extend(&bytes);
         }
     }
 
@@ -513,30 +594,14 @@ impl<'a> DERWriter<'a> {
     ///
     /// # Features
     ///
-    /// This method is enabled by `num` feature.
+    /// This method is enabled by `num-bigint` feature.
     ///
     /// ```toml
     /// [dependencies]
     /// yasna = { version = "*", features = ["num-bigint"] }
     /// ```
-    pub fn write_biguint(mut self, val: &BigUint) {
-        self.write_identifier(TAG_INTEGER, PCBit::Primitive);
-        let mut bytes = val.to_bytes_le();
-        if &bytes == &[0] {
-            self.write_length(1);
-            self.buf.push(0);
-            return;
-        }
-        let byteslen = bytes.len();
-        debug_assert!(bytes[byteslen-1] != 0);
-        if bytes[byteslen-1] >= 128 {
-            self.write_length(byteslen+1);
-            self.buf.push(0);
-        } else {
-            self.write_length(byteslen);
-        }
-        bytes.reverse();
-        self.buf.extend_from_slice(&bytes);
+    pub fn write_biguint(self, val: &BigUint) {
+        super::bigint::write(self, val)
     }
 
     #[cfg(feature = "bit-vec")]
@@ -595,17 +660,51 @@ impl<'a> DERWriter<'a> {
     /// ```
     pub fn write_bitvec_bytes(mut self, bytes: &[u8], len: usize) {
         use super::tags::TAG_BITSTRING;
-        self.write_identifier(TAG_BITSTRING, PCBit::Primitive);
         debug_assert!(len <= 8 * bytes.len());
         debug_assert!(8 * bytes.len() < len + 8);
-        self.write_length(1 + bytes.len());
-        let len_diff = 8 * bytes.len() - len;
-        self.buf.push(len_diff as u8);
-        if bytes.len() > 0 {
-            self.buf.extend_from_slice(&bytes[0 .. bytes.len() - 1]);
-            let mask = !(255u16 >> (8 - len_diff)) as u8;
-            self.buf.push(bytes[bytes.len() - 1] & mask);
+        if self.mode == BERMode::Cer && bytes.len() >= 1000 {
+            // Each segment has 999 octets of bits after the unused bits.
+            self.write_identifier(TAG_BITSTRING, PCBit::Constructed);
+            self.with_length(|writer| {
+                let mut rest = len;
+                for chunk in bytes.chunks(999) {
+                    let bits = cmp::min(rest, 8 * chunk.len());
+                    DERWriter::from_buf(writer.buf, writer.mode)
+                        .write_bitvec_bytes(chunk, bits);
+                    rest -= bits;
+                }
+            });
+            return;
         }
+        self.write_identifier(TAG_BITSTRING, PCBit::Primitive);
+        encode::write_bitvec_bytes(self.buf, bytes, len);
+    }
+
+    /// Writes the first `bit_len` bits of `bytes` as an ASN.1 BITSTRING
+    /// value.
+    ///
+    /// The bits are taken from the most significant bit of each byte, and
+    /// the trailing bytes and bits beyond `bit_len` are ignored. Unlike
+    /// [`write_bitvec_bytes`](Self::write_bitvec_bytes), `bytes` may be
+    /// longer than needed.
+    ///
+    /// # Panics
+    ///
+    /// It panics if `bytes` has fewer than `bit_len` bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_bitstring_bits(&[117, 13, 65, 255], 18)
+    /// });
+    /// assert_eq!(&der, &[3, 4, 6, 117, 13, 64]);
+    /// ```
+    pub fn write_bitstring_bits(self, bytes: &[u8], bit_len: usize) {
+        assert!(bit_len <= 8 * bytes.len(), "Too few bytes for the bits");
+        let len = bit_len / 8 + if bit_len & 7 == 0 { 0 } else { 1 };
+        self.write_bitvec_bytes(&bytes[..len], bit_len);
     }
 
     /// Writes `&[u8]` as an ASN.1 OCTETSTRING value.
@@ -619,10 +718,8 @@ impl<'a> DERWriter<'a> {
     /// });
     /// assert_eq!(der, vec![4, 6, 72, 101, 108, 108, 111, 33]);
     /// ```
-    pub fn write_bytes(mut self, bytes: &[u8]) {
-        self.write_identifier(TAG_OCTETSTRING, PCBit::Primitive);
-        self.write_length(bytes.len());
-        self.buf.extend_from_slice(bytes);
+    pub fn write_bytes(self, bytes: &[u8]) {
+        self.write_string(TAG_OCTETSTRING, bytes);
     }
 
     /// Writes `&str` as an ASN.1 UTF8String value.
@@ -636,10 +733,8 @@ impl<'a> DERWriter<'a> {
     /// });
     /// assert_eq!(der, vec![12, 6, 72, 101, 108, 108, 111, 33]);
     /// ```
-    pub fn write_utf8_string(mut self, string: &str) {
-        self.write_identifier(TAG_UTF8STRING, PCBit::Primitive);
-        self.write_length(string.len());
-        self.buf.extend_from_slice(string.as_bytes());
+    pub fn write_utf8_string(self, string: &str) {
+        self.write_string(TAG_UTF8STRING, string.as_bytes());
     }
 
     /// Writes `&str` as an ASN.1 IA5String value.
@@ -653,15 +748,39 @@ impl<'a> DERWriter<'a> {
     /// });
     /// assert_eq!(der, vec![22, 6, 72, 101, 108, 108, 111, 33]);
     /// ```
-    pub fn write_ia5_string(mut self, string: &str) {
+    pub fn write_ia5_string(self, string: &str) {
         assert!(string.is_ascii(), "IA5 string must be ASCII");
-        self.write_identifier(TAG_IA5STRING, PCBit::Primitive);
-        self.write_length(string.len());
-        self.buf.extend_from_slice(string.as_bytes());
+        self.write_string(TAG_IA5STRING, string.as_bytes());
+    }
+
+    /// Writes `&str` as an ASN.1 IA5String value.
+    ///
+    /// This is equivalent to [`write_ia5_string`](Self::write_ia5_string).
+    ///
+    /// # Panics
+    ///
+    /// It panics if `string` contains non-ASCII characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_ia5string("a@b")
+    /// });
+    /// assert_eq!(der, vec![22, 3, 97, 64, 98]);
+    /// ```
+    pub fn write_ia5string(self, string: &str) {
+        self.write_ia5_string(string)
     }
 
     /// Writes `&str` as an ASN.1 BMPString value.
     ///
+    /// # Panics
+    ///
+    /// It panics if `string` contains characters outside the Basic
+    /// Multilingual Plane, which UCS-2 cannot represent.
+    ///
     /// # Examples
     ///
     /// ```
@@ -671,7 +790,9 @@ impl<'a> DERWriter<'a> {
     /// });
     /// assert_eq!(der, vec![30, 10, 39, 100, 3, 192, 0, 252, 0, 50, 0, 63]);
     /// ```
-    pub fn write_bmp_string(mut self, string: &str) {
+    pub fn write_bmp_string(self, string: &str) {
+        assert!(string.chars().all(|c| (c as u32) < 0x10000),
+            "BMP string must be in the Basic Multilingual Plane");
         let utf16 : Vec<u16> = string.encode_utf16().collect();
 
         let mut bytes = Vec::with_capacity(utf16.len() * 2);
@@ -680,9 +801,30 @@ impl<'a> DERWriter<'a> {
             bytes.push((c % 256) as u8);
         }
 
-        self.write_identifier(TAG_BMPSTRING, PCBit::Primitive);
-        self.write_length(bytes.len());
-        self.buf.extend_from_slice(&bytes);
+        self.write_string(TAG_BMPSTRING, &bytes);
+    }
+
+    /// Writes `&str` as an ASN.1 UniversalString value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_universal_string("£\u{1F600}")
+    /// });
+    /// assert_eq!(der, vec![28, 8, 0, 0, 0, 0xA3, 0, 1, 0xF6, 0x00]);
+    /// ```
+    pub fn write_universal_string(self, string: &str) {
+        let mut bytes = Vec::with_capacity(string.len() * 4);
+        for c in string.chars() {
+            let code = c as u32;
+            bytes.extend_from_slice(&[(code >> 24) as u8, (code >> 16) as u8,
+                (code >> 8) as u8, code as u8]);
+        }
+        self.write_tagged_implicit(TAG_UNIVERSALSTRING, |writer| {
+            writer.write_bytes(&bytes)
+        });
     }
 
     /// Writes the ASN.1 NULL value.
@@ -701,6 +843,77 @@ impl<'a> DERWriter<'a> {
         self.write_length(0);
     }
 
+    /// Writes `f64` as an ASN.1 REAL value.
+    ///
+    /// Finite values are written in the binary encoding with base 2, as
+    /// DER requires (X.690 11.3): the mantissa is odd, and the exponent
+    /// is in the fewest octets. Infinities, NaN and minus zero are
+    /// written as the special values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_real(0.75)
+    /// });
+    /// assert_eq!(der, vec![9, 3, 128, 254, 3]);
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_real(-1.0 / 0.0)
+    /// });
+    /// assert_eq!(der, vec![9, 1, 65]);
+    /// ```
+    pub fn write_real(mut self, val: f64) {
+        self.write_identifier(TAG_REAL, PCBit::Primitive);
+        let bits = val.to_bits();
+        let negative = bits >> 63 != 0;
+        let biased_exponent = ((bits >> 52) & 0x7ff) as i64;
+        let fraction = bits & ((1 << 52) - 1);
+        let special = if val.is_nan() {
+            Some(0x42)
+        } else if biased_exponent == 0x7ff {
+            Some(if negative { 0x41 } else { 0x40 })
+        } else if val == 0.0 {
+            if !negative {
+                self.write_length(0);
+                return;
+            }
+            Some(0x43)
+        } else {
+            None
+        };
+        if let Some(special) = special {
+            self.write_length(1);
+            self.buf.push(special);
+            return;
+        }
+        let (mut mantissa, mut exponent) = if biased_exponent == 0 {
+            // Subnormal
+            (fraction, -1074)
+        } else {
+            (fraction | (1 << 52), biased_exponent - 1075)
+        };
+        let zeros = mantissa.trailing_zeros();
+        mantissa >>= zeros;
+        exponent += zeros as i64;
+        // The exponent fits in 2 octets, and the mantissa in 7.
+        let exponent_len =
+            if exponent as i8 as i64 == exponent { 1 } else { 2 };
+        let mut mantissa_len = 1;
+        while mantissa >> (mantissa_len * 8) != 0 {
+            mantissa_len += 1;
+        }
+        self.write_length(1 + exponent_len + mantissa_len);
+        self.buf.push(0x80 | (if negative { 0x40 } else { 0 }) |
+            (exponent_len as u8 - 1));
+        for i in (0..exponent_len).rev() {
+            self.buf.push((exponent >> (i * 8)) as u8);
+        }
+        for i in (0..mantissa_len).rev() {
+            self.buf.push((mantissa >> (i * 8)) as u8);
+        }
+    }
+
     /// Writes an ASN.1 object identifier.
     ///
     /// # Examples
@@ -719,44 +932,136 @@ impl<'a> DERWriter<'a> {
     ///
     /// It panics when the OID cannot be canonically encoded in BER.
     pub fn write_oid(mut self, oid: &ObjectIdentifier) {
-        assert!(oid.components().len() >= 2, "Invalid OID: too short");
-        let id0 = oid.components()[0];
-        let id1 = oid.components()[1];
-        assert!(
-            (id0 < 3) && (id1 < 18446744073709551535) &&
-            (id0 >= 2 || id1 < 40),
-            "Invalid OID {{{} {} ...}}", id0, id1);
-        let subid0 = id0 * 40 + id1;
-        let mut length = 0;
-        for i in 1..oid.components().len() {
-            let mut subid = if i == 1 {
-                subid0
-            } else {
-                oid.components()[i]
-            } | 1;
-            while subid > 0 {
-                length += 1;
-                subid >>= 7;
-            }
-        }
         self.write_identifier(TAG_OID, PCBit::Primitive);
-        self.write_length(length);
-        for i in 1..oid.components().len() {
-            let subid = if i == 1 {
-                subid0
-            } else {
-                oid.components()[i]
-            };
-            let mut shiftnum = 63; // ceil(64 / 7) * 7 - 7
-            while ((subid|1) >> shiftnum) == 0 {
-                shiftnum -= 7;
-            }
-            while shiftnum > 0 {
-                self.buf.push(128 | ((((subid|1) >> shiftnum) & 127) as u8));
-                shiftnum -= 7;
-            }
-            self.buf.push((subid & 127) as u8);
-        }
+        encode::write_oid(self.buf, oid);
+    }
+
+    /// Writes an ASN.1 OID-IRI.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// use yasna::models::OidIri;
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_oid_iri(&OidIri::new("/ISO".to_string()).unwrap())
+    /// });
+    /// assert_eq!(&der, &[31, 35, 4, 47, 73, 83, 79]);
+    /// ```
+    pub fn write_oid_iri(self, iri: &OidIri) {
+        use super::tags::TAG_OID_INTL_RESID;
+        self.write_tagged_implicit(TAG_OID_INTL_RESID, |writer| {
+            writer.write_bytes(iri.as_str().as_bytes())
+        })
+    }
+
+    /// Writes an ASN.1 RELATIVE-OID-IRI.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// use yasna::models::RelativeOidIri;
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_relative_oid_iri(
+    ///         &RelativeOidIri::new("Example/1".to_string()).unwrap())
+    /// });
+    /// assert_eq!(&der, &[31, 36, 9, 69, 120, 97, 109, 112, 108, 101, 47, 49]);
+    /// ```
+    pub fn write_relative_oid_iri(self, iri: &RelativeOidIri) {
+        use super::tags::TAG_RELATIVE_OID_INTL_RESID;
+        self.write_tagged_implicit(TAG_RELATIVE_OID_INTL_RESID, |writer| {
+            writer.write_bytes(iri.as_str().as_bytes())
+        })
+    }
+
+    /// Writes an ASN.1 DATE.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// use yasna::models::Date;
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_date(&Date::new(2024, 2, 29).unwrap())
+    /// });
+    /// assert_eq!(&der, &[31, 31, 8, 50, 48, 50, 52, 48, 50, 50, 57]);
+    /// ```
+    pub fn write_date(self, date: &Date) {
+        use super::tags::TAG_DATE;
+        self.write_tagged_implicit(TAG_DATE, |writer| {
+            writer.write_bytes(&date.to_bytes())
+        })
+    }
+
+    /// Writes an ASN.1 TIME-OF-DAY.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// use yasna::models::TimeOfDay;
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_time_of_day(&TimeOfDay::new(12, 34, 56).unwrap())
+    /// });
+    /// assert_eq!(&der, &[31, 32, 6, 49, 50, 51, 52, 53, 54]);
+    /// ```
+    pub fn write_time_of_day(self, time: &TimeOfDay) {
+        use super::tags::TAG_TIME_OF_DAY;
+        self.write_tagged_implicit(TAG_TIME_OF_DAY, |writer| {
+            writer.write_bytes(&time.to_bytes())
+        })
+    }
+
+    /// Writes an ASN.1 DATE-TIME.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// use yasna::models::DateTime;
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_date_time(&DateTime::parse(b"20240229123456").unwrap())
+    /// });
+    /// assert_eq!(&der, &[
+    ///     31, 33, 14, 50, 48, 50, 52, 48, 50, 50, 57, 49,
+    ///     50, 51, 52, 53, 54]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `datetime` has a fraction of a second, which DATE-TIME
+    /// can't express.
+    pub fn write_date_time(self, datetime: &DateTime) {
+        use super::tags::TAG_DATE_TIME;
+        assert!(datetime.nanosecond() == 0,
+            "Can't express the fraction of a second in DATE-TIME");
+        self.write_tagged_implicit(TAG_DATE_TIME, |writer| {
+            writer.write_bytes(&datetime.to_bytes())
+        })
+    }
+
+    /// Writes an ASN.1 DURATION.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// use yasna::models::Duration;
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_duration(&Duration::new(0, 0, 1, 12, 0, 0))
+    /// });
+    /// assert_eq!(&der, &[31, 34, 7, 80, 49, 68, 84, 49, 50, 72]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// It panics if weeks are combined with other non-zero components.
+    pub fn write_duration(self, duration: &Duration) {
+        use super::tags::TAG_DURATION;
+        self.write_tagged_implicit(TAG_DURATION, |writer| {
+            writer.write_bytes(&duration.to_bytes())
+        })
     }
 
     /// Writes an ASN.1 UTF8String.
@@ -806,6 +1111,7 @@ impl<'a> DERWriter<'a> {
         return self.with_length(|writer| {
             callback(&mut DERWriterSeq {
                 buf: writer.buf,
+                mode: writer.mode,
             })
         });
     }
@@ -843,6 +1149,13 @@ impl<'a> DERWriter<'a> {
     /// a [`DERWriterSet`], to which the contents of the
     /// SET are written.
     ///
+    /// The components are buffered and emitted in the order of their tags,
+    /// as DER requires, regardless of the order they are written in.
+    ///
+    /// # Panics
+    ///
+    /// It panics if two components have the same tag.
+    ///
     /// For SET OF values, use [`write_set_of`](Self::write_set_of) instead.
     ///
     /// # Examples
@@ -859,10 +1172,13 @@ impl<'a> DERWriter<'a> {
     /// ```
     pub fn write_set<T, F>(mut self, callback: F) -> T
         where F: FnOnce(&mut DERWriterSet) -> T {
-        let mut bufs = Vec::new();
+        let mut outputs = Vec::new();
         let result = callback(&mut DERWriterSet {
-            bufs: &mut bufs,
+            bufs: &mut outputs,
+            mode: self.mode,
         });
+        let mut bufs = outputs.into_iter().map(Output::finish)
+            .collect::<Vec<_>>();
         for buf in bufs.iter() {
             assert!(buf.len() > 0, "Empty output in write_set()");
         }
@@ -879,13 +1195,12 @@ impl<'a> DERWriter<'a> {
             }
             return buf0[1..].cmp(&buf1[1..]);
         });
-        // let bufs_len = bufs.iter().map(|buf| buf.len()).sum();
-        let bufs_len = bufs.iter().map(|buf| buf.len()).fold(0, |x, y| x + y);
-        self.write_identifier(TAG_SET, PCBit::Constructed);
-        self.write_length(bufs_len);
-        for buf in bufs.iter() {
-            self.buf.extend_from_slice(buf);
+        for pair in bufs.windows(2) {
+            assert!(set_component_tag(&pair[0]) != set_component_tag(&pair[1]),
+                "Duplicate tag in write_set()");
         }
+        self.write_identifier(TAG_SET, PCBit::Constructed);
+        self.write_set_contents(&bufs);
         return result;
     }
 
@@ -895,6 +1210,10 @@ impl<'a> DERWriter<'a> {
     /// a [`DERWriterSet`], to which the contents of the
     /// SET OF are written.
     ///
+    /// The elements are buffered and emitted in the ascending order of
+    /// their encodings, as DER requires, regardless of the order they
+    /// are written in.
+    ///
     /// For SET values, use [`write_set`](Self::write_set) instead.
     ///
     /// # Examples
@@ -912,21 +1231,19 @@ impl<'a> DERWriter<'a> {
     /// ```
     pub fn write_set_of<T, F>(mut self, callback: F) -> T
         where F: FnOnce(&mut DERWriterSet) -> T {
-        let mut bufs = Vec::new();
+        let mut outputs = Vec::new();
         let result = callback(&mut DERWriterSet {
-            bufs: &mut bufs,
+            bufs: &mut outputs,
+            mode: self.mode,
         });
+        let mut bufs = outputs.into_iter().map(Output::finish)
+            .collect::<Vec<_>>();
         for buf in bufs.iter() {
             assert!(buf.len() > 0, "Empty output in write_set_of()");
         }
         bufs.sort();
-        // let bufs_len = bufs.iter().map(|buf| buf.len()).sum();
-        let bufs_len = bufs.iter().map(|buf| buf.len()).fold(0, |x, y| x + y);
         self.write_identifier(TAG_SET, PCBit::Constructed);
-        self.write_length(bufs_len);
-        for buf in bufs.iter() {
-            self.buf.extend_from_slice(buf);
-        }
+        self.write_set_contents(&bufs);
         return result;
     }
 
@@ -969,7 +1286,7 @@ impl<'a> DERWriter<'a> {
             assert!(
                 byte == b' ' ||
                 (b'\'' <= byte && byte <= b':' && byte != b'*') ||
-                byte == b'=' ||
+                byte == b'=' || byte == b'?' ||
                 (b'A' <= byte && byte <= b'Z') ||
                 (b'a' <= byte && byte <= b'z'),
                 "Invalid PrintableString: {:?} appeared", byte);
@@ -1051,6 +1368,84 @@ impl<'a> DERWriter<'a> {
         });
     }
 
+    /// Writes a value of any type implementing [`AsnDateTime`]
+    /// as an ASN.1 UTCTime.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// # use yasna::models::AsnDateTime;
+    /// # #[derive(Debug, PartialEq)]
+    /// # struct UnixTime(i64, u32);
+    /// # impl AsnDateTime for UnixTime {
+    /// #     fn from_unix_time(secs: i64, nanos: u32) -> Option<Self> {
+    /// #         Some(UnixTime(secs, nanos))
+    /// #     }
+    /// #     fn to_unix_time(&self) -> (i64, u32) { (self.0, self.1) }
+    /// # }
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_utctime_from(&UnixTime(378820800, 0))
+    /// });
+    /// assert_eq!(&der, &[
+    ///     23, 13, 56, 50, 48, 49, 48, 50, 49, 50, 48, 48, 48, 48, 90]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics when UTCTime can't represent the time, that is, when the
+    /// year is not between 1950 and 2049 or the time has a fractional
+    /// part.
+    pub fn write_utctime_from<T: AsnDateTime>(self, datetime: &T) {
+        use super::tags::TAG_UTCTIME;
+        use super::models::format_utctime;
+        let (secs, nanos) = datetime.to_unix_time();
+        let bytes = format_utctime(secs, nanos)
+            .expect("Can't express the time in UTCTime");
+        self.write_tagged_implicit(TAG_UTCTIME, |writer| {
+            writer.write_bytes(&bytes)
+        });
+    }
+
+    /// Writes a value of any type implementing [`AsnDateTime`]
+    /// as an ASN.1 GeneralizedTime.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// # use yasna::models::AsnDateTime;
+    /// # #[derive(Debug, PartialEq)]
+    /// # struct UnixTime(i64, u32);
+    /// # impl AsnDateTime for UnixTime {
+    /// #     fn from_unix_time(secs: i64, nanos: u32) -> Option<Self> {
+    /// #         Some(UnixTime(secs, nanos))
+    /// #     }
+    /// #     fn to_unix_time(&self) -> (i64, u32) { (self.0, self.1) }
+    /// # }
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_generalized_time_from(&UnixTime(500159309, 724_000_000))
+    /// });
+    /// assert_eq!(&der, &[
+    ///     24, 19, 49, 57, 56, 53, 49, 49, 48, 54, 50,
+    ///     49, 48, 56, 50, 57, 46, 55, 50, 52, 90]);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics when GeneralizedTime can't represent the time, that is,
+    /// when the year is not between 0 and 9999.
+    pub fn write_generalized_time_from<T: AsnDateTime>(self, datetime: &T) {
+        use super::tags::TAG_GENERALIZEDTIME;
+        use super::models::format_generalized_time;
+        let (secs, nanos) = datetime.to_unix_time();
+        let bytes = format_generalized_time(secs, nanos)
+            .expect("Can't express the time in GeneralizedTime");
+        self.write_tagged_implicit(TAG_GENERALIZEDTIME, |writer| {
+            writer.write_bytes(&bytes)
+        });
+    }
+
     /// Writes an ASN.1 VisibleString.
     ///
     /// # Examples
@@ -1073,6 +1468,108 @@ impl<'a> DERWriter<'a> {
         });
     }
 
+    #[cfg(feature = "uuid")]
+    /// Writes a UUID as a 16-byte ASN.1 OCTET STRING.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// use uuid::Uuid;
+    /// let uuid = Uuid::parse_str(
+    ///     "f81d4fae-7dec-11d0-a765-00a0c91e6bf6").unwrap();
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_uuid(&uuid)
+    /// });
+    /// assert_eq!(&der, &[4, 16, 248, 29, 79, 174, 125, 236, 17, 208,
+    ///     167, 101, 0, 160, 201, 30, 107, 246]);
+    /// ```
+    ///
+    /// # Features
+    ///
+    /// This method is enabled by `uuid` feature.
+    ///
+    /// ```toml
+    /// [dependencies]
+    /// yasna = { version = "*", features = ["uuid"] }
+    /// ```
+    pub fn write_uuid(self, uuid: &Uuid) {
+        self.write_bytes(uuid.as_bytes())
+    }
+
+    #[cfg(feature = "uuid")]
+    /// Writes a UUID as an ASN.1 INTEGER, i.e. as the unsigned 128-bit
+    /// integer used in the `2.25` OID arc of X.667.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// use uuid::Uuid;
+    /// let uuid = Uuid::parse_str(
+    ///     "f81d4fae-7dec-11d0-a765-00a0c91e6bf6").unwrap();
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_uuid_integer(&uuid)
+    /// });
+    /// assert_eq!(&der, &[2, 17, 0, 248, 29, 79, 174, 125, 236, 17, 208,
+    ///     167, 101, 0, 160, 201, 30, 107, 246]);
+    /// ```
+    ///
+    /// # Features
+    ///
+    /// This method is enabled by `uuid` feature.
+    ///
+    /// ```toml
+    /// [dependencies]
+    /// yasna = { version = "*", features = ["uuid"] }
+    /// ```
+    pub fn write_uuid_integer(self, uuid: &Uuid) {
+        let bytes = uuid.as_bytes();
+        let start = bytes.iter().position(|&b| b != 0).unwrap_or(bytes.len());
+        self.write_bigint_bytes(&bytes[start..], true)
+    }
+
+    #[cfg(feature = "uuid")]
+    /// Writes a UUID as an ASN.1 RELATIVE-OID with a single arc,
+    /// whose value is the UUID as an unsigned 128-bit integer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// use uuid::Uuid;
+    /// let uuid = Uuid::parse_str(
+    ///     "f81d4fae-7dec-11d0-a765-00a0c91e6bf6").unwrap();
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_uuid_relative_oid(&uuid)
+    /// });
+    /// assert_eq!(&der, &[13, 19, 131, 240, 157, 167, 235, 207, 222, 224,
+    ///     199, 161, 167, 178, 192, 148, 140, 200, 249, 215, 118]);
+    /// ```
+    ///
+    /// # Features
+    ///
+    /// This method is enabled by `uuid` feature.
+    ///
+    /// ```toml
+    /// [dependencies]
+    /// yasna = { version = "*", features = ["uuid"] }
+    /// ```
+    pub fn write_uuid_relative_oid(mut self, uuid: &Uuid) {
+        use super::tags::TAG_RELATIVE_OID;
+        let arc = uuid.as_u128();
+        let mut septets = 1;
+        while septets < 19 && arc >> (7 * septets) != 0 {
+            septets += 1;
+        }
+        self.write_identifier(TAG_RELATIVE_OID, PCBit::Primitive);
+        self.write_length(septets);
+        for i in (0..septets).rev() {
+            let septet = ((arc >> (7 * i)) & 127) as u8;
+            self.buf.push(if i == 0 { septet } else { septet | 128 });
+        }
+    }
+
     /// Writes an (explicitly) tagged value.
     ///
     /// # Examples
@@ -1106,12 +1603,17 @@ impl<'a> DERWriter<'a> {
         where F: FnOnce(DERWriter) -> T {
         self.write_identifier(tag, PCBit::Constructed);
         return self.with_length(|writer| {
-            callback(DERWriter::from_buf(writer.buf))
+            callback(DERWriter::from_buf(writer.buf, writer.mode))
         });
     }
 
     /// Writes an implicitly tagged value.
     ///
+    /// `tag` replaces the tag of the value written by `callback`, which
+    /// stays primitive or constructed. The elements of a constructed value
+    /// keep their own tags. When implicit tagging is nested, the outermost
+    /// tag is written, as in ASN.1.
+    ///
     /// # Examples
     ///
     /// ```
@@ -1128,7 +1630,7 @@ impl<'a> DERWriter<'a> {
         where F: FnOnce(DERWriter) -> T {
         let tag = if let Some(tag) = self.implicit_tag { tag } else { tag };
         self.implicit_tag = None;
-        let mut writer = DERWriter::from_buf(self.buf);
+        let mut writer = DERWriter::from_buf(self.buf, self.mode);
         writer.implicit_tag = Some(tag);
         return callback(writer);
     }
@@ -1153,7 +1655,7 @@ impl<'a> DERWriter<'a> {
     pub fn write_tagged_der(mut self, der: &TaggedDerValue) {
         self.write_identifier(der.tag(), der.pcbit());
         self.write_length(der.value().len());
-        self.buf.extend_from_slice(der.value());
+        self.buf.extend(der.value());
     }
 
     /// Writes `&[u8]` into the DER output buffer directly. Properly encoded tag
@@ -1172,7 +1674,101 @@ impl<'a> DERWriter<'a> {
     /// assert_eq!(raw_der, der);
     /// ```
     pub fn write_der(self, der: &[u8]) {
-        self.buf.extend_from_slice(der);
+        self.buf.extend(der);
+    }
+
+    /// Writes an already encoded value verbatim, after checking that `der`
+    /// is a single TLV.
+    ///
+    /// Only the identifier and length octets are checked, as in
+    /// [`tlv_length`](super::tlv_length), so that e.g. the original
+    /// TBSCertificate can be carried over without being decoded. Nothing
+    /// is written if the check fails. Use [`write_der`](Self::write_der)
+    /// to skip the check.
+    ///
+    /// # Errors
+    ///
+    /// It fails with [`ASN1ErrorKind::Eof`] if `der` ends within the TLV,
+    /// with [`ASN1ErrorKind::Extra`] if data follows it, and with
+    /// [`ASN1ErrorKind::Invalid`] if a header isn't valid in DER.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna::{self, ASN1ErrorKind};
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_sequence(|writer| {
+    ///         writer.next().write_raw_der(&[2, 1, 10]).unwrap();
+    ///         let err = writer.next().write_raw_der(&[2, 1, 10, 0]);
+    ///         assert_eq!(err.unwrap_err().kind(), ASN1ErrorKind::Extra);
+    ///     })
+    /// });
+    /// assert_eq!(der, vec![48, 3, 2, 1, 10]);
+    /// ```
+    pub fn write_raw_der(self, der: &[u8]) -> ASN1Result<()> {
+        match tlv_length(der, BERMode::Der)? {
+            TlvLength::Complete(len) if len == der.len() => {},
+            TlvLength::Complete(_) => {
+                return Err(ASN1Error::new(ASN1ErrorKind::Extra));
+            },
+            TlvLength::Incomplete { .. } => {
+                return Err(ASN1Error::new(ASN1ErrorKind::Eof));
+            },
+        }
+        self.write_der(der);
+        Ok(())
+    }
+
+    /// Writes a value and feeds its encoding into `digest`.
+    ///
+    /// This function uses the loan pattern: `callback` is called back with
+    /// a [`DERWriter`], to which the value is written. Once `callback`
+    /// returns, the complete encoding of the value (identifier and length
+    /// included) is passed to `digest`. This allows signers to hash a
+    /// to-be-signed structure and write the signature after it in the same
+    /// pass, without encoding it twice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use yasna;
+    /// # struct Sha256(Vec<u8>);
+    /// # impl digest::Update for Sha256 {
+    /// #     fn update(&mut self, data: &[u8]) { self.0.extend_from_slice(data); }
+    /// # }
+    /// # impl Sha256 { fn new() -> Self { Sha256(Vec::new()) } }
+    /// let mut hasher = Sha256::new();
+    /// let der = yasna::construct_der(|writer| {
+    ///     writer.write_sequence(|writer| {
+    ///         writer.next().write_digested(&mut hasher, |writer| {
+    ///             writer.write_i64(10)
+    ///         });
+    ///         writer.next().write_bool(true);
+    ///     })
+    /// });
+    /// assert_eq!(der, vec![48, 6, 2, 1, 10, 1, 1, 255]);
+    /// # assert_eq!(hasher.0, vec![2, 1, 10]);
+    /// ```
+    ///
+    /// # Features
+    ///
+    /// This method is enabled by `digest` feature.
+    ///
+    /// ```toml
+    /// [dependencies]
+    /// yasna = { version = "*", features = ["digest"] }
+    /// ```
+    #[cfg(feature = "digest")]
+    pub fn write_digested<T, D, F>(self, digest: &mut D, callback: F) -> T
+        where D: digest::Update, F: FnOnce(DERWriter) -> T {
+        let start_pos = self.buf.len();
+        let result = callback(DERWriter {
+            buf: &mut *self.buf,
+            implicit_tag: self.implicit_tag,
+            mode: self.mode,
+        });
+        self.buf.for_each_piece(start_pos, |piece| digest.update(piece));
+        result
     }
 }
 
@@ -1197,13 +1793,14 @@ impl<'a> DERWriter<'a> {
 /// ```
 #[derive(Debug)]
 pub struct DERWriterSeq<'a> {
-    buf: &'a mut Vec<u8>,
+    buf: &'a mut Output,
+    mode: BERMode,
 }
 
 impl<'a> DERWriterSeq<'a> {
     /// Generates a new [`DERWriter`].
     pub fn next<'b>(&'b mut self) -> DERWriter<'b> {
-        return DERWriter::from_buf(self.buf);
+        return DERWriter::from_buf(self.buf, self.mode);
     }
 }
 
@@ -1226,16 +1823,28 @@ impl<'a> DERWriterSeq<'a> {
 /// ```
 #[derive(Debug)]
 pub struct DERWriterSet<'a> {
-    bufs: &'a mut Vec<Vec<u8>>,
+    bufs: &'a mut Vec<Output>,
+    mode: BERMode,
 }
 
 impl<'a> DERWriterSet<'a> {
     /// Generates a new [`DERWriter`].
     pub fn next<'b>(&'b mut self) -> DERWriter<'b> {
-        self.bufs.push(Vec::new());
-        return DERWriter::from_buf(self.bufs.last_mut().unwrap());
+        self.bufs.push(Output::new());
+        return DERWriter::from_buf(self.bufs.last_mut().unwrap(), self.mode);
     }
 }
 
+/// Returns the identifier octets of an encoded SET component, without the
+/// constructed bit.
+fn set_component_tag(buf: &[u8]) -> (u8, &[u8]) {
+    let len = if buf[0] & 31 == 31 {
+        buf[1..].iter().position(|x| x & 128 == 0).unwrap() + 1
+    } else {
+        0
+    };
+    (buf[0] & 223, &buf[1..len + 1])
+}
+
 #[cfg(test)]
 mod tests;
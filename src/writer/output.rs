@@ -0,0 +1,162 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+use alloc::vec::Vec;
+
+use super::encode::Sink;
+
+/// The longest length octets, those of a length of `u64`.
+pub(super) const MAX_LENGTH_LEN: usize = 9;
+
+/// The output of a [`DERWriter`](super::DERWriter).
+///
+/// The length of a constructed value is only known after its contents are
+/// written, so room for the longest length octets is reserved before
+/// them, and the unused part is left as a hole. Holes are removed at once
+/// by [`finish`](Self::finish), so that the contents are moved at most
+/// once, however deeply they are nested.
+///
+/// In a dry run, the bytes are only counted.
+#[derive(Debug, Default)]
+pub(super) struct Output {
+    /// The encoding, with holes
+    bytes: Vec<u8>,
+    /// The positions and the lengths of the holes, in no particular order
+    holes: Vec<(usize, usize)>,
+    /// The total length of the holes
+    slack: usize,
+    dry_run: bool,
+    /// The length of the encoding in a dry run
+    count: usize,
+}
+
+impl Output {
+    pub(super) fn new() -> Self {
+        Output::default()
+    }
+
+    pub(super) fn dry_run() -> Self {
+        Output {
+            dry_run: true,
+            ..Output::default()
+        }
+    }
+
+    /// Returns the length of the output so far, holes included.
+    pub(super) fn len(&self) -> usize {
+        if self.dry_run { self.count } else { self.bytes.len() }
+    }
+
+    pub(super) fn push(&mut self, byte: u8) {
+        self.extend(&[byte]);
+    }
+
+    pub(super) fn extend(&mut self, bytes: &[u8]) {
+        if self.dry_run {
+            self.count += bytes.len();
+        } else {
+            self.bytes.extend_from_slice(bytes);
+        }
+    }
+
+    /// Reserves the length octets of a value whose contents follow.
+    /// Returns what [`close_length`](Self::close_length) needs.
+    pub(super) fn open_length(&mut self) -> (usize, usize) {
+        let pos = self.len();
+        self.extend(&[0; MAX_LENGTH_LEN]);
+        (pos, self.slack)
+    }
+
+    /// Writes the length octets reserved by
+    /// [`open_length`](Self::open_length), now that the contents are
+    /// written.
+    pub(super) fn close_length(&mut self, opened: (usize, usize)) {
+        let (pos, slack) = opened;
+        let length = self.len() - (pos + MAX_LENGTH_LEN)
+            - (self.slack - slack);
+        let (octets, len) = length_octets(length);
+        if self.dry_run {
+            self.count -= MAX_LENGTH_LEN - len;
+            return;
+        }
+        self.bytes[pos..pos + len].copy_from_slice(&octets[..len]);
+        if len < MAX_LENGTH_LEN {
+            self.holes.push((pos + len, MAX_LENGTH_LEN - len));
+            self.slack += MAX_LENGTH_LEN - len;
+        }
+    }
+
+    /// Calls `f` with the pieces of the encoding from `start`, skipping
+    /// the holes. There must be no hole across `start`. It does nothing in
+    /// a dry run.
+    #[cfg(feature = "digest")]
+    pub(super) fn for_each_piece<F>(&self, start: usize, mut f: F)
+            where F: FnMut(&[u8]) {
+        if self.dry_run {
+            return;
+        }
+        let mut holes = self.holes.iter().filter(|hole| hole.0 >= start)
+            .cloned().collect::<Vec<_>>();
+        holes.sort();
+        let mut pos = start;
+        for (hole, len) in holes {
+            f(&self.bytes[pos..hole]);
+            pos = hole + len;
+        }
+        f(&self.bytes[pos..]);
+    }
+
+    /// Removes the holes, returning the encoding.
+    pub(super) fn finish(mut self) -> Vec<u8> {
+        if self.holes.is_empty() {
+            return self.bytes;
+        }
+        self.holes.sort();
+        let mut dst = self.holes[0].0;
+        for (i, &(hole, len)) in self.holes.iter().enumerate() {
+            let end = self.holes.get(i + 1).map_or(self.bytes.len(), |h| h.0);
+            for src in hole + len..end {
+                self.bytes[dst] = self.bytes[src];
+                dst += 1;
+            }
+        }
+        self.bytes.truncate(dst);
+        self.bytes
+    }
+}
+
+impl Sink for Output {
+    fn extend(&mut self, bytes: &[u8]) {
+        Output::extend(self, bytes);
+    }
+}
+
+/// Returns the BER length octets of `length` and their number.
+pub(super) fn length_octets(length: usize) -> ([u8; MAX_LENGTH_LEN], usize) {
+    let length = length as u64;
+    let mut octets = [0; MAX_LENGTH_LEN];
+    if length < 128 {
+        octets[0] = length as u8;
+        return (octets, 1);
+    }
+    let mut shiftnum = 56; // ceil(64 / 8) * 8 - 8
+    while (length >> shiftnum) == 0 {
+        shiftnum -= 8;
+    }
+    octets[0] = 128 | (shiftnum / 8 + 1) as u8;
+    let mut len = 1;
+    loop {
+        octets[len] = (length >> shiftnum) as u8;
+        len += 1;
+        if shiftnum == 0 {
+            break;
+        }
+        shiftnum -= 8;
+    }
+    (octets, len)
+}
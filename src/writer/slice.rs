@@ -0,0 +1,309 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![forbid(missing_docs)]
+
+use core::fmt::{self, Display};
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use super::super::{PCBit, Tag};
+use super::super::tags::{TAG_BOOLEAN, TAG_INTEGER, TAG_ENUM, TAG_NULL};
+use super::super::tags::{TAG_OCTETSTRING, TAG_BITSTRING, TAG_OID};
+use super::super::tags::{TAG_UTF8STRING, TAG_SEQUENCE};
+use super::super::models::ObjectIdentifier;
+use super::encode::{self, Sink};
+use super::output::length_octets;
+
+/// The error of [`encode_der_into`], telling that the output didn't fit in
+/// the given buffer.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct BufferTooSmall;
+
+impl Display for BufferTooSmall {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Buffer too small")
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for BufferTooSmall {}
+
+/// Constructs DER-encoded data into `buf`, without allocating.
+///
+/// This function uses the loan pattern: `callback` is called back with
+/// a [`SliceWriter`], to which the ASN.1 value is written. It returns the
+/// number of bytes written at the start of `buf`.
+///
+/// [`SliceWriter`] supports a subset of [`DERWriter`](super::DERWriter):
+/// the values which can be encoded without buffering, e.g. SET is missing
+/// because its components must be sorted.
+///
+/// As the lengths are only known after the contents are written, contents
+/// of 128 octets or more are moved within `buf` to make room for their
+/// length, once per enclosing value.
+///
+/// # Errors
+///
+/// It fails with [`BufferTooSmall`] if the value doesn't fit in `buf`.
+/// The contents of `buf` are unspecified then.
+///
+/// # Examples
+///
+/// ```
+/// use yasna;
+/// let mut buf = [0; 16];
+/// let len = yasna::encode_der_into(&mut buf, |writer| {
+///     writer.write_sequence(|writer| {
+///         writer.next().write_i64(10);
+///         writer.next().write_bool(true);
+///     })
+/// }).unwrap();
+/// assert_eq!(&buf[..len], &[48, 6, 2, 1, 10, 1, 1, 255]);
+/// let mut buf = [0; 4];
+/// assert!(yasna::encode_der_into(&mut buf, |writer| {
+///     writer.write_bytes(b"Hello!")
+/// }).is_err());
+/// ```
+pub fn encode_der_into<F>(buf: &mut [u8], callback: F)
+        -> Result<usize, BufferTooSmall>
+        where F: FnOnce(SliceWriter) {
+    let mut out = Output {
+        buf,
+        pos: 0,
+        overflow: false,
+    };
+    callback(SliceWriter {
+        out: &mut out,
+        implicit_tag: None,
+    });
+    if out.overflow {
+        return Err(BufferTooSmall);
+    }
+    Ok(out.pos)
+}
+
+/// The buffer being written. Once something doesn't fit, the rest is
+/// discarded.
+#[derive(Debug)]
+struct Output<'b> {
+    buf: &'b mut [u8],
+    pos: usize,
+    overflow: bool,
+}
+
+impl<'b> Sink for Output<'b> {
+    fn extend(&mut self, bytes: &[u8]) {
+        if self.overflow || bytes.len() > self.buf.len() - self.pos {
+            self.overflow = true;
+            return;
+        }
+        self.buf[self.pos..self.pos + bytes.len()].copy_from_slice(bytes);
+        self.pos += bytes.len();
+    }
+}
+
+/// A writer object that accepts an ASN.1 value, writing into a slice.
+///
+/// It is the counterpart of [`DERWriter`](super::DERWriter) given by
+/// [`encode_der_into`].
+#[derive(Debug)]
+pub struct SliceWriter<'a, 'b> {
+    out: &'a mut Output<'b>,
+    implicit_tag: Option<Tag>,
+}
+
+impl<'a, 'b> SliceWriter<'a, 'b> {
+    /// Writes BER identifier (tag + primitive/constructed) octets.
+    fn write_identifier(&mut self, tag: Tag, pc: PCBit) {
+        let tag = if let Some(tag) = self.implicit_tag { tag } else { tag };
+        self.implicit_tag = None;
+        encode::write_identifier(self.out, tag, pc);
+    }
+
+    /// Writes BER length octets.
+    fn write_length(&mut self, length: usize) {
+        encode::write_length(self.out, length);
+    }
+
+    /// Writes the contents with `callback`, then writes their length
+    /// before them.
+    ///
+    /// One octet is reserved for the length beforehand. Only contents of
+    /// 128 octets or more, whose length takes more, are moved to make
+    /// room for it. Thus a long value nested in `n` long constructed
+    /// values is moved `n` times, unlike with
+    /// [`DERWriter`](super::DERWriter), which has room to backpatch all
+    /// the lengths at the end.
+    fn with_length<T, F>(&mut self, callback: F) -> T
+        where F: FnOnce(&mut Self) -> T {
+        let start = self.out.pos;
+        self.out.extend(&[0]);
+        let result = callback(self);
+        if self.out.overflow {
+            return result;
+        }
+        let (octets, len) = length_octets(self.out.pos - start - 1);
+        if len > 1 {
+            self.out.extend(&octets[1..len]);
+            if self.out.overflow {
+                return result;
+            }
+            self.out.buf[start + 1..self.out.pos].rotate_right(len - 1);
+        }
+        self.out.buf[start..start + len].copy_from_slice(&octets[..len]);
+        result
+    }
+
+    /// Writes `bool` as an ASN.1 BOOLEAN value.
+    pub fn write_bool(mut self, val: bool) {
+        self.write_identifier(TAG_BOOLEAN, PCBit::Primitive);
+        self.out.extend(&[1, if val { 255 } else { 0 }]);
+    }
+
+    fn write_integer(mut self, tag: Tag, val: i64) {
+        self.write_identifier(tag, PCBit::Primitive);
+        encode::write_i64(self.out, val);
+    }
+
+    /// Writes `i64` as an ASN.1 ENUMERATED value.
+    pub fn write_enum(self, val: i64) {
+        self.write_integer(TAG_ENUM, val);
+    }
+
+    /// Writes `i64` as an ASN.1 INTEGER value.
+    pub fn write_i64(self, val: i64) {
+        self.write_integer(TAG_INTEGER, val);
+    }
+
+    /// Writes `u64` as an ASN.1 INTEGER value.
+    pub fn write_u64(mut self, val: u64) {
+        self.write_identifier(TAG_INTEGER, PCBit::Primitive);
+        encode::write_u64(self.out, val);
+    }
+
+    /// Writes `i32` as an ASN.1 INTEGER value.
+    pub fn write_i32(self, val: i32) {
+        self.write_i64(val as i64)
+    }
+
+    /// Writes `u32` as an ASN.1 INTEGER value.
+    pub fn write_u32(self, val: u32) {
+        self.write_i64(val as i64)
+    }
+
+    /// Writes the ASN.1 NULL value.
+    pub fn write_null(mut self) {
+        self.write_identifier(TAG_NULL, PCBit::Primitive);
+        self.write_length(0);
+    }
+
+    /// Writes `&[u8]` as an ASN.1 OCTETSTRING value.
+    pub fn write_bytes(mut self, bytes: &[u8]) {
+        self.write_identifier(TAG_OCTETSTRING, PCBit::Primitive);
+        self.write_length(bytes.len());
+        self.out.extend(bytes);
+    }
+
+    /// Writes `&str` as an ASN.1 UTF8String value.
+    pub fn write_utf8_string(mut self, string: &str) {
+        self.write_identifier(TAG_UTF8STRING, PCBit::Primitive);
+        self.write_length(string.len());
+        self.out.extend(string.as_bytes());
+    }
+
+    /// Writes `&[u8]` and `usize` as an ASN.1 BITSTRING value.
+    ///
+    /// See [`DERWriter::write_bitvec_bytes`].
+    ///
+    /// [`DERWriter::write_bitvec_bytes`]: super::DERWriter::write_bitvec_bytes
+    pub fn write_bitvec_bytes(mut self, bytes: &[u8], len: usize) {
+        self.write_identifier(TAG_BITSTRING, PCBit::Primitive);
+        encode::write_bitvec_bytes(self.out, bytes, len);
+    }
+
+    /// Writes an ASN.1 object identifier.
+    ///
+    /// # Panics
+    ///
+    /// It panics when the OID cannot be canonically encoded in BER.
+    pub fn write_oid(mut self, oid: &ObjectIdentifier) {
+        self.write_identifier(TAG_OID, PCBit::Primitive);
+        encode::write_oid(self.out, oid);
+    }
+
+    /// Writes ASN.1 SEQUENCE.
+    ///
+    /// This function uses the loan pattern: `callback` is called back with
+    /// a [`SliceWriterSeq`], to which the contents of the
+    /// SEQUENCE is written.
+    pub fn write_sequence<T, F>(mut self, callback: F) -> T
+        where F: FnOnce(&mut SliceWriterSeq) -> T {
+        self.write_identifier(TAG_SEQUENCE, PCBit::Constructed);
+        self.with_length(|writer| {
+            callback(&mut SliceWriterSeq {
+                out: &mut *writer.out,
+            })
+        })
+    }
+
+    /// Writes ASN.1 SEQUENCE OF.
+    ///
+    /// This is equivalent to [`write_sequence`](Self::write_sequence).
+    pub fn write_sequence_of<T, F>(self, callback: F) -> T
+        where F: FnOnce(&mut SliceWriterSeq) -> T {
+        self.write_sequence(callback)
+    }
+
+    /// Writes an explicitly tagged value.
+    pub fn write_tagged<T, F>(mut self, tag: Tag, callback: F) -> T
+        where F: FnOnce(SliceWriter) -> T {
+        self.write_identifier(tag, PCBit::Constructed);
+        self.with_length(|writer| {
+            callback(SliceWriter {
+                out: &mut *writer.out,
+                implicit_tag: None,
+            })
+        })
+    }
+
+    /// Writes an implicitly tagged value.
+    pub fn write_tagged_implicit<T, F>(self, tag: Tag, callback: F) -> T
+        where F: FnOnce(SliceWriter) -> T {
+        let tag = if let Some(tag) = self.implicit_tag { tag } else { tag };
+        callback(SliceWriter {
+            out: self.out,
+            implicit_tag: Some(tag),
+        })
+    }
+
+    /// Writes `&[u8]` into the output buffer directly. Properly encoded
+    /// tag and length must be included at the start of the passed buffer.
+    pub fn write_der(self, der: &[u8]) {
+        self.out.extend(der);
+    }
+}
+
+/// A writer object that accepts ASN.1 values, writing into a slice.
+///
+/// It is the counterpart of [`DERWriterSeq`](super::DERWriterSeq) given by
+/// [`write_sequence`](SliceWriter::write_sequence).
+#[derive(Debug)]
+pub struct SliceWriterSeq<'a, 'b> {
+    out: &'a mut Output<'b>,
+}
+
+impl<'a, 'b> SliceWriterSeq<'a, 'b> {
+    /// Generates a new [`SliceWriter`].
+    pub fn next<'c>(&'c mut self) -> SliceWriter<'c, 'b> {
+        SliceWriter {
+            out: &mut *self.out,
+            implicit_tag: None,
+        }
+    }
+}
@@ -0,0 +1,142 @@
+// Copyright 2016 Masaki Hara
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#![forbid(missing_docs)]
+
+use std::io::{self, Write};
+
+use super::super::{BERMode, PCBit, Tag};
+use super::{DERWriter, DERWriterSeq, Output};
+
+/// Writes BER-encoded data directly into an [`io::Write`], e.g. a file or
+/// a socket.
+///
+/// Constructed values are opened with [`begin`](Self::begin) and closed
+/// with [`end`](Self::end), using the indefinite length form, so their
+/// contents are never held in memory. The other values are written with
+/// [`write`](Self::write), which buffers only the value being written.
+/// Thus a large OCTET STRING can be streamed as a constructed one,
+/// segment by segment.
+///
+/// # Examples
+///
+/// ```
+/// use yasna::StreamWriter;
+/// use yasna::tags::{TAG_OCTETSTRING, TAG_SEQUENCE};
+/// let mut writer = StreamWriter::new(Vec::new());
+/// writer.begin(TAG_SEQUENCE).unwrap();
+/// writer.write(|writer| writer.write_i64(10)).unwrap();
+/// writer.begin(TAG_OCTETSTRING).unwrap();
+/// for chunk in [&b"Hello, "[..], b"world!"].iter() {
+///     writer.write(|writer| writer.write_bytes(chunk)).unwrap();
+/// }
+/// writer.end().unwrap();
+/// writer.end().unwrap();
+/// let ber = writer.finish().unwrap();
+/// let (i, bytes) = yasna::parse_ber(&ber, |reader| {
+///     reader.read_sequence(|reader| {
+///         Ok((reader.next().read_i64()?, reader.next().read_bytes()?))
+///     })
+/// }).unwrap();
+/// assert_eq!((i, &bytes[..]), (10, &b"Hello, world!"[..]));
+/// ```
+///
+/// # Features
+///
+/// This struct is enabled by `std` feature.
+///
+/// ```toml
+/// [dependencies]
+/// yasna = { version = "*", features = ["std"] }
+/// ```
+#[derive(Debug)]
+pub struct StreamWriter<W> {
+    inner: W,
+    depth: usize,
+}
+
+impl<W: Write> StreamWriter<W> {
+    /// Creates a writer emitting into `inner`.
+    pub fn new(inner: W) -> Self {
+        StreamWriter {
+            inner,
+            depth: 0,
+        }
+    }
+
+    /// Returns the number of constructed values not closed yet.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Opens a constructed value with `tag` and the indefinite length.
+    ///
+    /// The values written until the matching [`end`](Self::end) are its
+    /// contents.
+    pub fn begin(&mut self, tag: Tag) -> io::Result<()> {
+        let mut buf = Output::new();
+        DERWriter::from_buf(&mut buf, BERMode::Der)
+            .write_identifier(tag, PCBit::Constructed);
+        buf.push(128);
+        self.inner.write_all(&buf.finish())?;
+        self.depth += 1;
+        Ok(())
+    }
+
+    /// Closes the constructed value opened last.
+    ///
+    /// # Errors
+    ///
+    /// It fails with an error of kind `InvalidInput` if no value is open.
+    pub fn end(&mut self) -> io::Result<()> {
+        if self.depth == 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                "No constructed value to end"));
+        }
+        self.inner.write_all(&[0, 0])?;
+        self.depth -= 1;
+        Ok(())
+    }
+
+    /// Writes a value with `callback`.
+    ///
+    /// The value is encoded in memory with [`DERWriter`], then written.
+    pub fn write<T, F>(&mut self, callback: F) -> io::Result<T>
+            where F: FnOnce(DERWriter) -> T {
+        let mut buf = Output::new();
+        let result = {
+            let mut writer = DERWriterSeq {
+                buf: &mut buf,
+                mode: BERMode::Der,
+            };
+            callback(writer.next())
+        };
+        self.inner.write_all(&buf.finish())?;
+        Ok(result)
+    }
+
+    /// Flushes `inner`.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+
+    /// Flushes and returns `inner`.
+    ///
+    /// # Errors
+    ///
+    /// It fails with an error of kind `InvalidInput` if a constructed
+    /// value is still open.
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.depth != 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                "Constructed value not ended"));
+        }
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
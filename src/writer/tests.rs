@@ -213,8 +213,11 @@ fn test_der_write_bigint() {
         (-9223372036854775808, &[2, 8, 128, 0, 0, 0, 0, 0, 0, 0]),
         (-65537, &[2, 3, 254, 255, 255]),
         (-65536, &[2, 3, 255, 0, 0]),
+        (-65535, &[2, 3, 255, 0, 1]),
         (-32769, &[2, 3, 255, 127, 255]),
         (-32768, &[2, 2, 128, 0]),
+        (-256, &[2, 2, 255, 0]),
+        (-255, &[2, 2, 255, 1]),
         (-129, &[2, 2, 255, 127]),
         (-128, &[2, 1, 128]),
         (-1, &[2, 1, 255]),
@@ -291,6 +294,29 @@ fn test_der_write_biguint() {
     }
 }
 
+#[test]
+fn test_der_write_bitstring_bits() {
+    let tests : &[(&[u8], usize, &[u8])] = &[
+        (&[], 0, &[3, 1, 0]),
+        (&[255], 0, &[3, 1, 0]),
+        (&[255, 255], 1, &[3, 2, 7, 128]),
+        (&[1, 2], 16, &[3, 3, 0, 1, 2]),
+        (&[117, 13, 65, 255], 18, &[3, 4, 6, 117, 13, 64]),
+    ];
+    for &(bytes, bit_len, edata) in tests {
+        let data = construct_der(|writer| {
+            writer.write_bitstring_bits(bytes, bit_len)
+        });
+        assert_eq!(data, edata);
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_der_write_bitstring_bits_short() {
+    construct_der(|writer| writer.write_bitstring_bits(&[0], 9));
+}
+
 #[test]
 fn test_der_write_bytes() {
     let tests : &[(&[u8], &[u8])] = &[
@@ -306,6 +332,71 @@ fn test_der_write_bytes() {
     }
 }
 
+#[test]
+fn test_der_write_ia5string() {
+    let data = construct_der(|writer| writer.write_ia5string("~\u{7f}"));
+    assert_eq!(data, vec![22, 2, 126, 127]);
+}
+
+#[test]
+#[should_panic]
+fn test_der_write_ia5string_non_ascii() {
+    construct_der(|writer| writer.write_ia5string("\u{80}"));
+}
+
+#[test]
+fn test_der_write_bmp_string() {
+    let tests : &[(&str, &[u8])] = &[
+        ("", &[30, 0]),
+        ("a", &[30, 2, 0, 97]),
+        ("\u{FFFF}", &[30, 2, 255, 255]),
+    ];
+    for &(value, edata) in tests {
+        let data = construct_der(|writer| {
+            writer.write_bmp_string(value)
+        });
+        assert_eq!(data, edata);
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_der_write_bmp_string_outside_bmp() {
+    construct_der(|writer| writer.write_bmp_string("\u{10000}"));
+}
+
+#[test]
+fn test_der_write_universal_string() {
+    let tests : &[(&str, &[u8])] = &[
+        ("", &[28, 0]),
+        ("a", &[28, 4, 0, 0, 0, 97]),
+        ("\u{10FFFF}", &[28, 4, 0, 16, 255, 255]),
+    ];
+    for &(value, edata) in tests {
+        let data = construct_der(|writer| {
+            writer.write_universal_string(value)
+        });
+        assert_eq!(data, edata);
+    }
+}
+
+#[test]
+fn test_der_write_date_and_time() {
+    use crate::models::{Date, TimeOfDay, DateTime, Duration};
+    let date = Date::new(1, 2, 3).unwrap();
+    let time = TimeOfDay::new(4, 5, 6).unwrap();
+    assert_eq!(construct_der(|writer| writer.write_date(&date)),
+        b"\x1f\x1f\x0800010203");
+    assert_eq!(construct_der(|writer| writer.write_time_of_day(&time)),
+        b"\x1f\x20\x06040506");
+    assert_eq!(construct_der(|writer| {
+        writer.write_date_time(&DateTime::new(date, time))
+    }), b"\x1f\x21\x0e00010203040506");
+    assert_eq!(construct_der(|writer| {
+        writer.write_duration(&Duration::from_weeks(2))
+    }), b"\x1f\x22\x03P2W");
+}
+
 #[test]
 fn test_der_write_null() {
     let data = construct_der(|writer| {
@@ -314,6 +405,35 @@ fn test_der_write_null() {
     assert_eq!(data, vec![5, 0]);
 }
 
+#[test]
+fn test_der_write_real() {
+    let tests : &[(f64, &[u8])] = &[
+        (0.0, &[9, 0]),
+        (-0.0, &[9, 1, 67]),
+        (1.0 / 0.0, &[9, 1, 64]),
+        (-1.0 / 0.0, &[9, 1, 65]),
+        (1.0, &[9, 3, 128, 0, 1]),
+        (0.75, &[9, 3, 128, 254, 3]),
+        (-6.0, &[9, 3, 192, 1, 3]),
+        (1024.0, &[9, 3, 128, 10, 1]),
+        (1.7976931348623157e308,
+            &[9, 10, 129, 3, 203, 31, 255, 255, 255, 255, 255, 255]),
+        (2.2250738585072014e-308, &[9, 4, 129, 252, 2, 1]),
+        (5e-324, &[9, 4, 129, 251, 206, 1]),
+    ];
+    for &(value, edata) in tests {
+        let data = construct_der(|writer| writer.write_real(value));
+        assert_eq!(data, edata);
+        let decoded = super::super::parse_der(&data, |reader| {
+            reader.read_real()
+        }).unwrap();
+        assert_eq!(decoded.to_bits(), value.to_bits());
+    }
+    let data = construct_der(|writer| writer.write_real(
+        f64::from_bits(0x7ff8_0000_0000_0000)));
+    assert_eq!(data, vec![9, 1, 66]);
+}
+
 #[test]
 fn test_der_write_sequence_small() {
     let data = construct_der(|writer| {
@@ -361,6 +481,28 @@ fn test_der_write_sequence_medium() {
     assert_eq!(data.len(), 200010);
 }
 
+#[test]
+fn test_der_write_sequence_nested() {
+    let data = construct_der(|writer| {
+        writer.write_sequence(|writer| {
+            writer.next().write_tagged(Tag::context(0), |writer| {
+                writer.write_sequence(|writer| {
+                    writer.next().write_bytes(&vec![91; 70000]);
+                    writer.next().write_sequence(|_| {});
+                })
+            });
+            writer.next().write_sequence(|writer| {
+                writer.next().write_i64(10);
+            });
+        })
+    });
+    assert_eq!(data[..20].to_vec(), vec![
+        48, 131, 1, 17, 134, 160, 131, 1, 17, 124, 48, 131, 1, 17, 119,
+        4, 131, 1, 17, 112]);
+    assert_eq!(data[70020..].to_vec(), vec![48, 0, 48, 3, 2, 1, 10]);
+    assert_eq!(data.len(), 70027);
+}
+
 #[test]
 #[ignore]
 fn test_der_write_sequence_large() {
@@ -397,6 +539,20 @@ fn test_der_write_set() {
         77, 5, 4, 3, 66, 97, 114, 191, 149, 140, 78, 5, 4, 3, 70, 111, 111]);
 }
 
+#[test]
+#[should_panic]
+fn test_der_write_set_duplicate_tag() {
+    construct_der(|writer| {
+        writer.write_set(|writer| {
+            writer.next().write_tagged(Tag::context(345678), |writer| {
+                writer.write_bytes(b"Foo")
+            });
+            writer.next().write_tagged_implicit(Tag::context(345678),
+                |writer| writer.write_i64(1));
+        })
+    });
+}
+
 #[test]
 fn test_der_write_set_of() {
     let tests : &[(&[i64], &[u8])] = &[
@@ -420,6 +576,188 @@ fn test_der_write_set_of() {
     }
 }
 
+#[test]
+fn test_ber_write_indefinite() {
+    let data = construct_ber(|writer| {
+        writer.write_sequence(|writer| {
+            writer.next().write_tagged(Tag::context(3), |writer| {
+                writer.write_i64(10)
+            });
+            writer.next().write_set_of(|writer| {
+                writer.next().write_bool(true);
+                writer.next().write_sequence(|_| {});
+            });
+            writer.next().write_tagged_implicit(Tag::context(1), |writer| {
+                writer.write_bytes(b"a")
+            });
+        })
+    });
+    assert_eq!(data, vec![
+        48, 128, 163, 128, 2, 1, 10, 0, 0,
+        49, 128, 1, 1, 255, 48, 128, 0, 0, 0, 0,
+        129, 1, 97, 0, 0]);
+    let decoded = super::super::parse_ber(&data, |reader| {
+        reader.read_sequence(|reader| {
+            let i = reader.next().read_tagged(Tag::context(3), |reader| {
+                reader.read_i64()
+            })?;
+            reader.next().read_set_of(|reader| reader.read_der().map(|_| ()))?;
+            let bytes = reader.next().read_tagged_implicit(Tag::context(1),
+                |reader| reader.read_bytes())?;
+            Ok((i, bytes))
+        })
+    }).unwrap();
+    assert_eq!(decoded, (10, b"a".to_vec()));
+}
+
+#[test]
+fn test_cer_write() {
+    use super::super::{parse_ber_general, BERMode};
+    let data = construct_cer(|writer| writer.write_bytes(&[1; 1000]));
+    assert_eq!(&data[..4], &[4, 130, 3, 232]);
+    assert_eq!(data.len(), 1004);
+
+    let data = construct_cer(|writer| writer.write_bytes(&[1; 2001]));
+    assert_eq!(&data[..6], &[36, 128, 4, 130, 3, 232]);
+    assert_eq!(&data[1006..1010], &[4, 130, 3, 232]);
+    assert_eq!(&data[2010..], &[4, 1, 1, 0, 0]);
+    let decoded = parse_ber_general(&data, BERMode::Cer, |reader| {
+        reader.read_bytes()
+    }).unwrap();
+    assert_eq!(decoded, vec![1; 2001]);
+
+    let string = "x".repeat(1500);
+    let data = construct_cer(|writer| {
+        writer.write_sequence(|writer| {
+            writer.next().write_utf8_string(&string);
+            writer.next().write_tagged_implicit(Tag::context(0), |writer| {
+                writer.write_bytes(b"a")
+            });
+        })
+    });
+    assert_eq!(&data[..8], &[48, 128, 44, 128, 4, 130, 3, 232]);
+    let decoded = parse_ber_general(&data, BERMode::Cer, |reader| {
+        reader.read_sequence(|reader| {
+            let string = reader.next().read_utf8string()?;
+            let bytes = reader.next().read_tagged_implicit(Tag::context(0),
+                |reader| reader.read_bytes())?;
+            Ok((string, bytes))
+        })
+    }).unwrap();
+    assert_eq!(decoded, (string, b"a".to_vec()));
+
+    let data = construct_cer(|writer| {
+        writer.write_bitvec_bytes(&[255; 1000], 7995)
+    });
+    assert_eq!(&data[..7], &[35, 128, 3, 130, 3, 232, 0]);
+    assert_eq!(&data[1006..], &[3, 2, 5, 224, 0, 0]);
+}
+
+#[test]
+fn test_der_encoded_len() {
+    use super::super::models::ObjectIdentifier;
+    let oid = ObjectIdentifier::from_slice(&[1, 2, 840, 113549]);
+    let payload = vec![7; 70000];
+    let write = |writer: DERWriter| {
+        writer.write_sequence(|writer| {
+            writer.next().write_tagged(Tag::context(345678), |writer| {
+                writer.write_sequence_of(|writer| {
+                    writer.next().write_bytes(&payload);
+                    writer.next().write_sequence(|_| {});
+                })
+            });
+            writer.next().write_set(|writer| {
+                writer.next().write_oid(&oid);
+                writer.next().write_tagged_implicit(Tag::context(1),
+                    |writer| writer.write_utf8string("ρ"));
+            });
+            writer.next().write_u64(1 << 63);
+        })
+    };
+    let len = encoded_len(write);
+    assert_eq!(len, construct_der(write).len());
+    assert_eq!(len, 70050);
+    assert_eq!(encoded_len(|writer| writer.write_null()), 2);
+}
+
+#[test]
+fn test_der_encode_into() {
+    use super::super::models::ObjectIdentifier;
+    let oid = ObjectIdentifier::from_slice(&[2, 999, 1, 128, 1 << 63]);
+    let payload = vec![7; 300];
+    let expected = construct_der(|writer| {
+        writer.write_sequence(|writer| {
+            writer.next().write_i64(-129);
+            writer.next().write_u64(1 << 63);
+            writer.next().write_enum(0);
+            writer.next().write_null();
+            writer.next().write_oid(&oid);
+            writer.next().write_utf8_string("ρ");
+            writer.next().write_bitvec_bytes(&[117, 13, 65], 18);
+            writer.next().write_tagged(Tag::context(345678), |writer| {
+                writer.write_sequence_of(|writer| {
+                    writer.next().write_bytes(&payload);
+                })
+            });
+            writer.next().write_tagged_implicit(Tag::context(1), |writer| {
+                writer.write_bool(false)
+            });
+            writer.next().write_der(&[5, 0]);
+        })
+    });
+    let mut buf = [0; 400];
+    let len = encode_der_into(&mut buf, |writer| {
+        writer.write_sequence(|writer| {
+            writer.next().write_i64(-129);
+            writer.next().write_u64(1 << 63);
+            writer.next().write_enum(0);
+            writer.next().write_null();
+            writer.next().write_oid(&oid);
+            writer.next().write_utf8_string("ρ");
+            writer.next().write_bitvec_bytes(&[117, 13, 65], 18);
+            writer.next().write_tagged(Tag::context(345678), |writer| {
+                writer.write_sequence_of(|writer| {
+                    writer.next().write_bytes(&payload);
+                })
+            });
+            writer.next().write_tagged_implicit(Tag::context(1), |writer| {
+                writer.write_bool(false)
+            });
+            writer.next().write_der(&[5, 0]);
+        })
+    }).unwrap();
+    assert_eq!(&buf[..len], &expected[..]);
+
+    for &n in &[205, 206] {
+        let mut buf = vec![0; n];
+        let result = encode_der_into(&mut buf, |writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_bytes(&payload[..200]);
+            })
+        });
+        if n < 206 {
+            assert_eq!(result, Err(BufferTooSmall));
+        } else {
+            assert_eq!(result, Ok(206));
+            assert_eq!(&buf[..6], &[48, 129, 203, 4, 129, 200]);
+            assert_eq!(&buf[6..], &payload[..200]);
+        }
+    }
+
+    for &n in &[0, 1, 4, 5, 6, 7] {
+        let mut buf = vec![0; n];
+        let result = encode_der_into(&mut buf, |writer| {
+            writer.write_sequence(|writer| writer.next().write_i64(10))
+        });
+        if n < 5 {
+            assert_eq!(result, Err(BufferTooSmall));
+        } else {
+            assert_eq!(result, Ok(5));
+            assert_eq!(&buf[..5], &[48, 3, 2, 1, 10]);
+        }
+    }
+}
+
 #[test]
 fn test_der_write_tagged() {
     let data = construct_der(|writer| {
@@ -438,4 +776,208 @@ fn test_der_write_tagged_implicit() {
         })
     });
     assert_eq!(data, vec![131, 1, 10]);
+
+    let data = construct_der(|writer| {
+        writer.write_tagged_implicit(Tag::context(1), |writer| {
+            writer.write_tagged_implicit(Tag::application(2), |writer| {
+                writer.write_sequence(|writer| {
+                    writer.next().write_i64(10);
+                    writer.next().write_tagged_implicit(Tag::context(345678),
+                        |writer| writer.write_bool(true));
+                })
+            })
+        })
+    });
+    assert_eq!(data, vec![161, 9, 2, 1, 10, 159, 149, 140, 78, 1, 255]);
+}
+
+#[test]
+fn test_der_write_raw_der() {
+    use super::super::{ASN1Error, ASN1ErrorKind};
+    let tests : &[(&[u8], Result<(), ASN1ErrorKind>)] = &[
+        (&[5, 0], Ok(())),
+        (&[48, 3, 2, 1, 10], Ok(())),
+        (&[191, 149, 140, 78, 0], Ok(())),
+        (&[], Err(ASN1ErrorKind::Eof)),
+        (&[48, 3, 2, 1], Err(ASN1ErrorKind::Eof)),
+        (&[5, 0, 5, 0], Err(ASN1ErrorKind::Extra)),
+        (&[4, 129, 1, 0], Err(ASN1ErrorKind::Invalid)),
+        (&[48, 128, 0, 0], Err(ASN1ErrorKind::Invalid)),
+    ];
+    for &(der, ref expected) in tests {
+        let mut result = Ok(());
+        let data = construct_der(|writer| {
+            result = writer.write_raw_der(der);
+        });
+        assert_eq!(result, expected.map_err(ASN1Error::new));
+        if result.is_ok() {
+            assert_eq!(data, der);
+        } else {
+            assert!(data.is_empty());
+        }
+    }
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn test_der_write_uuid_forms() {
+    use uuid::Uuid;
+    let tests : &[(u128, &[u8], &[u8])] = &[
+        (0, &[2, 1, 0], &[13, 1, 0]),
+        (1, &[2, 1, 1], &[13, 1, 1]),
+        (128, &[2, 2, 0, 128], &[13, 2, 129, 0]),
+        (u128::max_value(),
+            &[2, 17, 0, 255, 255, 255, 255, 255, 255, 255, 255,
+                255, 255, 255, 255, 255, 255, 255, 255],
+            &[13, 19, 131, 255, 255, 255, 255, 255, 255, 255, 255,
+                255, 255, 255, 255, 255, 255, 255, 255, 255, 127]),
+    ];
+    for &(value, einteger, erelative_oid) in tests {
+        let uuid = Uuid::from_u128(value);
+        let data = construct_der(|writer| {
+            writer.write_uuid_integer(&uuid)
+        });
+        assert_eq!(data, einteger);
+        let data = construct_der(|writer| {
+            writer.write_uuid_relative_oid(&uuid)
+        });
+        assert_eq!(data, erelative_oid);
+    }
+}
+
+#[cfg(feature = "digest")]
+#[test]
+fn test_der_write_digested() {
+    struct Collect(Vec<u8>);
+    impl digest::Update for Collect {
+        fn update(&mut self, data: &[u8]) {
+            self.0.extend_from_slice(data);
+        }
+    }
+    let mut digest = Collect(Vec::new());
+    let data = construct_der(|writer| {
+        writer.write_sequence(|writer| {
+            writer.next().write_digested(&mut digest, |writer| {
+                writer.write_tagged_implicit(Tag::context(0), |writer| {
+                    writer.write_sequence(|writer| {
+                        writer.next().write_i64(10);
+                    })
+                })
+            });
+            writer.next().write_digested(&mut digest, |writer| {
+                writer.write_bool(true)
+            });
+        })
+    });
+    assert_eq!(data, vec![48, 8, 160, 3, 2, 1, 10, 1, 1, 255]);
+    assert_eq!(digest.0, vec![160, 3, 2, 1, 10, 1, 1, 255]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_der_write_time_from() {
+    use std::time::{Duration, UNIX_EPOCH};
+    let tests : &[(i64, u32, &[u8], &[u8])] = &[
+        (0, 0, b"\x17\x0d700101000000Z", b"\x18\x0f19700101000000Z"),
+        (-631152000, 0, b"\x17\x0d500101000000Z", b"\x18\x0f19500101000000Z"),
+        (951827696, 0, b"\x17\x0d000229123456Z", b"\x18\x0f20000229123456Z"),
+        (-1, 500_000_000, b"", b"\x18\x1119691231235959.5Z"),
+    ];
+    for &(secs, nanos, eutctime, egeneralized_time) in tests {
+        let datetime = if secs >= 0 {
+            UNIX_EPOCH + Duration::new(secs as u64, nanos)
+        } else {
+            UNIX_EPOCH - Duration::new((-secs) as u64, 0)
+                + Duration::new(0, nanos)
+        };
+        if !eutctime.is_empty() {
+            let data = construct_der(|writer| {
+                writer.write_utctime_from(&datetime)
+            });
+            assert_eq!(data, eutctime);
+        }
+        let data = construct_der(|writer| {
+            writer.write_generalized_time_from(&datetime)
+        });
+        assert_eq!(data, egeneralized_time);
+    }
+}
+
+#[test]
+fn test_der_write_time_from_date_time() {
+    use crate::models::{Date, DateTime, TimeOfDay};
+    let datetime = DateTime::new(Date::new(1985, 11, 6).unwrap(),
+        TimeOfDay::new(21, 6, 27).unwrap());
+    let tests : &[(u32, &[u8])] = &[
+        (0, b"\x18\x0f19851106210627Z"),
+        (300_000_000, b"\x18\x1119851106210627.3Z"),
+        (123_456_789, b"\x18\x1919851106210627.123456789Z"),
+        (1_000, b"\x18\x1619851106210627.000001Z"),
+    ];
+    for &(nanos, edata) in tests {
+        let datetime = datetime.with_nanosecond(nanos).unwrap();
+        let data = construct_der(|writer| {
+            writer.write_generalized_time_from(&datetime)
+        });
+        assert_eq!(data, edata);
+        let value: DateTime = super::super::parse_der(&data, |reader| {
+            reader.read_generalized_time_as()
+        }).unwrap();
+        assert_eq!(value, datetime);
+    }
+}
+
+#[test]
+#[should_panic]
+fn test_der_write_date_time_fraction() {
+    use crate::models::{Date, DateTime, TimeOfDay};
+    let datetime = DateTime::new(Date::new(1985, 11, 6).unwrap(),
+        TimeOfDay::new(21, 6, 27).unwrap()).with_nanosecond(1).unwrap();
+    construct_der(|writer| writer.write_date_time(&datetime));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_ber_stream_writer() {
+    use std::io;
+    use super::super::tags::{TAG_OCTETSTRING, TAG_SEQUENCE};
+    let mut writer = StreamWriter::new(Vec::new());
+    writer.begin(TAG_SEQUENCE).unwrap();
+    writer.begin(Tag::context(345678)).unwrap();
+    assert_eq!(writer.depth(), 2);
+    writer.write(|writer| writer.write_bool(true)).unwrap();
+    writer.end().unwrap();
+    writer.begin(TAG_OCTETSTRING).unwrap();
+    let len = writer.write(|writer| {
+        writer.write_bytes(&[1, 2]);
+        2
+    }).unwrap();
+    assert_eq!(len, 2);
+    writer.end().unwrap();
+    writer.end().unwrap();
+    let data = writer.finish().unwrap();
+    assert_eq!(data, vec![
+        48, 128, 191, 149, 140, 78, 128, 1, 1, 255, 0, 0,
+        36, 128, 4, 2, 1, 2, 0, 0, 0, 0]);
+    let data = construct_ber(|writer| {
+        writer.write_sequence(|writer| {
+            writer.next().write_tagged(Tag::context(345678), |writer| {
+                writer.write_bool(true)
+            });
+            writer.next().write_tagged_implicit(TAG_OCTETSTRING, |writer| {
+                writer.write_sequence(|writer| {
+                    writer.next().write_bytes(&[1, 2])
+                })
+            });
+        })
+    });
+    assert_eq!(data[..], [
+        48, 128, 191, 149, 140, 78, 128, 1, 1, 255, 0, 0,
+        36, 128, 4, 2, 1, 2, 0, 0, 0, 0]);
+
+    let mut writer = StreamWriter::new(Vec::new());
+    assert_eq!(writer.end().unwrap_err().kind(), io::ErrorKind::InvalidInput);
+    writer.begin(TAG_SEQUENCE).unwrap();
+    assert_eq!(writer.finish().unwrap_err().kind(),
+        io::ErrorKind::InvalidInput);
 }